@@ -0,0 +1,41 @@
+//! Prints every event for a window without polling, run with `--features async`
+
+use xproto::asynchronous::AsyncXContext;
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let xserver = tokio::net::UnixStream::connect(xproto::PATH_TO_UNIX_DOMAIN_SOCKET)
+            .await
+            .expect("cant connect to xserver");
+        let (ctx, mut events) = xproto::RequestConnection::new(
+            xproto::ByteOrder::LittleEndian,
+            11,
+            0,
+            xproto::AuthProtocol::None,
+        )
+        .connect_async(xserver)
+        .await
+        .unwrap();
+
+        let root = ctx.info().list_of_screen[0].root;
+        watch_focus(&ctx, root).await;
+        while let Some(event) = events.next_event().await {
+            match event {
+                Ok(event) => println!("event code = {}", xproto::events::Event::code_of(&event)),
+                Err(e) => println!("err = {:?}", e),
+            }
+        }
+    });
+}
+
+/// property changes on the root show up whenever the focused window changes
+async fn watch_focus(ctx: &AsyncXContext, root: xproto::WINDOW) {
+    ctx.send(|buf| xproto::select_input(buf, root, xproto::set_of_events_common::PROPERTY_CHANGE))
+        .await
+        .unwrap();
+    ctx.sync().await.unwrap();
+}
@@ -3,9 +3,6 @@ use std::os::unix::net::UnixStream;
 fn main() {
     let xserver =
         UnixStream::connect(xproto::PATH_TO_UNIX_DOMAIN_SOCKET).expect("cant connect to xserver");
-    xserver
-        .set_read_timeout(Some(std::time::Duration::from_millis(10)))
-        .unwrap();
 
     let mut ctx = xproto::RequestConnection::new(
         xproto::ByteOrder::LittleEndian,
@@ -26,16 +23,12 @@ fn main() {
         .build()
         .unwrap();
 
+    // the fd could just as well go into an epoll or mio loop, poll_for_event never blocks
     loop {
-        // listen for event messages
-        ctx.socket_cb(|socket| {
-            if let Ok(header) =
-                xproto::xio::read_primitive::<xproto::events::EventHeaderTest, _>(socket)
-            {
-                println!("event header = {:?}", header);
-            }
-        });
-
-        std::thread::sleep(std::time::Duration::from_millis(1));
+        match ctx.wait_for_event_timeout(std::time::Duration::from_secs(1)) {
+            Ok(Some(event)) => println!("event code = {}", xproto::events::Event::code_of(&event)),
+            Ok(None) => println!("no events for a second"),
+            Err(e) => println!("err = {:?}", e),
+        }
     }
 }
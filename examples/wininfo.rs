@@ -0,0 +1,58 @@
+//! Prints the window tree like `xwininfo -tree -root`, or the tree under the window id
+//! given as the first argument, along with each window's properties when `-props` follows it
+
+use std::os::unix::net::UnixStream;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let start = args.next().map(|id| {
+        let id = id.trim_start_matches("0x");
+        xproto::Atom(u32::from_str_radix(id, 16).expect("window ids are hex"))
+    });
+    let show_props = args.next().as_deref() == Some("-props");
+
+    let xserver =
+        UnixStream::connect(xproto::PATH_TO_UNIX_DOMAIN_SOCKET).expect("cant connect to xserver");
+    let ctx = xproto::RequestConnection::new(
+        xproto::ByteOrder::LittleEndian,
+        11,
+        0,
+        xproto::AuthProtocol::None,
+    )
+    .connect(xserver)
+    .unwrap();
+
+    let root = start.unwrap_or(ctx.info().list_of_screen[0].root);
+    for info in ctx.walk_tree(root).unwrap() {
+        let indent = "  ".repeat(info.depth);
+        let g = info.geometry;
+        let name = info.name.as_deref().unwrap_or("(has no name)");
+        let class = info
+            .class
+            .map(|(instance, class)| format!(" (\"{}\" \"{}\")", instance, class))
+            .unwrap_or_default();
+        println!(
+            "{}{:#x} \"{}\"{}: {}x{}+{}+{} {:?}{}",
+            indent,
+            { info.window.0 },
+            name,
+            class,
+            g.width,
+            g.height,
+            g.x,
+            g.y,
+            info.attributes.map_state,
+            if info.attributes.override_redirect {
+                " override-redirect"
+            } else {
+                ""
+            },
+        );
+        if show_props {
+            for atom in ctx.list_properties(info.window).unwrap_or_default() {
+                let name = ctx.get_atom_name(atom).unwrap_or_default();
+                println!("{}    {}", indent, name);
+            }
+        }
+    }
+}
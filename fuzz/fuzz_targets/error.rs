@@ -0,0 +1,22 @@
+#![no_main]
+//! error packets, down to the message they print as
+
+use libfuzzer_sys::fuzz_target;
+use xproto::proto::xproto::{RequestError, ValueError};
+use xproto::{xio, ExtensionRegistry, XErrorHeader, XErrorKind};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = RequestError::parse(data);
+    let _ = ValueError::parse(data);
+
+    let Ok(header) = xio::read_primitive::<XErrorHeader, _>(&mut &data[..]) else {
+        return;
+    };
+    if let Some(error) = XErrorKind::from_header(header) {
+        let _ = error.to_string();
+    }
+    let extensions = ExtensionRegistry::default();
+    if let Some(error) = XErrorKind::from_header_with_extensions(header, &extensions) {
+        let _ = error.to_string();
+    }
+});
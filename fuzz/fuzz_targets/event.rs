@@ -0,0 +1,55 @@
+#![no_main]
+//! the hand written and generated event decoders, on a full event and on `data` as it is
+
+use libfuzzer_sys::fuzz_target;
+use xproto::events::*;
+use xproto::proto::xproto::*;
+use xproto::RawEvent;
+
+macro_rules! parse_all {
+    ($data:expr; $($event:ident),*) => {$(
+        let _ = $event::parse($data);
+    )*};
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut event: RawEvent = [0; 32];
+    let len = data.len().min(32);
+    event[..len].copy_from_slice(&data[..len]);
+
+    let _ = KeyEvent::from_event(&event);
+    let _ = xproto::events::SelectionRequestEvent::from_event(&event);
+    let _ = xproto::events::SelectionNotifyEvent::from_event(&event);
+    let _ = xproto::events::SelectionClearEvent::from_event(&event);
+    let _ = xproto::events::PropertyNotifyEvent::from_event(&event);
+    let _ = FocusEvent::from_event(&event);
+    let _ = xproto::events::KeymapNotifyEvent::from_event(&event);
+    let randr = xproto::randr::RandrExtension {
+        info: xproto::ExtensionInfo {
+            major_opcode: 140,
+            first_event: 89,
+            first_error: 147,
+        },
+        major_version: 1,
+        minor_version: 5,
+    };
+    let _ = xproto::randr::RandrEvent::from_event(&randr, &event);
+
+    for data in [&event[..], data] {
+        parse_all!(data;
+            KeyPressEvent, ButtonPressEvent, MotionNotifyEvent, EnterNotifyEvent, FocusInEvent,
+            ExposeEvent, GraphicsExposureEvent, NoExposureEvent, VisibilityNotifyEvent,
+            CreateNotifyEvent, DestroyNotifyEvent, UnmapNotifyEvent, MapNotifyEvent,
+            MapRequestEvent, ReparentNotifyEvent, ConfigureNotifyEvent, ConfigureRequestEvent,
+            GravityNotifyEvent, ResizeRequestEvent, CirculateNotifyEvent, ColormapNotifyEvent,
+            ClientMessageEvent, MappingNotifyEvent
+        );
+        let _ = xproto::proto::xproto::KeymapNotifyEvent::parse(data);
+        let _ = xproto::proto::xproto::PropertyNotifyEvent::parse(data);
+        let _ = xproto::proto::xproto::SelectionClearEvent::parse(data);
+        let _ = xproto::proto::xproto::SelectionRequestEvent::parse(data);
+        let _ = xproto::proto::xproto::SelectionNotifyEvent::parse(data);
+        let _ = xproto::proto::randr::ScreenChangeNotifyEvent::parse(data);
+        let _ = xproto::proto::randr::NotifyEvent::parse(data);
+    }
+});
@@ -0,0 +1,24 @@
+#![no_main]
+//! GetKeyboardMapping and GetModifierMapping replies, then every lookup a Keymap does
+
+use libfuzzer_sys::fuzz_target;
+use xproto::keyboard::{KeyboardMapping, Keymap, ModifierMapping};
+use xproto::Keysym;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&first_keycode, data)) = data.split_first() else {
+        return;
+    };
+    // the modifier mapping gets the first 64 bytes past the keycode, the keysyms the rest
+    let split = data.len().min(64);
+    let Ok(mapping) = KeyboardMapping::parse(&data[split..], first_keycode) else {
+        return;
+    };
+    let modifiers = ModifierMapping::parse(&data[..split]).unwrap_or_default();
+    let keymap = Keymap::new(mapping, modifiers);
+    for keycode in 0..=255 {
+        let keysym = keymap.lookup(keycode, 0xffff);
+        let _ = keymap.keycodes_for(keysym);
+    }
+    let _ = keymap.keycodes_for(Keysym(0));
+});
@@ -0,0 +1,13 @@
+#![no_main]
+//! GetProperty replies
+
+use libfuzzer_sys::fuzz_target;
+use xproto::proto::xproto::GetPropertyReply;
+use xproto::PropertyReply;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(reply) = PropertyReply::parse(data) {
+        let _ = reply.as_u32s();
+    }
+    let _ = GetPropertyReply::parse(data);
+});
@@ -0,0 +1,15 @@
+#![no_main]
+//! QueryFont replies and glyph lookups into them
+
+use libfuzzer_sys::fuzz_target;
+use xproto::font::QueryFontReply;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(font) = QueryFontReply::parse(data) else {
+        return;
+    };
+    for ch in [0, 0xff, 0x100, 0xffff, font.info.default_char] {
+        let _ = font.char_info(ch);
+    }
+    let _ = font.info.font_name();
+});
@@ -0,0 +1,30 @@
+#![no_main]
+//! the connection setup reply, then BIG-REQUESTS and whatever else `connect` reads after it
+
+use std::io::{self, Read, Write};
+
+use libfuzzer_sys::fuzz_target;
+use xproto::{AuthProtocol, ByteOrder, RequestConnection};
+
+/// a server that answers with the fuzzer's bytes and ignores what we send
+struct Replay<'a>(&'a [u8]);
+
+impl Read for Replay<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for Replay<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let request = RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None);
+    let _ = request.connect(Replay(data));
+});
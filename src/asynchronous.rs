@@ -0,0 +1,641 @@
+//! A connection driven by an async runtime instead of blocking reads.\
+//! A background task owns the read half of the socket and hands replies to whoever sent the
+//! matching request, everything else goes out through the `EventStream`. Another one owns
+//! the write half, requests are handed to it whole so a dropped future never leaves half a
+//! request on the socket.\
+//! The tasks only need `futures_io::AsyncRead`/`AsyncWrite` halves and something to spawn
+//! them on, `connect_async_with` takes those for any runtime. `connect_async` does it for a
+//! tokio `UnixStream` (the `async` feature) and `connect_async_std` for an async-std one
+//! (the `async-std` feature)
+
+use super::*;
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use std::{
+    collections::HashMap,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+};
+// tokio's channels don't need its runtime
+use tokio::sync::{mpsc, oneshot};
+
+type ReplySender = oneshot::Sender<Result<Vec<u8>, XErrorKind>>;
+
+/// requests waiting on a reply, by sequence number
+type Pending = Arc<Mutex<HashMap<CARD16, ReplySender>>>;
+
+/// how many requests can wait for the writer task before `send` waits too
+const QUEUED_REQUESTS: usize = 64;
+
+/// reply bytes are read this much at a time, the length is the server's word
+const READ_CHUNK: usize = 4096;
+
+/// one of the two background tasks, for the `spawn` of `connect_async_with`
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The async counterpart of `XContext`, cheap to clone and usable from any task
+#[derive(Clone)]
+pub struct AsyncXContext {
+    /// complete requests for the writer task, in sequence order
+    requests: mpsc::Sender<Vec<u8>>,
+    /// sequence number of the last request handed to the writer task
+    sequence: Arc<Mutex<CARD16>>,
+    pending: Pending,
+    info: Arc<ConnectionAcceptedInfo>,
+    id_count: Arc<AtomicU32>,
+    max_request_length: CARD32,
+    /// shared with the reader task, which decodes errors with it
+    extensions: Arc<Mutex<ExtensionRegistry>>,
+}
+
+/// Events and the errors of requests that had no reply to carry them, in the order they arrived.\
+/// Ends once the connection is gone
+pub struct EventStream {
+    events: mpsc::UnboundedReceiver<Result<RawEvent, XErrorKind>>,
+}
+
+impl Stream for EventStream {
+    type Item = Result<RawEvent, XErrorKind>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+impl EventStream {
+    /// the next event, `None` once the connection is closed
+    pub async fn next_event(&mut self) -> Option<Result<RawEvent, XErrorKind>> {
+        self.events.recv().await
+    }
+}
+
+/// A reply that hasn't arrived yet, returned by `AsyncXContext::send_request`
+pub struct PendingReply {
+    reply: oneshot::Receiver<Result<Vec<u8>, XErrorKind>>,
+}
+
+impl Future for PendingReply {
+    type Output = Result<Vec<u8>, XErrorKind>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.reply).poll(cx).map(|reply| {
+            // the reader task dropping the sender means the socket is gone
+            reply.unwrap_or_else(|_| Err(connection_closed().into()))
+        })
+    }
+}
+
+fn connection_closed() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        "connection to the X server closed",
+    )
+}
+
+impl RequestConnection<'_> {
+    /// Does the connection handshake over a tokio `stream` and starts the background tasks,
+    /// which needs to be called from inside a tokio runtime.\
+    /// BIG-REQUESTS gets enabled when the server has it, same as `connect`
+    #[cfg(feature = "async")]
+    pub async fn connect_async(
+        self,
+        stream: tokio::net::UnixStream,
+    ) -> ConnectionResult<(AsyncXContext, EventStream)> {
+        let (read, write) = stream.into_split();
+        self.connect_async_with(TokioIo(read), TokioIo(write), |task| {
+            tokio::spawn(task);
+        })
+        .await
+    }
+
+    /// `connect_async` for an async-std `stream`, the tasks go on async-std's executor
+    #[cfg(feature = "async-std")]
+    pub async fn connect_async_std(
+        self,
+        stream: async_std::os::unix::net::UnixStream,
+    ) -> ConnectionResult<(AsyncXContext, EventStream)> {
+        self.connect_async_with(stream.clone(), stream, |task| {
+            async_std::task::spawn(task);
+        })
+        .await
+    }
+
+    /// Does the connection handshake over the two halves of a socket and hands the
+    /// reader and writer tasks to `spawn`, for runtimes other than tokio and async-std
+    pub async fn connect_async_with<R, W, F>(
+        self,
+        mut read: R,
+        mut write: W,
+        spawn: F,
+    ) -> ConnectionResult<(AsyncXContext, EventStream)>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        W: AsyncWrite + Unpin + Send + 'static,
+        F: FnMut(Task),
+    {
+        let mut request = vec![];
+        self.send_request_to_connect(&mut request)?;
+        write_all(&mut write, &request).await?;
+
+        // the first 8 bytes say how much is left, whatever the outcome
+        let mut response = vec![0u8; 8];
+        read_exact(&mut read, &mut response).await?;
+        let additional = CARD16::from_le_bytes([response[6], response[7]]) as usize;
+        response.resize(8 + additional * 4, 0);
+        read_exact(&mut read, &mut response[8..]).await?;
+        let info = self.read_response(response.as_slice())?;
+
+        let (mut ctx, events) = AsyncXContext::from_io(info, read, write, spawn);
+        if let Err(XErrorKind::SocketIO(e)) = ctx.enable_big_requests().await {
+            return Err(e.into());
+        }
+        Ok((ctx, events))
+    }
+}
+
+impl AsyncXContext {
+    fn from_io<R, W, F>(
+        info: ConnectionAcceptedInfo,
+        read: R,
+        write: W,
+        mut spawn: F,
+    ) -> (Self, EventStream)
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        W: AsyncWrite + Unpin + Send + 'static,
+        F: FnMut(Task),
+    {
+        let pending = Pending::default();
+        let extensions = Arc::<Mutex<ExtensionRegistry>>::default();
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let (requests, requests_rx) = mpsc::channel(QUEUED_REQUESTS);
+        spawn(Box::pin(write_requests(
+            write,
+            requests_rx,
+            events_tx.clone(),
+        )));
+        spawn(Box::pin(read_packets(
+            read,
+            pending.clone(),
+            extensions.clone(),
+            events_tx,
+        )));
+        let ctx = Self {
+            requests,
+            sequence: Arc::default(),
+            pending,
+            max_request_length: info.max_req_len as CARD32,
+            info: Arc::new(info),
+            id_count: Arc::new(AtomicU32::new(0)),
+            extensions,
+        };
+        (ctx, EventStream { events })
+    }
+
+    pub fn info(&self) -> &ConnectionAcceptedInfo {
+        &self.info
+    }
+
+    /// the longest request the server accepts, in 4 byte units
+    pub fn max_request_length(&self) -> CARD32 {
+        self.max_request_length
+    }
+
+    pub fn gen_id(&self) -> CARD32 {
+        self.id_count.fetch_add(1, Ordering::Relaxed) | self.info.resource_id_base
+    }
+
+    /// Sends a request that has no reply, `write` gets one of the free request functions.\
+    /// It only waits for room in the writer task's queue, dropping the future before it's
+    /// done sends nothing. Errors the request causes come out of the `EventStream`
+    pub async fn send<F>(&self, write: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        self.queue(write, None).await
+    }
+
+    /// Sends a request that has a reply and returns without waiting for it,
+    /// so several requests can be in flight at once. Dropping the future before it's done
+    /// sends nothing, the same as `send`.\
+    /// An error caused by the request resolves the returned future instead of the reply
+    pub async fn send_request<F>(&self, write: F) -> io::Result<PendingReply>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        let (tx, reply) = oneshot::channel();
+        self.queue(write, Some(tx)).await?;
+        Ok(PendingReply { reply })
+    }
+
+    /// Encodes the request and hands it to the writer task.\
+    /// The only await is for a queue slot, the sequence number is taken after it and together
+    /// with queueing, so the numbers always match the order requests go out in
+    async fn queue<F>(&self, write: F, reply: Option<ReplySender>) -> io::Result<()>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        let mut request = vec![];
+        write(&mut request)?;
+        let slot = self
+            .requests
+            .reserve()
+            .await
+            .map_err(|_| connection_closed())?;
+        let mut sequence = self.sequence.lock().unwrap();
+        *sequence = sequence.wrapping_add(1);
+        // registered before the request goes out so the reader can't see the reply first
+        if let Some(tx) = reply {
+            self.pending.lock().unwrap().insert(*sequence, tx);
+        }
+        slot.send(request);
+        Ok(())
+    }
+
+    /// `send_request` followed by waiting for the reply
+    pub async fn request<F>(&self, write: F) -> Result<Vec<u8>, XErrorKind>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        self.send_request(write).await?.await
+    }
+
+    /// Does a round trip to the server, everything sent before has been handled once it returns
+    pub async fn sync(&self) -> Result<(), XErrorKind> {
+        self.request(get_input_focus_request).await.map(|_| ())
+    }
+
+    /// Same as `XContext::query_extension`, the answers are cached and used to decode errors
+    pub async fn query_extension(&self, name: &str) -> Result<Option<ExtensionInfo>, XErrorKind> {
+        if let Some(info) = self.extensions.lock().unwrap().get(name) {
+            return Ok(info);
+        }
+        let reply = self
+            .request(|buf| query_extension_request(buf, name))
+            .await?;
+        let info = extension::parse_query_extension(&reply)?;
+        self.extensions.lock().unwrap().insert(name, info);
+        Ok(info)
+    }
+
+    /// Same as `XContext::register_decoder`
+    pub fn register_decoder<D>(&self, name: &str, decoder: D)
+    where
+        D: ExtensionDecoder + Send + Sync + 'static,
+    {
+        self.extensions
+            .lock()
+            .unwrap()
+            .insert_decoder(name, decoder);
+    }
+
+    async fn enable_big_requests(&mut self) -> Result<Option<CARD32>, XErrorKind> {
+        let Some(bigreq) = self.query_extension(bigreq::EXTENSION_NAME).await? else {
+            return Ok(None);
+        };
+        let reply = self
+            .request(|buf| bigreq::enable_request(buf, bigreq.major_opcode))
+            .await?;
+        self.max_request_length = read_primitive::<CARD32, _>(&mut &reply[8..12])?;
+        Ok(Some(self.max_request_length))
+    }
+}
+
+/// the writer task, runs until writing fails or every handle is dropped
+async fn write_requests<W: AsyncWrite + Unpin>(
+    mut socket: W,
+    mut requests: mpsc::Receiver<Vec<u8>>,
+    events: mpsc::UnboundedSender<Result<RawEvent, XErrorKind>>,
+) {
+    while let Some(request) = requests.recv().await {
+        let written = async {
+            write_all(&mut socket, &request).await?;
+            poll_fn(|cx| Pin::new(&mut socket).poll_flush(cx)).await
+        };
+        if let Err(e) = written.await {
+            // the reader fails the pending replies once the server side is gone
+            let _ = events.send(Err(e.into()));
+            return;
+        }
+    }
+}
+
+/// the reader task, runs until the socket closes or every handle is dropped
+async fn read_packets<R: AsyncRead + Unpin>(
+    mut socket: R,
+    pending: Pending,
+    extensions: Arc<Mutex<ExtensionRegistry>>,
+    events: mpsc::UnboundedSender<Result<RawEvent, XErrorKind>>,
+) {
+    loop {
+        let packet = match read_packet(&mut socket).await {
+            Ok(packet) => packet,
+            Err(e) => {
+                let _ = events.send(Err(e.into()));
+                // dropping the senders fails every pending reply
+                pending.lock().unwrap().clear();
+                return;
+            }
+        };
+        let sequence = CARD16::from_le_bytes([packet[2], packet[3]]);
+        match packet[0] {
+            0 => {
+                let header = read_primitive::<XErrorHeader, _>(&mut &packet[..32]);
+                let error = match header {
+                    Ok(header) => {
+                        XErrorKind::from_header_with_extensions(header, &extensions.lock().unwrap())
+                            .unwrap_or(XErrorKind::Unknown)
+                    }
+                    Err(e) => e.into(),
+                };
+                match pending.lock().unwrap().remove(&sequence) {
+                    Some(tx) => {
+                        let _ = tx.send(Err(error));
+                    }
+                    None => {
+                        let _ = events.send(Err(error));
+                    }
+                }
+            }
+            // a reply nobody is waiting on anymore is dropped
+            1 => {
+                if let Some(tx) = pending.lock().unwrap().remove(&sequence) {
+                    let _ = tx.send(Ok(packet));
+                }
+            }
+            _ => {
+                let mut event = [0u8; 32];
+                event.copy_from_slice(&packet[..32]);
+                // no one listening for events doesn't stop replies from being routed
+                let _ = events.send(Ok(event));
+            }
+        }
+    }
+}
+
+/// a 32 byte packet, plus the rest of it for replies
+async fn read_packet<R: AsyncRead + Unpin>(socket: &mut R) -> io::Result<Vec<u8>> {
+    let mut packet = vec![0u8; 32];
+    read_exact(socket, &mut packet).await?;
+    if packet[0] == 1 {
+        let reply_length = CARD32::from_le_bytes([packet[4], packet[5], packet[6], packet[7]]);
+        // grown as the bytes come in, the length is the server's word
+        let mut left = (reply_length as usize).saturating_mul(4);
+        while left > 0 {
+            let start = packet.len();
+            let chunk = left.min(READ_CHUNK);
+            packet.resize(start + chunk, 0);
+            read_exact(socket, &mut packet[start..]).await?;
+            left -= chunk;
+        }
+    }
+    Ok(packet)
+}
+
+async fn read_exact<R: AsyncRead + Unpin>(socket: &mut R, mut buf: &mut [u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+        match poll_fn(|cx| Pin::new(&mut *socket).poll_read(cx, buf)).await {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => buf = &mut buf[n..],
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+async fn write_all<W: AsyncWrite + Unpin>(socket: &mut W, mut buf: &[u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+        match poll_fn(|cx| Pin::new(&mut *socket).poll_write(cx, buf)).await {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => buf = &buf[n..],
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// a tokio socket half as a `futures_io` one
+#[cfg(feature = "async")]
+struct TokioIo<T>(T);
+
+#[cfg(feature = "async")]
+impl<T: tokio::io::AsyncRead + Unpin> AsyncRead for TokioIo<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut buf = tokio::io::ReadBuf::new(buf);
+        match Pin::new(&mut self.0).poll_read(cx, &mut buf) {
+            Poll::Ready(Ok(())) => Poll::Ready(Ok(buf.filled().len())),
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: tokio::io::AsyncWrite + Unpin> AsyncWrite for TokioIo<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+/// the module's end of a tokio socket pair, with the server end wrapped for the helpers above
+#[cfg(all(test, feature = "async"))]
+fn tokio_pair() -> (AsyncXContext, EventStream, TokioIo<tokio::net::UnixStream>) {
+    let (client, server) = tokio::net::UnixStream::pair().unwrap();
+    let (read, write) = client.into_split();
+    let (ctx, events) = AsyncXContext::from_io(
+        ConnectionAcceptedInfo::default(),
+        TokioIo(read),
+        TokioIo(write),
+        |task| {
+            tokio::spawn(task);
+        },
+    );
+    (ctx, events, TokioIo(server))
+}
+
+#[cfg(feature = "async")]
+#[test]
+pub fn async_reply_routing() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let (ctx, mut events, mut server) = tokio_pair();
+
+        let first = ctx.send_request(get_input_focus_request).await.unwrap();
+        let second = ctx.send_request(get_input_focus_request).await.unwrap();
+        let mut requests = [0u8; 8];
+        read_exact(&mut server, &mut requests).await.unwrap();
+
+        // an event, then the replies out of order, which can't happen on a real server
+        // but shows the routing goes by sequence number
+        let mut event = [0u8; 32];
+        event[0] = event_codes::FOCUS_IN;
+        write_all(&mut server, &event).await.unwrap();
+        for sequence in [2u16, 1] {
+            let mut reply = [0u8; 32];
+            reply[0] = 1;
+            reply[1] = sequence as u8;
+            reply[2..4].copy_from_slice(&sequence.to_le_bytes());
+            write_all(&mut server, &reply).await.unwrap();
+        }
+
+        assert_eq!(first.await.unwrap()[1], 1);
+        assert_eq!(second.await.unwrap()[1], 2);
+        assert_eq!(
+            events.next_event().await.unwrap().unwrap()[0],
+            event_codes::FOCUS_IN
+        );
+
+        drop(server);
+        assert!(matches!(
+            events.next_event().await,
+            Some(Err(XErrorKind::SocketIO(_)))
+        ));
+    });
+}
+
+#[cfg(feature = "async")]
+#[test]
+pub fn async_cancelled_send() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let (ctx, _events, mut server) = tokio_pair();
+        // the writer task doesn't get to run in between, so this fills its queue
+        for _ in 0..QUEUED_REQUESTS {
+            ctx.send(get_input_focus_request).await.unwrap();
+        }
+        // cancelled while it waits for room, the way a timeout or select! would
+        let mut waiting = Box::pin(ctx.send_request(get_input_focus_request));
+        let mut cx = Context::from_waker(std::task::Waker::noop());
+        assert!(waiting.as_mut().poll(&mut cx).is_pending());
+        drop(waiting);
+        assert!(ctx.pending.lock().unwrap().is_empty());
+
+        let last = ctx.send_request(get_input_focus_request).await.unwrap();
+        let mut requests = vec![0u8; (QUEUED_REQUESTS + 1) * 4];
+        read_exact(&mut server, &mut requests).await.unwrap();
+        let sequence = QUEUED_REQUESTS as u16 + 1;
+        let mut reply = [0u8; 32];
+        reply[0] = 1;
+        reply[2..4].copy_from_slice(&sequence.to_le_bytes());
+        write_all(&mut server, &reply).await.unwrap();
+        assert!(last.await.is_ok());
+    });
+}
+
+#[cfg(feature = "async")]
+#[test]
+pub fn async_extension_errors() {
+    struct Shm;
+    impl ExtensionDecoder for Shm {
+        fn error_name(&self, _info: &ExtensionInfo, code: CARD8) -> Option<&'static str> {
+            (code == 0).then_some("BadSeg")
+        }
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let (ctx, mut events, mut server) = tokio_pair();
+        ctx.register_decoder("MIT-SHM", Shm);
+        let answer = tokio::spawn(async move {
+            // QueryExtension with the name padded to 8 bytes
+            let mut request = [0u8; 16];
+            read_exact(&mut server, &mut request).await.unwrap();
+            let mut reply = [0u8; 32];
+            reply[0] = 1;
+            reply[2..4].copy_from_slice(&1u16.to_le_bytes());
+            reply[8..12].copy_from_slice(&[1, 130, 0, 128]);
+            write_all(&mut server, &reply).await.unwrap();
+            server
+        });
+        assert!(ctx.query_extension("MIT-SHM").await.unwrap().is_some());
+        let mut server = answer.await.unwrap();
+
+        let mut error = [0u8; 32];
+        error[1] = 128;
+        error[2..4].copy_from_slice(&2u16.to_le_bytes());
+        error[10] = 130;
+        write_all(&mut server, &error).await.unwrap();
+        match events.next_event().await {
+            Some(Err(XErrorKind::Extension {
+                extension, name, ..
+            })) => assert_eq!((extension.as_str(), name), ("MIT-SHM", Some("BadSeg"))),
+            other => panic!("{:?}", other.map(|event| event.map(|event| event[0]))),
+        }
+    });
+}
+
+#[cfg(feature = "async-std")]
+#[test]
+pub fn async_std_connect() {
+    use async_std::os::unix::net::UnixStream;
+
+    async_std::task::block_on(async {
+        let (client, mut server) = UnixStream::pair().unwrap();
+        let serve = async_std::task::spawn(async move {
+            let mut setup = [0u8; 12];
+            read_exact(&mut server, &mut setup).await.unwrap();
+            let setup = mock::MockSetup::default().encode();
+            write_all(&mut server, &setup).await.unwrap();
+            // QueryExtension for BIG-REQUESTS and GetInputFocus, both answered with zeros
+            for sequence in 1u16..=2 {
+                let mut header = [0u8; 4];
+                read_exact(&mut server, &mut header).await.unwrap();
+                let len = CARD16::from_le_bytes([header[2], header[3]]) as usize * 4;
+                read_exact(&mut server, &mut vec![0u8; len - 4])
+                    .await
+                    .unwrap();
+                let mut reply = [0u8; 32];
+                reply[0] = 1;
+                reply[2..4].copy_from_slice(&sequence.to_le_bytes());
+                write_all(&mut server, &reply).await.unwrap();
+            }
+            let mut event = [0u8; 32];
+            event[0] = event_codes::FOCUS_IN;
+            write_all(&mut server, &event).await.unwrap();
+        });
+        let (ctx, mut events) =
+            RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None)
+                .connect_async_std(client)
+                .await
+                .unwrap();
+        assert_eq!(ctx.info().list_of_screen[0].root, Atom(0x100));
+        ctx.sync().await.unwrap();
+        serve.await;
+        assert_eq!(
+            events.next_event().await.unwrap().unwrap()[0],
+            event_codes::FOCUS_IN
+        );
+    });
+}
@@ -0,0 +1,20 @@
+use super::*;
+
+use crate::proto::bigreq::EnableReply;
+pub use crate::proto::bigreq::{enable_request, opcodes, EXTENSION_NAME};
+
+impl<S: io::Read + io::Write> XContext<S> {
+    /// Switches the connection over to 32 bit request lengths.\
+    /// Returns the new maximum request length or `None` if the server doesn't
+    /// support BIG-REQUESTS. `RequestConnection::connect` already does this
+    pub fn enable_big_requests(&mut self) -> Result<Option<CARD32>, XErrorKind> {
+        let Some(info) = self.query_extension(EXTENSION_NAME)? else {
+            return Ok(None);
+        };
+        self.socket_cb(|socket| enable_request(socket, info.major_opcode))?;
+        let reply = self.read_reply()?;
+        let reply = EnableReply::parse(&reply)?;
+        self.max_request_length = reply.maximum_request_length;
+        Ok(Some(reply.maximum_request_length))
+    }
+}
@@ -0,0 +1,336 @@
+use super::*;
+pub use crate::proto::xproto::{
+    copy_colormap_and_free, free_colormap, free_colors, install_colormap,
+    list_installed_colormaps_request, query_colors_request, uninstall_colormap,
+};
+
+/// A color with 16 bits per channel, the way the protocol carries them
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Rgb {
+    pub red: CARD16,
+    pub green: CARD16,
+    pub blue: CARD16,
+}
+
+impl Rgb {
+    pub fn new(red: CARD16, green: CARD16, blue: CARD16) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// scales 8 bit channels up so 0xff becomes 0xffff
+    pub fn from_u8(red: u8, green: u8, blue: u8) -> Self {
+        Self::new(
+            red as CARD16 * 257,
+            green as CARD16 * 257,
+            blue as CARD16 * 257,
+        )
+    }
+
+    fn read(data: &[u8]) -> Self {
+        let channel = |i: usize| CARD16::from_le_bytes([data[i], data[i + 1]]);
+        Self::new(channel(0), channel(2), channel(4))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColormapAlloc {
+    /// entries start out unallocated
+    None = 0,
+    /// every entry is allocated writable, only for the dynamic visual classes
+    All = 1,
+}
+
+/// an entry for `store_colors`, only the channels flagged get written
+#[derive(Copy, Clone, Debug)]
+pub struct ColorItem {
+    pub pixel: CARD32,
+    pub color: Rgb,
+    pub do_red: bool,
+    pub do_green: bool,
+    pub do_blue: bool,
+}
+
+impl ColorItem {
+    /// writes all three channels of `pixel`
+    pub fn new(pixel: CARD32, color: Rgb) -> Self {
+        Self {
+            pixel,
+            color,
+            do_red: true,
+            do_green: true,
+            do_blue: true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct AllocColorReply {
+    pub pixel: CARD32,
+    /// the color the hardware actually gives, closest match to what was asked for
+    pub color: Rgb,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct NamedColor {
+    pub pixel: CARD32,
+    /// the color from the server's database
+    pub exact: Rgb,
+    /// what the screen can actually show
+    pub visual: Rgb,
+}
+
+#[derive(Clone, Debug)]
+pub struct ColorCells {
+    pub pixels: Vec<CARD32>,
+    pub masks: Vec<CARD32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ColorPlanes {
+    pub pixels: Vec<CARD32>,
+    pub red_mask: CARD32,
+    pub green_mask: CARD32,
+    pub blue_mask: CARD32,
+}
+
+pub fn create_colormap<S: io::Write>(
+    socket: &mut S,
+    alloc: ColormapAlloc,
+    mid: COLORMAP,
+    window: WINDOW,
+    visual: Atom,
+) -> io::Result<()> {
+    proto::xproto::create_colormap(socket, alloc as CARD8, mid, window, visual.0)
+}
+
+pub fn alloc_color_request<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    color: Rgb,
+) -> io::Result<()> {
+    proto::xproto::alloc_color_request(socket, cmap, color.red, color.green, color.blue)
+}
+
+pub fn alloc_named_color_request<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    name: &str,
+) -> io::Result<()> {
+    proto::xproto::alloc_named_color_request(socket, cmap, &text::latin1_name(name)?)
+}
+
+pub fn lookup_color_request<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    name: &str,
+) -> io::Result<()> {
+    proto::xproto::lookup_color_request(socket, cmap, &text::latin1_name(name)?)
+}
+
+pub fn alloc_color_cells_request<S: io::Write>(
+    socket: &mut S,
+    contiguous: bool,
+    cmap: COLORMAP,
+    colors: CARD16,
+    planes: CARD16,
+) -> io::Result<()> {
+    proto::xproto::alloc_color_cells_request(socket, contiguous as BOOL, cmap, colors, planes)
+}
+
+pub fn alloc_color_planes_request<S: io::Write>(
+    socket: &mut S,
+    contiguous: bool,
+    cmap: COLORMAP,
+    colors: CARD16,
+    planes: (CARD16, CARD16, CARD16),
+) -> io::Result<()> {
+    proto::xproto::alloc_color_planes_request(
+        socket,
+        contiguous as BOOL,
+        cmap,
+        colors,
+        planes.0,
+        planes.1,
+        planes.2,
+    )
+}
+
+pub fn store_colors<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    items: &[ColorItem],
+) -> io::Result<()> {
+    let items: Vec<_> = items
+        .iter()
+        .map(|item| proto::xproto::Coloritem {
+            pixel: item.pixel,
+            red: item.color.red,
+            green: item.color.green,
+            blue: item.color.blue,
+            flags: item.do_red as u8 | (item.do_green as u8) << 1 | (item.do_blue as u8) << 2,
+        })
+        .collect();
+    proto::xproto::store_colors(socket, cmap, &items)
+}
+
+/// reads `count` CARD32s starting at `offset` of a reply
+fn card32_list(reply: &[u8], offset: usize, count: usize) -> Result<Vec<CARD32>, XErrorKind> {
+    let mut data = reply.get(offset..).ok_or(XErrorKind::Unknown)?;
+    Ok(xio::read_primitive_list(&mut data, count)?)
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    /// makes a colormap for `visual` on the screen `window` is on, under a fresh id
+    pub fn create_colormap(
+        &mut self,
+        alloc: ColormapAlloc,
+        window: WINDOW,
+        visual: Atom,
+    ) -> io::Result<COLORMAP> {
+        let mid = self.gen_id();
+        self.socket_cb(|socket| create_colormap(socket, alloc, mid, window, visual))?;
+        Ok(mid)
+    }
+
+    /// like `copy_colormap_and_free` with a fresh id for the new colormap
+    pub fn copy_colormap_and_free(&mut self, src: COLORMAP) -> io::Result<COLORMAP> {
+        let mid = self.gen_id();
+        self.socket_cb(|socket| copy_colormap_and_free(socket, mid, src))?;
+        Ok(mid)
+    }
+
+    pub fn list_installed_colormaps(&self, window: WINDOW) -> Result<Vec<COLORMAP>, XErrorKind> {
+        self.socket_cb(|socket| list_installed_colormaps_request(socket, window))?;
+        let reply = self.read_reply()?;
+        card32_list(&reply, 32, card16_at(&reply, 8) as usize)
+    }
+
+    /// gets a read-only cell as close to `color` as the hardware allows
+    pub fn alloc_color(&self, cmap: COLORMAP, color: Rgb) -> Result<AllocColorReply, XErrorKind> {
+        self.socket_cb(|socket| alloc_color_request(socket, cmap, color))?;
+        let reply = self.read_reply()?;
+        Ok(AllocColorReply {
+            color: Rgb::read(&reply[8..14]),
+            pixel: card32_at(&reply, 16),
+        })
+    }
+
+    /// like `alloc_color` with a name from the server's color database, e.g. "slate grey"
+    pub fn alloc_named_color(&self, cmap: COLORMAP, name: &str) -> Result<NamedColor, XErrorKind> {
+        self.socket_cb(|socket| alloc_named_color_request(socket, cmap, name))?;
+        let reply = self.read_reply()?;
+        Ok(NamedColor {
+            pixel: card32_at(&reply, 8),
+            exact: Rgb::read(&reply[12..18]),
+            visual: Rgb::read(&reply[18..24]),
+        })
+    }
+
+    /// resolves a color name without allocating anything, returns (exact, visual)
+    pub fn lookup_color(&self, cmap: COLORMAP, name: &str) -> Result<(Rgb, Rgb), XErrorKind> {
+        self.socket_cb(|socket| lookup_color_request(socket, cmap, name))?;
+        let reply = self.read_reply()?;
+        Ok((Rgb::read(&reply[8..14]), Rgb::read(&reply[14..20])))
+    }
+
+    /// allocates `colors` writable cells and `planes` plane masks
+    pub fn alloc_color_cells(
+        &self,
+        contiguous: bool,
+        cmap: COLORMAP,
+        colors: CARD16,
+        planes: CARD16,
+    ) -> Result<ColorCells, XErrorKind> {
+        self.socket_cb(|socket| {
+            alloc_color_cells_request(socket, contiguous, cmap, colors, planes)
+        })?;
+        let reply = self.read_reply()?;
+        let (pixel_count, mask_count) = (
+            card16_at(&reply, 8) as usize,
+            card16_at(&reply, 10) as usize,
+        );
+        Ok(ColorCells {
+            pixels: card32_list(&reply, 32, pixel_count)?,
+            masks: card32_list(&reply, 32 + pixel_count * 4, mask_count)?,
+        })
+    }
+
+    /// `planes` is how many red, green and blue planes to allocate
+    pub fn alloc_color_planes(
+        &self,
+        contiguous: bool,
+        cmap: COLORMAP,
+        colors: CARD16,
+        planes: (CARD16, CARD16, CARD16),
+    ) -> Result<ColorPlanes, XErrorKind> {
+        self.socket_cb(|socket| {
+            alloc_color_planes_request(socket, contiguous, cmap, colors, planes)
+        })?;
+        let reply = self.read_reply()?;
+        Ok(ColorPlanes {
+            red_mask: card32_at(&reply, 12),
+            green_mask: card32_at(&reply, 16),
+            blue_mask: card32_at(&reply, 20),
+            pixels: card32_list(&reply, 32, card16_at(&reply, 8) as usize)?,
+        })
+    }
+
+    /// the colors stored at `pixels`, in the same order
+    pub fn query_colors(&self, cmap: COLORMAP, pixels: &[CARD32]) -> Result<Vec<Rgb>, XErrorKind> {
+        self.socket_cb(|socket| query_colors_request(socket, cmap, pixels))?;
+        let reply = self.read_reply()?;
+        let count = card16_at(&reply, 8) as usize;
+        let colors = reply.get(32..32 + count * 8).ok_or(XErrorKind::Unknown)?;
+        Ok(colors.chunks_exact(8).map(Rgb::read).collect())
+    }
+}
+
+impl XVisualType {
+    /// The pixel for an 8 bit per channel color, worked out from the visual's masks.\
+    /// Only TrueColor visuals have a fixed mapping, `None` for every other class
+    pub fn rgb_to_pixel(&self, red: u8, green: u8, blue: u8) -> Option<CARD32> {
+        if self.class != XClass::TrueColor {
+            return None;
+        }
+        let channel = |value: u8, mask: CARD32| {
+            if mask == 0 {
+                return 0;
+            }
+            let max = (1u64 << mask.count_ones()) - 1;
+            let scaled = (value as u64 * max + 127) / 255;
+            (scaled as CARD32) << mask.trailing_zeros() & mask
+        };
+        Some(
+            channel(red, self.red_mask)
+                | channel(green, self.green_mask)
+                | channel(blue, self.blue_mask),
+        )
+    }
+}
+
+#[test]
+pub fn true_color_pixels() {
+    let rgb888 = XVisualType {
+        class: XClass::TrueColor,
+        red_mask: 0xff0000,
+        green_mask: 0xff00,
+        blue_mask: 0xff,
+        ..Default::default()
+    };
+    assert_eq!(rgb888.rgb_to_pixel(0x12, 0x34, 0x56), Some(0x123456));
+    let rgb565 = XVisualType {
+        class: XClass::TrueColor,
+        red_mask: 0xf800,
+        green_mask: 0x07e0,
+        blue_mask: 0x001f,
+        ..Default::default()
+    };
+    assert_eq!(rgb565.rgb_to_pixel(255, 255, 255), Some(0xffff));
+    assert_eq!(rgb565.rgb_to_pixel(255, 0, 0), Some(0xf800));
+    assert_eq!(rgb565.rgb_to_pixel(0, 128, 0), Some(32 << 5));
+    let pseudo = XVisualType {
+        class: XClass::PseudoColor,
+        ..Default::default()
+    };
+    assert_eq!(pseudo.rgb_to_pixel(1, 2, 3), None);
+}
@@ -7,9 +7,9 @@ pub enum ByteOrder {
     BigEndian = b'b' as isize,
 }
 
-impl Into<u8> for ByteOrder {
-    fn into(self) -> u8 {
-        self as u8
+impl From<ByteOrder> for u8 {
+    fn from(order: ByteOrder) -> u8 {
+        order as u8
     }
 }
 
@@ -20,17 +20,21 @@ pub enum ConnectionStatus {
     AuthenticationNeeded = 2,
 }
 impl ConnectionStatus {
-    pub fn from_code(status_code: u8) -> Self {
+    /// `InvalidData` for anything the protocol doesn't have
+    pub fn from_code(status_code: u8) -> std::io::Result<Self> {
         match status_code {
-            0 => ConnectionStatus::Failed,
-            1 => ConnectionStatus::Accepted,
-            2 => ConnectionStatus::AuthenticationNeeded,
-            _ => panic!("invalid status code, possible IO bug"),
+            0 => Ok(ConnectionStatus::Failed),
+            1 => Ok(ConnectionStatus::Accepted),
+            2 => Ok(ConnectionStatus::AuthenticationNeeded),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unknown connection status {}", status_code),
+            )),
         }
     }
 }
 
-pub type ConnectionResult<T> = Result<T, ConnectionErr>;
+pub type ConnectionResult<T> = Result<T, XErrorKind>;
 
 #[derive(Clone, Default)]
 pub struct ConnectionAcceptedInfo {
@@ -79,7 +83,7 @@ impl ConnectionAcceptedInfo {
         res.min_keycode = xio::read_primitive(&mut socket)?;
         res.max_keycode = xio::read_primitive(&mut socket)?;
         res.unused_2 = xio::read_primitive(&mut socket)?;
-        res.vendor = xio::read_ascii_string(&mut socket, res.vendor_len as usize)?;
+        res.vendor = xio::read_latin1_string(&mut socket, res.vendor_len as usize)?;
         let _padding = xio::read_padding(&mut socket, res.vendor_len as usize)?;
         res.formats = xio::read_primitive_list(
             &mut socket,
@@ -92,16 +96,9 @@ impl ConnectionAcceptedInfo {
         //         - 2 * res.number_for_formats_in_pixmap_formats as usize)
         //     - res.vendor_len as usize;
 
-        let screen = XScreen::from_socket(
-            &mut socket,
-            res.number_for_formats_in_pixmap_formats as usize,
-        )?;
+        let screen = XScreen::from_socket(&mut socket)?;
         res.list_of_screen = vec![screen];
 
-        //i've read everything I need so I  to read everything to the end to make sure the server KNOWS i've read everything.
-        //I guess this is kind of like a flush()
-        xio::flush_read(&mut socket)?;
-
         Ok(res)
     }
 }
@@ -157,18 +154,6 @@ impl Debug for ConnectionAcceptedInfo {
     }
 }
 
-#[derive(Debug)]
-pub enum ConnectionErr {
-    ConnectionRefused(RefusedInfo),
-    SocketErr(std::io::Error),
-}
-
-impl From<std::io::Error> for ConnectionErr {
-    fn from(io_err: std::io::Error) -> Self {
-        Self::SocketErr(io_err)
-    }
-}
-
 #[derive(Debug, Default)]
 pub struct RefusedInfo {
     pub reason: String,
@@ -221,6 +206,25 @@ impl<'a> AuthProtocol<'a> {
     }
 }
 
+impl<S> XContext<S> {
+    /// a context for a socket that's past the connection handshake
+    pub(crate) fn new(socket: S, info: ConnectionAcceptedInfo) -> Self {
+        XContext {
+            id_count: Rc::new(Cell::new(0)),
+            socket: Rc::new(RefCell::new(socket)),
+            max_request_length: info.max_req_len as CARD32,
+            info: Rc::new(info),
+            events: Rc::new(RefCell::new(VecDeque::new())),
+            input: Rc::new(RefCell::new(vec![])),
+            extensions: Rc::new(RefCell::new(ExtensionRegistry::default())),
+            keymap: Rc::new(RefCell::new(None)),
+            requests: Rc::new(RefCell::new(xio::RequestCounter::default())),
+            deferred: Rc::new(RefCell::new(vec![])),
+            error_handler: Rc::new(RefCell::new(None)),
+        }
+    }
+}
+
 pub struct RequestConnection<'a> {
     order: ByteOrder,
     major: CARD16,
@@ -232,8 +236,8 @@ impl<'a> RequestConnection<'a> {
     pub fn new(order: ByteOrder, major: u16, minor: u16, protocol_kind: AuthProtocol<'a>) -> Self {
         Self {
             order,
-            major: major.into(),
-            minor: minor.into(),
+            major,
+            minor,
             protocol_name: protocol_kind.name(),
             protocol_data: protocol_kind.data(),
         }
@@ -241,17 +245,28 @@ impl<'a> RequestConnection<'a> {
     pub fn connect<T: Write + Read>(self, mut sock: T) -> ConnectionResult<XContext<T>> {
         self.send_request_to_connect(&mut sock)?;
         let resp = self.read_response(&mut sock)?;
-        Ok(XContext {
-            id_count: Cell::new(0),
-            socket: Rc::new(RefCell::new(sock)),
-            info: Rc::new(resp),
-        })
+        let mut ctx = XContext::new(sock, resp);
+        // without BIG-REQUESTS the server is still usable, just limited to 65535 unit requests,
+        // so only a broken socket is worth failing over
+        if let Err(XErrorKind::SocketIO(e)) = ctx.enable_big_requests() {
+            return Err(e.into());
+        }
+        Ok(ctx)
     }
-    fn read_response<T: Read>(
+    /// Reads exactly as much as the server says it sent, on a blocking socket
+    /// reading any further would wait forever
+    pub(crate) fn read_response<T: Read>(
         &self,
-        mut socket: T,
-    ) -> Result<ConnectionAcceptedInfo, ConnectionErr> {
-        let connection_status = ConnectionStatus::from_code(xio::read_primitive(&mut socket)?);
+        mut stream: T,
+    ) -> Result<ConnectionAcceptedInfo, XErrorKind> {
+        let mut header = [0u8; 8];
+        stream.read_exact(&mut header[..1])?;
+        let connection_status = ConnectionStatus::from_code(header[0])?;
+        stream.read_exact(&mut header[1..])?;
+        let additional = CARD16::from_le_bytes([header[6], header[7]]) as usize;
+        let mut response = header.to_vec();
+        xio::read_growing(&mut stream, &mut response, additional * 4)?;
+        let mut socket = &response[1..];
 
         match connection_status {
             ConnectionStatus::Accepted => {
@@ -262,26 +277,37 @@ impl<'a> RequestConnection<'a> {
                 let major = xio::read_primitive::<CARD16, _>(&mut socket)?;
                 let minor = xio::read_primitive::<CARD16, _>(&mut socket)?;
                 let _addition_data_len_4b = xio::read_primitive::<CARD16, _>(&mut socket)?;
-                let reason = xio::read_ascii_string(&mut socket, length_of_reason as usize)?;
+                let reason = xio::read_latin1_string(&mut socket, length_of_reason as usize)?;
                 xio::read_padding(socket, length_of_reason as usize)?;
-                Err(ConnectionErr::ConnectionRefused(RefusedInfo {
+                Err(XErrorKind::ConnectionRefused(RefusedInfo {
                     reason,
                     major,
                     minor,
                 }))
             }
             ConnectionStatus::AuthenticationNeeded => {
-                unimplemented!("auth not implemented")
+                // we don't do any authentication past the first round, so it's a refusal
+                let _unused: [u8; 5] = xio::read_primitive(&mut socket)?;
+                let length_4b = xio::read_primitive::<CARD16, _>(&mut socket)?;
+                let reason = xio::read_latin1_string(&mut socket, length_4b as usize * 4)?;
+                Err(XErrorKind::ConnectionRefused(RefusedInfo {
+                    reason: reason.trim_end_matches('\0').to_string(),
+                    major: 0,
+                    minor: 0,
+                }))
             }
         }
     }
 
-    fn send_request_to_connect<T: Write>(&self, mut out: T) -> Result<(), std::io::Error> {
-        out.write([self.order as u8, 0].as_slice())?;
+    pub(crate) fn send_request_to_connect<T: Write>(
+        &self,
+        mut out: T,
+    ) -> Result<(), std::io::Error> {
+        out.write_all([self.order as u8, 0].as_slice())?;
         xio::write_primitive(&mut out, self.major)?;
         xio::write_primitive(&mut out, self.minor)?;
-        xio::write_primitive(&mut out, self.protocol_name.len())?;
-        xio::write_primitive(&mut out, self.protocol_data.len())?;
+        xio::write_primitive(&mut out, self.protocol_name.len() as CARD16)?;
+        xio::write_primitive(&mut out, self.protocol_data.len() as CARD16)?;
         xio::write_primitive(&mut out, 0u16)?; //unused
         xio::write_primitive(&mut out, self.protocol_name)?;
         xio::write_padding(self.protocol_name.len(), &mut out)?;
@@ -290,3 +316,54 @@ impl<'a> RequestConnection<'a> {
         Ok(())
     }
 }
+
+#[test]
+pub fn malformed_setup() {
+    let request = RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None);
+    let refused = |response: &[u8]| match request.read_response(response) {
+        Err(XErrorKind::ConnectionRefused(info)) => Some(info.reason),
+        _ => None,
+    };
+
+    let mut auth = vec![2, 0, 0, 0, 0, 0, 1, 0];
+    auth.extend_from_slice(b"no\0\0");
+    assert_eq!(refused(&auth).as_deref(), Some("no"));
+    assert!(matches!(
+        request.read_response(&[7u8, 0, 0, 0][..]),
+        Err(XErrorKind::SocketIO(e)) if e.kind() == std::io::ErrorKind::InvalidData
+    ));
+    // a reason longer than what was sent
+    assert!(matches!(
+        request.read_response(&[0u8, 200, 11, 0, 0, 0, 50, 0, b'x'][..]),
+        Err(XErrorKind::SocketIO(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof
+    ));
+}
+
+#[test]
+pub fn connect_on_blocking_socket() {
+    use std::os::unix::net::UnixStream;
+
+    let (client, mut server) = UnixStream::pair().unwrap();
+    let server = std::thread::spawn(move || {
+        let mut request = [0u8; 12];
+        server.read_exact(&mut request).unwrap();
+        server
+            .write_all(&mock::MockSetup::default().encode())
+            .unwrap();
+        // QueryExtension for BIG-REQUESTS, which isn't there
+        let mut query = [0u8; 20];
+        server.read_exact(&mut query).unwrap();
+        let mut reply = [0u8; 32];
+        reply[0] = 1;
+        reply[2] = 1;
+        server.write_all(&reply).unwrap();
+        server
+    });
+    // a plain blocking socket, reading past the setup reply would never return
+    let ctx = RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None)
+        .connect(client)
+        .unwrap();
+    assert_eq!(ctx.info().list_of_screen[0].root, Atom(0x100));
+    assert_eq!(ctx.max_request_length(), 0xffff);
+    drop(server.join().unwrap());
+}
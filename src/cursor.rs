@@ -0,0 +1,641 @@
+use super::*;
+pub use crate::proto::xproto::free_cursor;
+use render::RenderExtension;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+/// used to be its own type, everything is an `XErrorKind` now
+#[deprecated(note = "use XErrorKind")]
+pub type CursorErr = XErrorKind;
+
+/// glyphs of the standard "cursor" font, each one's mask is the glyph right after it
+pub mod shapes {
+    pub const X_CURSOR: u16 = 0;
+    pub const ARROW: u16 = 2;
+    pub const BASED_ARROW_DOWN: u16 = 4;
+    pub const BASED_ARROW_UP: u16 = 6;
+    pub const BOAT: u16 = 8;
+    pub const BOGOSITY: u16 = 10;
+    pub const BOTTOM_LEFT_CORNER: u16 = 12;
+    pub const BOTTOM_RIGHT_CORNER: u16 = 14;
+    pub const BOTTOM_SIDE: u16 = 16;
+    pub const BOTTOM_TEE: u16 = 18;
+    pub const BOX_SPIRAL: u16 = 20;
+    pub const CENTER_PTR: u16 = 22;
+    pub const CIRCLE: u16 = 24;
+    pub const CLOCK: u16 = 26;
+    pub const COFFEE_MUG: u16 = 28;
+    pub const CROSS: u16 = 30;
+    pub const CROSS_REVERSE: u16 = 32;
+    pub const CROSSHAIR: u16 = 34;
+    pub const DIAMOND_CROSS: u16 = 36;
+    pub const DOT: u16 = 38;
+    pub const DOTBOX: u16 = 40;
+    pub const DOUBLE_ARROW: u16 = 42;
+    pub const DRAFT_LARGE: u16 = 44;
+    pub const DRAFT_SMALL: u16 = 46;
+    pub const DRAPED_BOX: u16 = 48;
+    pub const EXCHANGE: u16 = 50;
+    pub const FLEUR: u16 = 52;
+    pub const GOBBLER: u16 = 54;
+    pub const GUMBY: u16 = 56;
+    pub const HAND1: u16 = 58;
+    pub const HAND2: u16 = 60;
+    pub const HEART: u16 = 62;
+    pub const ICON: u16 = 64;
+    pub const IRON_CROSS: u16 = 66;
+    pub const LEFT_PTR: u16 = 68;
+    pub const LEFT_SIDE: u16 = 70;
+    pub const LEFT_TEE: u16 = 72;
+    pub const LEFTBUTTON: u16 = 74;
+    pub const LL_ANGLE: u16 = 76;
+    pub const LR_ANGLE: u16 = 78;
+    pub const MAN: u16 = 80;
+    pub const MIDDLEBUTTON: u16 = 82;
+    pub const MOUSE: u16 = 84;
+    pub const PENCIL: u16 = 86;
+    pub const PIRATE: u16 = 88;
+    pub const PLUS: u16 = 90;
+    pub const QUESTION_ARROW: u16 = 92;
+    pub const RIGHT_PTR: u16 = 94;
+    pub const RIGHT_SIDE: u16 = 96;
+    pub const RIGHT_TEE: u16 = 98;
+    pub const RIGHTBUTTON: u16 = 100;
+    pub const RTL_LOGO: u16 = 102;
+    pub const SAILBOAT: u16 = 104;
+    pub const SB_DOWN_ARROW: u16 = 106;
+    pub const SB_H_DOUBLE_ARROW: u16 = 108;
+    pub const SB_LEFT_ARROW: u16 = 110;
+    pub const SB_RIGHT_ARROW: u16 = 112;
+    pub const SB_UP_ARROW: u16 = 114;
+    pub const SB_V_DOUBLE_ARROW: u16 = 116;
+    pub const SHUTTLE: u16 = 118;
+    pub const SIZING: u16 = 120;
+    pub const SPIDER: u16 = 122;
+    pub const SPRAYCAN: u16 = 124;
+    pub const STAR: u16 = 126;
+    pub const TARGET: u16 = 128;
+    pub const TCROSS: u16 = 130;
+    pub const TOP_LEFT_ARROW: u16 = 132;
+    pub const TOP_LEFT_CORNER: u16 = 134;
+    pub const TOP_RIGHT_CORNER: u16 = 136;
+    pub const TOP_SIDE: u16 = 138;
+    pub const TOP_TEE: u16 = 140;
+    pub const TREK: u16 = 142;
+    pub const UL_ANGLE: u16 = 144;
+    pub const UMBRELLA: u16 = 146;
+    pub const UR_ANGLE: u16 = 148;
+    pub const WATCH: u16 = 150;
+    pub const XTERM: u16 = 152;
+
+    /// every shape's name, index `i` is glyph `2 * i`
+    pub const NAMES: [&str; 77] = [
+        "X_cursor",
+        "arrow",
+        "based_arrow_down",
+        "based_arrow_up",
+        "boat",
+        "bogosity",
+        "bottom_left_corner",
+        "bottom_right_corner",
+        "bottom_side",
+        "bottom_tee",
+        "box_spiral",
+        "center_ptr",
+        "circle",
+        "clock",
+        "coffee_mug",
+        "cross",
+        "cross_reverse",
+        "crosshair",
+        "diamond_cross",
+        "dot",
+        "dotbox",
+        "double_arrow",
+        "draft_large",
+        "draft_small",
+        "draped_box",
+        "exchange",
+        "fleur",
+        "gobbler",
+        "gumby",
+        "hand1",
+        "hand2",
+        "heart",
+        "icon",
+        "iron_cross",
+        "left_ptr",
+        "left_side",
+        "left_tee",
+        "leftbutton",
+        "ll_angle",
+        "lr_angle",
+        "man",
+        "middlebutton",
+        "mouse",
+        "pencil",
+        "pirate",
+        "plus",
+        "question_arrow",
+        "right_ptr",
+        "right_side",
+        "right_tee",
+        "rightbutton",
+        "rtl_logo",
+        "sailboat",
+        "sb_down_arrow",
+        "sb_h_double_arrow",
+        "sb_left_arrow",
+        "sb_right_arrow",
+        "sb_up_arrow",
+        "sb_v_double_arrow",
+        "shuttle",
+        "sizing",
+        "spider",
+        "spraycan",
+        "star",
+        "target",
+        "tcross",
+        "top_left_arrow",
+        "top_left_corner",
+        "top_right_corner",
+        "top_side",
+        "top_tee",
+        "trek",
+        "ul_angle",
+        "umbrella",
+        "ur_angle",
+        "watch",
+        "xterm",
+    ];
+}
+
+/// the glyph for a shape name like "left_ptr", see `shapes`
+pub fn shape_by_name(name: &str) -> Option<CARD16> {
+    shapes::NAMES
+        .iter()
+        .position(|&n| n == name)
+        .map(|index| index as CARD16 * 2)
+}
+
+/// a cursor made from a bitmap `source`, `mask` picks which pixels are drawn at all
+pub fn create_cursor<S: io::Write>(
+    socket: &mut S,
+    cid: CURSOR,
+    source: PIXMAP,
+    mask: Option<PIXMAP>,
+    fore: Rgb,
+    back: Rgb,
+    hotspot: (CARD16, CARD16),
+) -> io::Result<()> {
+    proto::xproto::create_cursor(
+        socket,
+        cid,
+        source,
+        mask.unwrap_or(0),
+        fore.red,
+        fore.green,
+        fore.blue,
+        back.red,
+        back.green,
+        back.blue,
+        hotspot.0,
+        hotspot.1,
+    )
+}
+
+/// a cursor made from font glyphs, the hotspot is the glyph's origin
+#[allow(clippy::too_many_arguments)]
+pub fn create_glyph_cursor<S: io::Write>(
+    socket: &mut S,
+    cid: CURSOR,
+    source_font: FONT,
+    mask_font: Option<FONT>,
+    source_char: CARD16,
+    mask_char: CARD16,
+    fore: Rgb,
+    back: Rgb,
+) -> io::Result<()> {
+    proto::xproto::create_glyph_cursor(
+        socket,
+        cid,
+        source_font,
+        mask_font.unwrap_or(0),
+        source_char,
+        mask_char,
+        fore.red,
+        fore.green,
+        fore.blue,
+        back.red,
+        back.green,
+        back.blue,
+    )
+}
+
+pub fn recolor_cursor<S: io::Write>(
+    socket: &mut S,
+    cursor: CURSOR,
+    fore: Rgb,
+    back: Rgb,
+) -> io::Result<()> {
+    proto::xproto::recolor_cursor(
+        socket, cursor, fore.red, fore.green, fore.blue, back.red, back.green, back.blue,
+    )
+}
+
+/// shows `cursor` while the pointer is in `window`, `None` goes back to the parent's
+pub fn define_cursor<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    cursor: Option<CURSOR>,
+) -> io::Result<()> {
+    let values = [(value_masks::CURSOR, cursor.unwrap_or(0))];
+    proto::xproto::change_window_attributes(socket, window, &values)
+}
+
+/// One frame of an Xcursor file
+#[derive(Clone, Debug, Default)]
+pub struct XcursorImage {
+    /// the size the image was drawn for, which may differ from `width`
+    pub nominal_size: CARD32,
+    pub width: CARD32,
+    pub height: CARD32,
+    pub xhot: CARD32,
+    pub yhot: CARD32,
+    /// how long the frame shows in milliseconds, for animated cursors
+    pub delay: CARD32,
+    /// premultiplied ARGB, row by row
+    pub pixels: Vec<CARD32>,
+}
+
+const XCURSOR_MAGIC: &[u8; 4] = b"Xcur";
+const XCURSOR_IMAGE_TYPE: CARD32 = 0xfffd0002;
+/// images past this are surely a broken file
+const XCURSOR_MAX_SIZE: CARD32 = 0x7fff;
+
+/// Parses an Xcursor file and returns the frames of the size closest to `size`.\
+/// Everything is bounds checked, a bad file is an `Err` and never a panic
+pub fn parse_xcursor(data: &[u8], size: CARD32) -> Result<Vec<XcursorImage>, XErrorKind> {
+    let card32 = |offset: usize| -> Result<CARD32, XErrorKind> {
+        data.get(offset..offset + 4)
+            .map(|b| CARD32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or(XErrorKind::BadCursorFile("truncated"))
+    };
+    if data.get(0..4) != Some(&XCURSOR_MAGIC[..]) {
+        return Err(XErrorKind::BadCursorFile("not an Xcursor file"));
+    }
+    let header_size = card32(4)? as usize;
+    let ntoc = card32(12)? as usize;
+    // (type, nominal size, position) of every chunk
+    let toc = (0..ntoc)
+        .map(|index| {
+            let entry = header_size
+                .checked_add(index * 12)
+                .ok_or(XErrorKind::BadCursorFile("bad toc"))?;
+            Ok((
+                card32(entry)?,
+                card32(entry + 4)?,
+                card32(entry + 8)? as usize,
+            ))
+        })
+        .collect::<Result<Vec<_>, XErrorKind>>()?;
+    let best = toc
+        .iter()
+        .filter(|&&(chunk_type, _, _)| chunk_type == XCURSOR_IMAGE_TYPE)
+        .map(|&(_, nominal, _)| nominal)
+        .min_by_key(|&nominal| nominal.abs_diff(size))
+        .ok_or(XErrorKind::BadCursorFile("no images"))?;
+
+    toc.iter()
+        .filter(|&&(chunk_type, nominal, _)| chunk_type == XCURSOR_IMAGE_TYPE && nominal == best)
+        .map(|&(_, _, position)| {
+            // chunk header: size, type, subtype, version, then the image fields
+            let field = |index: usize| card32(position + 16 + index * 4);
+            let (width, height) = (field(0)?, field(1)?);
+            if width > XCURSOR_MAX_SIZE || height > XCURSOR_MAX_SIZE {
+                return Err(XErrorKind::BadCursorFile("image too large"));
+            }
+            let start = position + 36;
+            let len = width as usize * height as usize * 4;
+            let pixels = data
+                .get(start..start + len)
+                .ok_or(XErrorKind::BadCursorFile("truncated image"))?
+                .chunks_exact(4)
+                .map(|b| CARD32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            Ok(XcursorImage {
+                nominal_size: best,
+                width,
+                height,
+                xhot: field(2)?.min(width.saturating_sub(1)),
+                yhot: field(3)?.min(height.saturating_sub(1)),
+                delay: field(4)?,
+                pixels,
+            })
+        })
+        .collect()
+}
+
+/// The theme and size from XCURSOR_THEME and XCURSOR_SIZE, "default" and 24 if unset
+pub fn theme_from_env() -> (String, CARD32) {
+    let theme = std::env::var("XCURSOR_THEME")
+        .ok()
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "default".to_string());
+    let size = std::env::var("XCURSOR_SIZE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&s| s > 0)
+        .unwrap_or(24);
+    (theme, size)
+}
+
+/// XCURSOR_PATH if set, the same default directories as libXcursor otherwise
+pub fn cursor_search_path() -> Vec<PathBuf> {
+    let path = std::env::var("XCURSOR_PATH").unwrap_or_else(|_| {
+        "~/.local/share/icons:~/.icons:/usr/share/icons:/usr/share/pixmaps:/usr/X11R6/lib/X11/icons"
+            .to_string()
+    });
+    let home = std::env::var("HOME").ok();
+    path.split(':')
+        .filter(|dir| !dir.is_empty())
+        .filter_map(|dir| match dir.strip_prefix("~/") {
+            Some(rest) => home.as_ref().map(|home| Path::new(home).join(rest)),
+            None => Some(PathBuf::from(dir)),
+        })
+        .collect()
+}
+
+/// Finds the file for cursor `name` in `theme`, walking the themes it inherits from
+pub fn find_theme_cursor(theme: &str, name: &str) -> Option<PathBuf> {
+    let path = cursor_search_path();
+    let mut visited = HashSet::new();
+    find_in_theme(&path, theme, name, &mut visited)
+}
+
+fn find_in_theme(
+    path: &[PathBuf],
+    theme: &str,
+    name: &str,
+    visited: &mut HashSet<String>,
+) -> Option<PathBuf> {
+    if !visited.insert(theme.to_string()) {
+        return None;
+    }
+    let found = path
+        .iter()
+        .map(|dir| dir.join(theme).join("cursors").join(name))
+        .find(|file| file.is_file());
+    if found.is_some() {
+        return found;
+    }
+    path.iter()
+        .filter_map(|dir| std::fs::read_to_string(dir.join(theme).join("index.theme")).ok())
+        .flat_map(|index| inherited_themes(&index))
+        .find_map(|parent| find_in_theme(path, &parent, name, visited))
+}
+
+/// the themes listed on the Inherits line of an index.theme
+fn inherited_themes(index: &str) -> Vec<String> {
+    index
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "Inherits").then_some(value)
+        })
+        .flat_map(|value| value.split([',', ';']))
+        .map(|theme| theme.trim().to_string())
+        .filter(|theme| !theme.is_empty())
+        .collect()
+}
+
+/// the bytes of a PutImage before its data
+const PUT_IMAGE_HEADER: usize = 24;
+
+impl<S: io::Read + io::Write> XContext<S> {
+    /// A cursor for one of the standard shapes, black on white.\
+    /// `shape` is a glyph from `shapes` or `shape_by_name`
+    pub fn create_glyph_cursor(&mut self, shape: CARD16) -> Result<CURSOR, XErrorKind> {
+        let font = self.open_font("cursor")?;
+        let cid = self.gen_id();
+        self.socket_cb(|socket| {
+            create_glyph_cursor(
+                socket,
+                cid,
+                font,
+                Some(font),
+                shape,
+                shape + 1,
+                Rgb::new(0, 0, 0),
+                Rgb::new(0xffff, 0xffff, 0xffff),
+            )?;
+            // the cursor keeps its own reference to the font
+            close_font(socket, font)
+        })?;
+        Ok(cid)
+    }
+
+    /// Loads cursor `name` (e.g. "left_ptr") from the Xcursor theme picked by
+    /// XCURSOR_THEME and XCURSOR_SIZE and uploads it as an ARGB cursor for the screen of `root`
+    pub fn load_theme_cursor(
+        &mut self,
+        render: &RenderExtension,
+        root: WINDOW,
+        name: &str,
+    ) -> Result<CURSOR, XErrorKind> {
+        let (theme, size) = theme_from_env();
+        let file = find_theme_cursor(&theme, name)
+            .ok_or_else(|| XErrorKind::CursorNotFound(name.to_string()))?;
+        let images = parse_xcursor(&std::fs::read(file)?, size)?;
+        self.create_argb_cursor(render, root, &images)
+    }
+
+    /// Uploads `images` through RENDER for the screen of `root`, more than one frame makes an
+    /// animated cursor. That needs RENDER 0.8, a still one 0.5.\
+    /// Images too big for one request go up in strips of rows
+    pub fn create_argb_cursor(
+        &mut self,
+        render: &RenderExtension,
+        root: WINDOW,
+        images: &[XcursorImage],
+    ) -> Result<CURSOR, XErrorKind> {
+        let animated = images.len() > 1;
+        if !render.has_version(0, if animated { 8 } else { 5 }) {
+            return Err(XErrorKind::ExtensionTooOld {
+                extension: render::EXTENSION_NAME,
+                major_version: render.major_version,
+                minor_version: render.minor_version,
+            });
+        }
+        let format = render.argb32_format().ok_or(XErrorKind::NoArgbFormat)?;
+        let room = (self.max_request_length() as usize * 4).saturating_sub(PUT_IMAGE_HEADER);
+        let mut frames = vec![];
+        for image in images {
+            let row = image.width as usize * 4;
+            let rows = room / row.max(1);
+            if rows == 0 {
+                let e = io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "a row of the cursor image doesn't fit in a request",
+                );
+                return Err(e.into());
+            }
+            let ids = [self.gen_id(), self.gen_id(), self.gen_id(), self.gen_id()];
+            let [pixmap, gc, picture, cursor] = ids;
+            let size = (image.width as CARD16, image.height as CARD16);
+            let data: Vec<u8> = image.pixels.iter().flat_map(|p| p.to_le_bytes()).collect();
+            self.socket_cb(|socket| {
+                create_pixmap(socket, 32, pixmap, root.0, size)?;
+                create_gc(socket, gc, pixmap)?;
+                for (index, strip) in data.chunks(rows * row.max(1)).enumerate() {
+                    let strip_size = (size.0, (strip.len() / row) as CARD16);
+                    let pos = (0, (index * rows) as INT16);
+                    put_image(
+                        socket,
+                        ImageFormat::ZPixmap,
+                        pixmap,
+                        gc,
+                        strip_size,
+                        pos,
+                        0,
+                        32,
+                        strip,
+                    )?;
+                }
+                render::create_picture(socket, render, picture, pixmap, format)?;
+                let hotspot = (image.xhot as CARD16, image.yhot as CARD16);
+                render::create_cursor(socket, render, cursor, picture, hotspot)?;
+                render::free_picture(socket, render, picture)?;
+                free_gc(socket, gc)?;
+                free_pixmap(socket, pixmap)
+            })?;
+            frames.push((cursor, image.delay));
+        }
+        let cursor = match frames.as_slice() {
+            [] => return Err(XErrorKind::BadCursorFile("no images")),
+            [(single, _)] => *single,
+            _ => {
+                let cid = self.gen_id();
+                self.socket_cb(|socket| {
+                    render::create_anim_cursor(socket, render, cid, &frames)?;
+                    // the animation holds on to its frames
+                    frames
+                        .iter()
+                        .try_for_each(|&(frame, _)| free_cursor(socket, frame))
+                })?;
+                cid
+            }
+        };
+        self.sync()?;
+        Ok(cursor)
+    }
+}
+
+#[test]
+pub fn xcursor_parsing() {
+    // two 2x1 frames at size 24 and one 1x1 image at size 48
+    let mut file = vec![];
+    let mut put = |v: u32| file.extend_from_slice(&v.to_le_bytes());
+    let chunk_len = 36 + 8;
+    for v in [u32::from_le_bytes(*b"Xcur"), 16, 0x10000, 3] {
+        put(v);
+    }
+    let first = 16 + 3 * 12;
+    for (index, nominal) in [(0, 24), (1, 48), (2, 24)] {
+        put(XCURSOR_IMAGE_TYPE);
+        put(nominal);
+        put(first + index * chunk_len);
+    }
+    for (nominal, delay, pixel) in [(24, 50, 0xff00_00ffu32), (48, 0, 1), (24, 70, 0xffff_ffff)] {
+        let (width, height) = if nominal == 48 { (1, 1) } else { (2, 1) };
+        for v in [
+            36,
+            XCURSOR_IMAGE_TYPE,
+            nominal,
+            1,
+            width,
+            height,
+            5,
+            0,
+            delay,
+        ] {
+            put(v);
+        }
+        put(pixel);
+        put(pixel);
+    }
+
+    let frames = parse_xcursor(&file, 20).unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!((frames[0].width, frames[0].height), (2, 1));
+    assert_eq!(frames[0].xhot, 1, "hotspot is clamped into the image");
+    assert_eq!(frames[1].delay, 70);
+    assert_eq!(frames[1].pixels, vec![0xffff_ffff; 2]);
+    assert_eq!(parse_xcursor(&file, 40).unwrap().len(), 1);
+    assert!(parse_xcursor(&file[..60], 24).is_err());
+    assert!(parse_xcursor(b"nope", 24).is_err());
+    assert_eq!(shape_by_name("left_ptr"), Some(shapes::LEFT_PTR));
+    assert_eq!(
+        inherited_themes("[Icon Theme]\nInherits = Adwaita, hicolor\n"),
+        vec!["Adwaita", "hicolor"]
+    );
+}
+
+#[test]
+pub fn argb_cursor_strips() {
+    let setup = mock::MockSetup {
+        // 256 bytes, room for 7 rows of an 8 pixel wide image
+        max_request_length: 64,
+        ..Default::default()
+    };
+    let (server, stream) = mock::MockServer::new(setup);
+    let mut ctx = RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None)
+        .connect(stream)
+        .unwrap();
+    let argb = render::PictFormInfo {
+        id: 0x30,
+        format_type: 1,
+        depth: 32,
+        alpha_shift: 24,
+        alpha_mask: 0xff,
+        red_shift: 16,
+        red_mask: 0xff,
+        green_shift: 8,
+        green_mask: 0xff,
+        blue_mask: 0xff,
+        ..Default::default()
+    };
+    let render = RenderExtension {
+        info: ExtensionInfo {
+            major_opcode: 140,
+            first_event: 0,
+            first_error: 0,
+        },
+        major_version: 0,
+        minor_version: 11,
+        formats: vec![argb],
+    };
+    let image = XcursorImage {
+        nominal_size: 8,
+        width: 8,
+        height: 8,
+        xhot: 0,
+        yhot: 0,
+        delay: 0,
+        pixels: vec![0xff00_0000; 64],
+    };
+    let root = ctx.info.list_of_screen[0].root;
+    server.take_requests();
+    ctx.create_argb_cursor(&render, root, &[image]).unwrap();
+    let requests = server.take_requests();
+    assert_eq!(Atom(requests[0].card32(8)), root);
+    let strips: Vec<_> = requests
+        .iter()
+        .filter(|r| r.opcode == opcodes::PUT_IMAGE)
+        .map(|r| (r.card16(14), r.card16(18)))
+        .collect();
+    // (height, y) of each
+    assert_eq!(strips, [(7, 0), (1, 7)]);
+}
@@ -1,12 +1,36 @@
 use super::*;
 
-#[derive(Debug, Clone, Copy)]
+/// Which request an X error is about
+#[derive(Debug, Clone)]
 pub struct XErrorGeneric {
     pub sequence_number: CARD16,
     pub major: CARD8,
     pub minor: CARD16,
+    /// the extension the request belongs to, when `major` is an extension's opcode the
+    /// connection has queried
+    pub extension: Option<String>,
 }
 
+impl XErrorGeneric {
+    /// `GetProperty` for core requests, `RANDR:5` for extension ones
+    pub fn request_name(&self) -> String {
+        match (request_name(self.major), &self.extension) {
+            (Some(name), _) => name.to_string(),
+            (None, Some(extension)) => format!("{}:{}", extension, self.minor),
+            (None, None) => format!("{}:{}", self.major, self.minor),
+        }
+    }
+}
+
+/// the name of core request `opcode`, `None` for extension opcodes
+pub fn request_name(opcode: CARD8) -> Option<&'static str> {
+    proto::xproto::REQUEST_NAMES
+        .get(opcode as usize)
+        .copied()
+        .filter(|name| !name.is_empty())
+}
+
+/// Everything that can go wrong talking to the server, from X errors to the socket going away
 #[derive(Debug)]
 pub enum XErrorKind {
     Request {
@@ -70,61 +94,161 @@ pub enum XErrorKind {
     Implmentation {
         generic: XErrorGeneric,
     },
+    /// an error that belongs to an extension
+    Extension {
+        generic: XErrorGeneric,
+        extension: String,
+        /// the error code relative to the extension's first error
+        code: CARD8,
+        /// what the extension's decoder calls it, only codes a decoder claims end up here
+        name: Option<&'static str>,
+        bad_value: CARD32,
+    },
+    /// an error code that's neither a core one nor claimed by an extension we know of
+    Unrecognized {
+        generic: XErrorGeneric,
+        code: CARD8,
+        bad_value: CARD32,
+    },
     SocketIO(io::Error),
+    /// the server turned the connection down during setup
+    ConnectionRefused(RefusedInfo),
+    /// GrabPointer or GrabKeyboard got a reply but not the grab
+    NotGranted(GrabStatus),
+    /// the keysym isn't on any key of the current keyboard mapping
+    KeysymNotMapped(Keysym),
+    /// the server doesn't have the extension by this name
+    ExtensionMissing(&'static str),
+    /// the server's version of the extension is older than what was asked of it
+    ExtensionTooOld {
+        extension: &'static str,
+        major_version: CARD32,
+        minor_version: CARD32,
+    },
+    /// MIT-SHM CreateSegment got a reply without a file descriptor
+    MissingFd,
+    /// another client took the selection before we could
+    NotSelectionOwner,
+    /// the selection owner didn't answer a conversion, or the next INCR piece, in time
+    SelectionTimeout,
+    /// RandR SetCrtcConfig didn't go through, one of `randr::set_config`
+    ConfigFailed(CARD8),
+    /// no cursor theme on the search path has a cursor by that name
+    CursorNotFound(String),
+    /// the Xcursor file is truncated or isn't one
+    BadCursorFile(&'static str),
+    /// RENDER has no 32 bit ARGB picture format for ARGB cursors
+    NoArgbFormat,
     Unknown,
 }
 
+/// used to be its own type, everything is an `XErrorKind` now
+#[deprecated(note = "use XErrorKind")]
+pub type GrabErr = XErrorKind;
+
+/// used to be its own type, everything is an `XErrorKind` now
+#[deprecated(note = "use XErrorKind")]
+pub type ConnectionErr = XErrorKind;
+
 impl XErrorKind {
-    pub fn generic(&self) -> Option<XErrorGeneric> {
+    /// which request failed, `None` for errors that didn't come from the server
+    pub fn generic(&self) -> Option<&XErrorGeneric> {
         match self {
-            &Self::Request { generic, .. } => Some(generic),
-            &Self::Value { generic, .. } => Some(generic),
-            &Self::Window { generic, .. } => Some(generic),
-            &Self::Pixmap { generic, .. } => Some(generic),
-            &Self::Atom { generic, .. } => Some(generic),
-            &Self::Cursor { generic, .. } => Some(generic),
-            &Self::Font { generic, .. } => Some(generic),
-            &Self::Match { generic, .. } => Some(generic),
-            &Self::Drawable { generic, .. } => Some(generic),
-            &Self::Access { generic, .. } => Some(generic),
-            &Self::Alloc { generic, .. } => Some(generic),
-            &Self::ColorMap { generic, .. } => Some(generic),
-            &Self::GContext { generic, .. } => Some(generic),
-            &Self::IDChoice { generic, .. } => Some(generic),
-            &Self::Name { generic, .. } => Some(generic),
-            &Self::Length { generic, .. } => Some(generic),
-            &Self::Implmentation { generic, .. } => Some(generic),
+            Self::Request { generic, .. } => Some(generic),
+            Self::Value { generic, .. } => Some(generic),
+            Self::Window { generic, .. } => Some(generic),
+            Self::Pixmap { generic, .. } => Some(generic),
+            Self::Atom { generic, .. } => Some(generic),
+            Self::Cursor { generic, .. } => Some(generic),
+            Self::Font { generic, .. } => Some(generic),
+            Self::Match { generic, .. } => Some(generic),
+            Self::Drawable { generic, .. } => Some(generic),
+            Self::Access { generic, .. } => Some(generic),
+            Self::Alloc { generic, .. } => Some(generic),
+            Self::ColorMap { generic, .. } => Some(generic),
+            Self::GContext { generic, .. } => Some(generic),
+            Self::IDChoice { generic, .. } => Some(generic),
+            Self::Name { generic, .. } => Some(generic),
+            Self::Length { generic, .. } => Some(generic),
+            Self::Implmentation { generic, .. } => Some(generic),
+            Self::Extension { generic, .. } => Some(generic),
+            Self::Unrecognized { generic, .. } => Some(generic),
             _ => None,
         }
     }
-    pub fn from_header(header: XErrorHeader) -> Result<(), Self> {
+
+    /// the resource id or value the server complained about, for the errors that carry one
+    pub fn bad_value(&self) -> Option<CARD32> {
+        match *self {
+            Self::Value { bad_val, .. } => Some(bad_val),
+            Self::Window { bad_id, .. }
+            | Self::Pixmap { bad_id, .. }
+            | Self::Atom { bad_id, .. }
+            | Self::Cursor { bad_id, .. }
+            | Self::Font { bad_id, .. }
+            | Self::Drawable { bad_id, .. }
+            | Self::ColorMap { bad_id, .. }
+            | Self::GContext { bad_id, .. }
+            | Self::IDChoice { bad_id, .. } => Some(bad_id),
+            Self::Extension { bad_value, .. } | Self::Unrecognized { bad_value, .. } => {
+                Some(bad_value)
+            }
+            _ => None,
+        }
+    }
+
+    /// the name X uses for the error, `BadWindow` and the like
+    fn x_name(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Request { .. } => "BadRequest",
+            Self::Value { .. } => "BadValue",
+            Self::Window { .. } => "BadWindow",
+            Self::Pixmap { .. } => "BadPixmap",
+            Self::Atom { .. } => "BadAtom",
+            Self::Cursor { .. } => "BadCursor",
+            Self::Font { .. } => "BadFont",
+            Self::Match { .. } => "BadMatch",
+            Self::Drawable { .. } => "BadDrawable",
+            Self::Access { .. } => "BadAccess",
+            Self::Alloc { .. } => "BadAlloc",
+            Self::ColorMap { .. } => "BadColormap",
+            Self::GContext { .. } => "BadGC",
+            Self::IDChoice { .. } => "BadIDChoice",
+            Self::Name { .. } => "BadName",
+            Self::Length { .. } => "BadLength",
+            Self::Implmentation { .. } => "BadImplementation",
+            _ => return None,
+        })
+    }
+
+    /// The error an error packet describes, `None` if the packet is a reply or an event
+    pub fn from_header(header: XErrorHeader) -> Option<Self> {
         if header.error != 0 {
-            // header.error should always be zero
-            // therefore incoming data is not an error
-            return Ok(());
+            return None;
         }
-        
+
         let generic = XErrorGeneric {
             sequence_number: header.sequence_number,
             major: header.major,
             minor: header.minor,
+            extension: None,
         };
 
         let bad_id = header.bad_id_or_value;
-        
+
         let err_kind = match header.code {
-            01 => XErrorKind::Request { generic },
-            02 => XErrorKind::Value {
+            1 => XErrorKind::Request { generic },
+            2 => XErrorKind::Value {
                 generic,
                 bad_val: bad_id,
             },
-            03 => XErrorKind::Window { generic, bad_id },
-            04 => XErrorKind::Pixmap { generic, bad_id },
-            05 => XErrorKind::Atom { generic, bad_id },
-            06 => XErrorKind::Cursor { generic, bad_id },
-            07 => XErrorKind::Font { generic, bad_id },
-            08 => XErrorKind::Match { generic },
-            09 => XErrorKind::Drawable { generic, bad_id },
+            3 => XErrorKind::Window { generic, bad_id },
+            4 => XErrorKind::Pixmap { generic, bad_id },
+            5 => XErrorKind::Atom { generic, bad_id },
+            6 => XErrorKind::Cursor { generic, bad_id },
+            7 => XErrorKind::Font { generic, bad_id },
+            8 => XErrorKind::Match { generic },
+            9 => XErrorKind::Drawable { generic, bad_id },
             10 => XErrorKind::Access { generic },
             11 => XErrorKind::Alloc { generic },
             12 => XErrorKind::ColorMap { generic, bad_id },
@@ -133,9 +257,147 @@ impl XErrorKind {
             15 => XErrorKind::Name { generic },
             16 => XErrorKind::Length { generic },
             17 => XErrorKind::Implmentation { generic },
-            _ => Self::Unknown,
+            code => Self::Unrecognized {
+                generic,
+                code,
+                bad_value: bad_id,
+            },
+        };
+        Some(err_kind)
+    }
+
+    /// Same as `from_header` but codes past the core ones are looked up in `extensions`,
+    /// and so is the extension the failed request belongs to.\
+    /// A code no registered decoder has a name for stays `Unrecognized`
+    pub fn from_header_with_extensions(
+        header: XErrorHeader,
+        extensions: &ExtensionRegistry,
+    ) -> Option<Self> {
+        let owner = extensions.extension_for_error(header.code);
+        let mut error = match owner {
+            Some((extension, info, name))
+                if header.error == 0 && header.code >= FIRST_EXTENSION_ERROR =>
+            {
+                let code = header.code - info.first_error;
+                Self::Extension {
+                    generic: XErrorGeneric {
+                        sequence_number: header.sequence_number,
+                        major: header.major,
+                        minor: header.minor,
+                        extension: None,
+                    },
+                    extension: extension.to_string(),
+                    code,
+                    name: Some(name),
+                    bad_value: header.bad_id_or_value,
+                }
+            }
+            _ => Self::from_header(header)?,
         };
-        Err(err_kind)
+        if let Some(generic) = error.generic_mut() {
+            generic.extension = extensions
+                .extension_for_opcode(generic.major)
+                .map(|(name, _)| name.to_string());
+        }
+        Some(error)
+    }
+
+    fn generic_mut(&mut self) -> Option<&mut XErrorGeneric> {
+        match self {
+            Self::Request { generic, .. }
+            | Self::Value { generic, .. }
+            | Self::Window { generic, .. }
+            | Self::Pixmap { generic, .. }
+            | Self::Atom { generic, .. }
+            | Self::Cursor { generic, .. }
+            | Self::Font { generic, .. }
+            | Self::Match { generic, .. }
+            | Self::Drawable { generic, .. }
+            | Self::Access { generic, .. }
+            | Self::Alloc { generic, .. }
+            | Self::ColorMap { generic, .. }
+            | Self::GContext { generic, .. }
+            | Self::IDChoice { generic, .. }
+            | Self::Name { generic, .. }
+            | Self::Length { generic, .. }
+            | Self::Implmentation { generic, .. }
+            | Self::Extension { generic, .. }
+            | Self::Unrecognized { generic, .. } => Some(generic),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for XErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SocketIO(e) => return write!(f, "connection to the X server failed: {}", e),
+            Self::ConnectionRefused(info) => {
+                return write!(f, "the X server refused the connection: {}", info.reason)
+            }
+            Self::NotGranted(status) => return write!(f, "grab not granted: {:?}", status),
+            Self::KeysymNotMapped(keysym) => {
+                return write!(f, "keysym {:#x} isn't on any key", keysym.0)
+            }
+            Self::ExtensionMissing(name) => {
+                return write!(f, "the X server doesn't have the {} extension", name)
+            }
+            Self::ExtensionTooOld {
+                extension,
+                major_version,
+                minor_version,
+            } => {
+                return write!(
+                    f,
+                    "the X server's {} {}.{} is too old for this",
+                    extension, major_version, minor_version
+                )
+            }
+            Self::MissingFd => return write!(f, "the X server didn't pass a file descriptor"),
+            Self::NotSelectionOwner => return write!(f, "another client owns the selection"),
+            Self::SelectionTimeout => return write!(f, "the selection owner didn't answer"),
+            Self::ConfigFailed(status) => {
+                return write!(f, "SetCrtcConfig failed with status {}", status)
+            }
+            Self::CursorNotFound(name) => return write!(f, "no cursor theme has {:?}", name),
+            Self::BadCursorFile(reason) => return write!(f, "bad Xcursor file: {}", reason),
+            Self::NoArgbFormat => return write!(f, "RENDER has no ARGB32 picture format"),
+            Self::Unknown => return write!(f, "unrecognized error from the X server"),
+            Self::Unrecognized { code, .. } => write!(f, "X error {}", code)?,
+            Self::Extension {
+                extension,
+                code,
+                name,
+                ..
+            } => match name {
+                Some(name) => write!(f, "{} error {}", extension, name)?,
+                None => write!(f, "{} error {}", extension, code)?,
+            },
+            _ => write!(f, "{}", self.x_name().unwrap_or("X error"))?,
+        }
+        if let Some(generic) = self.generic() {
+            write!(
+                f,
+                " in {} (major {}, minor {}), sequence {}",
+                generic.request_name(),
+                generic.major,
+                generic.minor,
+                generic.sequence_number
+            )?;
+        }
+        if let Some(value) = self.bad_value() {
+            write!(f, ", bad value {:#x}", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for XErrorKind {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::SocketIO(e) => Some(e),
+            _ => None,
+        }
     }
 }
 impl From<io::Error> for XErrorKind {
@@ -144,8 +406,7 @@ impl From<io::Error> for XErrorKind {
     }
 }
 
-#[repr(C, packed(1))]
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
 pub struct XErrorHeader {
     error: CARD8,
     code: CARD8,
@@ -156,14 +417,97 @@ pub struct XErrorHeader {
     padding: [u8; 21],
 }
 
+/// Reads one packet and returns it if it's an error.\
+/// Meant for sockets with a read timeout, where running out of time reads as `Ok` too.
+/// Events and replies it reads are lost, `XContext::sync` or `wait_for_event_timeout` don't
+/// have either problem
+#[deprecated(note = "use XContext::sync, it doesn't lose events and replies")]
 pub fn check_for_error<S: io::Read>(sock: &mut S) -> Result<(), XErrorKind> {
     match xio::read_primitive::<XErrorHeader, _>(sock) {
-        Ok(h) => XErrorKind::from_header(h),
+        Ok(h) => XErrorKind::from_header(h).map_or(Ok(()), Err),
         Err(e) => match e.kind() {
             //ignore timeouts
-            io::ErrorKind::WouldBlock => return Ok(()),
+            io::ErrorKind::WouldBlock => Ok(()),
             //forward other error
             _ => Err(XErrorKind::SocketIO(e)),
         },
     }
 }
+
+#[test]
+pub fn error_context() {
+    let (server, ctx) = mock::MockServer::connect();
+    server.on_request(opcodes::MAP_WINDOW, |request| {
+        Some(mock::MockResponse::error(3, request.card32(4)))
+    });
+    ctx.socket_cb(|socket| map_window(socket, 0x1234)).unwrap();
+    let error = ctx.sync().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "BadWindow in MapWindow (major 8, minor 0), sequence 2, bad value 0x1234"
+    );
+
+    // an earlier request's error doesn't take the place of a reply
+    ctx.socket_cb(|socket| map_window(socket, 0x1234)).unwrap();
+    let atom = ctx.intern_atom("_FIRST", false).unwrap();
+    assert_eq!(Some(atom), server.atom("_FIRST"));
+    assert_eq!(ctx.get_atom_name(atom).unwrap(), "_FIRST");
+    assert!(matches!(
+        ctx.next_event(),
+        Err(XErrorKind::Window { bad_id: 0x1234, .. })
+    ));
+
+    // with a handler the error doesn't stop next_event
+    let errors = Rc::new(RefCell::new(vec![]));
+    let seen = errors.clone();
+    ctx.set_error_handler(move |e| seen.borrow_mut().push(e));
+    ctx.socket_cb(|socket| map_window(socket, 0x1234)).unwrap();
+    let mut event = [0u8; 32];
+    event[0] = event_codes::EXPOSE;
+    server.send_event(event);
+    assert_eq!(ctx.next_event().unwrap()[0], event_codes::EXPOSE);
+    assert_eq!(errors.borrow()[0].bad_value(), Some(0x1234));
+    assert_eq!(
+        errors.borrow()[0].generic().unwrap().request_name(),
+        "MapWindow"
+    );
+
+    // a code nobody claims still says which request failed
+    let header = XErrorHeader {
+        code: 200,
+        sequence_number: 7,
+        bad_id_or_value: 0x42,
+        major: 18,
+        ..Default::default()
+    };
+    let error = XErrorKind::from_header(header).unwrap();
+    assert_eq!(error.generic().unwrap().request_name(), "ChangeProperty");
+    assert_eq!(request_name(opcodes::NO_OPERATION), Some("NoOperation"));
+    assert_eq!(request_name(120), None);
+    assert_eq!(
+        error.to_string(),
+        "X error 200 in ChangeProperty (major 18, minor 0), sequence 7, bad value 0x42"
+    );
+    // nor is one an extension without a decoder may or may not own
+    let mut extensions = ExtensionRegistry::default();
+    let shm = ExtensionInfo {
+        major_opcode: 130,
+        first_event: 65,
+        first_error: 128,
+    };
+    extensions.insert("MIT-SHM", Some(shm));
+    let header = XErrorHeader {
+        code: 129,
+        ..header
+    };
+    assert!(matches!(
+        XErrorKind::from_header_with_extensions(header, &extensions),
+        Some(XErrorKind::Unrecognized { code: 129, .. })
+    ));
+    let missing = XErrorKind::ExtensionMissing("RANDR");
+    assert_eq!(
+        missing.to_string(),
+        "the X server doesn't have the RANDR extension"
+    );
+    assert!(std::error::Error::source(&missing).is_none());
+}
@@ -0,0 +1,181 @@
+use super::*;
+use std::{
+    os::unix::io::{AsRawFd, RawFd},
+    time::{Duration, Instant},
+};
+
+/// the socket's fd, for registering the connection with epoll, mio and the like
+impl<S: AsRawFd> AsRawFd for XContext<S> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.borrow().as_raw_fd()
+    }
+}
+
+/// Puts an fd into non-blocking mode and gives it back its old flags when dropped, even if a
+/// read in between fails or panics. The file description may be shared with other handles
+struct NonBlocking {
+    fd: RawFd,
+    flags: libc::c_int,
+}
+
+impl NonBlocking {
+    fn set(fd: RawFd) -> io::Result<Self> {
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+        if flags < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if flags & libc::O_NONBLOCK == 0
+            && unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { fd, flags })
+    }
+}
+
+impl Drop for NonBlocking {
+    fn drop(&mut self) {
+        if self.flags & libc::O_NONBLOCK == 0 {
+            unsafe { libc::fcntl(self.fd, libc::F_SETFL, self.flags) };
+        }
+    }
+}
+
+/// Reads whatever `socket` has without blocking, appending it to `input`.\
+/// The fd is switched to non-blocking for the duration so the reads can go through `S`
+/// and wrappers like `Traced` see them
+fn read_available<S: io::Read + AsRawFd>(socket: &mut S, input: &mut Vec<u8>) -> io::Result<()> {
+    let _guard = NonBlocking::set(socket.as_raw_fd())?;
+    let mut chunk = [0u8; 4096];
+    loop {
+        match socket.read(&mut chunk) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the X server closed the connection",
+                ))
+            }
+            Ok(n) => input.extend_from_slice(&chunk[..n]),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+impl<S: io::Read + io::Write + AsRawFd> XContext<S> {
+    /// Returns a queued event or one that's already on the socket, never blocks.\
+    /// Bytes of a packet that hasn't fully arrived are kept for the next call, so this is safe
+    /// to call whenever the fd polls readable. Errors come out as `Err` like `next_event`
+    pub fn poll_for_event(&self) -> Result<Option<RawEvent>, XErrorKind> {
+        if let Some(event) = self.take_queued(|_| true)? {
+            return Ok(Some(event));
+        }
+        self.queue_available()?;
+        self.take_queued(|_| true)
+    }
+
+    /// Like `next_event` but gives up after `timeout`, returning `None`
+    pub fn wait_for_event_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<RawEvent>, XErrorKind> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(event) = self.poll_for_event()? {
+                return Ok(Some(event));
+            }
+            if !self.wait_readable(deadline)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Blocks until another event `pred` accepts is queued, returning false if none showed up
+    /// by `deadline`. Unlike `wait_for_event` the events stay queued
+    pub(crate) fn wait_until_queued<F>(
+        &self,
+        mut pred: F,
+        deadline: Instant,
+    ) -> Result<bool, XErrorKind>
+    where
+        F: FnMut(&RawEvent) -> bool,
+    {
+        let mut count = || {
+            self.events
+                .borrow()
+                .iter()
+                .filter(|p| p[0] != 0 && pred(p))
+                .count()
+        };
+        let before = count();
+        loop {
+            self.queue_available()?;
+            if count() > before {
+                return Ok(true);
+            }
+            if !self.wait_readable(deadline)? {
+                return Ok(false);
+            }
+        }
+    }
+
+    /// Reads what the socket has without blocking and files every complete packet away.\
+    /// Bytes of a packet that hasn't fully arrived are kept for the next call
+    fn queue_available(&self) -> Result<(), XErrorKind> {
+        read_available(&mut *self.lock_socket()?, &mut self.input.borrow_mut())?;
+        while xio::packet_len(&self.input.borrow()).is_some() {
+            // the packet is all buffered so this never reaches the socket
+            let packet = self.read_packet(&mut io::empty())?;
+            // a reply with nobody waiting on it is dropped
+            self.dispatch_packet(&mut io::empty(), packet)?;
+        }
+        Ok(())
+    }
+
+    /// Waits for the socket to have something to read, false once `deadline` has passed
+    fn wait_readable(&self, deadline: Instant) -> Result<bool, XErrorKind> {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Ok(false);
+        }
+        let mut fds = libc::pollfd {
+            fd: self.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // rounded up so a sub millisecond wait doesn't turn into a busy loop
+        let millis = left
+            .as_micros()
+            .div_ceil(1000)
+            .min(libc::c_int::MAX as u128);
+        let ready = unsafe { libc::poll(&mut fds, 1, millis as libc::c_int) };
+        if ready < 0 {
+            let e = io::Error::last_os_error();
+            if e.kind() != io::ErrorKind::Interrupted {
+                return Err(e.into());
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[test]
+pub fn partial_packets_are_kept() {
+    use std::os::unix::net::UnixStream;
+
+    let (client, mut server) = UnixStream::pair().unwrap();
+    let ctx = XContext::new(client, ConnectionAcceptedInfo::default());
+    assert!(ctx.poll_for_event().unwrap().is_none());
+
+    let mut event = [0u8; 32];
+    event[0] = event_codes::EXPOSE;
+    event[4] = 0x42;
+    io::Write::write_all(&mut server, &event[..20]).unwrap();
+    assert!(ctx.poll_for_event().unwrap().is_none());
+    io::Write::write_all(&mut server, &event[20..]).unwrap();
+    assert_eq!(ctx.poll_for_event().unwrap(), Some(event));
+
+    let timeout = ctx.wait_for_event_timeout(Duration::from_millis(20));
+    assert!(timeout.unwrap().is_none());
+}
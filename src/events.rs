@@ -1,44 +1,323 @@
-use super::*; 
+use super::*;
 
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct EventHeaderTest {
+    code: CARD8,
+    detail: CARD8,
+    sequence_number: CARD16,
+    padding: [u8; 28],
+}
+
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct EventHeader {
+    code: CARD8,
+    detail: CARD8,
+    sequence_number: CARD16,
+}
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+struct GenericEvent {
+    header: EventHeader,
+    time: CARD32,
+    root: Atom,
+    event: Atom,
+    child: Atom,
+    root_x: CARD16,
+    root_y: CARD16,
+    event_x: CARD16,
+    event_y: CARD16,
+    state: CARD16,
+    same_screen: BOOL,
+    unuused: CARD8,
+}
+
+/// KeyPress and KeyRelease
+#[derive(Copy, Clone, Debug)]
+pub struct KeyEvent {
+    pub pressed: bool,
+    pub keycode: KEYCODE,
+    pub sequence_number: CARD16,
+    pub time: Timestamp,
+    pub root: WINDOW,
+    pub event: WINDOW,
+    pub child: WINDOW,
+    pub root_x: INT16,
+    pub root_y: INT16,
+    pub event_x: INT16,
+    pub event_y: INT16,
+    /// modifier and button state right before the event, see `key_masks`
+    pub state: CARD16,
+    pub same_screen: bool,
+}
+
+impl KeyEvent {
+    /// returns `None` if `event` isn't a KeyPress or KeyRelease
+    pub fn from_event(event: &RawEvent) -> Option<Self> {
+        let code = Event::code_of(event);
+        if code != event_codes::KEY_PRESS && code != event_codes::KEY_RELEASE {
+            return None;
+        }
+        let e = xio::read_primitive::<GenericEvent, _>(&mut &event[..]).ok()?;
+        Some(Self {
+            pressed: code == event_codes::KEY_PRESS,
+            keycode: e.header.detail,
+            sequence_number: e.header.sequence_number,
+            time: e.time.into(),
+            root: e.root,
+            event: e.event,
+            child: e.child,
+            root_x: e.root_x as INT16,
+            root_y: e.root_y as INT16,
+            event_x: e.event_x as INT16,
+            event_y: e.event_y as INT16,
+            state: e.state,
+            same_screen: e.same_screen != 0,
+        })
+    }
+}
+
+/// Sent to a selection owner when another client asks for its contents
+#[derive(Copy, Clone, Debug)]
+pub struct SelectionRequestEvent {
+    pub time: Timestamp,
+    pub owner: WINDOW,
+    pub requestor: WINDOW,
+    pub selection: Atom,
+    pub target: Atom,
+    /// `Atom(0)` from obsolete clients, which expect the reply in `target`
+    pub property: Atom,
+}
+
+impl SelectionRequestEvent {
+    pub fn from_event(event: &RawEvent) -> Option<Self> {
+        (Event::code_of(event) == event_codes::SELECTION_REQUEST).then(|| Self {
+            time: card32_at(event, 4).into(),
+            owner: Atom(card32_at(event, 8)),
+            requestor: Atom(card32_at(event, 12)),
+            selection: Atom(card32_at(event, 16)),
+            target: Atom(card32_at(event, 20)),
+            property: Atom(card32_at(event, 24)),
+        })
+    }
+}
+
+/// The answer to a ConvertSelection, `property` is `Atom(0)` if the conversion was refused
+#[derive(Copy, Clone, Debug)]
+pub struct SelectionNotifyEvent {
+    pub time: Timestamp,
+    pub requestor: WINDOW,
+    pub selection: Atom,
+    pub target: Atom,
+    pub property: Atom,
+}
+
+impl SelectionNotifyEvent {
+    pub fn from_event(event: &RawEvent) -> Option<Self> {
+        (Event::code_of(event) == event_codes::SELECTION_NOTIFY).then(|| Self {
+            time: card32_at(event, 4).into(),
+            requestor: Atom(card32_at(event, 8)),
+            selection: Atom(card32_at(event, 12)),
+            target: Atom(card32_at(event, 16)),
+            property: Atom(card32_at(event, 20)),
+        })
+    }
+
+    /// the wire form, for handing to `send_event`
+    pub fn to_raw(&self) -> RawEvent {
+        let mut raw = [0u8; 32];
+        raw[0] = event_codes::SELECTION_NOTIFY;
+        let fields = [
+            self.time.data(),
+            self.requestor.0,
+            self.selection.0,
+            self.target.0,
+            self.property.0,
+        ];
+        for (index, field) in fields.iter().enumerate() {
+            raw[4 + index * 4..8 + index * 4].copy_from_slice(&field.to_le_bytes());
+        }
+        raw
+    }
+}
+
+/// Tells the old owner it lost the selection
+#[derive(Copy, Clone, Debug)]
+pub struct SelectionClearEvent {
+    pub time: Timestamp,
+    pub owner: WINDOW,
+    pub selection: Atom,
+}
+
+impl SelectionClearEvent {
+    pub fn from_event(event: &RawEvent) -> Option<Self> {
+        (Event::code_of(event) == event_codes::SELECTION_CLEAR).then(|| Self {
+            time: card32_at(event, 4).into(),
+            owner: Atom(card32_at(event, 8)),
+            selection: Atom(card32_at(event, 12)),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct PropertyNotifyEvent {
+    pub window: WINDOW,
+    pub atom: Atom,
+    pub time: Timestamp,
+    /// false for NewValue, true when the property was deleted
+    pub deleted: bool,
+}
 
-#[repr(C,packed(1))]
-#[derive(Copy,Clone,Default,Debug)]
-pub struct EventHeaderTest{
-    code:CARD8,
-    detail:CARD8,
-    sequence_number:CARD16,
-    padding:[u8;28]
+impl PropertyNotifyEvent {
+    pub fn from_event(event: &RawEvent) -> Option<Self> {
+        (Event::code_of(event) == event_codes::PROPERTY_NOTIFY).then(|| Self {
+            window: Atom(card32_at(event, 4)),
+            atom: Atom(card32_at(event, 8)),
+            time: card32_at(event, 12).into(),
+            deleted: event[16] == 1,
+        })
+    }
+
+    /// the wire form, mostly for tests standing in for the server
+    pub fn to_raw(&self) -> RawEvent {
+        let mut raw = [0u8; 32];
+        raw[0] = event_codes::PROPERTY_NOTIFY;
+        raw[4..8].copy_from_slice(&self.window.0.to_le_bytes());
+        raw[8..12].copy_from_slice(&self.atom.0.to_le_bytes());
+        raw[12..16].copy_from_slice(&self.time.data().to_le_bytes());
+        raw[16] = self.deleted as u8;
+        raw
+    }
 }
 
+/// how the focus moved relative to the window getting the event
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FocusDetail {
+    Ancestor,
+    Virtual,
+    Inferior,
+    Nonlinear,
+    NonlinearVirtual,
+    Pointer,
+    PointerRoot,
+    None,
+}
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FocusMode {
+    Normal,
+    /// caused by a keyboard grab starting
+    Grab,
+    /// caused by a keyboard grab ending
+    Ungrab,
+    WhileGrabbed,
+}
 
-#[repr(C,packed(1))]
-#[derive(Copy,Clone,Default,Debug)]
-pub struct EventHeader{
-    code:CARD8,
-    detail:CARD8,
-    sequence_number:CARD16,
+/// FocusIn and FocusOut
+#[derive(Copy, Clone, Debug)]
+pub struct FocusEvent {
+    pub focus_in: bool,
+    pub detail: FocusDetail,
+    pub event: WINDOW,
+    pub mode: FocusMode,
 }
 
-#[repr(C,packed(1))]
-#[derive(Copy,Clone,Default)]
-struct GenericEvent{
-    header:EventHeader,
-    time:CARD32,
-    root:Atom, 
-    event:Atom,
-    child:Atom, 
-    root_x:CARD16,
-    root_y:CARD16, 
-    event_x:CARD16, 
-    event_y:CARD16, 
-    state:CARD16, 
-    same_screen:BOOL,
-    unuused:CARD8,
+impl FocusEvent {
+    /// returns `None` if `event` isn't a FocusIn or FocusOut
+    pub fn from_event(event: &RawEvent) -> Option<Self> {
+        let code = Event::code_of(event);
+        if code != event_codes::FOCUS_IN && code != event_codes::FOCUS_OUT {
+            return None;
+        }
+        let detail = match event[1] {
+            0 => FocusDetail::Ancestor,
+            1 => FocusDetail::Virtual,
+            2 => FocusDetail::Inferior,
+            3 => FocusDetail::Nonlinear,
+            4 => FocusDetail::NonlinearVirtual,
+            5 => FocusDetail::Pointer,
+            6 => FocusDetail::PointerRoot,
+            _ => FocusDetail::None,
+        };
+        let mode = match event[8] {
+            1 => FocusMode::Grab,
+            2 => FocusMode::Ungrab,
+            3 => FocusMode::WhileGrabbed,
+            _ => FocusMode::Normal,
+        };
+        Some(Self {
+            focus_in: code == event_codes::FOCUS_IN,
+            detail,
+            event: Atom(card32_at(event, 4)),
+            mode,
+        })
+    }
 }
 
+/// Sent right after an EnterNotify or FocusIn to clients that selected KEYMAP_STATE
+#[derive(Copy, Clone, Debug)]
+pub struct KeymapNotifyEvent {
+    /// keycodes 0 to 7 don't exist so their bits are always clear
+    pub keys: KeyVector,
+}
+
+impl KeymapNotifyEvent {
+    pub fn from_event(event: &RawEvent) -> Option<Self> {
+        (Event::code_of(event) == event_codes::KEYMAP_NOTIFY).then(|| {
+            // there's no sequence number, the vector starts at byte 1 with keycode 8
+            let mut keys = KeyVector::default();
+            keys.0[1..].copy_from_slice(&event[1..]);
+            Self { keys }
+        })
+    }
+}
 
 #[test]
-pub fn event_size(){
-    assert_eq!( 32, std::mem::size_of::<GenericEvent>() );
-}
\ No newline at end of file
+pub fn event_size() {
+    assert_eq!(32, std::mem::size_of::<GenericEvent>());
+}
+/// An event after it's been run past the registered extension decoders
+pub enum Event {
+    /// an event from the core protocol, not decoded any further yet
+    Core(RawEvent),
+    /// an extension event that a registered decoder understood
+    Extension {
+        name: String,
+        event: Box<dyn std::any::Any>,
+    },
+    /// an extension event nobody could decode
+    Unknown(RawEvent),
+}
+
+impl Event {
+    /// the code with the SendEvent bit masked off
+    pub fn code_of(event: &RawEvent) -> CARD8 {
+        event[0] & 0x7f
+    }
+}
+
+impl Debug for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Core(raw) => write!(f, "Core(code = {})", Self::code_of(raw)),
+            Self::Extension { name, .. } => write!(f, "Extension({})", name),
+            Self::Unknown(raw) => write!(f, "Unknown(code = {})", Self::code_of(raw)),
+        }
+    }
+}
+
+impl<S> XContext<S> {
+    pub fn decode_event(&self, event: &RawEvent) -> Event {
+        let code = Event::code_of(event);
+        if code < FIRST_EXTENSION_EVENT {
+            return Event::Core(*event);
+        }
+        let extensions = self.extensions.borrow();
+        match extensions.decode_event(event) {
+            Some((name, event)) => Event::Extension {
+                name: name.to_string(),
+                event,
+            },
+            None => Event::Unknown(*event),
+        }
+    }
+}
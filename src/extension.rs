@@ -0,0 +1,250 @@
+use super::*;
+pub use crate::proto::xproto::list_extensions_request;
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+/// event codes below this belong to the core protocol
+pub const FIRST_EXTENSION_EVENT: CARD8 = 64;
+/// error codes below this belong to the core protocol
+pub const FIRST_EXTENSION_ERROR: CARD8 = 128;
+
+/// What the server told us about an extension it supports
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtensionInfo {
+    pub major_opcode: CARD8,
+    pub first_event: CARD8,
+    pub first_error: CARD8,
+}
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+struct QueryExtensionReply {
+    reply: CARD8,
+    unused: CARD8,
+    sequence_number: CARD16,
+    reply_length: CARD32,
+    present: BOOL,
+    major_opcode: CARD8,
+    first_event: CARD8,
+    first_error: CARD8,
+    unused_2: [u8; 20],
+}
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+struct ListExtensionsReply {
+    reply: CARD8,
+    number_of_names: CARD8,
+    sequence_number: CARD16,
+    reply_length: CARD32,
+    unused: [u8; 24],
+}
+
+/// Turns the events and errors of one extension into something typed.\
+/// Register one with `XContext::register_decoder` so `XContext::decode_event`
+/// and error handling know about the extension
+pub trait ExtensionDecoder {
+    /// `event` is the raw packet, its code is somewhere at or after `info.first_event`
+    fn decode_event(&self, _info: &ExtensionInfo, _event: &RawEvent) -> Option<Box<dyn Any>> {
+        None
+    }
+
+    /// `code` is relative to `info.first_error`
+    fn error_name(&self, _info: &ExtensionInfo, _code: CARD8) -> Option<&'static str> {
+        None
+    }
+}
+
+/// a registered decoder, `Send + Sync` so every connection type can share the registry
+pub type Decoder = Arc<dyn ExtensionDecoder + Send + Sync>;
+
+/// Caches everything learned about extensions over the lifetime of a connection
+#[derive(Default)]
+pub struct ExtensionRegistry {
+    /// `None` means we asked and the server doesn't have it
+    queried: HashMap<String, Option<ExtensionInfo>>,
+    decoders: HashMap<String, Decoder>,
+}
+
+impl ExtensionRegistry {
+    /// returns `None` if the extension was never queried
+    pub fn get(&self, name: &str) -> Option<Option<ExtensionInfo>> {
+        self.queried.get(name).copied()
+    }
+
+    pub fn insert(&mut self, name: &str, info: Option<ExtensionInfo>) {
+        self.queried.insert(name.to_string(), info);
+    }
+
+    pub fn decoder(&self, name: &str) -> Option<Decoder> {
+        self.decoders.get(name).cloned()
+    }
+
+    pub fn insert_decoder<D>(&mut self, name: &str, decoder: D)
+    where
+        D: ExtensionDecoder + Send + Sync + 'static,
+    {
+        self.decoders.insert(name.to_string(), Arc::new(decoder));
+    }
+
+    /// every present extension, in no particular order
+    pub fn present(&self) -> impl Iterator<Item = (&str, &ExtensionInfo)> {
+        self.queried
+            .iter()
+            .filter_map(|(name, info)| Some((name.as_str(), info.as_ref()?)))
+    }
+
+    /// finds the extension that owns `major_opcode`
+    pub fn extension_for_opcode(&self, major_opcode: CARD8) -> Option<(&str, &ExtensionInfo)> {
+        self.present()
+            .find(|(_, info)| info.major_opcode == major_opcode)
+    }
+
+    /// Present extensions with a decoder whose codes could include `code`, closest first.\
+    /// Extensions don't say how many codes they use, so only a decoder can tell
+    fn candidates(
+        &self,
+        code: CARD8,
+        first: fn(&ExtensionInfo) -> CARD8,
+    ) -> impl Iterator<Item = (&str, &ExtensionInfo, &Decoder)> {
+        let mut candidates: Vec<_> = self
+            .present()
+            .filter(|(_, info)| first(info) != 0 && first(info) <= code)
+            .filter_map(|(name, info)| Some((name, info, self.decoders.get(name)?)))
+            .collect();
+        candidates.sort_by_key(|(_, info, _)| std::cmp::Reverse(first(info)));
+        candidates.into_iter()
+    }
+
+    /// Decodes an extension event with the first registered decoder that claims it.\
+    /// `None` when none does, codes no decoder knows aren't guessed at
+    pub fn decode_event(&self, event: &RawEvent) -> Option<(&str, Box<dyn Any>)> {
+        let code = event[0] & 0x7f;
+        self.candidates(code, |info| info.first_event)
+            .find_map(|(name, info, decoder)| Some((name, decoder.decode_event(info, event)?)))
+    }
+
+    /// Finds the extension whose decoder has a name for error `code`, along with that name.\
+    /// `None` when no registered decoder knows the code
+    pub fn extension_for_error(&self, code: CARD8) -> Option<(&str, &ExtensionInfo, &'static str)> {
+        self.candidates(code, |info| info.first_error)
+            .find_map(|(name, info, decoder)| {
+                let error = decoder.error_name(info, code - info.first_error)?;
+                Some((name, info, error))
+            })
+    }
+}
+
+pub fn query_extension_request<S: io::Write>(socket: &mut S, name: &str) -> io::Result<()> {
+    proto::xproto::query_extension_request(socket, &text::latin1_name(name)?)
+}
+
+/// what a QueryExtension reply says, `None` when the extension isn't present
+pub(crate) fn parse_query_extension(reply: &[u8]) -> io::Result<Option<ExtensionInfo>> {
+    let reply = read_primitive::<QueryExtensionReply, _>(&mut &reply[..])?;
+    Ok((reply.present != 0).then_some(ExtensionInfo {
+        major_opcode: reply.major_opcode,
+        first_event: reply.first_event,
+        first_error: reply.first_error,
+    }))
+}
+
+impl<S> XContext<S> {
+    pub fn extensions(&self) -> std::cell::Ref<'_, ExtensionRegistry> {
+        self.extensions.borrow()
+    }
+
+    /// Hooks `decoder` up to the events and errors of extension `name`.\
+    /// The extension has to be queried for the decoder to ever be used
+    pub fn register_decoder<D>(&self, name: &str, decoder: D)
+    where
+        D: ExtensionDecoder + Send + Sync + 'static,
+    {
+        self.extensions.borrow_mut().insert_decoder(name, decoder);
+    }
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    /// Asks the server if it supports the extension called `name`.\
+    /// Returns `None` when the extension isn't present.
+    /// Answers are cached so only the first call for a name costs a round trip
+    pub fn query_extension(&self, name: &str) -> Result<Option<ExtensionInfo>, XErrorKind> {
+        if let Some(info) = self.extensions.borrow().get(name) {
+            return Ok(info);
+        }
+        self.socket_cb(|socket| query_extension_request(socket, name))?;
+        let info = parse_query_extension(&self.read_reply()?)?;
+        self.extensions.borrow_mut().insert(name, info);
+        Ok(info)
+    }
+
+    /// names of every extension the server supports
+    pub fn list_extensions(&self) -> Result<Vec<String>, XErrorKind> {
+        self.socket_cb(|socket| list_extensions_request(socket))?;
+        let reply = self.read_reply()?;
+        let mut data = reply.as_slice();
+        let header = read_primitive::<ListExtensionsReply, _>(&mut data)?;
+        (0..header.number_of_names)
+            .map(|_| {
+                let len = read_primitive::<CARD8, _>(&mut data)?;
+                Ok(xio::read_latin1_string(&mut data, len as usize)?)
+            })
+            .collect()
+    }
+}
+
+#[test]
+pub fn registry_dispatch() {
+    let mut registry = ExtensionRegistry::default();
+    let shm = ExtensionInfo {
+        major_opcode: 130,
+        first_event: 65,
+        first_error: 128,
+    };
+    let randr = ExtensionInfo {
+        major_opcode: 140,
+        first_event: 89,
+        first_error: 147,
+    };
+    registry.insert("MIT-SHM", Some(shm));
+    registry.insert("RANDR", Some(randr));
+    registry.insert("BIG-REQUESTS", Some(ExtensionInfo::default()));
+    registry.insert("NOPE", None);
+
+    // nothing is attributed without a decoder claiming it
+    let mut event: RawEvent = [0; 32];
+    event[0] = 90;
+    assert!(registry.decode_event(&event).is_none());
+    assert!(registry.extension_for_error(129).is_none());
+
+    struct Claims(CARD8);
+    impl ExtensionDecoder for Claims {
+        fn decode_event(&self, info: &ExtensionInfo, event: &RawEvent) -> Option<Box<dyn Any>> {
+            (event[0] & 0x7f == info.first_event + self.0).then(|| Box::new(self.0) as Box<dyn Any>)
+        }
+
+        fn error_name(&self, _info: &ExtensionInfo, code: CARD8) -> Option<&'static str> {
+            (code == self.0).then_some("BadThing")
+        }
+    }
+    registry.insert_decoder("MIT-SHM", Claims(0));
+    registry.insert_decoder("RANDR", Claims(1));
+    assert!(registry
+        .decode_event(&event)
+        .is_some_and(|(name, _)| name == "RANDR"));
+    // past RANDR's first event but not one of its own, and too far for MIT-SHM to claim
+    event[0] = 91;
+    assert!(registry.decode_event(&event).is_none());
+    event[0] = 65 | 0x80;
+    assert!(registry
+        .decode_event(&event)
+        .is_some_and(|(name, _)| name == "MIT-SHM"));
+    assert_eq!(
+        registry.extension_for_error(148).map(|e| (e.0, e.2)),
+        Some(("RANDR", "BadThing"))
+    );
+    assert!(registry.extension_for_error(149).is_none());
+    assert_eq!(
+        registry.extension_for_opcode(140).map(|e| e.0),
+        Some("RANDR")
+    );
+    assert_eq!(registry.get("NOPE"), Some(None));
+    assert_eq!(registry.get("XKEYBOARD"), None);
+}
@@ -0,0 +1,238 @@
+use super::*;
+pub use crate::proto::xproto::get_input_focus_request;
+use std::{
+    os::unix::io::AsRawFd,
+    time::{Duration, Instant},
+};
+
+/// where the focus goes if the focused window becomes unviewable
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RevertTo {
+    None = 0,
+    PointerRoot = 1,
+    Parent = 2,
+}
+
+impl RevertTo {
+    fn from_code(code: CARD8) -> Self {
+        match code {
+            1 => Self::PointerRoot,
+            2 => Self::Parent,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Focus {
+    /// keyboard events are thrown away
+    None,
+    /// the focus follows the root window the pointer is on
+    PointerRoot,
+    Window(WINDOW),
+}
+
+impl Focus {
+    fn to_card32(self) -> CARD32 {
+        match self {
+            Self::None => 0,
+            Self::PointerRoot => 1,
+            Self::Window(window) => window.0,
+        }
+    }
+
+    fn from_card32(value: CARD32) -> Self {
+        match value {
+            0 => Self::None,
+            1 => Self::PointerRoot,
+            window => Self::Window(Atom(window)),
+        }
+    }
+}
+
+/// Focusing a window that isn't viewable yet fails with BadMatch.\
+/// `time` should be the timestamp of the event that caused the change,
+/// requests older than the last focus change are ignored
+pub fn set_input_focus<S: io::Write>(
+    socket: &mut S,
+    revert_to: RevertTo,
+    focus: Focus,
+    time: Timestamp,
+) -> io::Result<()> {
+    proto::xproto::set_input_focus(
+        socket,
+        revert_to as CARD8,
+        Atom(focus.to_card32()),
+        time.data(),
+    )
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    pub fn get_input_focus(&self) -> Result<(Focus, RevertTo), XErrorKind> {
+        self.socket_cb(|socket| get_input_focus_request(socket))?;
+        let reply = self.read_reply()?;
+        let focus = CARD32::from_le_bytes([reply[8], reply[9], reply[10], reply[11]]);
+        Ok((Focus::from_card32(focus), RevertTo::from_code(reply[1])))
+    }
+}
+
+impl<S: io::Read + io::Write + AsRawFd> XWindow<S> {
+    /// Gives this window the keyboard focus once it's viewable.\
+    /// A window manager may take a while to map a window after MapWindow, so this waits up
+    /// to `timeout` for the window to show up and returns false if it never did.
+    /// StructureNotify and VisibilityChange are selected on the window meanwhile, events for
+    /// the ones the application hadn't selected itself are dropped
+    pub fn focus(&self, revert_to: RevertTo, timeout: Duration) -> Result<bool, XErrorKind> {
+        use set_of_events_common::{STRUCTURE_NOTIFY, VISIBILITY_CHANGE};
+        let ctx = self.context();
+        let (id, deadline) = (self.id(), Instant::now() + timeout);
+        let selected = ctx.get_window_attributes(id)?.your_event_mask;
+        let added = (STRUCTURE_NOTIFY | VISIBILITY_CHANGE) & !selected;
+        if added != 0 {
+            ctx.socket_cb(|socket| select_input(socket, id, selected | added))?;
+        }
+        // the events only say something changed, the map state says whether it's viewable
+        let mut viewable;
+        loop {
+            viewable = ctx.get_window_attributes(id)?.map_state == MapState::Viewable;
+            if viewable || !ctx.wait_until_queued(|e| shows_window(e, id), deadline)? {
+                break;
+            }
+        }
+        if added != 0 {
+            ctx.socket_cb(|socket| select_input(socket, id, selected))?;
+        }
+        if viewable {
+            let focus = Focus::Window(id);
+            ctx.socket_cb(|socket| {
+                set_input_focus(socket, revert_to, focus, Timestamp::current_time())
+            })?;
+        }
+        ctx.sync()?;
+        // everything the added masks brought in has arrived by now
+        ctx.discard_queued(|e| e[4..8] == id.0.to_le_bytes() && added_event(e, added));
+        Ok(viewable)
+    }
+}
+
+/// a MapNotify or VisibilityNotify about `window` itself
+fn shows_window(event: &RawEvent, window: WINDOW) -> bool {
+    let code = events::Event::code_of(event);
+    (code == event_codes::MAP_NOTIFY || code == event_codes::VISIBILITY_NOTIFY)
+        && event[4..8] == window.0.to_le_bytes()
+}
+
+/// whether `event` only came in because `focus` selected `added`
+fn added_event(event: &RawEvent, added: CARD32) -> bool {
+    use set_of_events_common::{STRUCTURE_NOTIFY, VISIBILITY_CHANGE};
+    match events::Event::code_of(event) {
+        event_codes::VISIBILITY_NOTIFY => added & VISIBILITY_CHANGE != 0,
+        event_codes::DESTROY_NOTIFY
+        | event_codes::UNMAP_NOTIFY
+        | event_codes::MAP_NOTIFY
+        | event_codes::REPARENT_NOTIFY
+        | event_codes::CONFIGURE_NOTIFY
+        | event_codes::GRAVITY_NOTIFY
+        | event_codes::CIRCULATE_NOTIFY => added & STRUCTURE_NOTIFY != 0,
+        _ => false,
+    }
+}
+
+#[test]
+pub fn focus_encoding() {
+    let mut out = vec![];
+    set_input_focus(
+        &mut out,
+        RevertTo::Parent,
+        Focus::Window(Atom(0x400001)),
+        Timestamp::from(77),
+    )
+    .unwrap();
+    assert_eq!(out.len(), 12);
+    assert_eq!(out[0], opcodes::SET_INPUT_FOCUS);
+    assert_eq!(out[1], 2);
+    assert_eq!(&out[4..8], &0x400001u32.to_le_bytes());
+    assert_eq!(&out[8..12], &77u32.to_le_bytes());
+    assert_eq!(Focus::from_card32(1), Focus::PointerRoot);
+}
+
+#[test]
+pub fn focus_waits_for_the_window() {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let (client, mut server) = UnixStream::pair().unwrap();
+    let server = std::thread::spawn(move || {
+        let mut setup = [0u8; 12];
+        server.read_exact(&mut setup).unwrap();
+        server
+            .write_all(&mock::MockSetup::default().encode())
+            .unwrap();
+        let mut seen = vec![];
+        let mut sequence = 0u16;
+        let mut attributes_asked = 0;
+        loop {
+            let mut header = [0u8; 4];
+            if server.read_exact(&mut header).is_err() {
+                return seen;
+            }
+            let len = u16::from_le_bytes([header[2], header[3]]) as usize * 4;
+            let mut body = vec![0u8; len - 4];
+            server.read_exact(&mut body).unwrap();
+            sequence += 1;
+            let mut reply = [0u8; 44];
+            reply[0] = 1;
+            reply[2..4].copy_from_slice(&sequence.to_le_bytes());
+            match header[0] {
+                opcodes::GET_WINDOW_ATTRIBUTES => {
+                    attributes_asked += 1;
+                    reply[4] = 3; //reply-length
+                                  // the window manager maps it after the second look
+                    reply[26] = if attributes_asked > 2 { 2 } else { 0 };
+                    reply[36..40].copy_from_slice(&set_of_events_common::EXPOSURE.to_le_bytes());
+                    server.write_all(&reply).unwrap();
+                    if attributes_asked == 2 {
+                        let mut visible = [0u8; 32];
+                        visible[0] = event_codes::VISIBILITY_NOTIFY;
+                        visible[2..4].copy_from_slice(&sequence.to_le_bytes());
+                        visible[4..8].copy_from_slice(&body[..4]);
+                        server.write_all(&visible).unwrap();
+                    }
+                }
+                opcodes::GET_INPUT_FOCUS | opcodes::QUERY_EXTENSION => {
+                    server.write_all(&reply[..32]).unwrap()
+                }
+                opcodes::CHANGE_WINDOW_ATTRIBUTES => seen.push((
+                    header[0],
+                    u32::from_le_bytes(body[8..12].try_into().unwrap()),
+                )),
+                opcode => seen.push((opcode, 0)),
+            }
+        }
+    });
+    let mut ctx = RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None)
+        .connect(client)
+        .unwrap();
+    let window = WindowBuilder::new(&mut ctx).build().unwrap();
+    let focused = window
+        .focus(RevertTo::Parent, Duration::from_secs(5))
+        .unwrap();
+    assert!(focused);
+    // the VisibilityNotify was only there because of `focus`
+    assert!(ctx.poll_for_event().unwrap().is_none());
+    drop((window, ctx));
+    let seen = server.join().unwrap();
+    let watch = set_of_events_common::STRUCTURE_NOTIFY | set_of_events_common::VISIBILITY_CHANGE;
+    let exposure = set_of_events_common::EXPOSURE;
+    let seen = seen
+        .into_iter()
+        .skip_while(|&(opcode, _)| opcode != opcodes::CHANGE_WINDOW_ATTRIBUTES);
+    assert_eq!(
+        seen.collect::<Vec<_>>(),
+        [
+            (opcodes::CHANGE_WINDOW_ATTRIBUTES, exposure | watch),
+            (opcodes::CHANGE_WINDOW_ATTRIBUTES, exposure),
+            (opcodes::SET_INPUT_FOCUS, 0),
+        ]
+    );
+}
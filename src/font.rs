@@ -0,0 +1,478 @@
+use super::*;
+pub use crate::proto::xproto::{close_font, get_font_path_request, query_font_request};
+use std::io::Write;
+
+/// Metrics of a single glyph, or the min/max over the whole font
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharInfo {
+    pub left_side_bearing: INT16,
+    pub right_side_bearing: INT16,
+    pub character_width: INT16,
+    pub ascent: INT16,
+    pub descent: INT16,
+    pub attributes: CARD16,
+}
+
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct FontProp {
+    pub name: Atom,
+    /// an atom for properties like FONT_NAME, a number for the rest
+    pub value: CARD32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DrawDirection {
+    LeftToRight = 0,
+    RightToLeft = 1,
+}
+
+/// The part of the QueryFont reply that ListFontsWithInfo shares
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+struct FontReplyHeader {
+    reply: CARD8,
+    /// name length in ListFontsWithInfo, unused in QueryFont
+    name_length: CARD8,
+    sequence_number: CARD16,
+    reply_length: CARD32,
+    min_bounds: CharInfo,
+    unused0: [u8; 4],
+    max_bounds: CharInfo,
+    unused1: [u8; 4],
+    min_char_or_byte2: CARD16,
+    max_char_or_byte2: CARD16,
+    default_char: CARD16,
+    properties: CARD16,
+    draw_direction: CARD8,
+    min_byte1: CARD8,
+    max_byte1: CARD8,
+    all_chars_exist: BOOL,
+    font_ascent: INT16,
+    font_descent: INT16,
+    /// char-infos in QueryFont, replies-hint in ListFontsWithInfo
+    count: CARD32,
+}
+
+#[derive(Clone, Debug)]
+pub struct FontInfo {
+    pub min_bounds: CharInfo,
+    pub max_bounds: CharInfo,
+    pub min_char_or_byte2: CARD16,
+    pub max_char_or_byte2: CARD16,
+    pub default_char: CARD16,
+    pub draw_direction: DrawDirection,
+    pub min_byte1: CARD8,
+    pub max_byte1: CARD8,
+    pub all_chars_exist: bool,
+    pub font_ascent: INT16,
+    pub font_descent: INT16,
+    pub properties: Vec<FontProp>,
+}
+
+impl FontInfo {
+    fn from_header(header: &FontReplyHeader, properties: Vec<FontProp>) -> Self {
+        Self {
+            min_bounds: header.min_bounds,
+            max_bounds: header.max_bounds,
+            min_char_or_byte2: header.min_char_or_byte2,
+            max_char_or_byte2: header.max_char_or_byte2,
+            default_char: header.default_char,
+            draw_direction: if header.draw_direction == 1 {
+                DrawDirection::RightToLeft
+            } else {
+                DrawDirection::LeftToRight
+            },
+            min_byte1: header.min_byte1,
+            max_byte1: header.max_byte1,
+            all_chars_exist: header.all_chars_exist != 0,
+            font_ascent: header.font_ascent,
+            font_descent: header.font_descent,
+            properties,
+        }
+    }
+
+    /// the value of property `name`, e.g. `predefined_atoms::POINT_SIZE`
+    pub fn property(&self, name: Atom) -> Option<CARD32> {
+        self.properties
+            .iter()
+            .find(|prop| prop.name == name)
+            .map(|prop| prop.value)
+    }
+
+    /// the FONT_NAME property, an atom to pass to `XContext::get_atom_name`
+    pub fn font_name(&self) -> Option<Atom> {
+        self.property(predefined_atoms::FONT_NAME).map(Atom)
+    }
+
+    pub fn family_name(&self) -> Option<Atom> {
+        self.property(predefined_atoms::FAMILY_NAME).map(Atom)
+    }
+
+    /// 0 to 1000, 500 being a regular weight
+    pub fn weight(&self) -> Option<CARD32> {
+        self.property(predefined_atoms::WEIGHT)
+    }
+
+    /// in tenths of a point
+    pub fn point_size(&self) -> Option<CARD32> {
+        self.property(predefined_atoms::POINT_SIZE)
+    }
+
+    /// in pixels, per the X_HEIGHT property
+    pub fn x_height(&self) -> Option<INT32> {
+        self.property(predefined_atoms::X_HEIGHT)
+            .map(|v| v as INT32)
+    }
+
+    /// the font's height in pixels, ascent plus descent
+    pub fn height(&self) -> INT16 {
+        self.font_ascent + self.font_descent
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct QueryFontReply {
+    pub info: FontInfo,
+    /// empty if every glyph has `info.max_bounds` as its metrics
+    pub char_infos: Vec<CharInfo>,
+}
+
+impl QueryFontReply {
+    /// decodes a QueryFont reply
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let mut data = reply;
+        let header = read_primitive::<FontReplyHeader, _>(&mut data)?;
+        let properties = xio::read_primitive_list(&mut data, header.properties as usize)?;
+        let char_infos = xio::read_primitive_list(&mut data, header.count as usize)?;
+        Ok(Self {
+            info: FontInfo::from_header(&header, properties),
+            char_infos,
+        })
+    }
+
+    /// metrics for a glyph, `ch` is byte1 << 8 | byte2 for matrix (two byte) fonts
+    pub fn char_info(&self, ch: CARD16) -> Option<CharInfo> {
+        let info = &self.info;
+        let (byte1, byte2) = ((ch >> 8) as CARD8, ch & 0xff);
+        if byte1 < info.min_byte1
+            || byte1 > info.max_byte1
+            || byte2 < info.min_char_or_byte2
+            || byte2 > info.max_char_or_byte2
+        {
+            return None;
+        }
+        if self.char_infos.is_empty() {
+            return Some(info.max_bounds);
+        }
+        // widened, a server can claim 0..=0xffff even though byte2 stops at 0xff
+        let row = (info.max_char_or_byte2 - info.min_char_or_byte2) as usize + 1;
+        let index =
+            (byte1 - info.min_byte1) as usize * row + (byte2 - info.min_char_or_byte2) as usize;
+        self.char_infos.get(index).copied()
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct TextExtents {
+    reply: CARD8,
+    draw_direction: CARD8,
+    sequence_number: CARD16,
+    reply_length: CARD32,
+    pub font_ascent: INT16,
+    pub font_descent: INT16,
+    pub overall_ascent: INT16,
+    pub overall_descent: INT16,
+    pub overall_width: INT32,
+    pub overall_left: INT32,
+    pub overall_right: INT32,
+    unused: [u8; 4],
+}
+
+/// One piece of a PolyText request
+#[derive(Clone, Debug)]
+pub enum TextItem<T> {
+    /// `delta` moves the pen before the text is drawn
+    Text { delta: INT8, text: T },
+    /// switches the gc to another font for the items after it
+    Font(FONT),
+}
+
+pub fn open_font<S: io::Write>(socket: &mut S, fid: FONT, name: &str) -> io::Result<()> {
+    proto::xproto::open_font(socket, fid, &text::latin1_name(name)?)
+}
+
+pub fn query_text_extents_request<S: io::Write>(
+    socket: &mut S,
+    font: FONT,
+    text: &[CARD16],
+) -> io::Result<()> {
+    proto::xproto::query_text_extents_request(socket, font, &char2b(text))
+}
+
+pub fn list_fonts_request<S: io::Write>(
+    socket: &mut S,
+    max_names: CARD16,
+    pattern: &str,
+) -> io::Result<()> {
+    proto::xproto::list_fonts_request(socket, max_names, &text::latin1_name(pattern)?)
+}
+
+pub fn list_fonts_with_info_request<S: io::Write>(
+    socket: &mut S,
+    max_names: CARD16,
+    pattern: &str,
+) -> io::Result<()> {
+    proto::xproto::list_fonts_with_info_request(socket, max_names, &text::latin1_name(pattern)?)
+}
+
+/// an empty `path` puts the server's default path back
+pub fn set_font_path<S: io::Write>(socket: &mut S, path: &[&str]) -> io::Result<()> {
+    let path = path
+        .iter()
+        .map(|dir| {
+            let name = text::latin1_name(dir)?;
+            let name_len = CARD8::try_from(name.len()).map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, "font path entry too long")
+            })?;
+            Ok(proto::xproto::Str {
+                name_len,
+                name: name.into_owned(),
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    proto::xproto::set_font_path(socket, &path)
+}
+
+/// points the gc at `font` for the text requests below
+pub fn set_gc_font<S: io::Write>(socket: &mut S, gc: GCONTEXT, font: FONT) -> io::Result<()> {
+    proto::xproto::change_gc(socket, gc, &[(proto::xproto::gc::FONT, font)])
+}
+
+/// Draws `text` with the background filled in, at most 255 characters
+pub fn image_text8<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    pos: (INT16, INT16),
+    text: &[u8],
+) -> io::Result<()> {
+    proto::xproto::image_text8(socket, drawable, gc, pos.0, pos.1, text)
+}
+
+/// like `image_text8` for two byte fonts, characters are byte1 << 8 | byte2
+pub fn image_text16<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    pos: (INT16, INT16),
+    text: &[CARD16],
+) -> io::Result<()> {
+    proto::xproto::image_text16(socket, drawable, gc, pos.0, pos.1, &char2b(text))
+}
+
+/// Draws only the glyphs' foreground, texts longer than 254 are split into several items
+pub fn poly_text8<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    pos: (INT16, INT16),
+    items: &[TextItem<&[u8]>],
+) -> io::Result<()> {
+    let mut encoded = vec![];
+    encode_text_items(&mut encoded, items, |out, text| out.write_all(text))?;
+    proto::xproto::poly_text8(socket, drawable, gc, pos.0, pos.1, &encoded)
+}
+
+pub fn poly_text16<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    pos: (INT16, INT16),
+    items: &[TextItem<&[CARD16]>],
+) -> io::Result<()> {
+    let mut encoded = vec![];
+    encode_text_items(&mut encoded, items, write_char2b)?;
+    proto::xproto::poly_text16(socket, drawable, gc, pos.0, pos.1, &encoded)
+}
+
+/// Writes TEXTITEM8/16s, the request's padding doubles as the terminating empty item
+fn encode_text_items<W, T, F>(
+    out: &mut W,
+    items: &[TextItem<&[T]>],
+    mut write_text: F,
+) -> io::Result<()>
+where
+    W: io::Write,
+    F: FnMut(&mut W, &[T]) -> io::Result<()>,
+{
+    const FONT_SHIFT: u8 = 255;
+    const MAX_ITEM: usize = 254;
+    for item in items {
+        match *item {
+            TextItem::Font(font) => {
+                write_primitive(out, FONT_SHIFT)?;
+                // the one place in the protocol that's always big endian
+                out.write_all(&font.to_be_bytes())?;
+            }
+            TextItem::Text { delta, text } => {
+                if text.is_empty() {
+                    write_primitive(out, 0u8)?;
+                    write_primitive(out, delta)?;
+                }
+                for (index, piece) in text.chunks(MAX_ITEM).enumerate() {
+                    write_primitive(out, piece.len() as u8)?;
+                    write_primitive(out, if index == 0 { delta } else { 0 })?;
+                    write_text(out, piece)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// CHAR2B is byte1 then byte2, i.e. big endian
+fn char2b(text: &[CARD16]) -> Vec<proto::xproto::Char2b> {
+    text.iter()
+        .map(|ch| {
+            let [byte1, byte2] = ch.to_be_bytes();
+            proto::xproto::Char2b { byte1, byte2 }
+        })
+        .collect()
+}
+
+fn write_char2b<W: io::Write>(out: &mut W, text: &[CARD16]) -> io::Result<()> {
+    text.iter()
+        .try_for_each(|ch| out.write_all(&ch.to_be_bytes()))
+}
+
+/// reads `count` length-prefixed strings
+fn read_str_list(data: &mut &[u8], count: usize) -> Result<Vec<String>, XErrorKind> {
+    (0..count)
+        .map(|_| {
+            let (&len, rest) = data.split_first().ok_or(XErrorKind::Unknown)?;
+            let text = rest.get(..len as usize).ok_or(XErrorKind::Unknown)?;
+            *data = &rest[len as usize..];
+            Ok(text::decode_latin1(text))
+        })
+        .collect()
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    /// opens the font called `name` (an XLFD or alias like "fixed") under a fresh id
+    pub fn open_font(&mut self, name: &str) -> Result<FONT, XErrorKind> {
+        let fid = self.gen_id();
+        self.socket_cb(|socket| open_font(socket, fid, name))?;
+        // an unknown name only shows up as a BadName error
+        self.sync()?;
+        Ok(fid)
+    }
+
+    pub fn query_font(&self, font: FONT) -> Result<QueryFontReply, XErrorKind> {
+        self.socket_cb(|socket| query_font_request(socket, font))?;
+        Ok(QueryFontReply::parse(&self.read_reply()?)?)
+    }
+
+    /// the size of `text` drawn with `font`, characters are byte1 << 8 | byte2
+    pub fn query_text_extents(
+        &self,
+        font: FONT,
+        text: &[CARD16],
+    ) -> Result<TextExtents, XErrorKind> {
+        self.socket_cb(|socket| query_text_extents_request(socket, font, text))?;
+        let reply = self.read_reply()?;
+        Ok(read_primitive::<TextExtents, _>(&mut reply.as_slice())?)
+    }
+
+    /// names matching `pattern`, which can use `*` and `?`
+    pub fn list_fonts(&self, pattern: &str, max_names: CARD16) -> Result<Vec<String>, XErrorKind> {
+        self.socket_cb(|socket| list_fonts_request(socket, max_names, pattern))?;
+        let reply = self.read_reply()?;
+        let count = read_primitive::<CARD16, _>(&mut &reply[8..10])?;
+        read_str_list(&mut &reply[32..], count as usize)
+    }
+
+    /// like `list_fonts` but with the metrics of every match, which the server sends one reply each
+    pub fn list_fonts_with_info(
+        &self,
+        pattern: &str,
+        max_names: CARD16,
+    ) -> Result<Vec<(String, FontInfo)>, XErrorKind> {
+        self.socket_cb(|socket| list_fonts_with_info_request(socket, max_names, pattern))?;
+        let mut fonts = vec![];
+        loop {
+            let reply = self.read_reply()?;
+            let mut data = reply.as_slice();
+            let header = read_primitive::<FontReplyHeader, _>(&mut data)?;
+            // the last reply has an empty name
+            if header.name_length == 0 {
+                return Ok(fonts);
+            }
+            let properties = xio::read_primitive_list(&mut data, header.properties as usize)?;
+            let name = data
+                .get(..header.name_length as usize)
+                .ok_or(XErrorKind::Unknown)?;
+            fonts.push((
+                text::decode_latin1(name),
+                FontInfo::from_header(&header, properties),
+            ));
+        }
+    }
+
+    pub fn get_font_path(&self) -> Result<Vec<String>, XErrorKind> {
+        self.socket_cb(|socket| get_font_path_request(socket))?;
+        let reply = self.read_reply()?;
+        let count = read_primitive::<CARD16, _>(&mut &reply[8..10])?;
+        read_str_list(&mut &reply[32..], count as usize)
+    }
+}
+
+#[test]
+pub fn text_item_encoding() {
+    assert_eq!(60, std::mem::size_of::<FontReplyHeader>());
+    assert_eq!(32, std::mem::size_of::<TextExtents>());
+
+    let long = [b'a'; 300];
+    let mut out = vec![];
+    let items = [
+        TextItem::Text {
+            delta: -2,
+            text: &long[..],
+        },
+        TextItem::Font(0x01020304),
+    ];
+    encode_text_items(&mut out, &items, |out, text| out.write_all(text)).unwrap();
+    assert_eq!(&out[..2], &[254, (-2i8) as u8]);
+    assert_eq!(&out[256..258], &[46, 0]);
+    assert_eq!(&out[304..], &[255, 1, 2, 3, 4]);
+
+    let mut out = vec![];
+    let wide = [0x0102u16, 0x0304];
+    encode_text_items(
+        &mut out,
+        &[TextItem::Text {
+            delta: 0,
+            text: &wide[..],
+        }],
+        write_char2b,
+    )
+    .unwrap();
+    assert_eq!(out, vec![2, 0, 1, 2, 3, 4]);
+}
+
+#[test]
+pub fn query_font_parse() {
+    let mut reply = vec![0u8; 60];
+    reply[0] = 1;
+    // a whole 16 bit range claimed for byte2, which only goes up to 0xff
+    reply[42..44].copy_from_slice(&0xffffu16.to_le_bytes());
+    reply[56..60].copy_from_slice(&2u32.to_le_bytes());
+    for width in [5i16, 7] {
+        reply.extend([0; 4]);
+        reply.extend(width.to_le_bytes());
+        reply.extend([0; 6]);
+    }
+    let font = QueryFontReply::parse(&reply).unwrap();
+    assert_eq!(font.char_info(1).unwrap().character_width, 7);
+    assert_eq!(font.char_info(2), None);
+    assert_eq!(font.char_info(0x100), None);
+    assert!(QueryFontReply::parse(&reply[..70]).is_err());
+}
@@ -0,0 +1,546 @@
+use super::*;
+
+#[derive(Copy, Clone)]
+pub enum SynchKind {
+    Synchronous = 0,
+    Asynchronous = 1,
+}
+
+/// The status GrabPointer and GrabKeyboard reply with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GrabStatus {
+    Success,
+    /// another client has it grabbed
+    AlreadyGrabbed,
+    /// the timestamp is older than the last grab or newer than the server's time
+    InvalidTime,
+    /// the grab or confine_to window isn't viewable
+    NotViewable,
+    /// another client froze the device with a synchronous grab
+    Frozen,
+}
+
+impl GrabStatus {
+    /// `InvalidData` for anything the protocol doesn't have
+    pub fn from_code(code: CARD8) -> io::Result<Self> {
+        match code {
+            0 => Ok(Self::Success),
+            1 => Ok(Self::AlreadyGrabbed),
+            2 => Ok(Self::InvalidTime),
+            3 => Ok(Self::NotViewable),
+            4 => Ok(Self::Frozen),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown grab status {}", code),
+            )),
+        }
+    }
+
+    /// turns anything but `Success` into `XErrorKind::NotGranted`
+    pub fn ok(self) -> Result<(), XErrorKind> {
+        match self {
+            Self::Success => Ok(()),
+            status => Err(XErrorKind::NotGranted(status)),
+        }
+    }
+}
+
+/// `timestamp` should come from the event that started the grab, an old grab request
+/// arriving late then fails with `InvalidTime` instead of stealing a newer grab
+pub fn grab_keyboard_request<S: io::Write>(
+    socket: &mut S,
+    owner_events: bool,
+    grab_window: WINDOW,
+    timestamp: Timestamp,
+    pointer_mode: SynchKind,
+    keyboard_mode: SynchKind,
+) -> io::Result<()> {
+    proto::xproto::grab_keyboard_request(
+        socket,
+        owner_events as BOOL,
+        grab_window,
+        timestamp.data(),
+        pointer_mode as CARD8,
+        keyboard_mode as CARD8,
+    )
+}
+
+pub fn ungrab_keyboard<S: io::Write>(socket: &mut S, timestamp: Timestamp) -> io::Result<()> {
+    proto::xproto::ungrab_keyboard(socket, timestamp.data())
+}
+
+/// `confine_to` and `cursor` are `None` to leave the pointer free and the cursor alone
+#[allow(clippy::too_many_arguments)]
+pub fn grab_pointer_request<S: io::Write>(
+    socket: &mut S,
+    owner_events: bool,
+    grab_window: WINDOW,
+    event_mask: PointerEventMask,
+    pointer_mode: SynchKind,
+    keyboard_mode: SynchKind,
+    confine_to: Option<WINDOW>,
+    cursor: Option<CURSOR>,
+    timestamp: Timestamp,
+) -> io::Result<()> {
+    proto::xproto::grab_pointer_request(
+        socket,
+        owner_events as BOOL,
+        grab_window,
+        event_mask.bits(),
+        pointer_mode as CARD8,
+        keyboard_mode as CARD8,
+        confine_to.unwrap_or(Atom(0)),
+        cursor.unwrap_or(0),
+        timestamp.data(),
+    )
+}
+
+pub fn ungrab_pointer<S: io::Write>(socket: &mut S, timestamp: Timestamp) -> io::Result<()> {
+    proto::xproto::ungrab_pointer(socket, timestamp.data())
+}
+
+/// Swaps the cursor and event mask of an active pointer grab, for instance once a
+/// region selection goes from waiting for a press to dragging
+pub fn change_active_pointer_grab<S: io::Write>(
+    socket: &mut S,
+    cursor: Option<CURSOR>,
+    timestamp: Timestamp,
+    event_mask: PointerEventMask,
+) -> io::Result<()> {
+    proto::xproto::change_active_pointer_grab(
+        socket,
+        cursor.unwrap_or(0),
+        timestamp.data(),
+        event_mask.bits(),
+    )
+}
+
+/// stops the server from handling any other client's requests until `ungrab_server`
+pub fn grab_server<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    proto::xproto::grab_server(socket)
+}
+
+pub fn ungrab_server<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    proto::xproto::ungrab_server(socket)
+}
+
+/// Holds the server grabbed until it's released or dropped, see `XContext::grab_server`
+pub struct ServerGrab<S: io::Write> {
+    ctx: XContext<S>,
+    held: bool,
+}
+
+impl<S: io::Write> ServerGrab<S> {
+    pub fn context(&self) -> &XContext<S> {
+        &self.ctx
+    }
+
+    /// Ungrabs the server now, unlike dropping the guard this says whether it worked
+    pub fn release(mut self) -> io::Result<()> {
+        self.held = false;
+        self.ctx
+            .socket_cb(|socket| ungrab_server(socket).and_then(|_| io::Write::flush(socket)))
+    }
+}
+
+impl<S: io::Write> Drop for ServerGrab<S> {
+    fn drop(&mut self) {
+        if self.held {
+            self.ctx.send_or_defer(ungrab_server);
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum EventMode {
+    AsyncPointer = 0,
+    SyncPointer = 1,
+    ReplayPointer = 2,
+    AsyncKeyboard = 3,
+    SyncKeyboard = 4,
+    ReplayKeyboard = 5,
+    AsyncBoth = 6,
+    SyncBoth = 7,
+}
+
+pub fn allow_events<S: io::Write>(
+    socket: &mut S,
+    mode: EventMode,
+    timestamp: Timestamp,
+) -> io::Result<()> {
+    proto::xproto::allow_events(socket, mode as CARD8, timestamp.data())
+}
+
+/// `button` is `None` for any button
+#[allow(clippy::too_many_arguments)]
+pub fn grab_button<S: io::Write>(
+    socket: &mut S,
+    owner_events: bool,
+    grab_window: WINDOW,
+    event_mask: PointerEventMask,
+    pointer_mode: SynchKind,
+    keyboard_mode: SynchKind,
+    confine_to: Option<WINDOW>,
+    cursor: Option<CURSOR>,
+    button: Option<CARD8>,
+    modifiers: ModMask,
+) -> io::Result<()> {
+    proto::xproto::grab_button(
+        socket,
+        owner_events as BOOL,
+        grab_window,
+        event_mask.bits(),
+        pointer_mode as CARD8,
+        keyboard_mode as CARD8,
+        confine_to.unwrap_or(Atom(0)),
+        cursor.unwrap_or(0),
+        button.unwrap_or(0),
+        modifiers.bits(),
+    )
+}
+
+pub fn ungrab_button<S: io::Write>(
+    socket: &mut S,
+    grab_window: WINDOW,
+    button: Option<CARD8>,
+    modifiers: ModMask,
+) -> io::Result<()> {
+    proto::xproto::ungrab_button(socket, button.unwrap_or(0), grab_window, modifiers.bits())
+}
+
+/// `key` is `None` for any key
+pub fn grab_key<S: io::Write>(
+    socket: &mut S,
+    owner_events: bool,
+    grab_window: WINDOW,
+    modifiers: ModMask,
+    key: Option<KEYCODE>,
+    pointer_mode: SynchKind,
+    keyboard_mode: SynchKind,
+) -> io::Result<()> {
+    proto::xproto::grab_key(
+        socket,
+        owner_events as BOOL,
+        grab_window,
+        modifiers.bits(),
+        key.unwrap_or(0),
+        pointer_mode as CARD8,
+        keyboard_mode as CARD8,
+    )
+}
+
+pub fn ungrab_key<S: io::Write>(
+    socket: &mut S,
+    grab_window: WINDOW,
+    modifiers: ModMask,
+    key: Option<KEYCODE>,
+) -> io::Result<()> {
+    proto::xproto::ungrab_key(socket, key.unwrap_or(0), grab_window, modifiers.bits())
+}
+
+/// A keysym + modifiers combination grabbed with `XContext::grab_keysym`
+#[derive(Clone, Debug)]
+pub struct KeyBinding {
+    pub window: WINDOW,
+    pub keysym: Keysym,
+    pub modifiers: ModMask,
+    /// every keycode that produces the keysym along with the modifiers grabbed for it
+    grabs: Vec<(KEYCODE, ModMask)>,
+    /// the lock style modifiers the grab ignores
+    ignored: ModMask,
+}
+
+impl KeyBinding {
+    /// true if `event` is a press or release of this binding
+    pub fn matches(&self, event: &events::KeyEvent) -> bool {
+        let state = ModMask::from_state(event.state) & !self.ignored;
+        self.grabs
+            .iter()
+            .any(|&(keycode, modifiers)| keycode == event.keycode && modifiers == state)
+    }
+
+    /// Every combination of `modifiers` with the ignored locks switched on or off
+    fn variants(&self, modifiers: ModMask) -> impl Iterator<Item = ModMask> + '_ {
+        let ignored = self.ignored.bits();
+        // walk every subset of the ignored bits
+        let mut subset = Some(0u16);
+        std::iter::from_fn(move || {
+            let current = subset?;
+            subset = (current != ignored).then(|| (current.wrapping_sub(ignored)) & ignored);
+            Some(modifiers | ModMask(current))
+        })
+    }
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    pub fn grab_keyboard(
+        &self,
+        owner_events: bool,
+        grab_window: WINDOW,
+        timestamp: Timestamp,
+        pointer_mode: SynchKind,
+        keyboard_mode: SynchKind,
+    ) -> Result<GrabStatus, XErrorKind> {
+        self.socket_cb(|socket| {
+            grab_keyboard_request(
+                socket,
+                owner_events,
+                grab_window,
+                timestamp,
+                pointer_mode,
+                keyboard_mode,
+            )
+        })?;
+        let reply = self.read_reply()?;
+        Ok(GrabStatus::from_code(reply[1])?)
+    }
+
+    /// Grabs the pointer so every pointer event in `event_mask` goes to `grab_window`.\
+    /// `confine_to` keeps the pointer inside that window and `cursor` is shown for the
+    /// whole grab, whatever window the pointer is over
+    #[allow(clippy::too_many_arguments)]
+    pub fn grab_pointer(
+        &self,
+        owner_events: bool,
+        grab_window: WINDOW,
+        event_mask: PointerEventMask,
+        pointer_mode: SynchKind,
+        keyboard_mode: SynchKind,
+        confine_to: Option<WINDOW>,
+        cursor: Option<CURSOR>,
+        timestamp: Timestamp,
+    ) -> Result<GrabStatus, XErrorKind> {
+        self.socket_cb(|socket| {
+            grab_pointer_request(
+                socket,
+                owner_events,
+                grab_window,
+                event_mask,
+                pointer_mode,
+                keyboard_mode,
+                confine_to,
+                cursor,
+                timestamp,
+            )
+        })?;
+        let reply = self.read_reply()?;
+        Ok(GrabStatus::from_code(reply[1])?)
+    }
+
+    /// Grabs the server, which stays grabbed until the returned guard is released or dropped.\
+    /// Nothing else gets drawn while it's held, so keep it short
+    pub fn grab_server(&self) -> io::Result<ServerGrab<S>> {
+        self.socket_cb(|socket| grab_server(socket))?;
+        Ok(ServerGrab {
+            ctx: self.clone(),
+            held: true,
+        })
+    }
+
+    /// Grabs `keysym` pressed together with `modifiers` on `window`.\
+    /// The keycodes come from the keyboard mapping and the grab is registered once for every
+    /// NumLock/CapsLock combination so it still fires with the locks on.
+    /// Keysyms that need Shift to be typed get Shift added to their modifiers
+    pub fn grab_keysym(
+        &self,
+        window: WINDOW,
+        keysym: Keysym,
+        modifiers: ModMask,
+    ) -> Result<KeyBinding, XErrorKind> {
+        let keymap = self.keymap()?;
+        let grabs = keymap
+            .keycodes_for(keysym)
+            .into_iter()
+            .filter_map(|(keycode, index)| match index {
+                0 => Some((keycode, modifiers)),
+                1 => Some((keycode, modifiers | ModMask::SHIFT)),
+                // other positions need Mode_switch, which can't be grabbed for reliably
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if grabs.is_empty() {
+            return Err(XErrorKind::KeysymNotMapped(keysym));
+        }
+        let binding = KeyBinding {
+            window,
+            keysym,
+            modifiers,
+            grabs,
+            ignored: ModMask::LOCK | ModMask(keymap.num_lock_mask()),
+        };
+        self.socket_cb(|socket| {
+            for &(keycode, modifiers) in &binding.grabs {
+                for variant in binding.variants(modifiers) {
+                    grab_key(
+                        socket,
+                        false,
+                        window,
+                        variant,
+                        Some(keycode),
+                        SynchKind::Asynchronous,
+                        SynchKind::Asynchronous,
+                    )?;
+                }
+            }
+            Ok::<_, io::Error>(())
+        })?;
+        // someone else holding the combination shows up as BadAccess
+        if let Err(e) = self.sync() {
+            // don't leave half of the variants grabbed
+            self.ungrab_keysym(&binding)?;
+            return Err(e);
+        }
+        Ok(binding)
+    }
+
+    /// releases every grab made for `binding`
+    pub fn ungrab_keysym(&self, binding: &KeyBinding) -> io::Result<()> {
+        self.socket_cb(|socket| {
+            for &(keycode, modifiers) in &binding.grabs {
+                for variant in binding.variants(modifiers) {
+                    ungrab_key(socket, binding.window, variant, Some(keycode))?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+#[test]
+pub fn key_binding_variants() {
+    let binding = KeyBinding {
+        window: Atom(1),
+        keysym: keysym::keysyms::F1,
+        modifiers: ModMask::CONTROL,
+        grabs: vec![(67, ModMask::CONTROL)],
+        ignored: ModMask::LOCK | ModMask::MOD2,
+    };
+    let variants = binding.variants(ModMask::CONTROL).collect::<Vec<_>>();
+    assert_eq!(
+        variants,
+        vec![
+            ModMask::CONTROL,
+            ModMask::CONTROL | ModMask::LOCK,
+            ModMask::CONTROL | ModMask::MOD2,
+            ModMask::CONTROL | ModMask::LOCK | ModMask::MOD2,
+        ]
+    );
+}
+
+#[test]
+pub fn grab_pointer_encoding() {
+    let mut out = vec![];
+    grab_pointer_request(
+        &mut out,
+        true,
+        Atom(0x200),
+        PointerEventMask::BUTTON_PRESS | PointerEventMask::BUTTON_MOTION,
+        SynchKind::Asynchronous,
+        SynchKind::Asynchronous,
+        Some(Atom(0x200)),
+        Some(0x300),
+        Timestamp::from(1234),
+    )
+    .unwrap();
+    assert_eq!(out.len(), 24);
+    assert_eq!(out[1], 1);
+    assert_eq!(&out[8..10], &0x2004u16.to_le_bytes());
+    assert_eq!(&out[16..20], &0x300u32.to_le_bytes());
+    assert_eq!(&out[20..24], &1234u32.to_le_bytes());
+    assert!(matches!(
+        GrabStatus::from_code(3).unwrap().ok(),
+        Err(XErrorKind::NotGranted(GrabStatus::NotViewable))
+    ));
+    assert!(GrabStatus::from_code(5).is_err());
+}
+
+#[test]
+pub fn server_grab_is_released() {
+    let (server, ctx) = mock::MockServer::connect();
+    let opcodes = |server: &mock::MockServer| {
+        let requests = server.take_requests().into_iter();
+        requests.map(|r| r.opcode).collect::<Vec<_>>()
+    };
+    drop(ctx.grab_server().unwrap());
+    assert_eq!(
+        opcodes(&server),
+        [opcodes::GRAB_SERVER, opcodes::UNGRAB_SERVER]
+    );
+
+    ctx.grab_server().unwrap().release().unwrap();
+    assert_eq!(
+        opcodes(&server),
+        [opcodes::GRAB_SERVER, opcodes::UNGRAB_SERVER]
+    );
+
+    // dropped while the socket is in use, it goes out ahead of the next request
+    let mut grab = Some(ctx.grab_server().unwrap());
+    ctx.socket_cb(|socket| {
+        grab.take();
+        map_window(socket, 0x1234)
+    })
+    .unwrap();
+    assert_eq!(
+        opcodes(&server),
+        [opcodes::GRAB_SERVER, opcodes::MAP_WINDOW]
+    );
+    ctx.sync().unwrap();
+    assert_eq!(
+        opcodes(&server),
+        [opcodes::UNGRAB_SERVER, opcodes::GET_INPUT_FOCUS]
+    );
+}
+
+#[test]
+pub fn grab_reply_after_event() {
+    let (server, ctx) = mock::MockServer::connect();
+    server.on_request(opcodes::GRAB_POINTER, |_| {
+        Some(mock::MockResponse::Reply {
+            data: 4, // Frozen
+            body: vec![],
+        })
+    });
+    // already on the wire when the grab goes out, it must not be taken for the reply
+    let mut motion = [0; 32];
+    motion[0] = event_codes::MOTION_NOTIFY;
+    server.send_event(motion);
+    let status = ctx
+        .grab_pointer(
+            false,
+            Atom(0x100),
+            PointerEventMask::POINTER_MOTION,
+            SynchKind::Asynchronous,
+            SynchKind::Asynchronous,
+            None,
+            None,
+            Timestamp::current_time(),
+        )
+        .unwrap();
+    assert_eq!(status, GrabStatus::Frozen);
+    assert_eq!(ctx.events.borrow().len(), 1);
+}
+
+#[test]
+pub fn deferred_ungrab_after_reply_request() {
+    let (server, ctx) = mock::MockServer::connect();
+    let mut grab = Some(ctx.grab_server().unwrap());
+    // the ungrab goes out after GetInputFocus, the reply read is still that one's
+    ctx.socket_cb(|socket| {
+        proto::xproto::get_input_focus_request(socket)?;
+        grab.take();
+        io::Result::Ok(())
+    })
+    .unwrap();
+    let reply = ctx.read_reply().unwrap();
+    assert_eq!(reply[0], 1);
+    let requests = server.take_requests();
+    let opcodes: Vec<_> = requests.iter().map(|r| r.opcode).collect();
+    assert_eq!(
+        opcodes,
+        [
+            opcodes::GRAB_SERVER,
+            opcodes::GET_INPUT_FOCUS,
+            opcodes::UNGRAB_SERVER
+        ]
+    );
+}
@@ -0,0 +1,424 @@
+use super::*;
+pub use crate::proto::xproto::{
+    bell, get_keyboard_control_request, get_keyboard_mapping_request, get_modifier_mapping_request,
+    query_keymap_request,
+};
+use keysym::keysyms;
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+struct MappingReplyHeader {
+    reply: CARD8,
+    /// keysyms-per-keycode or keycodes-per-modifier
+    per: CARD8,
+    sequence_number: CARD16,
+    reply_length: CARD32,
+    unused: [u8; 24],
+}
+
+/// The keysyms bound to a run of keycodes, `keysyms_per_keycode` each
+#[derive(Clone, Debug, Default)]
+pub struct KeyboardMapping {
+    pub first_keycode: KEYCODE,
+    pub keysyms_per_keycode: CARD8,
+    pub keysyms: Vec<Keysym>,
+}
+
+/// Which keycodes act as Shift, Lock, Control and Mod1 to Mod5, in that order
+#[derive(Clone, Debug, Default)]
+pub struct ModifierMapping {
+    pub keycodes_per_modifier: CARD8,
+    pub keycodes: Vec<KEYCODE>,
+}
+
+impl KeyboardMapping {
+    /// decodes a GetKeyboardMapping reply, `first_keycode` is the one the request asked for
+    pub fn parse(reply: &[u8], first_keycode: KEYCODE) -> io::Result<Self> {
+        let mut data = reply;
+        let header = read_primitive::<MappingReplyHeader, _>(&mut data)?;
+        let count = data.len() / 4;
+        let keysyms = xio::read_primitive_list::<CARD32, _>(&mut data, count)?;
+        Ok(Self {
+            first_keycode,
+            keysyms_per_keycode: header.per,
+            keysyms: keysyms.into_iter().map(Keysym).collect(),
+        })
+    }
+}
+
+impl ModifierMapping {
+    /// decodes a GetModifierMapping reply
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let mut data = reply;
+        let header = read_primitive::<MappingReplyHeader, _>(&mut data)?;
+        Ok(Self {
+            keycodes_per_modifier: header.per,
+            keycodes: xio::read_primitive_list(&mut data, 8 * header.per as usize)?,
+        })
+    }
+
+    /// keycodes bound to modifier `index` (0 = Shift .. 7 = Mod5), zeroes are unused slots
+    pub fn keycodes(&self, index: usize) -> &[KEYCODE] {
+        let per = self.keycodes_per_modifier as usize;
+        self.keycodes
+            .get(index * per..(index + 1) * per)
+            .unwrap_or(&[])
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LockKind {
+    Ignored,
+    CapsLock,
+    ShiftLock,
+}
+
+/// The client side copy of the keyboard mapping used to turn keycodes into keysyms
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    mapping: KeyboardMapping,
+    modifiers: ModifierMapping,
+    lock: LockKind,
+    mode_switch_mask: CARD16,
+    num_lock_mask: CARD16,
+}
+
+impl Keymap {
+    pub fn new(mapping: KeyboardMapping, modifiers: ModifierMapping) -> Self {
+        let mut keymap = Self {
+            mapping,
+            modifiers,
+            lock: LockKind::Ignored,
+            mode_switch_mask: 0,
+            num_lock_mask: 0,
+        };
+        keymap.mode_switch_mask = keymap.modifier_mask_for(keysyms::MODE_SWITCH);
+        keymap.num_lock_mask = keymap.modifier_mask_for(keysyms::NUM_LOCK);
+        let lock_keysyms = || {
+            keymap
+                .modifiers
+                .keycodes(1)
+                .iter()
+                .flat_map(|&keycode| keymap.keysyms_for(keycode))
+        };
+        keymap.lock = if lock_keysyms().any(|&k| k == keysyms::CAPS_LOCK) {
+            LockKind::CapsLock
+        } else if lock_keysyms().any(|&k| k == keysyms::SHIFT_LOCK) {
+            LockKind::ShiftLock
+        } else {
+            LockKind::Ignored
+        };
+        keymap
+    }
+
+    pub fn modifiers(&self) -> &ModifierMapping {
+        &self.modifiers
+    }
+
+    pub fn lock_kind(&self) -> LockKind {
+        self.lock
+    }
+
+    pub fn num_lock_mask(&self) -> CARD16 {
+        self.num_lock_mask
+    }
+
+    pub fn mode_switch_mask(&self) -> CARD16 {
+        self.mode_switch_mask
+    }
+
+    /// every keysym bound to `keycode`, empty for keycodes outside the mapping
+    pub fn keysyms_for(&self, keycode: KEYCODE) -> &[Keysym] {
+        let per = self.mapping.keysyms_per_keycode as usize;
+        let Some(index) = keycode.checked_sub(self.mapping.first_keycode) else {
+            return &[];
+        };
+        let start = index as usize * per;
+        self.mapping.keysyms.get(start..start + per).unwrap_or(&[])
+    }
+
+    /// the keycodes that have `keysym` somewhere in their list, with its position in that list
+    pub fn keycodes_for(&self, keysym: Keysym) -> Vec<(KEYCODE, usize)> {
+        let per = self.mapping.keysyms_per_keycode.max(1) as usize;
+        self.mapping
+            .keysyms
+            .chunks(per)
+            .enumerate()
+            .filter_map(|(index, syms)| {
+                let position = syms.iter().position(|&k| k == keysym)?;
+                // a server sending more keysyms than keycodes can't make this wrap around
+                let keycode = KEYCODE::try_from(index).ok()?;
+                Some((self.mapping.first_keycode.checked_add(keycode)?, position))
+            })
+            .collect()
+    }
+
+    /// the mask of every modifier that has a key producing `keysym` bound to it
+    pub fn modifier_mask_for(&self, keysym: Keysym) -> CARD16 {
+        (0..8)
+            .filter(|&index| {
+                self.modifiers
+                    .keycodes(index)
+                    .iter()
+                    .any(|&keycode| keycode != 0 && self.keysyms_for(keycode).contains(&keysym))
+            })
+            .fold(0, |mask, index| mask | (1 << index))
+    }
+
+    /// Picks the keysym for `keycode` given the modifier `state` of an input event,
+    /// following the group/shift/lock rules of section 5 of the protocol spec
+    pub fn lookup(&self, keycode: KEYCODE, state: CARD16) -> Keysym {
+        let no = keysyms::NO_SYMBOL;
+        let syms = self.keysyms_for(keycode);
+        let len = syms.iter().rposition(|&k| k != no).map_or(0, |p| p + 1);
+        let list = match syms[..len] {
+            [] => [no; 4],
+            [k] => [k, no, k, no],
+            [k1, k2] => [k1, k2, k1, k2],
+            [k1, k2, k3] => [k1, k2, k3, no],
+            [k1, k2, k3, k4, ..] => [k1, k2, k3, k4],
+        };
+
+        let group = if self.mode_switch_mask != 0 && state & self.mode_switch_mask != 0 {
+            &list[2..4]
+        } else {
+            &list[0..2]
+        };
+        let (mut first, mut second) = (group[0], group[1]);
+        if second == no {
+            let (lower, upper) = first.convert_case();
+            if lower != upper {
+                (first, second) = (lower, upper);
+            } else {
+                second = first;
+            }
+        }
+
+        let shift = state & key_masks::SHIFT != 0;
+        let lock = state & key_masks::LOCK != 0;
+        if self.num_lock_mask != 0 && state & self.num_lock_mask != 0 && second.is_keypad() {
+            return if shift || (lock && self.lock == LockKind::ShiftLock) {
+                first
+            } else {
+                second
+            };
+        }
+        match (shift, lock, self.lock) {
+            (false, false, _) | (false, true, LockKind::Ignored) => first,
+            (false, true, LockKind::CapsLock) => first.convert_case().1,
+            (true, true, LockKind::CapsLock) => second.convert_case().1,
+            _ => second,
+        }
+    }
+}
+
+/// One bit per keycode, set for the keys that are held down
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyVector(pub [u8; 32]);
+
+impl KeyVector {
+    pub fn is_pressed(&self, keycode: KEYCODE) -> bool {
+        self.0[keycode as usize / 8] & (1 << (keycode % 8)) != 0
+    }
+
+    pub fn pressed(&self) -> impl Iterator<Item = KEYCODE> + '_ {
+        (0..=KEYCODE::MAX).filter(|&keycode| self.is_pressed(keycode))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AutoRepeatMode {
+    Off = 0,
+    On = 1,
+    Default = 2,
+}
+
+/// The GetKeyboardControl reply
+#[derive(Copy, Clone, Debug)]
+pub struct KeyboardControl {
+    pub global_auto_repeat: bool,
+    /// bit `n` is LED `n + 1`
+    pub led_mask: CARD32,
+    pub key_click_percent: CARD8,
+    pub bell_percent: CARD8,
+    /// in hertz
+    pub bell_pitch: CARD16,
+    /// in milliseconds
+    pub bell_duration: CARD16,
+    /// the keys that repeat when held, if `global_auto_repeat` is on
+    pub auto_repeats: KeyVector,
+}
+
+/// Changes for ChangeKeyboardControl, every `None` is left alone.\
+/// -1 puts the percent, pitch and duration fields back to their defaults
+#[derive(Copy, Clone, Debug, Default)]
+pub struct KeyboardControlChange {
+    pub key_click_percent: Option<INT8>,
+    pub bell_percent: Option<INT8>,
+    pub bell_pitch: Option<INT16>,
+    pub bell_duration: Option<INT16>,
+    /// (LED, on), the LED being `None` changes all of them
+    pub led: Option<(Option<CARD8>, bool)>,
+    /// (key, mode), the key being `None` changes the global auto repeat
+    pub auto_repeat: Option<(Option<KEYCODE>, AutoRepeatMode)>,
+}
+
+impl KeyboardControlChange {
+    /// the (value-mask bit, value) pairs
+    fn values(&self) -> Vec<(CARD32, CARD32)> {
+        let mut values = vec![];
+        if let Some(percent) = self.key_click_percent {
+            values.push((0x01, percent as CARD32));
+        }
+        if let Some(percent) = self.bell_percent {
+            values.push((0x02, percent as CARD32));
+        }
+        if let Some(pitch) = self.bell_pitch {
+            values.push((0x04, pitch as CARD32));
+        }
+        if let Some(duration) = self.bell_duration {
+            values.push((0x08, duration as CARD32));
+        }
+        if let Some((led, on)) = self.led {
+            if let Some(led) = led {
+                values.push((0x10, led as CARD32));
+            }
+            values.push((0x20, on as CARD32));
+        }
+        if let Some((key, mode)) = self.auto_repeat {
+            if let Some(key) = key {
+                values.push((0x40, key as CARD32));
+            }
+            values.push((0x80, mode as CARD32));
+        }
+        values
+    }
+}
+
+pub fn change_keyboard_control<S: io::Write>(
+    socket: &mut S,
+    change: &KeyboardControlChange,
+) -> io::Result<()> {
+    proto::xproto::change_keyboard_control(socket, &change.values())
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    pub fn get_keyboard_mapping(
+        &self,
+        first_keycode: KEYCODE,
+        count: CARD8,
+    ) -> Result<KeyboardMapping, XErrorKind> {
+        self.socket_cb(|socket| get_keyboard_mapping_request(socket, first_keycode, count))?;
+        Ok(KeyboardMapping::parse(&self.read_reply()?, first_keycode)?)
+    }
+
+    pub fn get_modifier_mapping(&self) -> Result<ModifierMapping, XErrorKind> {
+        self.socket_cb(|socket| get_modifier_mapping_request(socket))?;
+        Ok(ModifierMapping::parse(&self.read_reply()?)?)
+    }
+
+    /// Returns the cached keymap, fetching it first if there isn't one yet
+    /// or a MappingNotify threw the old one out
+    pub fn keymap(&self) -> Result<Rc<Keymap>, XErrorKind> {
+        if let Some(keymap) = self.keymap.borrow().as_ref() {
+            return Ok(keymap.clone());
+        }
+        let (min, max) = (self.info.min_keycode, self.info.max_keycode);
+        // the setup reply is the server's word, 0..=255 or a max below min don't fit a CARD8
+        let count = max.checked_sub(min).and_then(|span| span.checked_add(1));
+        let count = count.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("keycode range {}..={}", min, max),
+            )
+        })?;
+        let mapping = self.get_keyboard_mapping(self.info.min_keycode, count)?;
+        let modifiers = self.get_modifier_mapping()?;
+        let keymap = Rc::new(Keymap::new(mapping, modifiers));
+        *self.keymap.borrow_mut() = Some(keymap.clone());
+        Ok(keymap)
+    }
+
+    /// shorthand for `keymap()?.lookup(keycode, state)`
+    pub fn lookup_keysym(&self, keycode: KEYCODE, state: CARD16) -> Result<Keysym, XErrorKind> {
+        Ok(self.keymap()?.lookup(keycode, state))
+    }
+
+    /// which keys are held down right now
+    pub fn query_keymap(&self) -> Result<KeyVector, XErrorKind> {
+        self.socket_cb(|socket| query_keymap_request(socket))?;
+        let reply = self.read_reply()?;
+        let mut keys = KeyVector::default();
+        keys.0
+            .copy_from_slice(reply.get(8..40).ok_or(XErrorKind::Unknown)?);
+        Ok(keys)
+    }
+
+    pub fn get_keyboard_control(&self) -> Result<KeyboardControl, XErrorKind> {
+        self.socket_cb(|socket| get_keyboard_control_request(socket))?;
+        let reply = self.read_reply()?;
+        let reply = reply.get(..52).ok_or(XErrorKind::Unknown)?;
+        let mut auto_repeats = KeyVector::default();
+        auto_repeats.0.copy_from_slice(&reply[20..52]);
+        Ok(KeyboardControl {
+            global_auto_repeat: reply[1] != 0,
+            led_mask: CARD32::from_le_bytes([reply[8], reply[9], reply[10], reply[11]]),
+            key_click_percent: reply[12],
+            bell_percent: reply[13],
+            bell_pitch: CARD16::from_le_bytes([reply[14], reply[15]]),
+            bell_duration: CARD16::from_le_bytes([reply[16], reply[17]]),
+            auto_repeats,
+        })
+    }
+}
+
+#[test]
+pub fn keymap_lookup() {
+    let no = keysyms::NO_SYMBOL;
+    // keycode 10: a A, 11: 1 exclam, 12: KP_End KP_1, 13: Caps_Lock, 14: Num_Lock, 15: adiaeresis
+    let mapping = KeyboardMapping {
+        first_keycode: 10,
+        keysyms_per_keycode: 2,
+        keysyms: vec![
+            Keysym(0x61),
+            no,
+            Keysym(0x31),
+            Keysym(0x21),
+            keysyms::KP_END,
+            Keysym(0xffb1),
+            keysyms::CAPS_LOCK,
+            no,
+            keysyms::NUM_LOCK,
+            no,
+            Keysym(0xe4),
+            no,
+        ],
+    };
+    let modifiers = ModifierMapping {
+        keycodes_per_modifier: 1,
+        keycodes: vec![0, 13, 0, 0, 14, 0, 0, 0],
+    };
+    let keymap = Keymap::new(mapping, modifiers);
+    let (shift, lock, num) = (key_masks::SHIFT, key_masks::LOCK, key_masks::MOD2);
+    assert_eq!(keymap.lock_kind(), LockKind::CapsLock);
+    assert_eq!(keymap.num_lock_mask(), num);
+    assert_eq!(keymap.lookup(10, 0), Keysym(0x61));
+    assert_eq!(keymap.lookup(10, shift), Keysym(0x41));
+    assert_eq!(keymap.lookup(10, lock), Keysym(0x41));
+    assert_eq!(keymap.lookup(11, lock), Keysym(0x31));
+    assert_eq!(keymap.lookup(11, shift | lock), Keysym(0x21));
+    assert_eq!(keymap.lookup(12, 0), keysyms::KP_END);
+    assert_eq!(keymap.lookup(12, num), Keysym(0xffb1));
+    assert_eq!(keymap.lookup(12, num | shift), keysyms::KP_END);
+    assert_eq!(keymap.lookup(15, shift).to_char(), Some('Ä'));
+    assert_eq!(keymap.keycodes_for(Keysym(0x21)), vec![(11, 1)]);
+
+    // more keysyms than keycodes left, the ones past 255 are dropped instead of wrapping
+    let mut reply = vec![0u8; 32];
+    reply[1] = 1;
+    reply.extend((0..10).flat_map(|_| 0x61u32.to_le_bytes()));
+    let mapping = KeyboardMapping::parse(&reply, 250).unwrap();
+    let keymap = Keymap::new(mapping, ModifierMapping::default());
+    let keycodes = keymap.keycodes_for(Keysym(0x61));
+    assert_eq!(keycodes.first(), Some(&(250, 0)));
+    assert_eq!(keycodes.last(), Some(&(255, 0)));
+}
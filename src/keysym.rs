@@ -0,0 +1,1145 @@
+use super::*;
+
+/// A symbol engraved on a key, see appendix A of the protocol spec
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Keysym(pub CARD32);
+
+/// keysyms from `keysymdef.h` that don't have a printable Latin-1 form
+pub mod keysyms {
+    use super::Keysym;
+    pub const NO_SYMBOL: Keysym = Keysym(0);
+    pub const VOID_SYMBOL: Keysym = Keysym(0xffffff);
+    pub const BACKSPACE: Keysym = Keysym(0xff08);
+    pub const TAB: Keysym = Keysym(0xff09);
+    pub const LINEFEED: Keysym = Keysym(0xff0a);
+    pub const CLEAR: Keysym = Keysym(0xff0b);
+    pub const RETURN: Keysym = Keysym(0xff0d);
+    pub const PAUSE: Keysym = Keysym(0xff13);
+    pub const SCROLL_LOCK: Keysym = Keysym(0xff14);
+    pub const SYS_REQ: Keysym = Keysym(0xff15);
+    pub const ESCAPE: Keysym = Keysym(0xff1b);
+    pub const MULTI_KEY: Keysym = Keysym(0xff20);
+    pub const HOME: Keysym = Keysym(0xff50);
+    pub const LEFT: Keysym = Keysym(0xff51);
+    pub const UP: Keysym = Keysym(0xff52);
+    pub const RIGHT: Keysym = Keysym(0xff53);
+    pub const DOWN: Keysym = Keysym(0xff54);
+    pub const PAGE_UP: Keysym = Keysym(0xff55);
+    pub const PAGE_DOWN: Keysym = Keysym(0xff56);
+    pub const END: Keysym = Keysym(0xff57);
+    pub const BEGIN: Keysym = Keysym(0xff58);
+    pub const SELECT: Keysym = Keysym(0xff60);
+    pub const PRINT: Keysym = Keysym(0xff61);
+    pub const EXECUTE: Keysym = Keysym(0xff62);
+    pub const INSERT: Keysym = Keysym(0xff63);
+    pub const UNDO: Keysym = Keysym(0xff65);
+    pub const REDO: Keysym = Keysym(0xff66);
+    pub const MENU: Keysym = Keysym(0xff67);
+    pub const FIND: Keysym = Keysym(0xff68);
+    pub const CANCEL: Keysym = Keysym(0xff69);
+    pub const HELP: Keysym = Keysym(0xff6a);
+    pub const BREAK: Keysym = Keysym(0xff6b);
+    pub const MODE_SWITCH: Keysym = Keysym(0xff7e);
+    pub const NUM_LOCK: Keysym = Keysym(0xff7f);
+    pub const KP_SPACE: Keysym = Keysym(0xff80);
+    pub const KP_TAB: Keysym = Keysym(0xff89);
+    pub const KP_ENTER: Keysym = Keysym(0xff8d);
+    pub const KP_HOME: Keysym = Keysym(0xff95);
+    pub const KP_LEFT: Keysym = Keysym(0xff96);
+    pub const KP_UP: Keysym = Keysym(0xff97);
+    pub const KP_RIGHT: Keysym = Keysym(0xff98);
+    pub const KP_DOWN: Keysym = Keysym(0xff99);
+    pub const KP_PAGE_UP: Keysym = Keysym(0xff9a);
+    pub const KP_PAGE_DOWN: Keysym = Keysym(0xff9b);
+    pub const KP_END: Keysym = Keysym(0xff9c);
+    pub const KP_BEGIN: Keysym = Keysym(0xff9d);
+    pub const KP_INSERT: Keysym = Keysym(0xff9e);
+    pub const KP_DELETE: Keysym = Keysym(0xff9f);
+    pub const KP_MULTIPLY: Keysym = Keysym(0xffaa);
+    pub const KP_ADD: Keysym = Keysym(0xffab);
+    pub const KP_SEPARATOR: Keysym = Keysym(0xffac);
+    pub const KP_SUBTRACT: Keysym = Keysym(0xffad);
+    pub const KP_DECIMAL: Keysym = Keysym(0xffae);
+    pub const KP_DIVIDE: Keysym = Keysym(0xffaf);
+    pub const KP_0: Keysym = Keysym(0xffb0);
+    pub const KP_9: Keysym = Keysym(0xffb9);
+    pub const KP_EQUAL: Keysym = Keysym(0xffbd);
+    pub const F1: Keysym = Keysym(0xffbe);
+    pub const F35: Keysym = Keysym(0xffe0);
+    pub const SHIFT_L: Keysym = Keysym(0xffe1);
+    pub const SHIFT_R: Keysym = Keysym(0xffe2);
+    pub const CONTROL_L: Keysym = Keysym(0xffe3);
+    pub const CONTROL_R: Keysym = Keysym(0xffe4);
+    pub const CAPS_LOCK: Keysym = Keysym(0xffe5);
+    pub const SHIFT_LOCK: Keysym = Keysym(0xffe6);
+    pub const META_L: Keysym = Keysym(0xffe7);
+    pub const META_R: Keysym = Keysym(0xffe8);
+    pub const ALT_L: Keysym = Keysym(0xffe9);
+    pub const ALT_R: Keysym = Keysym(0xffea);
+    pub const SUPER_L: Keysym = Keysym(0xffeb);
+    pub const SUPER_R: Keysym = Keysym(0xffec);
+    pub const HYPER_L: Keysym = Keysym(0xffed);
+    pub const HYPER_R: Keysym = Keysym(0xffee);
+    pub const ISO_LEVEL3_SHIFT: Keysym = Keysym(0xfe03);
+    pub const ISO_LEFT_TAB: Keysym = Keysym(0xfe20);
+    pub const DELETE: Keysym = Keysym(0xffff);
+}
+
+/// keysyms at or above this are `UNICODE_OFFSET + code point`
+const UNICODE_OFFSET: CARD32 = 0x0100_0000;
+
+/// names of the printable ASCII keysyms 0x20..=0x7e
+#[rustfmt::skip]
+const ASCII_NAMES: [&str; 95] = [
+    "space", "exclam", "quotedbl", "numbersign", "dollar", "percent", "ampersand", "apostrophe",
+    "parenleft", "parenright", "asterisk", "plus", "comma", "minus", "period", "slash",
+    "0", "1", "2", "3", "4", "5", "6", "7", "8", "9",
+    "colon", "semicolon", "less", "equal", "greater", "question", "at",
+    "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M",
+    "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+    "bracketleft", "backslash", "bracketright", "asciicircum", "underscore", "grave",
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m",
+    "n", "o", "p", "q", "r", "s", "t", "u", "v", "w", "x", "y", "z",
+    "braceleft", "bar", "braceright", "asciitilde",
+];
+
+/// names of the Latin-1 keysyms 0xa0..=0xff
+#[rustfmt::skip]
+const LATIN1_NAMES: [&str; 96] = [
+    "nobreakspace", "exclamdown", "cent", "sterling", "currency", "yen", "brokenbar", "section",
+    "diaeresis", "copyright", "ordfeminine", "guillemotleft", "notsign", "hyphen", "registered", "macron",
+    "degree", "plusminus", "twosuperior", "threesuperior", "acute", "mu", "paragraph", "periodcentered",
+    "cedilla", "onesuperior", "masculine", "guillemotright", "onequarter", "onehalf", "threequarters", "questiondown",
+    "Agrave", "Aacute", "Acircumflex", "Atilde", "Adiaeresis", "Aring", "AE", "Ccedilla",
+    "Egrave", "Eacute", "Ecircumflex", "Ediaeresis", "Igrave", "Iacute", "Icircumflex", "Idiaeresis",
+    "ETH", "Ntilde", "Ograve", "Oacute", "Ocircumflex", "Otilde", "Odiaeresis", "multiply",
+    "Oslash", "Ugrave", "Uacute", "Ucircumflex", "Udiaeresis", "Yacute", "THORN", "ssharp",
+    "agrave", "aacute", "acircumflex", "atilde", "adiaeresis", "aring", "ae", "ccedilla",
+    "egrave", "eacute", "ecircumflex", "ediaeresis", "igrave", "iacute", "icircumflex", "idiaeresis",
+    "eth", "ntilde", "ograve", "oacute", "ocircumflex", "otilde", "odiaeresis", "division",
+    "oslash", "ugrave", "uacute", "ucircumflex", "udiaeresis", "yacute", "thorn", "ydiaeresis",
+];
+
+/// function keys and friends: (keysym, name, text the key produces)
+#[rustfmt::skip]
+const FUNCTION_KEYS: &[(CARD32, &str, Option<char>)] = &[
+    (0xff08, "BackSpace", Some('\u{8}')),
+    (0xff09, "Tab", Some('\t')),
+    (0xff0a, "Linefeed", Some('\n')),
+    (0xff0b, "Clear", None),
+    (0xff0d, "Return", Some('\r')),
+    (0xff13, "Pause", None),
+    (0xff14, "Scroll_Lock", None),
+    (0xff15, "Sys_Req", None),
+    (0xff1b, "Escape", Some('\u{1b}')),
+    (0xff20, "Multi_key", None),
+    (0xff50, "Home", None),
+    (0xff51, "Left", None),
+    (0xff52, "Up", None),
+    (0xff53, "Right", None),
+    (0xff54, "Down", None),
+    (0xff55, "Prior", None),
+    (0xff56, "Next", None),
+    (0xff57, "End", None),
+    (0xff58, "Begin", None),
+    (0xff60, "Select", None),
+    (0xff61, "Print", None),
+    (0xff62, "Execute", None),
+    (0xff63, "Insert", None),
+    (0xff65, "Undo", None),
+    (0xff66, "Redo", None),
+    (0xff67, "Menu", None),
+    (0xff68, "Find", None),
+    (0xff69, "Cancel", None),
+    (0xff6a, "Help", None),
+    (0xff6b, "Break", None),
+    (0xff7e, "Mode_switch", None),
+    (0xff7f, "Num_Lock", None),
+    (0xff80, "KP_Space", Some(' ')),
+    (0xff89, "KP_Tab", Some('\t')),
+    (0xff8d, "KP_Enter", Some('\r')),
+    (0xff91, "KP_F1", None),
+    (0xff92, "KP_F2", None),
+    (0xff93, "KP_F3", None),
+    (0xff94, "KP_F4", None),
+    (0xff95, "KP_Home", None),
+    (0xff96, "KP_Left", None),
+    (0xff97, "KP_Up", None),
+    (0xff98, "KP_Right", None),
+    (0xff99, "KP_Down", None),
+    (0xff9a, "KP_Prior", None),
+    (0xff9b, "KP_Next", None),
+    (0xff9c, "KP_End", None),
+    (0xff9d, "KP_Begin", None),
+    (0xff9e, "KP_Insert", None),
+    (0xff9f, "KP_Delete", None),
+    (0xffaa, "KP_Multiply", Some('*')),
+    (0xffab, "KP_Add", Some('+')),
+    (0xffac, "KP_Separator", Some(',')),
+    (0xffad, "KP_Subtract", Some('-')),
+    (0xffae, "KP_Decimal", Some('.')),
+    (0xffaf, "KP_Divide", Some('/')),
+    (0xffb0, "KP_0", Some('0')),
+    (0xffb1, "KP_1", Some('1')),
+    (0xffb2, "KP_2", Some('2')),
+    (0xffb3, "KP_3", Some('3')),
+    (0xffb4, "KP_4", Some('4')),
+    (0xffb5, "KP_5", Some('5')),
+    (0xffb6, "KP_6", Some('6')),
+    (0xffb7, "KP_7", Some('7')),
+    (0xffb8, "KP_8", Some('8')),
+    (0xffb9, "KP_9", Some('9')),
+    (0xffbd, "KP_Equal", Some('=')),
+    (0xffe1, "Shift_L", None),
+    (0xffe2, "Shift_R", None),
+    (0xffe3, "Control_L", None),
+    (0xffe4, "Control_R", None),
+    (0xffe5, "Caps_Lock", None),
+    (0xffe6, "Shift_Lock", None),
+    (0xffe7, "Meta_L", None),
+    (0xffe8, "Meta_R", None),
+    (0xffe9, "Alt_L", None),
+    (0xffea, "Alt_R", None),
+    (0xffeb, "Super_L", None),
+    (0xffec, "Super_R", None),
+    (0xffed, "Hyper_L", None),
+    (0xffee, "Hyper_R", None),
+    (0xfe03, "ISO_Level3_Shift", None),
+    (0xfe20, "ISO_Left_Tab", Some('\t')),
+    (0xffff, "Delete", Some('\u{7f}')),
+];
+
+/// the legacy sets of `keysymdef.h` (Latin-2..9, Kana, Arabic, Cyrillic, Greek, technical,
+/// publishing, APL, Hebrew, Thai, Korean, ...): (keysym, name, unicode equivalent).\
+/// Sorted by keysym, an alias comes after the preferred name. The unicode column is the
+/// table of xkbcommon's keysym-utf.c
+#[rustfmt::skip]
+const LEGACY_KEYSYMS: &[(CARD32, &str, Option<char>)] = &[
+    (0x01a1, "Aogonek", Some('\u{104}')),
+    (0x01a2, "breve", Some('\u{2d8}')),
+    (0x01a3, "Lstroke", Some('\u{141}')),
+    (0x01a5, "Lcaron", Some('\u{13d}')),
+    (0x01a6, "Sacute", Some('\u{15a}')),
+    (0x01a9, "Scaron", Some('\u{160}')),
+    (0x01aa, "Scedilla", Some('\u{15e}')),
+    (0x01ab, "Tcaron", Some('\u{164}')),
+    (0x01ac, "Zacute", Some('\u{179}')),
+    (0x01ae, "Zcaron", Some('\u{17d}')),
+    (0x01af, "Zabovedot", Some('\u{17b}')),
+    (0x01b1, "aogonek", Some('\u{105}')),
+    (0x01b2, "ogonek", Some('\u{2db}')),
+    (0x01b3, "lstroke", Some('\u{142}')),
+    (0x01b5, "lcaron", Some('\u{13e}')),
+    (0x01b6, "sacute", Some('\u{15b}')),
+    (0x01b7, "caron", Some('\u{2c7}')),
+    (0x01b9, "scaron", Some('\u{161}')),
+    (0x01ba, "scedilla", Some('\u{15f}')),
+    (0x01bb, "tcaron", Some('\u{165}')),
+    (0x01bc, "zacute", Some('\u{17a}')),
+    (0x01bd, "doubleacute", Some('\u{2dd}')),
+    (0x01be, "zcaron", Some('\u{17e}')),
+    (0x01bf, "zabovedot", Some('\u{17c}')),
+    (0x01c0, "Racute", Some('\u{154}')),
+    (0x01c3, "Abreve", Some('\u{102}')),
+    (0x01c5, "Lacute", Some('\u{139}')),
+    (0x01c6, "Cacute", Some('\u{106}')),
+    (0x01c8, "Ccaron", Some('\u{10c}')),
+    (0x01ca, "Eogonek", Some('\u{118}')),
+    (0x01cc, "Ecaron", Some('\u{11a}')),
+    (0x01cf, "Dcaron", Some('\u{10e}')),
+    (0x01d0, "Dstroke", Some('\u{110}')),
+    (0x01d1, "Nacute", Some('\u{143}')),
+    (0x01d2, "Ncaron", Some('\u{147}')),
+    (0x01d5, "Odoubleacute", Some('\u{150}')),
+    (0x01d8, "Rcaron", Some('\u{158}')),
+    (0x01d9, "Uring", Some('\u{16e}')),
+    (0x01db, "Udoubleacute", Some('\u{170}')),
+    (0x01de, "Tcedilla", Some('\u{162}')),
+    (0x01e0, "racute", Some('\u{155}')),
+    (0x01e3, "abreve", Some('\u{103}')),
+    (0x01e5, "lacute", Some('\u{13a}')),
+    (0x01e6, "cacute", Some('\u{107}')),
+    (0x01e8, "ccaron", Some('\u{10d}')),
+    (0x01ea, "eogonek", Some('\u{119}')),
+    (0x01ec, "ecaron", Some('\u{11b}')),
+    (0x01ef, "dcaron", Some('\u{10f}')),
+    (0x01f0, "dstroke", Some('\u{111}')),
+    (0x01f1, "nacute", Some('\u{144}')),
+    (0x01f2, "ncaron", Some('\u{148}')),
+    (0x01f5, "odoubleacute", Some('\u{151}')),
+    (0x01f8, "rcaron", Some('\u{159}')),
+    (0x01f9, "uring", Some('\u{16f}')),
+    (0x01fb, "udoubleacute", Some('\u{171}')),
+    (0x01fe, "tcedilla", Some('\u{163}')),
+    (0x01ff, "abovedot", Some('\u{2d9}')),
+    (0x02a1, "Hstroke", Some('\u{126}')),
+    (0x02a6, "Hcircumflex", Some('\u{124}')),
+    (0x02a9, "Iabovedot", Some('\u{130}')),
+    (0x02ab, "Gbreve", Some('\u{11e}')),
+    (0x02ac, "Jcircumflex", Some('\u{134}')),
+    (0x02b1, "hstroke", Some('\u{127}')),
+    (0x02b6, "hcircumflex", Some('\u{125}')),
+    (0x02b9, "idotless", Some('\u{131}')),
+    (0x02bb, "gbreve", Some('\u{11f}')),
+    (0x02bc, "jcircumflex", Some('\u{135}')),
+    (0x02c5, "Cabovedot", Some('\u{10a}')),
+    (0x02c6, "Ccircumflex", Some('\u{108}')),
+    (0x02d5, "Gabovedot", Some('\u{120}')),
+    (0x02d8, "Gcircumflex", Some('\u{11c}')),
+    (0x02dd, "Ubreve", Some('\u{16c}')),
+    (0x02de, "Scircumflex", Some('\u{15c}')),
+    (0x02e5, "cabovedot", Some('\u{10b}')),
+    (0x02e6, "ccircumflex", Some('\u{109}')),
+    (0x02f5, "gabovedot", Some('\u{121}')),
+    (0x02f8, "gcircumflex", Some('\u{11d}')),
+    (0x02fd, "ubreve", Some('\u{16d}')),
+    (0x02fe, "scircumflex", Some('\u{15d}')),
+    (0x03a2, "kra", Some('\u{138}')),
+    (0x03a3, "Rcedilla", Some('\u{156}')),
+    (0x03a5, "Itilde", Some('\u{128}')),
+    (0x03a6, "Lcedilla", Some('\u{13b}')),
+    (0x03aa, "Emacron", Some('\u{112}')),
+    (0x03ab, "Gcedilla", Some('\u{122}')),
+    (0x03ac, "Tslash", Some('\u{166}')),
+    (0x03b3, "rcedilla", Some('\u{157}')),
+    (0x03b5, "itilde", Some('\u{129}')),
+    (0x03b6, "lcedilla", Some('\u{13c}')),
+    (0x03ba, "emacron", Some('\u{113}')),
+    (0x03bb, "gcedilla", Some('\u{123}')),
+    (0x03bc, "tslash", Some('\u{167}')),
+    (0x03bd, "ENG", Some('\u{14a}')),
+    (0x03bf, "eng", Some('\u{14b}')),
+    (0x03c0, "Amacron", Some('\u{100}')),
+    (0x03c7, "Iogonek", Some('\u{12e}')),
+    (0x03cc, "Eabovedot", Some('\u{116}')),
+    (0x03cf, "Imacron", Some('\u{12a}')),
+    (0x03d1, "Ncedilla", Some('\u{145}')),
+    (0x03d2, "Omacron", Some('\u{14c}')),
+    (0x03d3, "Kcedilla", Some('\u{136}')),
+    (0x03d9, "Uogonek", Some('\u{172}')),
+    (0x03dd, "Utilde", Some('\u{168}')),
+    (0x03de, "Umacron", Some('\u{16a}')),
+    (0x03e0, "amacron", Some('\u{101}')),
+    (0x03e7, "iogonek", Some('\u{12f}')),
+    (0x03ec, "eabovedot", Some('\u{117}')),
+    (0x03ef, "imacron", Some('\u{12b}')),
+    (0x03f1, "ncedilla", Some('\u{146}')),
+    (0x03f2, "omacron", Some('\u{14d}')),
+    (0x03f3, "kcedilla", Some('\u{137}')),
+    (0x03f9, "uogonek", Some('\u{173}')),
+    (0x03fd, "utilde", Some('\u{169}')),
+    (0x03fe, "umacron", Some('\u{16b}')),
+    (0x047e, "overline", Some('\u{203e}')),
+    (0x04a1, "kana_fullstop", Some('\u{3002}')),
+    (0x04a2, "kana_openingbracket", Some('\u{300c}')),
+    (0x04a3, "kana_closingbracket", Some('\u{300d}')),
+    (0x04a4, "kana_comma", Some('\u{3001}')),
+    (0x04a5, "kana_conjunctive", Some('\u{30fb}')),
+    (0x04a6, "kana_WO", Some('\u{30f2}')),
+    (0x04a7, "kana_a", Some('\u{30a1}')),
+    (0x04a8, "kana_i", Some('\u{30a3}')),
+    (0x04a9, "kana_u", Some('\u{30a5}')),
+    (0x04aa, "kana_e", Some('\u{30a7}')),
+    (0x04ab, "kana_o", Some('\u{30a9}')),
+    (0x04ac, "kana_ya", Some('\u{30e3}')),
+    (0x04ad, "kana_yu", Some('\u{30e5}')),
+    (0x04ae, "kana_yo", Some('\u{30e7}')),
+    (0x04af, "kana_tsu", Some('\u{30c3}')),
+    (0x04b0, "prolongedsound", Some('\u{30fc}')),
+    (0x04b1, "kana_A", Some('\u{30a2}')),
+    (0x04b2, "kana_I", Some('\u{30a4}')),
+    (0x04b3, "kana_U", Some('\u{30a6}')),
+    (0x04b4, "kana_E", Some('\u{30a8}')),
+    (0x04b5, "kana_O", Some('\u{30aa}')),
+    (0x04b6, "kana_KA", Some('\u{30ab}')),
+    (0x04b7, "kana_KI", Some('\u{30ad}')),
+    (0x04b8, "kana_KU", Some('\u{30af}')),
+    (0x04b9, "kana_KE", Some('\u{30b1}')),
+    (0x04ba, "kana_KO", Some('\u{30b3}')),
+    (0x04bb, "kana_SA", Some('\u{30b5}')),
+    (0x04bc, "kana_SHI", Some('\u{30b7}')),
+    (0x04bd, "kana_SU", Some('\u{30b9}')),
+    (0x04be, "kana_SE", Some('\u{30bb}')),
+    (0x04bf, "kana_SO", Some('\u{30bd}')),
+    (0x04c0, "kana_TA", Some('\u{30bf}')),
+    (0x04c1, "kana_CHI", Some('\u{30c1}')),
+    (0x04c2, "kana_TSU", Some('\u{30c4}')),
+    (0x04c3, "kana_TE", Some('\u{30c6}')),
+    (0x04c4, "kana_TO", Some('\u{30c8}')),
+    (0x04c5, "kana_NA", Some('\u{30ca}')),
+    (0x04c6, "kana_NI", Some('\u{30cb}')),
+    (0x04c7, "kana_NU", Some('\u{30cc}')),
+    (0x04c8, "kana_NE", Some('\u{30cd}')),
+    (0x04c9, "kana_NO", Some('\u{30ce}')),
+    (0x04ca, "kana_HA", Some('\u{30cf}')),
+    (0x04cb, "kana_HI", Some('\u{30d2}')),
+    (0x04cc, "kana_FU", Some('\u{30d5}')),
+    (0x04cd, "kana_HE", Some('\u{30d8}')),
+    (0x04ce, "kana_HO", Some('\u{30db}')),
+    (0x04cf, "kana_MA", Some('\u{30de}')),
+    (0x04d0, "kana_MI", Some('\u{30df}')),
+    (0x04d1, "kana_MU", Some('\u{30e0}')),
+    (0x04d2, "kana_ME", Some('\u{30e1}')),
+    (0x04d3, "kana_MO", Some('\u{30e2}')),
+    (0x04d4, "kana_YA", Some('\u{30e4}')),
+    (0x04d5, "kana_YU", Some('\u{30e6}')),
+    (0x04d6, "kana_YO", Some('\u{30e8}')),
+    (0x04d7, "kana_RA", Some('\u{30e9}')),
+    (0x04d8, "kana_RI", Some('\u{30ea}')),
+    (0x04d9, "kana_RU", Some('\u{30eb}')),
+    (0x04da, "kana_RE", Some('\u{30ec}')),
+    (0x04db, "kana_RO", Some('\u{30ed}')),
+    (0x04dc, "kana_WA", Some('\u{30ef}')),
+    (0x04dd, "kana_N", Some('\u{30f3}')),
+    (0x04de, "voicedsound", Some('\u{309b}')),
+    (0x04df, "semivoicedsound", Some('\u{309c}')),
+    (0x05ac, "Arabic_comma", Some('\u{60c}')),
+    (0x05bb, "Arabic_semicolon", Some('\u{61b}')),
+    (0x05bf, "Arabic_question_mark", Some('\u{61f}')),
+    (0x05c1, "Arabic_hamza", Some('\u{621}')),
+    (0x05c2, "Arabic_maddaonalef", Some('\u{622}')),
+    (0x05c3, "Arabic_hamzaonalef", Some('\u{623}')),
+    (0x05c4, "Arabic_hamzaonwaw", Some('\u{624}')),
+    (0x05c5, "Arabic_hamzaunderalef", Some('\u{625}')),
+    (0x05c6, "Arabic_hamzaonyeh", Some('\u{626}')),
+    (0x05c7, "Arabic_alef", Some('\u{627}')),
+    (0x05c8, "Arabic_beh", Some('\u{628}')),
+    (0x05c9, "Arabic_tehmarbuta", Some('\u{629}')),
+    (0x05ca, "Arabic_teh", Some('\u{62a}')),
+    (0x05cb, "Arabic_theh", Some('\u{62b}')),
+    (0x05cc, "Arabic_jeem", Some('\u{62c}')),
+    (0x05cd, "Arabic_hah", Some('\u{62d}')),
+    (0x05ce, "Arabic_khah", Some('\u{62e}')),
+    (0x05cf, "Arabic_dal", Some('\u{62f}')),
+    (0x05d0, "Arabic_thal", Some('\u{630}')),
+    (0x05d1, "Arabic_ra", Some('\u{631}')),
+    (0x05d2, "Arabic_zain", Some('\u{632}')),
+    (0x05d3, "Arabic_seen", Some('\u{633}')),
+    (0x05d4, "Arabic_sheen", Some('\u{634}')),
+    (0x05d5, "Arabic_sad", Some('\u{635}')),
+    (0x05d6, "Arabic_dad", Some('\u{636}')),
+    (0x05d7, "Arabic_tah", Some('\u{637}')),
+    (0x05d8, "Arabic_zah", Some('\u{638}')),
+    (0x05d9, "Arabic_ain", Some('\u{639}')),
+    (0x05da, "Arabic_ghain", Some('\u{63a}')),
+    (0x05e0, "Arabic_tatweel", Some('\u{640}')),
+    (0x05e1, "Arabic_feh", Some('\u{641}')),
+    (0x05e2, "Arabic_qaf", Some('\u{642}')),
+    (0x05e3, "Arabic_kaf", Some('\u{643}')),
+    (0x05e4, "Arabic_lam", Some('\u{644}')),
+    (0x05e5, "Arabic_meem", Some('\u{645}')),
+    (0x05e6, "Arabic_noon", Some('\u{646}')),
+    (0x05e7, "Arabic_ha", Some('\u{647}')),
+    (0x05e8, "Arabic_waw", Some('\u{648}')),
+    (0x05e9, "Arabic_alefmaksura", Some('\u{649}')),
+    (0x05ea, "Arabic_yeh", Some('\u{64a}')),
+    (0x05eb, "Arabic_fathatan", Some('\u{64b}')),
+    (0x05ec, "Arabic_dammatan", Some('\u{64c}')),
+    (0x05ed, "Arabic_kasratan", Some('\u{64d}')),
+    (0x05ee, "Arabic_fatha", Some('\u{64e}')),
+    (0x05ef, "Arabic_damma", Some('\u{64f}')),
+    (0x05f0, "Arabic_kasra", Some('\u{650}')),
+    (0x05f1, "Arabic_shadda", Some('\u{651}')),
+    (0x05f2, "Arabic_sukun", Some('\u{652}')),
+    (0x06a1, "Serbian_dje", Some('\u{452}')),
+    (0x06a2, "Macedonia_gje", Some('\u{453}')),
+    (0x06a3, "Cyrillic_io", Some('\u{451}')),
+    (0x06a4, "Ukrainian_ie", Some('\u{454}')),
+    (0x06a5, "Macedonia_dse", Some('\u{455}')),
+    (0x06a6, "Ukrainian_i", Some('\u{456}')),
+    (0x06a7, "Ukrainian_yi", Some('\u{457}')),
+    (0x06a8, "Cyrillic_je", Some('\u{458}')),
+    (0x06a9, "Cyrillic_lje", Some('\u{459}')),
+    (0x06aa, "Cyrillic_nje", Some('\u{45a}')),
+    (0x06ab, "Serbian_tshe", Some('\u{45b}')),
+    (0x06ac, "Macedonia_kje", Some('\u{45c}')),
+    (0x06ad, "Ukrainian_ghe_with_upturn", Some('\u{491}')),
+    (0x06ae, "Byelorussian_shortu", Some('\u{45e}')),
+    (0x06af, "Cyrillic_dzhe", Some('\u{45f}')),
+    (0x06b0, "numerosign", Some('\u{2116}')),
+    (0x06b1, "Serbian_DJE", Some('\u{402}')),
+    (0x06b2, "Macedonia_GJE", Some('\u{403}')),
+    (0x06b3, "Cyrillic_IO", Some('\u{401}')),
+    (0x06b4, "Ukrainian_IE", Some('\u{404}')),
+    (0x06b5, "Macedonia_DSE", Some('\u{405}')),
+    (0x06b6, "Ukrainian_I", Some('\u{406}')),
+    (0x06b7, "Ukrainian_YI", Some('\u{407}')),
+    (0x06b8, "Cyrillic_JE", Some('\u{408}')),
+    (0x06b9, "Cyrillic_LJE", Some('\u{409}')),
+    (0x06ba, "Cyrillic_NJE", Some('\u{40a}')),
+    (0x06bb, "Serbian_TSHE", Some('\u{40b}')),
+    (0x06bc, "Macedonia_KJE", Some('\u{40c}')),
+    (0x06bd, "Ukrainian_GHE_WITH_UPTURN", Some('\u{490}')),
+    (0x06be, "Byelorussian_SHORTU", Some('\u{40e}')),
+    (0x06bf, "Cyrillic_DZHE", Some('\u{40f}')),
+    (0x06c0, "Cyrillic_yu", Some('\u{44e}')),
+    (0x06c1, "Cyrillic_a", Some('\u{430}')),
+    (0x06c2, "Cyrillic_be", Some('\u{431}')),
+    (0x06c3, "Cyrillic_tse", Some('\u{446}')),
+    (0x06c4, "Cyrillic_de", Some('\u{434}')),
+    (0x06c5, "Cyrillic_ie", Some('\u{435}')),
+    (0x06c6, "Cyrillic_ef", Some('\u{444}')),
+    (0x06c7, "Cyrillic_ghe", Some('\u{433}')),
+    (0x06c8, "Cyrillic_ha", Some('\u{445}')),
+    (0x06c9, "Cyrillic_i", Some('\u{438}')),
+    (0x06ca, "Cyrillic_shorti", Some('\u{439}')),
+    (0x06cb, "Cyrillic_ka", Some('\u{43a}')),
+    (0x06cc, "Cyrillic_el", Some('\u{43b}')),
+    (0x06cd, "Cyrillic_em", Some('\u{43c}')),
+    (0x06ce, "Cyrillic_en", Some('\u{43d}')),
+    (0x06cf, "Cyrillic_o", Some('\u{43e}')),
+    (0x06d0, "Cyrillic_pe", Some('\u{43f}')),
+    (0x06d1, "Cyrillic_ya", Some('\u{44f}')),
+    (0x06d2, "Cyrillic_er", Some('\u{440}')),
+    (0x06d3, "Cyrillic_es", Some('\u{441}')),
+    (0x06d4, "Cyrillic_te", Some('\u{442}')),
+    (0x06d5, "Cyrillic_u", Some('\u{443}')),
+    (0x06d6, "Cyrillic_zhe", Some('\u{436}')),
+    (0x06d7, "Cyrillic_ve", Some('\u{432}')),
+    (0x06d8, "Cyrillic_softsign", Some('\u{44c}')),
+    (0x06d9, "Cyrillic_yeru", Some('\u{44b}')),
+    (0x06da, "Cyrillic_ze", Some('\u{437}')),
+    (0x06db, "Cyrillic_sha", Some('\u{448}')),
+    (0x06dc, "Cyrillic_e", Some('\u{44d}')),
+    (0x06dd, "Cyrillic_shcha", Some('\u{449}')),
+    (0x06de, "Cyrillic_che", Some('\u{447}')),
+    (0x06df, "Cyrillic_hardsign", Some('\u{44a}')),
+    (0x06e0, "Cyrillic_YU", Some('\u{42e}')),
+    (0x06e1, "Cyrillic_A", Some('\u{410}')),
+    (0x06e2, "Cyrillic_BE", Some('\u{411}')),
+    (0x06e3, "Cyrillic_TSE", Some('\u{426}')),
+    (0x06e4, "Cyrillic_DE", Some('\u{414}')),
+    (0x06e5, "Cyrillic_IE", Some('\u{415}')),
+    (0x06e6, "Cyrillic_EF", Some('\u{424}')),
+    (0x06e7, "Cyrillic_GHE", Some('\u{413}')),
+    (0x06e8, "Cyrillic_HA", Some('\u{425}')),
+    (0x06e9, "Cyrillic_I", Some('\u{418}')),
+    (0x06ea, "Cyrillic_SHORTI", Some('\u{419}')),
+    (0x06eb, "Cyrillic_KA", Some('\u{41a}')),
+    (0x06ec, "Cyrillic_EL", Some('\u{41b}')),
+    (0x06ed, "Cyrillic_EM", Some('\u{41c}')),
+    (0x06ee, "Cyrillic_EN", Some('\u{41d}')),
+    (0x06ef, "Cyrillic_O", Some('\u{41e}')),
+    (0x06f0, "Cyrillic_PE", Some('\u{41f}')),
+    (0x06f1, "Cyrillic_YA", Some('\u{42f}')),
+    (0x06f2, "Cyrillic_ER", Some('\u{420}')),
+    (0x06f3, "Cyrillic_ES", Some('\u{421}')),
+    (0x06f4, "Cyrillic_TE", Some('\u{422}')),
+    (0x06f5, "Cyrillic_U", Some('\u{423}')),
+    (0x06f6, "Cyrillic_ZHE", Some('\u{416}')),
+    (0x06f7, "Cyrillic_VE", Some('\u{412}')),
+    (0x06f8, "Cyrillic_SOFTSIGN", Some('\u{42c}')),
+    (0x06f9, "Cyrillic_YERU", Some('\u{42b}')),
+    (0x06fa, "Cyrillic_ZE", Some('\u{417}')),
+    (0x06fb, "Cyrillic_SHA", Some('\u{428}')),
+    (0x06fc, "Cyrillic_E", Some('\u{42d}')),
+    (0x06fd, "Cyrillic_SHCHA", Some('\u{429}')),
+    (0x06fe, "Cyrillic_CHE", Some('\u{427}')),
+    (0x06ff, "Cyrillic_HARDSIGN", Some('\u{42a}')),
+    (0x07a1, "Greek_ALPHAaccent", Some('\u{386}')),
+    (0x07a2, "Greek_EPSILONaccent", Some('\u{388}')),
+    (0x07a3, "Greek_ETAaccent", Some('\u{389}')),
+    (0x07a4, "Greek_IOTAaccent", Some('\u{38a}')),
+    (0x07a5, "Greek_IOTAdieresis", Some('\u{3aa}')),
+    (0x07a7, "Greek_OMICRONaccent", Some('\u{38c}')),
+    (0x07a8, "Greek_UPSILONaccent", Some('\u{38e}')),
+    (0x07a9, "Greek_UPSILONdieresis", Some('\u{3ab}')),
+    (0x07ab, "Greek_OMEGAaccent", Some('\u{38f}')),
+    (0x07ae, "Greek_accentdieresis", Some('\u{385}')),
+    (0x07af, "Greek_horizbar", Some('\u{2015}')),
+    (0x07b1, "Greek_alphaaccent", Some('\u{3ac}')),
+    (0x07b2, "Greek_epsilonaccent", Some('\u{3ad}')),
+    (0x07b3, "Greek_etaaccent", Some('\u{3ae}')),
+    (0x07b4, "Greek_iotaaccent", Some('\u{3af}')),
+    (0x07b5, "Greek_iotadieresis", Some('\u{3ca}')),
+    (0x07b6, "Greek_iotaaccentdieresis", Some('\u{390}')),
+    (0x07b7, "Greek_omicronaccent", Some('\u{3cc}')),
+    (0x07b8, "Greek_upsilonaccent", Some('\u{3cd}')),
+    (0x07b9, "Greek_upsilondieresis", Some('\u{3cb}')),
+    (0x07ba, "Greek_upsilonaccentdieresis", Some('\u{3b0}')),
+    (0x07bb, "Greek_omegaaccent", Some('\u{3ce}')),
+    (0x07c1, "Greek_ALPHA", Some('\u{391}')),
+    (0x07c2, "Greek_BETA", Some('\u{392}')),
+    (0x07c3, "Greek_GAMMA", Some('\u{393}')),
+    (0x07c4, "Greek_DELTA", Some('\u{394}')),
+    (0x07c5, "Greek_EPSILON", Some('\u{395}')),
+    (0x07c6, "Greek_ZETA", Some('\u{396}')),
+    (0x07c7, "Greek_ETA", Some('\u{397}')),
+    (0x07c8, "Greek_THETA", Some('\u{398}')),
+    (0x07c9, "Greek_IOTA", Some('\u{399}')),
+    (0x07ca, "Greek_KAPPA", Some('\u{39a}')),
+    (0x07cb, "Greek_LAMDA", Some('\u{39b}')),
+    (0x07cb, "Greek_LAMBDA", Some('\u{39b}')),
+    (0x07cc, "Greek_MU", Some('\u{39c}')),
+    (0x07cd, "Greek_NU", Some('\u{39d}')),
+    (0x07ce, "Greek_XI", Some('\u{39e}')),
+    (0x07cf, "Greek_OMICRON", Some('\u{39f}')),
+    (0x07d0, "Greek_PI", Some('\u{3a0}')),
+    (0x07d1, "Greek_RHO", Some('\u{3a1}')),
+    (0x07d2, "Greek_SIGMA", Some('\u{3a3}')),
+    (0x07d4, "Greek_TAU", Some('\u{3a4}')),
+    (0x07d5, "Greek_UPSILON", Some('\u{3a5}')),
+    (0x07d6, "Greek_PHI", Some('\u{3a6}')),
+    (0x07d7, "Greek_CHI", Some('\u{3a7}')),
+    (0x07d8, "Greek_PSI", Some('\u{3a8}')),
+    (0x07d9, "Greek_OMEGA", Some('\u{3a9}')),
+    (0x07e1, "Greek_alpha", Some('\u{3b1}')),
+    (0x07e2, "Greek_beta", Some('\u{3b2}')),
+    (0x07e3, "Greek_gamma", Some('\u{3b3}')),
+    (0x07e4, "Greek_delta", Some('\u{3b4}')),
+    (0x07e5, "Greek_epsilon", Some('\u{3b5}')),
+    (0x07e6, "Greek_zeta", Some('\u{3b6}')),
+    (0x07e7, "Greek_eta", Some('\u{3b7}')),
+    (0x07e8, "Greek_theta", Some('\u{3b8}')),
+    (0x07e9, "Greek_iota", Some('\u{3b9}')),
+    (0x07ea, "Greek_kappa", Some('\u{3ba}')),
+    (0x07eb, "Greek_lamda", Some('\u{3bb}')),
+    (0x07eb, "Greek_lambda", Some('\u{3bb}')),
+    (0x07ec, "Greek_mu", Some('\u{3bc}')),
+    (0x07ed, "Greek_nu", Some('\u{3bd}')),
+    (0x07ee, "Greek_xi", Some('\u{3be}')),
+    (0x07ef, "Greek_omicron", Some('\u{3bf}')),
+    (0x07f0, "Greek_pi", Some('\u{3c0}')),
+    (0x07f1, "Greek_rho", Some('\u{3c1}')),
+    (0x07f2, "Greek_sigma", Some('\u{3c3}')),
+    (0x07f3, "Greek_finalsmallsigma", Some('\u{3c2}')),
+    (0x07f4, "Greek_tau", Some('\u{3c4}')),
+    (0x07f5, "Greek_upsilon", Some('\u{3c5}')),
+    (0x07f6, "Greek_phi", Some('\u{3c6}')),
+    (0x07f7, "Greek_chi", Some('\u{3c7}')),
+    (0x07f8, "Greek_psi", Some('\u{3c8}')),
+    (0x07f9, "Greek_omega", Some('\u{3c9}')),
+    (0x08a1, "leftradical", Some('\u{23b7}')),
+    (0x08a2, "topleftradical", None),
+    (0x08a3, "horizconnector", None),
+    (0x08a4, "topintegral", Some('\u{2320}')),
+    (0x08a5, "botintegral", Some('\u{2321}')),
+    (0x08a6, "vertconnector", None),
+    (0x08a7, "topleftsqbracket", Some('\u{23a1}')),
+    (0x08a8, "botleftsqbracket", Some('\u{23a3}')),
+    (0x08a9, "toprightsqbracket", Some('\u{23a4}')),
+    (0x08aa, "botrightsqbracket", Some('\u{23a6}')),
+    (0x08ab, "topleftparens", Some('\u{239b}')),
+    (0x08ac, "botleftparens", Some('\u{239d}')),
+    (0x08ad, "toprightparens", Some('\u{239e}')),
+    (0x08ae, "botrightparens", Some('\u{23a0}')),
+    (0x08af, "leftmiddlecurlybrace", Some('\u{23a8}')),
+    (0x08b0, "rightmiddlecurlybrace", Some('\u{23ac}')),
+    (0x08b1, "topleftsummation", None),
+    (0x08b2, "botleftsummation", None),
+    (0x08b3, "topvertsummationconnector", None),
+    (0x08b4, "botvertsummationconnector", None),
+    (0x08b5, "toprightsummation", None),
+    (0x08b6, "botrightsummation", None),
+    (0x08b7, "rightmiddlesummation", None),
+    (0x08bc, "lessthanequal", Some('\u{2264}')),
+    (0x08bd, "notequal", Some('\u{2260}')),
+    (0x08be, "greaterthanequal", Some('\u{2265}')),
+    (0x08bf, "integral", Some('\u{222b}')),
+    (0x08c0, "therefore", Some('\u{2234}')),
+    (0x08c1, "variation", Some('\u{221d}')),
+    (0x08c2, "infinity", Some('\u{221e}')),
+    (0x08c5, "nabla", Some('\u{2207}')),
+    (0x08c8, "approximate", Some('\u{223c}')),
+    (0x08c9, "similarequal", Some('\u{2243}')),
+    (0x08cd, "ifonlyif", Some('\u{21d4}')),
+    (0x08ce, "implies", Some('\u{21d2}')),
+    (0x08cf, "identical", Some('\u{2261}')),
+    (0x08d6, "radical", Some('\u{221a}')),
+    (0x08da, "includedin", Some('\u{2282}')),
+    (0x08db, "includes", Some('\u{2283}')),
+    (0x08dc, "intersection", Some('\u{2229}')),
+    (0x08dd, "union", Some('\u{222a}')),
+    (0x08de, "logicaland", Some('\u{2227}')),
+    (0x08df, "logicalor", Some('\u{2228}')),
+    (0x08ef, "partialderivative", Some('\u{2202}')),
+    (0x08f6, "function", Some('\u{192}')),
+    (0x08fb, "leftarrow", Some('\u{2190}')),
+    (0x08fc, "uparrow", Some('\u{2191}')),
+    (0x08fd, "rightarrow", Some('\u{2192}')),
+    (0x08fe, "downarrow", Some('\u{2193}')),
+    (0x09df, "blank", None),
+    (0x09e0, "soliddiamond", Some('\u{25c6}')),
+    (0x09e1, "checkerboard", Some('\u{2592}')),
+    (0x09e2, "ht", Some('\u{2409}')),
+    (0x09e3, "ff", Some('\u{240c}')),
+    (0x09e4, "cr", Some('\u{240d}')),
+    (0x09e5, "lf", Some('\u{240a}')),
+    (0x09e8, "nl", Some('\u{2424}')),
+    (0x09e9, "vt", Some('\u{240b}')),
+    (0x09ea, "lowrightcorner", Some('\u{2518}')),
+    (0x09eb, "uprightcorner", Some('\u{2510}')),
+    (0x09ec, "upleftcorner", Some('\u{250c}')),
+    (0x09ed, "lowleftcorner", Some('\u{2514}')),
+    (0x09ee, "crossinglines", Some('\u{253c}')),
+    (0x09ef, "horizlinescan1", Some('\u{23ba}')),
+    (0x09f0, "horizlinescan3", Some('\u{23bb}')),
+    (0x09f1, "horizlinescan5", Some('\u{2500}')),
+    (0x09f2, "horizlinescan7", Some('\u{23bc}')),
+    (0x09f3, "horizlinescan9", Some('\u{23bd}')),
+    (0x09f4, "leftt", Some('\u{251c}')),
+    (0x09f5, "rightt", Some('\u{2524}')),
+    (0x09f6, "bott", Some('\u{2534}')),
+    (0x09f7, "topt", Some('\u{252c}')),
+    (0x09f8, "vertbar", Some('\u{2502}')),
+    (0x0aa1, "emspace", Some('\u{2003}')),
+    (0x0aa2, "enspace", Some('\u{2002}')),
+    (0x0aa3, "em3space", Some('\u{2004}')),
+    (0x0aa4, "em4space", Some('\u{2005}')),
+    (0x0aa5, "digitspace", Some('\u{2007}')),
+    (0x0aa6, "punctspace", Some('\u{2008}')),
+    (0x0aa7, "thinspace", Some('\u{2009}')),
+    (0x0aa8, "hairspace", Some('\u{200a}')),
+    (0x0aa9, "emdash", Some('\u{2014}')),
+    (0x0aaa, "endash", Some('\u{2013}')),
+    (0x0aac, "signifblank", None),
+    (0x0aae, "ellipsis", Some('\u{2026}')),
+    (0x0aaf, "doubbaselinedot", Some('\u{2025}')),
+    (0x0ab0, "onethird", Some('\u{2153}')),
+    (0x0ab1, "twothirds", Some('\u{2154}')),
+    (0x0ab2, "onefifth", Some('\u{2155}')),
+    (0x0ab3, "twofifths", Some('\u{2156}')),
+    (0x0ab4, "threefifths", Some('\u{2157}')),
+    (0x0ab5, "fourfifths", Some('\u{2158}')),
+    (0x0ab6, "onesixth", Some('\u{2159}')),
+    (0x0ab7, "fivesixths", Some('\u{215a}')),
+    (0x0ab8, "careof", Some('\u{2105}')),
+    (0x0abb, "figdash", Some('\u{2012}')),
+    (0x0abc, "leftanglebracket", None),
+    (0x0abd, "decimalpoint", None),
+    (0x0abe, "rightanglebracket", None),
+    (0x0abf, "marker", None),
+    (0x0ac3, "oneeighth", Some('\u{215b}')),
+    (0x0ac4, "threeeighths", Some('\u{215c}')),
+    (0x0ac5, "fiveeighths", Some('\u{215d}')),
+    (0x0ac6, "seveneighths", Some('\u{215e}')),
+    (0x0ac9, "trademark", Some('\u{2122}')),
+    (0x0aca, "signaturemark", None),
+    (0x0acb, "trademarkincircle", None),
+    (0x0acc, "leftopentriangle", None),
+    (0x0acd, "rightopentriangle", None),
+    (0x0ace, "emopencircle", None),
+    (0x0acf, "emopenrectangle", None),
+    (0x0ad0, "leftsinglequotemark", Some('\u{2018}')),
+    (0x0ad1, "rightsinglequotemark", Some('\u{2019}')),
+    (0x0ad2, "leftdoublequotemark", Some('\u{201c}')),
+    (0x0ad3, "rightdoublequotemark", Some('\u{201d}')),
+    (0x0ad4, "prescription", Some('\u{211e}')),
+    (0x0ad5, "permille", Some('\u{2030}')),
+    (0x0ad6, "minutes", Some('\u{2032}')),
+    (0x0ad7, "seconds", Some('\u{2033}')),
+    (0x0ad9, "latincross", Some('\u{271d}')),
+    (0x0ada, "hexagram", None),
+    (0x0adb, "filledrectbullet", None),
+    (0x0adc, "filledlefttribullet", None),
+    (0x0add, "filledrighttribullet", None),
+    (0x0ade, "emfilledcircle", None),
+    (0x0adf, "emfilledrect", None),
+    (0x0ae0, "enopencircbullet", None),
+    (0x0ae1, "enopensquarebullet", None),
+    (0x0ae2, "openrectbullet", None),
+    (0x0ae3, "opentribulletup", None),
+    (0x0ae4, "opentribulletdown", None),
+    (0x0ae5, "openstar", None),
+    (0x0ae6, "enfilledcircbullet", None),
+    (0x0ae7, "enfilledsqbullet", None),
+    (0x0ae8, "filledtribulletup", None),
+    (0x0ae9, "filledtribulletdown", None),
+    (0x0aea, "leftpointer", None),
+    (0x0aeb, "rightpointer", None),
+    (0x0aec, "club", Some('\u{2663}')),
+    (0x0aed, "diamond", Some('\u{2666}')),
+    (0x0aee, "heart", Some('\u{2665}')),
+    (0x0af0, "maltesecross", Some('\u{2720}')),
+    (0x0af1, "dagger", Some('\u{2020}')),
+    (0x0af2, "doubledagger", Some('\u{2021}')),
+    (0x0af3, "checkmark", Some('\u{2713}')),
+    (0x0af4, "ballotcross", Some('\u{2717}')),
+    (0x0af5, "musicalsharp", Some('\u{266f}')),
+    (0x0af6, "musicalflat", Some('\u{266d}')),
+    (0x0af7, "malesymbol", Some('\u{2642}')),
+    (0x0af8, "femalesymbol", Some('\u{2640}')),
+    (0x0af9, "telephone", Some('\u{260e}')),
+    (0x0afa, "telephonerecorder", Some('\u{2315}')),
+    (0x0afb, "phonographcopyright", Some('\u{2117}')),
+    (0x0afc, "caret", Some('\u{2038}')),
+    (0x0afd, "singlelowquotemark", Some('\u{201a}')),
+    (0x0afe, "doublelowquotemark", Some('\u{201e}')),
+    (0x0aff, "cursor", None),
+    (0x0ba3, "leftcaret", None),
+    (0x0ba6, "rightcaret", None),
+    (0x0ba8, "downcaret", None),
+    (0x0ba9, "upcaret", None),
+    (0x0bc0, "overbar", None),
+    (0x0bc2, "downtack", Some('\u{22a4}')),
+    (0x0bc3, "upshoe", None),
+    (0x0bc4, "downstile", Some('\u{230a}')),
+    (0x0bc6, "underbar", None),
+    (0x0bca, "jot", Some('\u{2218}')),
+    (0x0bcc, "quad", Some('\u{2395}')),
+    (0x0bce, "uptack", Some('\u{22a5}')),
+    (0x0bcf, "circle", Some('\u{25cb}')),
+    (0x0bd3, "upstile", Some('\u{2308}')),
+    (0x0bd6, "downshoe", None),
+    (0x0bd8, "rightshoe", None),
+    (0x0bda, "leftshoe", None),
+    (0x0bdc, "lefttack", Some('\u{22a3}')),
+    (0x0bfc, "righttack", Some('\u{22a2}')),
+    (0x0cdf, "hebrew_doublelowline", Some('\u{2017}')),
+    (0x0ce0, "hebrew_aleph", Some('\u{5d0}')),
+    (0x0ce1, "hebrew_bet", Some('\u{5d1}')),
+    (0x0ce2, "hebrew_gimel", Some('\u{5d2}')),
+    (0x0ce3, "hebrew_dalet", Some('\u{5d3}')),
+    (0x0ce4, "hebrew_he", Some('\u{5d4}')),
+    (0x0ce5, "hebrew_waw", Some('\u{5d5}')),
+    (0x0ce6, "hebrew_zain", Some('\u{5d6}')),
+    (0x0ce7, "hebrew_chet", Some('\u{5d7}')),
+    (0x0ce8, "hebrew_tet", Some('\u{5d8}')),
+    (0x0ce9, "hebrew_yod", Some('\u{5d9}')),
+    (0x0cea, "hebrew_finalkaph", Some('\u{5da}')),
+    (0x0ceb, "hebrew_kaph", Some('\u{5db}')),
+    (0x0cec, "hebrew_lamed", Some('\u{5dc}')),
+    (0x0ced, "hebrew_finalmem", Some('\u{5dd}')),
+    (0x0cee, "hebrew_mem", Some('\u{5de}')),
+    (0x0cef, "hebrew_finalnun", Some('\u{5df}')),
+    (0x0cf0, "hebrew_nun", Some('\u{5e0}')),
+    (0x0cf1, "hebrew_samech", Some('\u{5e1}')),
+    (0x0cf2, "hebrew_ayin", Some('\u{5e2}')),
+    (0x0cf3, "hebrew_finalpe", Some('\u{5e3}')),
+    (0x0cf4, "hebrew_pe", Some('\u{5e4}')),
+    (0x0cf5, "hebrew_finalzade", Some('\u{5e5}')),
+    (0x0cf6, "hebrew_zade", Some('\u{5e6}')),
+    (0x0cf7, "hebrew_qoph", Some('\u{5e7}')),
+    (0x0cf8, "hebrew_resh", Some('\u{5e8}')),
+    (0x0cf9, "hebrew_shin", Some('\u{5e9}')),
+    (0x0cfa, "hebrew_taw", Some('\u{5ea}')),
+    (0x0da1, "Thai_kokai", Some('\u{e01}')),
+    (0x0da2, "Thai_khokhai", Some('\u{e02}')),
+    (0x0da3, "Thai_khokhuat", Some('\u{e03}')),
+    (0x0da4, "Thai_khokhwai", Some('\u{e04}')),
+    (0x0da5, "Thai_khokhon", Some('\u{e05}')),
+    (0x0da6, "Thai_khorakhang", Some('\u{e06}')),
+    (0x0da7, "Thai_ngongu", Some('\u{e07}')),
+    (0x0da8, "Thai_chochan", Some('\u{e08}')),
+    (0x0da9, "Thai_choching", Some('\u{e09}')),
+    (0x0daa, "Thai_chochang", Some('\u{e0a}')),
+    (0x0dab, "Thai_soso", Some('\u{e0b}')),
+    (0x0dac, "Thai_chochoe", Some('\u{e0c}')),
+    (0x0dad, "Thai_yoying", Some('\u{e0d}')),
+    (0x0dae, "Thai_dochada", Some('\u{e0e}')),
+    (0x0daf, "Thai_topatak", Some('\u{e0f}')),
+    (0x0db0, "Thai_thothan", Some('\u{e10}')),
+    (0x0db1, "Thai_thonangmontho", Some('\u{e11}')),
+    (0x0db2, "Thai_thophuthao", Some('\u{e12}')),
+    (0x0db3, "Thai_nonen", Some('\u{e13}')),
+    (0x0db4, "Thai_dodek", Some('\u{e14}')),
+    (0x0db5, "Thai_totao", Some('\u{e15}')),
+    (0x0db6, "Thai_thothung", Some('\u{e16}')),
+    (0x0db7, "Thai_thothahan", Some('\u{e17}')),
+    (0x0db8, "Thai_thothong", Some('\u{e18}')),
+    (0x0db9, "Thai_nonu", Some('\u{e19}')),
+    (0x0dba, "Thai_bobaimai", Some('\u{e1a}')),
+    (0x0dbb, "Thai_popla", Some('\u{e1b}')),
+    (0x0dbc, "Thai_phophung", Some('\u{e1c}')),
+    (0x0dbd, "Thai_fofa", Some('\u{e1d}')),
+    (0x0dbe, "Thai_phophan", Some('\u{e1e}')),
+    (0x0dbf, "Thai_fofan", Some('\u{e1f}')),
+    (0x0dc0, "Thai_phosamphao", Some('\u{e20}')),
+    (0x0dc1, "Thai_moma", Some('\u{e21}')),
+    (0x0dc2, "Thai_yoyak", Some('\u{e22}')),
+    (0x0dc3, "Thai_rorua", Some('\u{e23}')),
+    (0x0dc4, "Thai_ru", Some('\u{e24}')),
+    (0x0dc5, "Thai_loling", Some('\u{e25}')),
+    (0x0dc6, "Thai_lu", Some('\u{e26}')),
+    (0x0dc7, "Thai_wowaen", Some('\u{e27}')),
+    (0x0dc8, "Thai_sosala", Some('\u{e28}')),
+    (0x0dc9, "Thai_sorusi", Some('\u{e29}')),
+    (0x0dca, "Thai_sosua", Some('\u{e2a}')),
+    (0x0dcb, "Thai_hohip", Some('\u{e2b}')),
+    (0x0dcc, "Thai_lochula", Some('\u{e2c}')),
+    (0x0dcd, "Thai_oang", Some('\u{e2d}')),
+    (0x0dce, "Thai_honokhuk", Some('\u{e2e}')),
+    (0x0dcf, "Thai_paiyannoi", Some('\u{e2f}')),
+    (0x0dd0, "Thai_saraa", Some('\u{e30}')),
+    (0x0dd1, "Thai_maihanakat", Some('\u{e31}')),
+    (0x0dd2, "Thai_saraaa", Some('\u{e32}')),
+    (0x0dd3, "Thai_saraam", Some('\u{e33}')),
+    (0x0dd4, "Thai_sarai", Some('\u{e34}')),
+    (0x0dd5, "Thai_saraii", Some('\u{e35}')),
+    (0x0dd6, "Thai_saraue", Some('\u{e36}')),
+    (0x0dd7, "Thai_sarauee", Some('\u{e37}')),
+    (0x0dd8, "Thai_sarau", Some('\u{e38}')),
+    (0x0dd9, "Thai_sarauu", Some('\u{e39}')),
+    (0x0dda, "Thai_phinthu", Some('\u{e3a}')),
+    (0x0dde, "Thai_maihanakat_maitho", None),
+    (0x0ddf, "Thai_baht", Some('\u{e3f}')),
+    (0x0de0, "Thai_sarae", Some('\u{e40}')),
+    (0x0de1, "Thai_saraae", Some('\u{e41}')),
+    (0x0de2, "Thai_sarao", Some('\u{e42}')),
+    (0x0de3, "Thai_saraaimaimuan", Some('\u{e43}')),
+    (0x0de4, "Thai_saraaimaimalai", Some('\u{e44}')),
+    (0x0de5, "Thai_lakkhangyao", Some('\u{e45}')),
+    (0x0de6, "Thai_maiyamok", Some('\u{e46}')),
+    (0x0de7, "Thai_maitaikhu", Some('\u{e47}')),
+    (0x0de8, "Thai_maiek", Some('\u{e48}')),
+    (0x0de9, "Thai_maitho", Some('\u{e49}')),
+    (0x0dea, "Thai_maitri", Some('\u{e4a}')),
+    (0x0deb, "Thai_maichattawa", Some('\u{e4b}')),
+    (0x0dec, "Thai_thanthakhat", Some('\u{e4c}')),
+    (0x0ded, "Thai_nikhahit", Some('\u{e4d}')),
+    (0x0df0, "Thai_leksun", Some('\u{e50}')),
+    (0x0df1, "Thai_leknung", Some('\u{e51}')),
+    (0x0df2, "Thai_leksong", Some('\u{e52}')),
+    (0x0df3, "Thai_leksam", Some('\u{e53}')),
+    (0x0df4, "Thai_leksi", Some('\u{e54}')),
+    (0x0df5, "Thai_lekha", Some('\u{e55}')),
+    (0x0df6, "Thai_lekhok", Some('\u{e56}')),
+    (0x0df7, "Thai_lekchet", Some('\u{e57}')),
+    (0x0df8, "Thai_lekpaet", Some('\u{e58}')),
+    (0x0df9, "Thai_lekkao", Some('\u{e59}')),
+    (0x0ea1, "Hangul_Kiyeog", Some('\u{3131}')),
+    (0x0ea2, "Hangul_SsangKiyeog", Some('\u{3132}')),
+    (0x0ea3, "Hangul_KiyeogSios", Some('\u{3133}')),
+    (0x0ea4, "Hangul_Nieun", Some('\u{3134}')),
+    (0x0ea5, "Hangul_NieunJieuj", Some('\u{3135}')),
+    (0x0ea6, "Hangul_NieunHieuh", Some('\u{3136}')),
+    (0x0ea7, "Hangul_Dikeud", Some('\u{3137}')),
+    (0x0ea8, "Hangul_SsangDikeud", Some('\u{3138}')),
+    (0x0ea9, "Hangul_Rieul", Some('\u{3139}')),
+    (0x0eaa, "Hangul_RieulKiyeog", Some('\u{313a}')),
+    (0x0eab, "Hangul_RieulMieum", Some('\u{313b}')),
+    (0x0eac, "Hangul_RieulPieub", Some('\u{313c}')),
+    (0x0ead, "Hangul_RieulSios", Some('\u{313d}')),
+    (0x0eae, "Hangul_RieulTieut", Some('\u{313e}')),
+    (0x0eaf, "Hangul_RieulPhieuf", Some('\u{313f}')),
+    (0x0eb0, "Hangul_RieulHieuh", Some('\u{3140}')),
+    (0x0eb1, "Hangul_Mieum", Some('\u{3141}')),
+    (0x0eb2, "Hangul_Pieub", Some('\u{3142}')),
+    (0x0eb3, "Hangul_SsangPieub", Some('\u{3143}')),
+    (0x0eb4, "Hangul_PieubSios", Some('\u{3144}')),
+    (0x0eb5, "Hangul_Sios", Some('\u{3145}')),
+    (0x0eb6, "Hangul_SsangSios", Some('\u{3146}')),
+    (0x0eb7, "Hangul_Ieung", Some('\u{3147}')),
+    (0x0eb8, "Hangul_Jieuj", Some('\u{3148}')),
+    (0x0eb9, "Hangul_SsangJieuj", Some('\u{3149}')),
+    (0x0eba, "Hangul_Cieuc", Some('\u{314a}')),
+    (0x0ebb, "Hangul_Khieuq", Some('\u{314b}')),
+    (0x0ebc, "Hangul_Tieut", Some('\u{314c}')),
+    (0x0ebd, "Hangul_Phieuf", Some('\u{314d}')),
+    (0x0ebe, "Hangul_Hieuh", Some('\u{314e}')),
+    (0x0ebf, "Hangul_A", Some('\u{314f}')),
+    (0x0ec0, "Hangul_AE", Some('\u{3150}')),
+    (0x0ec1, "Hangul_YA", Some('\u{3151}')),
+    (0x0ec2, "Hangul_YAE", Some('\u{3152}')),
+    (0x0ec3, "Hangul_EO", Some('\u{3153}')),
+    (0x0ec4, "Hangul_E", Some('\u{3154}')),
+    (0x0ec5, "Hangul_YEO", Some('\u{3155}')),
+    (0x0ec6, "Hangul_YE", Some('\u{3156}')),
+    (0x0ec7, "Hangul_O", Some('\u{3157}')),
+    (0x0ec8, "Hangul_WA", Some('\u{3158}')),
+    (0x0ec9, "Hangul_WAE", Some('\u{3159}')),
+    (0x0eca, "Hangul_OE", Some('\u{315a}')),
+    (0x0ecb, "Hangul_YO", Some('\u{315b}')),
+    (0x0ecc, "Hangul_U", Some('\u{315c}')),
+    (0x0ecd, "Hangul_WEO", Some('\u{315d}')),
+    (0x0ece, "Hangul_WE", Some('\u{315e}')),
+    (0x0ecf, "Hangul_WI", Some('\u{315f}')),
+    (0x0ed0, "Hangul_YU", Some('\u{3160}')),
+    (0x0ed1, "Hangul_EU", Some('\u{3161}')),
+    (0x0ed2, "Hangul_YI", Some('\u{3162}')),
+    (0x0ed3, "Hangul_I", Some('\u{3163}')),
+    (0x0ed4, "Hangul_J_Kiyeog", Some('\u{11a8}')),
+    (0x0ed5, "Hangul_J_SsangKiyeog", Some('\u{11a9}')),
+    (0x0ed6, "Hangul_J_KiyeogSios", Some('\u{11aa}')),
+    (0x0ed7, "Hangul_J_Nieun", Some('\u{11ab}')),
+    (0x0ed8, "Hangul_J_NieunJieuj", Some('\u{11ac}')),
+    (0x0ed9, "Hangul_J_NieunHieuh", Some('\u{11ad}')),
+    (0x0eda, "Hangul_J_Dikeud", Some('\u{11ae}')),
+    (0x0edb, "Hangul_J_Rieul", Some('\u{11af}')),
+    (0x0edc, "Hangul_J_RieulKiyeog", Some('\u{11b0}')),
+    (0x0edd, "Hangul_J_RieulMieum", Some('\u{11b1}')),
+    (0x0ede, "Hangul_J_RieulPieub", Some('\u{11b2}')),
+    (0x0edf, "Hangul_J_RieulSios", Some('\u{11b3}')),
+    (0x0ee0, "Hangul_J_RieulTieut", Some('\u{11b4}')),
+    (0x0ee1, "Hangul_J_RieulPhieuf", Some('\u{11b5}')),
+    (0x0ee2, "Hangul_J_RieulHieuh", Some('\u{11b6}')),
+    (0x0ee3, "Hangul_J_Mieum", Some('\u{11b7}')),
+    (0x0ee4, "Hangul_J_Pieub", Some('\u{11b8}')),
+    (0x0ee5, "Hangul_J_PieubSios", Some('\u{11b9}')),
+    (0x0ee6, "Hangul_J_Sios", Some('\u{11ba}')),
+    (0x0ee7, "Hangul_J_SsangSios", Some('\u{11bb}')),
+    (0x0ee8, "Hangul_J_Ieung", Some('\u{11bc}')),
+    (0x0ee9, "Hangul_J_Jieuj", Some('\u{11bd}')),
+    (0x0eea, "Hangul_J_Cieuc", Some('\u{11be}')),
+    (0x0eeb, "Hangul_J_Khieuq", Some('\u{11bf}')),
+    (0x0eec, "Hangul_J_Tieut", Some('\u{11c0}')),
+    (0x0eed, "Hangul_J_Phieuf", Some('\u{11c1}')),
+    (0x0eee, "Hangul_J_Hieuh", Some('\u{11c2}')),
+    (0x0eef, "Hangul_RieulYeorinHieuh", Some('\u{316d}')),
+    (0x0ef0, "Hangul_SunkyeongeumMieum", Some('\u{3171}')),
+    (0x0ef1, "Hangul_SunkyeongeumPieub", Some('\u{3178}')),
+    (0x0ef2, "Hangul_PanSios", Some('\u{317f}')),
+    (0x0ef3, "Hangul_KkogjiDalrinIeung", Some('\u{3181}')),
+    (0x0ef4, "Hangul_SunkyeongeumPhieuf", Some('\u{3184}')),
+    (0x0ef5, "Hangul_YeorinHieuh", Some('\u{3186}')),
+    (0x0ef6, "Hangul_AraeA", Some('\u{318d}')),
+    (0x0ef7, "Hangul_AraeAE", Some('\u{318e}')),
+    (0x0ef8, "Hangul_J_PanSios", Some('\u{11eb}')),
+    (0x0ef9, "Hangul_J_KkogjiDalrinIeung", Some('\u{11f0}')),
+    (0x0efa, "Hangul_J_YeorinHieuh", Some('\u{11f9}')),
+    (0x0eff, "Korean_Won", None),
+    (0x13bc, "OE", Some('\u{152}')),
+    (0x13bd, "oe", Some('\u{153}')),
+    (0x13be, "Ydiaeresis", Some('\u{178}')),
+    (0x20ac, "EuroSign", Some('\u{20ac}')),
+];
+
+impl Keysym {
+    pub fn is_keypad(&self) -> bool {
+        (keysyms::KP_SPACE.0..=keysyms::KP_EQUAL.0).contains(&self.0)
+    }
+
+    /// the first `LEGACY_KEYSYMS` entry of this keysym
+    fn legacy(&self) -> Option<&'static (CARD32, &'static str, Option<char>)> {
+        let first = LEGACY_KEYSYMS.partition_point(|(sym, ..)| *sym < self.0);
+        LEGACY_KEYSYMS.get(first).filter(|(sym, ..)| *sym == self.0)
+    }
+
+    /// Returns the keysym's name as spelled in `keysymdef.h`
+    pub fn name(&self) -> Option<&'static str> {
+        match self.0 {
+            0x20..=0x7e => Some(ASCII_NAMES[self.0 as usize - 0x20]),
+            0xa0..=0xff => Some(LATIN1_NAMES[self.0 as usize - 0xa0]),
+            0xffbe..=0xffe0 => Some(F_KEY_NAMES[self.0 as usize - 0xffbe]),
+            0x100..=0x20ff => self.legacy().map(|(_, name, _)| *name),
+            sym => FUNCTION_KEYS
+                .iter()
+                .find(|(s, ..)| *s == sym)
+                .map(|(_, name, _)| *name),
+        }
+    }
+
+    /// inverse of `name`, also accepts `U+XXXX` style names for unicode keysyms
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(hex) = name.strip_prefix('U') {
+            if let Some(c) = CARD32::from_str_radix(hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+            {
+                return Some(Self::from_char(c));
+            }
+        }
+        let position = |table: &[&str]| table.iter().position(|n| *n == name);
+        position(&ASCII_NAMES)
+            .map(|i| Self(i as CARD32 + 0x20))
+            .or_else(|| position(&LATIN1_NAMES).map(|i| Self(i as CARD32 + 0xa0)))
+            .or_else(|| position(&F_KEY_NAMES).map(|i| Self(i as CARD32 + 0xffbe)))
+            .or_else(|| {
+                FUNCTION_KEYS
+                    .iter()
+                    .find(|(_, n, _)| *n == name)
+                    .map(|(sym, ..)| Self(*sym))
+            })
+            .or_else(|| {
+                LEGACY_KEYSYMS
+                    .iter()
+                    .find(|(_, n, _)| *n == name)
+                    .map(|(sym, ..)| Self(*sym))
+            })
+    }
+
+    /// The text this keysym produces when typed, if any.\
+    /// Covers Latin-1, the legacy sets, the unicode keysym range and keys like Return or the
+    /// keypad digits
+    pub fn to_char(&self) -> Option<char> {
+        match self.0 {
+            0x20..=0x7e | 0xa0..=0xff => char::from_u32(self.0),
+            0x100..=0x20ff => self.legacy().and_then(|(.., c)| *c),
+            sym if sym >= UNICODE_OFFSET => char::from_u32(sym - UNICODE_OFFSET),
+            sym => FUNCTION_KEYS
+                .iter()
+                .find(|(s, ..)| *s == sym)
+                .and_then(|(.., c)| *c),
+        }
+    }
+
+    /// the keysym a server would use for `c`: Latin-1 where it exists, the unicode range otherwise
+    pub fn from_char(c: char) -> Self {
+        match c as CARD32 {
+            code @ (0x20..=0x7e | 0xa0..=0xff) => Self(code),
+            code => Self(code + UNICODE_OFFSET),
+        }
+    }
+
+    /// `(lowercase, uppercase)`, both the same if the keysym has no case
+    pub fn convert_case(&self) -> (Keysym, Keysym) {
+        match self.0 {
+            0x41..=0x5a | 0xc0..=0xd6 | 0xd8..=0xde => (Self(self.0 + 0x20), *self),
+            0x61..=0x7a | 0xe0..=0xf6 | 0xf8..=0xfe => (*self, Self(self.0 - 0x20)),
+            sym if sym >= UNICODE_OFFSET => {
+                let Some(c) = self.to_char() else {
+                    return (*self, *self);
+                };
+                // only one to one mappings count, 'ß' -> "SS" isn't a keysym
+                let single = |converted: String| {
+                    let mut chars = converted.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(k), None) => Self::from_char(k),
+                        _ => *self,
+                    }
+                };
+                (
+                    single(c.to_lowercase().collect()),
+                    single(c.to_uppercase().collect()),
+                )
+            }
+            _ => (*self, *self),
+        }
+    }
+
+    pub fn is_lower(&self) -> bool {
+        let (lower, upper) = self.convert_case();
+        lower == *self && upper != *self
+    }
+}
+
+#[rustfmt::skip]
+const F_KEY_NAMES: [&str; 35] = [
+    "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+    "F13", "F14", "F15", "F16", "F17", "F18", "F19", "F20", "F21", "F22", "F23", "F24",
+    "F25", "F26", "F27", "F28", "F29", "F30", "F31", "F32", "F33", "F34", "F35",
+];
+
+impl Debug for Keysym {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "Keysym({})", name),
+            None => write!(f, "Keysym({:#x})", self.0),
+        }
+    }
+}
+
+#[test]
+pub fn keysym_names_and_text() {
+    assert_eq!(Keysym(0xe4).name(), Some("adiaeresis"));
+    assert_eq!(Keysym(0xe4).to_char(), Some('ä'));
+    assert_eq!(Keysym::from_char('ä'), Keysym(0xe4));
+    assert_eq!(Keysym::from_char('€'), Keysym(0x10020ac));
+    assert_eq!(Keysym(0x10020ac).to_char(), Some('€'));
+    assert_eq!(Keysym::from_name("Return"), Some(keysyms::RETURN));
+    assert_eq!(Keysym::from_name("F24"), Some(Keysym(0xffbe + 23)));
+    assert_eq!(Keysym(0xffbe + 23).name(), Some("F24"));
+    assert_eq!(keysyms::KP_0.to_char(), Some('0'));
+    assert_eq!(Keysym(0xe4).convert_case(), (Keysym(0xe4), Keysym(0xc4)));
+    assert_eq!(Keysym(0xdf).convert_case(), (Keysym(0xdf), Keysym(0xdf)));
+    assert_eq!(Keysym(0x6c0).name(), Some("Cyrillic_yu"));
+    assert_eq!(Keysym(0x6c0).to_char(), Some('ю'));
+    assert_eq!(Keysym(0x7e1).to_char(), Some('α'));
+    assert_eq!(Keysym(0x1a1).to_char(), Some('Ą'));
+    assert_eq!(Keysym(0x4b1).name(), Some("kana_A"));
+    assert_eq!(Keysym(0x20ac).to_char(), Some('€'));
+    assert_eq!(Keysym::from_name("Greek_alpha"), Some(Keysym(0x7e1)));
+    // aliases are accepted, the name is the one listed first
+    assert_eq!(Keysym::from_name("Greek_lambda"), Some(Keysym(0x7eb)));
+    assert_eq!(Keysym(0x7eb).name(), Some("Greek_lamda"));
+}
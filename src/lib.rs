@@ -1,18 +1,68 @@
+// the wire derives name everything through `::xproto`, this is that inside the crate
+extern crate self as xproto;
+
 use std::{
-    cell::{Cell, RefCell},
+    cell::{Cell, RefCell, RefMut},
+    collections::VecDeque,
     fmt::Debug,
     io,
-    mem::MaybeUninit,
     rc::Rc,
 };
 
 pub const PATH_TO_UNIX_DOMAIN_SOCKET: &str = "/tmp/.X11-unix/X0";
 
+/// tokio or async-std based connection with futures for replies and a stream of events
+#[cfg(feature = "async-core")]
+pub mod asynchronous;
+/// BIG-REQUESTS extension
+pub mod bigreq;
+/// colormaps and color allocation
+pub mod colormap;
 /// code makes the first connection to the server
 mod connection;
+/// core cursors and Xcursor themes
+pub mod cursor;
 pub mod error;
+/// fd access and non-blocking event polling for outside event loops
+pub mod event_loop;
 pub mod events;
+/// extension discovery
+pub mod extension;
+/// input focus
+pub mod focus;
+/// server side core fonts and text drawing
+pub mod font;
+/// keyboard, button and pointer grabs
+pub mod grab;
+/// keyboard mapping and keycode to keysym translation
+pub mod keyboard;
+/// keysym names and their unicode equivalents
+pub mod keysym;
+/// an in-process X server for tests
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+/// pointer position, warping, acceleration and button mapping
+pub mod pointer;
 pub mod property;
+/// generated from the xcb-proto XML in proto/, rerun `cargo run -p xcbgen` after changing it
+pub mod proto;
+/// RandR, outputs, CRTCs and monitors
+pub mod randr;
+/// RENDER extension
+pub mod render;
+/// selections, the clipboard and the INCR protocol
+pub mod selection;
+/// a connection that can be shared between threads
+pub mod shared;
+/// MIT-SHM extension
+pub mod shm;
+/// Latin-1, COMPOUND_TEXT and UTF-8 text
+pub mod text;
+/// protocol logging
+#[cfg(feature = "trace")]
+pub mod trace;
+/// window tree walking and introspection
+pub mod tree;
 pub mod window;
 /// module containing common x constants
 pub mod xconsts;
@@ -21,22 +71,126 @@ pub mod xio;
 /// module containing all xtypes
 pub mod xtypes;
 
-use xio::{read_primitive, write_padding, write_primitive};
+use xio::{card16_at, card32_at, read_primitive, write_primitive, Deserialize, Serialize};
+
+pub use self::{
+    colormap::*, connection::*, cursor::*, error::*, extension::*, focus::*, font::*, grab::*,
+    keyboard::*, keysym::Keysym, pointer::*, property::*, selection::*, tree::*, window::*,
+    xconsts::*, xtypes::*,
+};
+pub use proto::xproto::{destroy_window, free_gc, free_pixmap};
 
-pub use self::{connection::*, error::*, property::*, window::*, xconsts::*, xtypes::*};
+/// a raw event packet, every event on the wire is exactly 32 bytes
+pub type RawEvent = [u8; 32];
+
+type ErrorHandler = Box<dyn FnMut(XErrorKind)>;
 
 pub struct XContext<Socket> {
     pub socket: Rc<RefCell<Socket>>,
-    id_count: Cell<CARD32>,
+    /// shared with every clone so they never hand out the same id
+    id_count: Rc<Cell<CARD32>>,
     info: Rc<connection::ConnectionAcceptedInfo>,
+    /// events that showed up while we were waiting on a reply
+    events: Rc<RefCell<VecDeque<RawEvent>>>,
+    /// bytes `poll_for_event` read that don't make up a whole packet yet
+    input: Rc<RefCell<Vec<u8>>>,
+    extensions: Rc<RefCell<ExtensionRegistry>>,
+    /// `None` until first needed or after a MappingNotify invalidates it
+    keymap: Rc<RefCell<Option<Rc<Keymap>>>>,
+    /// sequence numbers of the requests written so far
+    requests: Rc<RefCell<xio::RequestCounter>>,
+    /// requests `send_or_defer` couldn't write because the socket was in use
+    deferred: Rc<RefCell<Vec<u8>>>,
+    /// in 4 byte units, raised past 65535 when BIG-REQUESTS is enabled
+    max_request_length: CARD32,
+    /// gets the errors of requests without replies instead of the event functions returning them
+    error_handler: Rc<RefCell<Option<ErrorHandler>>>,
 }
 impl<Socket> XContext<Socket> {
-    pub fn socket_cb<F>(&self, mut cb: F)
+    pub fn info(&self) -> &connection::ConnectionAcceptedInfo {
+        &self.info
+    }
+
+    /// the longest request the server accepts, in 4 byte units
+    pub fn max_request_length(&self) -> CARD32 {
+        self.max_request_length
+    }
+
+    /// sequence number of the last request sent
+    pub fn last_sequence(&self) -> u64 {
+        self.requests.borrow().sequence()
+    }
+
+    /// Sends X errors that would come out of `next_event`, `wait_for_event` or `poll_for_event`
+    /// to `handler` instead, those always belong to requests that have no reply.\
+    /// It runs while the socket is borrowed, so it can't make requests of its own
+    pub fn set_error_handler<F: FnMut(XErrorKind) + 'static>(&self, handler: F) {
+        *self.error_handler.borrow_mut() = Some(Box::new(handler));
+    }
+
+    /// errors go back to being returned
+    pub fn clear_error_handler(&self) {
+        self.error_handler.borrow_mut().take();
+    }
+
+    /// hands an X error to the error handler if there is one, anything else is given back
+    fn handle_unchecked(&self, error: XErrorKind) -> Result<(), XErrorKind> {
+        if error.generic().is_none() {
+            return Err(error);
+        }
+        let Some(mut handler) = self.error_handler.borrow_mut().take() else {
+            return Err(error);
+        };
+        handler(error);
+        // unless the handler replaced itself
+        self.error_handler.borrow_mut().get_or_insert(handler);
+        Ok(())
+    }
+}
+impl<Socket: io::Write> XContext<Socket> {
+    /// Calls `cb` with the socket, the requests it writes are counted so their replies and
+    /// errors can be told apart from those of other requests
+    pub fn socket_cb<F, R>(&self, mut cb: F) -> R
+    where
+        F: FnMut(&mut xio::Counted<'_, Socket>) -> R,
+    {
+        // a failed write of the deferred requests fails `cb`'s as well
+        let mut borrowed_sock = self.socket.as_ref().borrow_mut();
+        let socket = &mut xio::Counted::new(&mut *borrowed_sock, &self.requests);
+        let _ = self.write_deferred(socket);
+        cb(socket)
+    }
+
+    /// Borrows the socket, writing out what `send_or_defer` couldn't first
+    fn lock_socket(&self) -> io::Result<RefMut<'_, Socket>> {
+        let mut socket = self.socket.as_ref().borrow_mut();
+        self.write_deferred(&mut xio::Counted::new(&mut *socket, &self.requests))?;
+        Ok(socket)
+    }
+
+    fn write_deferred<W: io::Write>(&self, socket: &mut W) -> io::Result<()> {
+        let mut deferred = self.deferred.borrow_mut();
+        if deferred.is_empty() {
+            return Ok(());
+        }
+        socket.write_all(&deferred)?;
+        deferred.clear();
+        socket.flush()
+    }
+
+    /// Sends a request from a `Drop` impl, `write` encodes it.\
+    /// When the socket is in use, say the value is dropped inside `socket_cb`, the request
+    /// goes out ahead of anything else the next time it's used
+    pub(crate) fn send_or_defer<F>(&self, write: F)
     where
-        F: FnMut(&mut Socket),
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
     {
-        let borrowed_sock = &mut *self.socket.as_ref().borrow_mut();
-        cb(borrowed_sock)
+        // writing to a Vec doesn't fail
+        let _ = write(&mut self.deferred.borrow_mut());
+        if let Ok(mut socket) = self.socket.try_borrow_mut() {
+            // a closed socket took whatever this was undoing with it
+            let _ = self.write_deferred(&mut xio::Counted::new(&mut *socket, &self.requests));
+        }
     }
 }
 impl<S> Clone for XContext<S> {
@@ -45,173 +199,285 @@ impl<S> Clone for XContext<S> {
             socket: self.socket.clone(),
             id_count: self.id_count.clone(),
             info: self.info.clone(),
+            events: self.events.clone(),
+            input: self.input.clone(),
+            extensions: self.extensions.clone(),
+            keymap: self.keymap.clone(),
+            requests: self.requests.clone(),
+            deferred: self.deferred.clone(),
+            max_request_length: self.max_request_length,
+            error_handler: self.error_handler.clone(),
         }
     }
 }
 impl<Socket: io::Read + io::Write> XContext<Socket> {
     pub fn gen_id(&mut self) -> CARD32 {
         let id = self.id_count.get();
-        *self.id_count.get_mut() += 1;
+        self.id_count.set(id + 1);
         id | self.info.resource_id_base
     }
 
     pub fn create_window(&mut self) -> WindowBuilder<'_, Socket> {
         WindowBuilder::new(self)
     }
-}
 
-pub fn map_window<S: io::Write>(socket: &mut S, window_id: CARD32) -> io::Result<()> {
-    write_primitive(socket, opcodes::MAP_WINDOW)?;
-    write_primitive(socket, 0u8)?;
-    write_primitive(socket, 2u16)?;
-    write_primitive(socket, window_id)
-}
+    /// Reads packets off the socket until the reply to the last request sent arrives, call it
+    /// right after sending a request that has one.\
+    /// An error for that request is returned as `Err`. Events read along the way are queued up
+    /// for `next_event`, errors of earlier requests go to the error handler or the same queue
+    pub fn read_reply(&self) -> Result<Vec<u8>, XErrorKind> {
+        // taken before `lock_socket` writes deferred requests, they'd come after the one
+        // the caller wants the reply to
+        let sequence = self.last_sequence();
+        let socket = &mut *self.lock_socket()?;
+        self.read_reply_from(socket, sequence)
+    }
 
-#[derive(Copy, Clone)]
-pub enum SynchKind {
-    Synchronous = 0,
-    Asynchronous = 1,
-}
+    fn read_reply_from<R: io::Read>(
+        &self,
+        socket: &mut R,
+        sequence: u64,
+    ) -> Result<Vec<u8>, XErrorKind> {
+        loop {
+            let packet = self.read_packet(socket)?;
+            if let Some(reply) = self.dispatch_reply(socket, packet, sequence)? {
+                return Ok(reply);
+            }
+        }
+    }
 
-#[derive(Debug)]
-pub enum GrabErr {
-    InvalidStatus,
-    InvalidReplyHeader,
-    AlreadyGrabbed,
-    InvalidTime,
-    NotViewable,
-    Frozen,
-    IO(io::Error),
-}
+    /// `dispatch_packet` that also takes the reply or error of request `sequence`
+    pub(crate) fn dispatch_reply<R: io::Read>(
+        &self,
+        socket: &mut R,
+        packet: RawEvent,
+        sequence: u64,
+    ) -> Result<Option<Vec<u8>>, XErrorKind> {
+        if packet[0] == 0 && self.packet_sequence(&packet) == sequence {
+            return Err(self.decode_error(&packet));
+        }
+        match self.dispatch_packet(socket, packet)? {
+            Some(reply) if self.packet_sequence(&reply) == sequence => Ok(Some(reply)),
+            // the reply to an earlier request nobody waited on
+            _ => Ok(None),
+        }
+    }
 
-impl From<io::Error> for GrabErr {
-    fn from(e: io::Error) -> Self {
-        Self::IO(e)
+    /// the full sequence number of the request a reply, error or event came after
+    fn packet_sequence(&self, packet: &[u8]) -> u64 {
+        let sequence = CARD16::from_le_bytes([packet[2], packet[3]]);
+        self.requests.borrow().widen(sequence)
     }
-}
 
-pub type GrabResult = Result<(), GrabErr>;
+    fn decode_error(&self, packet: &RawEvent) -> XErrorKind {
+        read_primitive::<XErrorHeader, _>(&mut &packet[..])
+            .ok()
+            .and_then(|header| {
+                XErrorKind::from_header_with_extensions(header, &self.extensions.borrow())
+            })
+            .unwrap_or(XErrorKind::Unknown)
+    }
 
-#[repr(C, packed(1))]
-#[derive(Copy, Clone, Default)]
-struct GrabResultMessage {
-    reply: CARD8,
-    status: CARD8,
-    sequence_number: CARD16,
-    reply_length: CARD32,
-    unused: [u8; 24],
-}
+    fn read_packet<R: io::Read>(&self, socket: &mut R) -> io::Result<RawEvent> {
+        let mut packet = [0u8; 32];
+        self.read_buffered(socket, &mut packet)?;
+        Ok(packet)
+    }
 
-pub fn grab_keyboard<S: io::Write + io::Read>(
-    socket: &mut S,
-    owner_events: bool,
-    grab_window: WINDOW,
-    timestamp: Timestamp,
-    pointer_mode: SynchKind,
-    keyboard_mode: SynchKind,
-) -> GrabResult {
-    write_primitive(socket, opcodes::GRAB_KEYBOARD)?;
-    write_primitive(socket, owner_events as u8)?;
-    write_primitive(socket, 4u16)?; //request-length
-    write_primitive(socket, grab_window)?;
-    write_primitive(socket, timestamp.data())?;
-    write_primitive(socket, pointer_mode as u8)?; //pointer-mode
-    write_primitive(socket, keyboard_mode as u8)?; //keyboard-mode
-    write_primitive(socket, &[0u8; 2][..])?; //unused
-
-    let response = read_primitive::<GrabResultMessage, _>(socket)?;
-
-    (response.reply == 1)
-        .then(|| ())
-        .ok_or(GrabErr::InvalidReplyHeader)?;
-
-    let e = match response.status {
-        0 => return Ok(()),
-        1 => GrabErr::AlreadyGrabbed,
-        2 => GrabErr::InvalidTime,
-        3 => GrabErr::NotViewable,
-        4 => GrabErr::Frozen,
-        _ => GrabErr::InvalidStatus,
-    };
-    Err(e)
-}
+    /// fills `out` with what `poll_for_event` left buffered first, then from the socket
+    fn read_buffered<R: io::Read>(&self, socket: &mut R, out: &mut [u8]) -> io::Result<()> {
+        let mut input = self.input.borrow_mut();
+        let buffered = input.len().min(out.len());
+        out[..buffered].copy_from_slice(&input[..buffered]);
+        input.drain(..buffered);
+        socket.read_exact(&mut out[buffered..])
+    }
+
+    /// `read_buffered` for a length the server gave, `out` grows as the bytes come in
+    fn read_buffered_growing<R: io::Read>(
+        &self,
+        socket: &mut R,
+        out: &mut Vec<u8>,
+        len: usize,
+    ) -> io::Result<()> {
+        let mut input = self.input.borrow_mut();
+        let buffered = input.len().min(len);
+        out.extend(input.drain(..buffered));
+        drop(input);
+        xio::read_growing(socket, out, len - buffered)
+    }
+
+    /// Sorts a freshly read packet, replies are returned and everything else is filed away:
+    /// events are queued and errors go to the error handler, or the queue if there is none
+    pub(crate) fn dispatch_packet<R: io::Read>(
+        &self,
+        socket: &mut R,
+        packet: RawEvent,
+    ) -> io::Result<Option<Vec<u8>>> {
+        match packet[0] {
+            0 => {
+                if self.handle_unchecked(self.decode_error(&packet)).is_err() {
+                    self.events.borrow_mut().push_back(packet);
+                }
+                Ok(None)
+            }
+            1 => {
+                let reply_length = read_primitive::<CARD32, _>(&mut &packet[4..8])?;
+                let mut reply = packet.to_vec();
+                let len = (reply_length as usize).saturating_mul(4);
+                self.read_buffered_growing(socket, &mut reply, len)?;
+                Ok(Some(reply))
+            }
+            _ => {
+                // a changed mapping makes the cached keymap stale, it'll be refetched when next used
+                const POINTER: u8 = 2;
+                if events::Event::code_of(&packet) == event_codes::MAPPING_NOTIFY
+                    && packet[4] != POINTER
+                {
+                    self.keymap.borrow_mut().take();
+                }
+                self.events.borrow_mut().push_back(packet);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Takes the first queued event `pred` accepts.\
+    /// A queued error ahead of it is taken instead and returned as `Err`
+    fn take_queued<F>(&self, mut pred: F) -> Result<Option<RawEvent>, XErrorKind>
+    where
+        F: FnMut(&RawEvent) -> bool,
+    {
+        let mut events = self.events.borrow_mut();
+        let Some(index) = events.iter().position(|p| p[0] == 0 || pred(p)) else {
+            return Ok(None);
+        };
+        let packet = events.remove(index).unwrap();
+        match packet[0] {
+            0 => Err(self.decode_error(&packet)),
+            _ => Ok(Some(packet)),
+        }
+    }
+
+    /// drops every queued event `pred` accepts, queued errors stay
+    pub(crate) fn discard_queued<F>(&self, mut pred: F)
+    where
+        F: FnMut(&RawEvent) -> bool,
+    {
+        self.events.borrow_mut().retain(|p| p[0] == 0 || !pred(p));
+    }
+
+    /// Does a round trip to the server.\
+    /// The first error of a request sent before this that the error handler didn't take
+    /// shows up as `Err`
+    pub fn sync(&self) -> Result<(), XErrorKind> {
+        self.socket_cb(|socket| get_input_focus_request(socket))?;
+        self.read_reply()?;
+        self.take_queued(|_| false).map(|_| ())
+    }
+
+    /// Returns the first event `pred` accepts, blocking on the socket until one shows up.\
+    /// Events it passes over stay queued for `next_event` in their original order
+    pub fn wait_for_event<F>(&self, mut pred: F) -> Result<RawEvent, XErrorKind>
+    where
+        F: FnMut(&RawEvent) -> bool,
+    {
+        if let Some(event) = self.take_queued(&mut pred)? {
+            return Ok(event);
+        }
+        let socket = &mut *self.lock_socket()?;
+        loop {
+            let packet = self.read_packet(socket)?;
+            // a reply with nobody waiting on it is dropped
+            if self.dispatch_packet(socket, packet)?.is_some() {
+                continue;
+            }
+            let mut events = self.events.borrow_mut();
+            match events.back() {
+                Some(packet) if packet[0] == 0 => {
+                    let packet = events.pop_back().unwrap();
+                    return Err(self.decode_error(&packet));
+                }
+                Some(packet) if pred(packet) => return Ok(events.pop_back().unwrap()),
+                _ => {}
+            }
+        }
+    }
 
-pub fn ungrab_keyboard<S: io::Write>(socket: &mut S, timestamp: Timestamp) -> io::Result<()> {
-    write_primitive(socket, opcodes::UNGRAB_KEYBOARD)?;
-    write_primitive(socket, 0u8)?; //unused
-    write_primitive(socket, 2u16)?; //request-length
-    write_primitive(socket, timestamp.data())
+    /// Returns the next event, either from the queue or by blocking on the socket.\
+    /// Errors that arrive in place of an event are returned as `Err`
+    pub fn next_event(&self) -> Result<RawEvent, XErrorKind> {
+        if let Some(event) = self.take_queued(|_| true)? {
+            return Ok(event);
+        }
+        let socket = &mut *self.lock_socket()?;
+        loop {
+            let packet = self.read_packet(socket)?;
+            // a reply with nobody waiting on it is dropped
+            self.dispatch_packet(socket, packet)?;
+            if let Some(event) = self.take_queued(|_| true)? {
+                return Ok(event);
+            }
+        }
+    }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-pub enum EventMode {
-    AsyncPointer = 0,
-    SyncPointer = 1,
-    ReplayPointer = 2,
-    AsyncKeyboard = 3,
-    SyncKeyboard = 4,
-    ReplayKeyboard = 5,
-    AsyncBoth = 6,
-    SyncBoth = 7,
+pub fn map_window<S: io::Write>(socket: &mut S, window_id: CARD32) -> io::Result<()> {
+    proto::xproto::map_window(socket, Atom(window_id))
 }
 
-pub fn allow_events<S: io::Write>(
+/// sets the events this client gets for `window`, replacing whatever it selected before
+pub fn select_input<S: io::Write>(
     socket: &mut S,
-    mode: EventMode,
-    timestamp: Timestamp,
+    window: WINDOW,
+    event_mask: CARD32,
 ) -> io::Result<()> {
-    write_primitive(socket, opcodes::ALLOW_EVENTS)?;
-    write_primitive(socket, mode as u8)?;
-    write_primitive(socket, 2u16)?; //request-length
-    write_primitive(socket, timestamp.data())
+    let values = [(value_masks::EVENT_MASK, event_mask)];
+    proto::xproto::change_window_attributes(socket, window, &values)
 }
 
-pub fn grab_button<S: io::Write>(
+/// `size` is (width, height)
+pub fn create_pixmap<S: io::Write>(
     socket: &mut S,
-    owner_events: bool,
-    grab_window: Atom,
-    event_mask: CARD16,
-    pointer_mode: SynchKind,
-    keyboard_mode: SynchKind,
-    confine_to: Atom,
-    cursor: Atom,
-    button: CARD8,
-    modifiers: Option<CARD16>,
+    depth: CARD8,
+    pid: PIXMAP,
+    drawable: DRAWABLE,
+    size: (CARD16, CARD16),
 ) -> io::Result<()> {
-    write_primitive(socket, opcodes::GRAB_BUTTON)?;
-    write_primitive(socket, owner_events as u8)?;
-    write_primitive(socket, 6u16)?; //request-length
-    write_primitive(socket, grab_window)?;
-    write_primitive(socket, event_mask)?;
-    write_primitive(socket, pointer_mode as u8)?;
-    write_primitive(socket, keyboard_mode as u8)?;
-    write_primitive(socket, confine_to)?;
-    write_primitive(socket, cursor)?;
-    write_primitive(socket, button)?;
-    write_primitive(socket, 0u8)?;//unused
-    write_primitive(socket, modifiers.unwrap_or(0x8000))?; //any modifier
-    Ok(())
+    proto::xproto::create_pixmap(socket, depth, pid, drawable, size.0, size.1)
 }
 
-
-
-pub fn grab_key<S: io::Write>(
+/// Uploads `data` to `drawable` through the socket.\
+/// Requests past `XContext::max_request_length` are rejected by the server,
+/// split the image into strips when it's that big
+#[allow(clippy::too_many_arguments)]
+pub fn put_image<S: io::Write>(
     socket: &mut S,
-    owner_events: bool,
-    grab_window: Atom,
-    modifiers: Option<CARD16>,
-    key:CARD8,
-    pointer_mode: SynchKind,
-    keyboard_mode: SynchKind,
+    format: ImageFormat,
+    drawable: CARD32,
+    gc: CARD32,
+    size: (CARD16, CARD16),
+    dst_pos: (INT16, INT16),
+    left_pad: CARD8,
+    depth: CARD8,
+    data: &[u8],
 ) -> io::Result<()> {
-    write_primitive(socket, opcodes::GRAB_KEY)?;
-    write_primitive(socket, owner_events as u8)?;
-    write_primitive(socket, 4u16)?; //request-length
-    write_primitive(socket, grab_window)?;
-    write_primitive(socket, modifiers.unwrap_or(0x8000))?; //any modifier
-    write_primitive(socket,key)?; //any modifier
-    write_primitive(socket, pointer_mode as u8)?;
-    write_primitive(socket, keyboard_mode as u8)?;
-    write_primitive(socket, [0u8;3].as_slice())?;//3 bytes unused
-    Ok(())
-}
\ No newline at end of file
+    proto::xproto::put_image(
+        socket,
+        format as CARD8,
+        drawable,
+        gc,
+        size.0,
+        size.1,
+        dst_pos.0,
+        dst_pos.1,
+        left_pad,
+        depth,
+        data,
+    )
+}
+
+/// creates a graphics context with every value left at its default
+pub fn create_gc<S: io::Write>(socket: &mut S, cid: CARD32, drawable: CARD32) -> io::Result<()> {
+    proto::xproto::create_gc(socket, cid, drawable, &[])
+}
@@ -0,0 +1,799 @@
+//! An X server that lives in the test process.\
+//! `MockStream` goes where the socket would, everything the client writes is parsed into
+//! `MockRequest`s and answered right away, either by the handlers a test registered with
+//! `MockServer::on_request` or by the few defaults below: atoms, properties, round trips and
+//! an empty extension list.\
+//! The stream acts like a blocking socket, reading with nothing to read would wait forever
+//! there so it panics instead. `MockServer::set_blocking(false)` makes it fail with
+//! `WouldBlock` the way a socket with a read timeout would.\
+//! Its fd is a pipe that's readable while the mock has something for the client, so `poll`
+//! and the `event_loop` functions work on it, reads fail with `WouldBlock` while the fd is
+//! switched to non-blocking
+
+use super::*;
+use std::{
+    collections::HashMap,
+    os::unix::io::{AsRawFd, RawFd},
+};
+
+/// What the server answers a request with
+#[derive(Clone, Debug)]
+pub enum MockResponse {
+    /// `body` starts at byte 8 of the reply, it's padded up to the 32 byte minimum
+    Reply {
+        data: CARD8,
+        body: Vec<u8>,
+    },
+    Error {
+        code: CARD8,
+        bad_value: CARD32,
+    },
+    /// for requests without a reply
+    Nothing,
+    /// `response` followed by `events`, say the ones other clients set off while reacting to it
+    WithEvents {
+        response: Box<MockResponse>,
+        events: Vec<RawEvent>,
+    },
+}
+
+impl MockResponse {
+    /// a reply that's all zeroes apart from `data` in byte 1
+    pub fn empty_reply(data: CARD8) -> Self {
+        Self::Reply { data, body: vec![] }
+    }
+
+    pub fn error(code: CARD8, bad_value: CARD32) -> Self {
+        Self::Error { code, bad_value }
+    }
+
+    pub fn with_events(self, events: Vec<RawEvent>) -> Self {
+        Self::WithEvents {
+            response: Box::new(self),
+            events,
+        }
+    }
+}
+
+/// One request as the server saw it
+#[derive(Clone, Debug)]
+pub struct MockRequest {
+    pub sequence: CARD16,
+    pub opcode: CARD8,
+    /// the byte after the opcode, the minor opcode for extensions
+    pub data: CARD8,
+    /// everything after the 4 byte header
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// the 16 bit value at `offset` into the whole request, header included
+    pub fn card16(&self, offset: usize) -> CARD16 {
+        CARD16::from_le_bytes([self.body[offset - 4], self.body[offset - 3]])
+    }
+
+    /// the 32 bit value at `offset` into the whole request, header included
+    pub fn card32(&self, offset: usize) -> CARD32 {
+        let at = offset - 4;
+        CARD32::from_le_bytes([
+            self.body[at],
+            self.body[at + 1],
+            self.body[at + 2],
+            self.body[at + 3],
+        ])
+    }
+
+    /// `len` bytes starting at `offset` into the whole request
+    pub fn bytes(&self, offset: usize, len: usize) -> &[u8] {
+        &self.body[offset - 4..offset - 4 + len]
+    }
+
+    pub fn decode(&self) -> DecodedRequest {
+        match self.opcode {
+            opcodes::CREATE_WINDOW => DecodedRequest::CreateWindow {
+                depth: self.data,
+                window: Atom(self.card32(4)),
+                parent: Atom(self.card32(8)),
+                x: self.card16(12) as INT16,
+                y: self.card16(14) as INT16,
+                width: self.card16(16),
+                height: self.card16(18),
+                border_width: self.card16(20),
+                class: self.card16(22),
+                visual: Atom(self.card32(24)),
+                value_mask: self.card32(28),
+                values: (0..self.card32(28).count_ones() as usize)
+                    .map(|i| self.card32(32 + i * 4))
+                    .collect(),
+            },
+            opcodes::MAP_WINDOW => DecodedRequest::MapWindow(Atom(self.card32(4))),
+            opcodes::DESTROY_WINDOW => DecodedRequest::DestroyWindow(Atom(self.card32(4))),
+            opcodes::INTERN_ATOM => DecodedRequest::InternAtom {
+                name: String::from_utf8_lossy(self.bytes(8, self.card16(4) as usize)).into_owned(),
+                only_if_exists: self.data != 0,
+            },
+            opcodes::CHANGE_PROPERTY => {
+                let format = self.body[12];
+                let len = self.card32(20) as usize * (format as usize / 8);
+                DecodedRequest::ChangeProperty {
+                    mode: self.data,
+                    window: Atom(self.card32(4)),
+                    property: Atom(self.card32(8)),
+                    ptype: Atom(self.card32(12)),
+                    format,
+                    value: self.bytes(24, len).to_vec(),
+                }
+            }
+            opcodes::DELETE_PROPERTY => DecodedRequest::DeleteProperty {
+                window: Atom(self.card32(4)),
+                property: Atom(self.card32(8)),
+            },
+            opcodes::GET_PROPERTY => DecodedRequest::GetProperty {
+                delete: self.data != 0,
+                window: Atom(self.card32(4)),
+                property: Atom(self.card32(8)),
+                ptype: Atom(self.card32(12)),
+                offset: self.card32(16),
+                length: self.card32(20),
+            },
+            _ => DecodedRequest::Other,
+        }
+    }
+}
+
+/// The requests window and property code sends, pulled apart
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodedRequest {
+    CreateWindow {
+        depth: CARD8,
+        window: WINDOW,
+        parent: WINDOW,
+        x: INT16,
+        y: INT16,
+        width: CARD16,
+        height: CARD16,
+        border_width: CARD16,
+        class: CARD16,
+        visual: Atom,
+        value_mask: CARD32,
+        values: Vec<CARD32>,
+    },
+    MapWindow(WINDOW),
+    DestroyWindow(WINDOW),
+    InternAtom {
+        name: String,
+        only_if_exists: bool,
+    },
+    ChangeProperty {
+        mode: CARD8,
+        window: WINDOW,
+        property: Atom,
+        ptype: Atom,
+        format: CARD8,
+        value: Vec<u8>,
+    },
+    DeleteProperty {
+        window: WINDOW,
+        property: Atom,
+    },
+    GetProperty {
+        delete: bool,
+        window: WINDOW,
+        property: Atom,
+        ptype: Atom,
+        offset: CARD32,
+        length: CARD32,
+    },
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockProperty {
+    pub ptype: Atom,
+    pub format: CARD8,
+    pub value: Vec<u8>,
+}
+
+/// The setup reply the server hands out, defaults to one 1920x1080 screen with a 24 bit
+/// TrueColor root visual and a 32 bit ARGB visual
+#[derive(Clone, Debug)]
+pub struct MockSetup {
+    pub resource_id_base: CARD32,
+    pub resource_id_mask: CARD32,
+    pub motion_buffer_size: CARD32,
+    pub max_request_length: CARD16,
+    pub min_keycode: KEYCODE,
+    pub max_keycode: KEYCODE,
+    pub vendor: String,
+    pub formats: Vec<XFormat>,
+    pub screen: XScreen,
+}
+
+impl Default for MockSetup {
+    fn default() -> Self {
+        let visual = |id, red_mask, green_mask, blue_mask| XVisualType {
+            visual_id: Atom(id),
+            class: XClass::TrueColor,
+            bits_per_rgb_value: 8,
+            colormap_entries: 256,
+            red_mask,
+            green_mask,
+            blue_mask,
+        };
+        let depth = |depth, visuals: Vec<XVisualType>| XDepth {
+            depth,
+            number_of_visual_types: visuals.len() as CARD16,
+            visuals,
+            ..Default::default()
+        };
+        let format = |depth, bits_per_pixel| XFormat {
+            depth,
+            bits_per_pixel,
+            scanline_pad: 32,
+            padding: [0; 5],
+        };
+        Self {
+            resource_id_base: 0x0400_0000,
+            resource_id_mask: 0x001f_ffff,
+            motion_buffer_size: 256,
+            max_request_length: 0xffff,
+            min_keycode: 8,
+            max_keycode: 255,
+            vendor: "xproto mock".to_string(),
+            formats: vec![format(1, 1), format(24, 32), format(32, 32)],
+            screen: XScreen {
+                root: Atom(0x100),
+                default_colormap: 0x20,
+                white_pixel: 0xffffff,
+                black_pixel: 0,
+                width_pixels: 1920,
+                height_pixels: 1080,
+                width_in_millimeters: 508,
+                height_in_millimeters: 286,
+                min_installed_maps: 1,
+                max_installed_maps: 1,
+                root_visual: Atom(0x21),
+                root_depth: 24,
+                number_of_depths_in_allowed_depths: 2,
+                depth_list: vec![
+                    depth(24, vec![visual(0x21, 0xff0000, 0xff00, 0xff)]),
+                    depth(32, vec![visual(0x22, 0xff0000, 0xff00, 0xff)]),
+                ],
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl MockSetup {
+    /// the bytes of a successful connection setup reply
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut body = vec![];
+        let out = &mut body;
+        let vendor = self.vendor.as_bytes();
+        let put = |out: &mut Vec<u8>, bytes: &[u8]| out.extend_from_slice(bytes);
+        put(out, &self.motion_buffer_size.to_le_bytes());
+        put(out, &(vendor.len() as CARD16).to_le_bytes());
+        put(out, &self.max_request_length.to_le_bytes());
+        put(out, &[1, self.formats.len() as u8, 0, 0, 32, 32]);
+        put(out, &[self.min_keycode, self.max_keycode, 0, 0, 0, 0]);
+        put(out, vendor);
+        out.resize(out.len() + (4 - vendor.len() % 4) % 4, 0);
+        for format in &self.formats {
+            put(
+                out,
+                &[format.depth, format.bits_per_pixel, format.scanline_pad],
+            );
+            put(out, &[0; 5]);
+        }
+        let screen = &self.screen;
+        for value in [
+            screen.root.0,
+            screen.default_colormap,
+            screen.white_pixel,
+            screen.black_pixel,
+            screen.current_input_masks,
+        ] {
+            put(out, &value.to_le_bytes());
+        }
+        for value in [
+            screen.width_pixels,
+            screen.height_pixels,
+            screen.width_in_millimeters,
+            screen.height_in_millimeters,
+            screen.min_installed_maps,
+            screen.max_installed_maps,
+        ] {
+            put(out, &value.to_le_bytes());
+        }
+        put(out, &screen.root_visual.0.to_le_bytes());
+        put(out, &[screen.backing_stores, screen.save_unders]);
+        put(out, &[screen.root_depth, screen.depth_list.len() as u8]);
+        for depth in &screen.depth_list {
+            put(out, &[depth.depth, 0]);
+            put(out, &(depth.visuals.len() as CARD16).to_le_bytes());
+            put(out, &[0; 4]);
+            for visual in &depth.visuals {
+                put(out, &visual.visual_id.0.to_le_bytes());
+                put(out, &[visual.class as u8, visual.bits_per_rgb_value]);
+                put(out, &visual.colormap_entries.to_le_bytes());
+                put(out, &visual.red_mask.to_le_bytes());
+                put(out, &visual.green_mask.to_le_bytes());
+                put(out, &visual.blue_mask.to_le_bytes());
+                put(out, &[0; 4]);
+            }
+        }
+
+        let mut reply = vec![1, 0];
+        reply.extend_from_slice(&11u16.to_le_bytes());
+        reply.extend_from_slice(&0u16.to_le_bytes());
+        // the release number and resource ids count towards the length too
+        let additional = (12 + body.len()) / 4;
+        reply.extend_from_slice(&(additional as CARD16).to_le_bytes());
+        reply.extend_from_slice(&0u32.to_le_bytes());
+        reply.extend_from_slice(&self.resource_id_base.to_le_bytes());
+        reply.extend_from_slice(&self.resource_id_mask.to_le_bytes());
+        reply.extend_from_slice(&body);
+        reply
+    }
+}
+
+type Handler = Box<dyn FnMut(&MockRequest) -> Option<MockResponse>>;
+
+struct State {
+    setup: MockSetup,
+    connected: bool,
+    /// what the client wrote that doesn't make up a whole request yet
+    input: Vec<u8>,
+    output: VecDeque<u8>,
+    sequence: CARD16,
+    requests: Vec<MockRequest>,
+    handlers: HashMap<CARD8, Handler>,
+    blocking: bool,
+    atoms: Vec<String>,
+    properties: HashMap<(WINDOW, Atom), MockProperty>,
+    /// the pipe behind `MockStream::as_raw_fd`, it holds a byte while `output` isn't empty
+    ready: [RawFd; 2],
+    signaled: bool,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.ready[0]);
+            libc::close(self.ready[1]);
+        }
+    }
+}
+
+/// atoms the mock interns start here, well past the predefined ones
+const FIRST_ATOM: CARD32 = 0x200;
+
+impl State {
+    /// makes the pipe readable exactly while there's output
+    fn update_ready(&mut self) {
+        let byte = &mut 0u8 as *mut u8 as *mut libc::c_void;
+        if self.output.is_empty() == self.signaled {
+            let done = match self.signaled {
+                true => unsafe { libc::read(self.ready[0], byte, 1) },
+                false => unsafe { libc::write(self.ready[1], byte, 1) },
+            };
+            assert_eq!(done, 1, "the mock's pipe failed");
+            self.signaled = !self.signaled;
+        }
+    }
+
+    /// handles every complete request in `input`
+    fn process(&mut self) {
+        if !self.connected {
+            let Some(len) = xio::setup_request_len(&self.input) else {
+                return;
+            };
+            self.input.drain(..len);
+            self.connected = true;
+            let reply = self.setup.encode();
+            self.output.extend(reply);
+        }
+        while let Some(len) = xio::request_len(&self.input) {
+            let mut request: Vec<u8> = self.input.drain(..len).collect();
+            let header_len = if request[2..4] == [0, 0] { 8 } else { 4 };
+            self.sequence = self.sequence.wrapping_add(1);
+            let request = MockRequest {
+                sequence: self.sequence,
+                opcode: request[0],
+                data: request[1],
+                body: request.split_off(header_len),
+            };
+            let response = match self.handlers.get_mut(&request.opcode) {
+                Some(handler) => handler(&request),
+                None => None,
+            }
+            .unwrap_or_else(|| self.default_response(&request));
+            self.respond(&request, response);
+            self.requests.push(request);
+        }
+    }
+
+    fn respond(&mut self, request: &MockRequest, response: MockResponse) {
+        let sequence = request.sequence.to_le_bytes();
+        match response {
+            MockResponse::Reply { data, mut body } => {
+                body.resize(body.len().max(24), 0);
+                body.resize(body.len().div_ceil(4) * 4, 0);
+                let reply_length = (body.len() as CARD32 - 24) / 4;
+                self.output.extend([1, data, sequence[0], sequence[1]]);
+                self.output.extend(reply_length.to_le_bytes());
+                self.output.extend(body);
+            }
+            MockResponse::Error { code, bad_value } => {
+                self.output.extend([0, code, sequence[0], sequence[1]]);
+                self.output.extend(bad_value.to_le_bytes());
+                self.output.extend([request.data, 0, request.opcode]);
+                self.output.extend([0; 21]);
+            }
+            MockResponse::Nothing => {}
+            MockResponse::WithEvents { response, events } => {
+                self.respond(request, *response);
+                for mut event in events {
+                    if event[0] & 0x7f != event_codes::KEYMAP_NOTIFY {
+                        event[2..4].copy_from_slice(&sequence);
+                    }
+                    self.output.extend(event);
+                }
+            }
+        }
+    }
+
+    fn default_response(&mut self, request: &MockRequest) -> MockResponse {
+        match request.opcode {
+            opcodes::GET_INPUT_FOCUS => MockResponse::Reply {
+                data: 1, // RevertToPointerRoot
+                body: 1u32.to_le_bytes().to_vec(),
+            },
+            // no extensions here
+            opcodes::QUERY_EXTENSION => MockResponse::empty_reply(0),
+            opcodes::LIST_EXTENSIONS => MockResponse::empty_reply(0),
+            opcodes::INTERN_ATOM => {
+                let DecodedRequest::InternAtom {
+                    name,
+                    only_if_exists,
+                } = request.decode()
+                else {
+                    unreachable!()
+                };
+                let atom = match self.atoms.iter().position(|a| *a == name) {
+                    Some(index) => FIRST_ATOM + index as CARD32,
+                    None if only_if_exists => 0,
+                    None => {
+                        self.atoms.push(name);
+                        FIRST_ATOM + self.atoms.len() as CARD32 - 1
+                    }
+                };
+                MockResponse::Reply {
+                    data: 0,
+                    body: atom.to_le_bytes().to_vec(),
+                }
+            }
+            opcodes::GET_ATOM_NAME => {
+                let index = request.card32(4).wrapping_sub(FIRST_ATOM) as usize;
+                match self.atoms.get(index) {
+                    Some(name) => {
+                        let mut body = (name.len() as CARD16).to_le_bytes().to_vec();
+                        body.resize(24, 0);
+                        body.extend_from_slice(name.as_bytes());
+                        MockResponse::Reply { data: 0, body }
+                    }
+                    None => MockResponse::error(5, request.card32(4)), // BadAtom
+                }
+            }
+            opcodes::CHANGE_PROPERTY => {
+                let DecodedRequest::ChangeProperty {
+                    mode,
+                    window,
+                    property,
+                    ptype,
+                    format,
+                    value,
+                } = request.decode()
+                else {
+                    unreachable!()
+                };
+                let stored = self
+                    .properties
+                    .entry((window, property))
+                    .or_insert(MockProperty {
+                        ptype,
+                        format,
+                        value: vec![],
+                    });
+                match mode {
+                    1 => stored.value.splice(0..0, value).for_each(drop), // Prepend
+                    2 => stored.value.extend(value),                      // Append
+                    _ => {
+                        *stored = MockProperty {
+                            ptype,
+                            format,
+                            value,
+                        }
+                    }
+                }
+                MockResponse::Nothing
+            }
+            opcodes::DELETE_PROPERTY => {
+                let key = (Atom(request.card32(4)), Atom(request.card32(8)));
+                self.properties.remove(&key);
+                MockResponse::Nothing
+            }
+            opcodes::GET_PROPERTY => self.get_property(request),
+            _ => MockResponse::Nothing,
+        }
+    }
+
+    fn get_property(&mut self, request: &MockRequest) -> MockResponse {
+        let DecodedRequest::GetProperty {
+            delete,
+            window,
+            property,
+            ptype,
+            offset,
+            length,
+        } = request.decode()
+        else {
+            unreachable!()
+        };
+        let Some(stored) = self.properties.get(&(window, property)).cloned() else {
+            return MockResponse::Reply {
+                data: 0,
+                body: vec![0; 24],
+            };
+        };
+        let mut body = stored.ptype.0.to_le_bytes().to_vec();
+        if ptype.0 != 0 && ptype != stored.ptype {
+            // wrong type, the reply only says what's there
+            body.extend_from_slice(&(stored.value.len() as CARD32).to_le_bytes());
+            body.resize(24, 0);
+            return MockResponse::Reply {
+                data: stored.format,
+                body,
+            };
+        }
+        let start = (offset as usize * 4).min(stored.value.len());
+        let end = (start + length as usize * 4).min(stored.value.len());
+        let bytes_after = (stored.value.len() - end) as CARD32;
+        let unit = (stored.format as usize / 8).max(1);
+        body.extend_from_slice(&bytes_after.to_le_bytes());
+        body.extend_from_slice(&(((end - start) / unit) as CARD32).to_le_bytes());
+        body.resize(24, 0);
+        body.extend_from_slice(&stored.value[start..end]);
+        if delete && bytes_after == 0 {
+            self.properties.remove(&(window, property));
+        }
+        MockResponse::Reply {
+            data: stored.format,
+            body,
+        }
+    }
+}
+
+/// The test's side of the mock, cloning it gives another handle to the same server
+#[derive(Clone)]
+pub struct MockServer {
+    state: Rc<RefCell<State>>,
+}
+
+/// The client's side of the mock, pass it to `RequestConnection::connect`
+pub struct MockStream {
+    state: Rc<RefCell<State>>,
+}
+
+impl MockServer {
+    pub fn new(setup: MockSetup) -> (Self, MockStream) {
+        let mut ready = [0; 2];
+        let piped = unsafe { libc::pipe2(ready.as_mut_ptr(), libc::O_CLOEXEC) };
+        assert_eq!(piped, 0, "can't make the mock's pipe");
+        let state = Rc::new(RefCell::new(State {
+            setup,
+            connected: false,
+            input: vec![],
+            output: VecDeque::new(),
+            sequence: 0,
+            requests: vec![],
+            handlers: HashMap::new(),
+            blocking: true,
+            atoms: vec![],
+            properties: HashMap::new(),
+            ready,
+            signaled: false,
+        }));
+        (
+            Self {
+                state: state.clone(),
+            },
+            MockStream { state },
+        )
+    }
+
+    /// a mock with the default setup and a client already connected to it
+    pub fn connect() -> (Self, XContext<MockStream>) {
+        let (server, stream) = Self::new(MockSetup::default());
+        let ctx = RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None)
+            .connect(stream)
+            .expect("the mock always accepts");
+        // the handshake's requests aren't interesting to tests
+        server.take_requests();
+        (server, ctx)
+    }
+
+    /// Answers every later request with `opcode` using `handler`.\
+    /// Returning `None` falls back to the default answer
+    pub fn on_request<F>(&self, opcode: CARD8, handler: F)
+    where
+        F: FnMut(&MockRequest) -> Option<MockResponse> + 'static,
+    {
+        self.state
+            .borrow_mut()
+            .handlers
+            .insert(opcode, Box::new(handler));
+    }
+
+    /// Queues an event for the client, the sequence number is filled in unless it's a
+    /// KeymapNotify, which has none
+    pub fn send_event(&self, mut event: RawEvent) {
+        let mut state = self.state.borrow_mut();
+        if event[0] & 0x7f != event_codes::KEYMAP_NOTIFY {
+            event[2..4].copy_from_slice(&state.sequence.to_le_bytes());
+        }
+        state.output.extend(event);
+        state.update_ready();
+    }
+
+    /// `false` makes reads with nothing to read fail with `WouldBlock` instead of panicking
+    pub fn set_blocking(&self, blocking: bool) {
+        self.state.borrow_mut().blocking = blocking;
+    }
+
+    /// every request so far, oldest first
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.borrow().requests.clone()
+    }
+
+    /// like `requests` but clears the list
+    pub fn take_requests(&self) -> Vec<MockRequest> {
+        std::mem::take(&mut self.state.borrow_mut().requests)
+    }
+
+    pub fn property(&self, window: WINDOW, property: Atom) -> Option<MockProperty> {
+        self.state
+            .borrow()
+            .properties
+            .get(&(window, property))
+            .cloned()
+    }
+
+    pub fn set_property(&self, window: WINDOW, property: Atom, value: MockProperty) {
+        self.state
+            .borrow_mut()
+            .properties
+            .insert((window, property), value);
+    }
+
+    /// the atom the mock handed out for `name`, if it was interned
+    pub fn atom(&self, name: &str) -> Option<Atom> {
+        let state = self.state.borrow();
+        let index = state.atoms.iter().position(|a| a == name)?;
+        Some(Atom(FIRST_ATOM + index as CARD32))
+    }
+}
+
+impl io::Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.borrow_mut();
+        state.input.extend_from_slice(buf);
+        state.process();
+        state.update_ready();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.state.borrow_mut();
+        if state.output.is_empty() {
+            let flags = unsafe { libc::fcntl(state.ready[0], libc::F_GETFL) };
+            if state.blocking && flags & libc::O_NONBLOCK == 0 {
+                panic!("read with nothing to read, a blocking socket would hang here");
+            }
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        let len = buf.len().min(state.output.len());
+        for (byte, out) in state.output.drain(..len).zip(buf) {
+            *out = byte;
+        }
+        state.update_ready();
+        Ok(len)
+    }
+}
+
+impl AsRawFd for MockStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.state.borrow().ready[0]
+    }
+}
+
+#[test]
+pub fn mock_window_and_properties() {
+    let (server, mut ctx) = MockServer::connect();
+    let info = ctx.info();
+    assert_eq!(info.resource_id_base, 0x0400_0000);
+    assert_eq!(info.list_of_screen[0].root, Atom(0x100));
+    assert_eq!(info.list_of_screen[0].depth_list.len(), 2);
+    assert!(info.list_of_screen[0].argb_visual().is_some());
+
+    let window = ctx
+        .create_window()
+        .with_pos((10, 20))
+        .with_width(300)
+        .with_height(200)
+        .with_title("mock")
+        .build()
+        .unwrap();
+    let requests = server.take_requests();
+    assert!(matches!(
+        requests[0].decode(),
+        DecodedRequest::CreateWindow { window: w, x: 10, y: 20, width: 300, height: 200, .. }
+            if w == window.id()
+    ));
+    assert!(requests
+        .iter()
+        .any(|r| r.decode() == DecodedRequest::MapWindow(window.id())));
+    assert_eq!(
+        server
+            .property(window.id(), predefined_atoms::WM_NAME)
+            .unwrap()
+            .value,
+        b"mock"
+    );
+
+    let atom = ctx.intern_atom("_TEST", false).unwrap();
+    assert_eq!(Some(atom), server.atom("_TEST"));
+    assert_eq!(ctx.get_atom_name(atom).unwrap(), "_TEST");
+    ctx.socket_cb(|socket| {
+        property::change_property(
+            socket,
+            PropertyMode::Replace,
+            atom,
+            predefined_atoms::STRING,
+            window.id().0,
+            PropertyFormat::Bytes,
+            "hello",
+        )
+    })
+    .unwrap();
+    let reply = ctx
+        .get_property_all(false, window.id(), atom, None)
+        .unwrap();
+    assert_eq!(reply.value, b"hello");
+
+    // scripted errors and events
+    server.on_request(opcodes::GET_PROPERTY, |request| {
+        Some(MockResponse::error(3, request.card32(4)))
+    });
+    assert!(matches!(
+        ctx.get_property_all(false, Atom(0xdead), atom, None),
+        Err(XErrorKind::Window { bad_id: 0xdead, .. })
+    ));
+    let mut event = [0u8; 32];
+    event[0] = event_codes::EXPOSE;
+    server.send_event(event);
+    assert_eq!(ctx.next_event().unwrap()[0], event_codes::EXPOSE);
+
+    server.set_blocking(false);
+    assert!(matches!(
+        ctx.next_event(),
+        Err(XErrorKind::SocketIO(e)) if e.kind() == io::ErrorKind::WouldBlock
+    ));
+}
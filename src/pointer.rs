@@ -0,0 +1,219 @@
+use super::*;
+pub use crate::proto::xproto::{
+    get_pointer_control_request, get_pointer_mapping_request, query_pointer_request,
+    set_pointer_mapping_request,
+};
+
+#[derive(Copy, Clone, Debug)]
+pub struct QueryPointerReply {
+    /// false if the pointer isn't on the same screen as the window asked about
+    pub same_screen: bool,
+    pub root: WINDOW,
+    /// the child of the window the pointer is in, `None` if it's in no child
+    pub child: Option<WINDOW>,
+    pub root_x: INT16,
+    pub root_y: INT16,
+    /// relative to the window, zero when `same_screen` is false
+    pub win_x: INT16,
+    pub win_y: INT16,
+    /// modifier and button state, see `key_masks`
+    pub mask: CARD16,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TranslateCoordinatesReply {
+    pub same_screen: bool,
+    /// the child of the destination window containing the point
+    pub child: Option<WINDOW>,
+    pub dst_x: INT16,
+    pub dst_y: INT16,
+}
+
+/// a pointer position from the server's motion history
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
+pub struct TimeCoord {
+    pub time: CARD32,
+    pub x: INT16,
+    pub y: INT16,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PointerControl {
+    /// the pointer moves `numerator / denominator` times faster past `threshold` pixels
+    pub acceleration_numerator: CARD16,
+    pub acceleration_denominator: CARD16,
+    pub threshold: CARD16,
+}
+
+/// how a Set*Mapping request went
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MappingStatus {
+    Success,
+    /// a button or key to be changed is held down
+    Busy,
+    /// the server refused the mapping
+    Failed,
+}
+
+impl MappingStatus {
+    fn from_code(code: CARD8) -> Self {
+        match code {
+            0 => Self::Success,
+            1 => Self::Busy,
+            _ => Self::Failed,
+        }
+    }
+}
+
+pub fn get_motion_events_request<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    start: Timestamp,
+    stop: Timestamp,
+) -> io::Result<()> {
+    proto::xproto::get_motion_events_request(socket, window, start.data(), stop.data())
+}
+
+pub fn translate_coordinates_request<S: io::Write>(
+    socket: &mut S,
+    src: WINDOW,
+    dst: WINDOW,
+    pos: (INT16, INT16),
+) -> io::Result<()> {
+    proto::xproto::translate_coordinates_request(socket, src, dst, pos.0, pos.1)
+}
+
+/// Moves the pointer to `dst_pos` relative to `dst`, or by `dst_pos` if `dst` is `None`.\
+/// With a `src` window the move only happens if the pointer is inside
+/// the (x, y, width, height) `src_rect` of it, a zero width or height reaching to the edge
+pub fn warp_pointer<S: io::Write>(
+    socket: &mut S,
+    src: Option<WINDOW>,
+    dst: Option<WINDOW>,
+    src_rect: (INT16, INT16, CARD16, CARD16),
+    dst_pos: (INT16, INT16),
+) -> io::Result<()> {
+    proto::xproto::warp_pointer(
+        socket,
+        src.unwrap_or(Atom(0)),
+        dst.unwrap_or(Atom(0)),
+        src_rect.0,
+        src_rect.1,
+        src_rect.2,
+        src_rect.3,
+        dst_pos.0,
+        dst_pos.1,
+    )
+}
+
+/// `None` leaves that setting alone, `Some(-1)` puts the server default back
+pub fn change_pointer_control<S: io::Write>(
+    socket: &mut S,
+    acceleration: Option<(INT16, INT16)>,
+    threshold: Option<INT16>,
+) -> io::Result<()> {
+    let (numerator, denominator) = acceleration.unwrap_or((0, 0));
+    proto::xproto::change_pointer_control(
+        socket,
+        numerator,
+        denominator,
+        threshold.unwrap_or(0),
+        acceleration.is_some() as BOOL,
+        threshold.is_some() as BOOL,
+    )
+}
+
+fn window_or_none(id: CARD32) -> Option<WINDOW> {
+    (id != 0).then_some(Atom(id))
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    /// where the pointer is, relative to `window` and to its root
+    pub fn query_pointer(&self, window: WINDOW) -> Result<QueryPointerReply, XErrorKind> {
+        self.socket_cb(|socket| query_pointer_request(socket, window))?;
+        let reply = self.read_reply()?;
+        Ok(QueryPointerReply {
+            same_screen: reply[1] != 0,
+            root: Atom(card32_at(&reply, 8)),
+            child: window_or_none(card32_at(&reply, 12)),
+            root_x: card16_at(&reply, 16) as INT16,
+            root_y: card16_at(&reply, 18) as INT16,
+            win_x: card16_at(&reply, 20) as INT16,
+            win_y: card16_at(&reply, 22) as INT16,
+            mask: card16_at(&reply, 24),
+        })
+    }
+
+    /// The pointer positions the server recorded between `start` and `stop`.\
+    /// Servers without a motion buffer (`motion_buffer_size` of 0 in the setup) never record
+    /// any, so that case skips the round trip
+    pub fn get_motion_events(
+        &self,
+        window: WINDOW,
+        start: Timestamp,
+        stop: Timestamp,
+    ) -> Result<Vec<TimeCoord>, XErrorKind> {
+        if self.info.motion_buffer_size == 0 {
+            return Ok(vec![]);
+        }
+        self.socket_cb(|socket| get_motion_events_request(socket, window, start, stop))?;
+        let reply = self.read_reply()?;
+        let count = card32_at(&reply, 8) as usize;
+        let mut data = reply.get(32..).ok_or(XErrorKind::Unknown)?;
+        Ok(xio::read_primitive_list(&mut data, count)?)
+    }
+
+    /// `pos` relative to `src` converted to be relative to `dst`
+    pub fn translate_coordinates(
+        &self,
+        src: WINDOW,
+        dst: WINDOW,
+        pos: (INT16, INT16),
+    ) -> Result<TranslateCoordinatesReply, XErrorKind> {
+        self.socket_cb(|socket| translate_coordinates_request(socket, src, dst, pos))?;
+        let reply = self.read_reply()?;
+        Ok(TranslateCoordinatesReply {
+            same_screen: reply[1] != 0,
+            child: window_or_none(card32_at(&reply, 8)),
+            dst_x: card16_at(&reply, 12) as INT16,
+            dst_y: card16_at(&reply, 14) as INT16,
+        })
+    }
+
+    pub fn get_pointer_control(&self) -> Result<PointerControl, XErrorKind> {
+        self.socket_cb(|socket| get_pointer_control_request(socket))?;
+        let reply = self.read_reply()?;
+        Ok(PointerControl {
+            acceleration_numerator: card16_at(&reply, 8),
+            acceleration_denominator: card16_at(&reply, 10),
+            threshold: card16_at(&reply, 12),
+        })
+    }
+
+    /// `Busy` if one of the buttons being remapped is held down, nothing changes then
+    pub fn set_pointer_mapping(&self, map: &[CARD8]) -> Result<MappingStatus, XErrorKind> {
+        self.socket_cb(|socket| set_pointer_mapping_request(socket, map))?;
+        let reply = self.read_reply()?;
+        Ok(MappingStatus::from_code(reply[1]))
+    }
+
+    pub fn get_pointer_mapping(&self) -> Result<Vec<CARD8>, XErrorKind> {
+        self.socket_cb(|socket| get_pointer_mapping_request(socket))?;
+        let reply = self.read_reply()?;
+        let len = reply[1] as usize;
+        Ok(reply.get(32..32 + len).ok_or(XErrorKind::Unknown)?.to_vec())
+    }
+}
+
+#[test]
+pub fn warp_pointer_encoding() {
+    let mut out = vec![];
+    warp_pointer(&mut out, None, Some(Atom(0x1234)), (0, 0, 0, 0), (-5, 300)).unwrap();
+    assert_eq!(out.len(), 24);
+    assert_eq!(out[0], opcodes::WARP_POINTER);
+    assert_eq!(&out[2..4], &6u16.to_le_bytes());
+    assert_eq!(&out[4..8], &[0; 4]);
+    assert_eq!(&out[8..12], &0x1234u32.to_le_bytes());
+    assert_eq!(&out[20..22], &(-5i16).to_le_bytes());
+    assert_eq!(&out[22..24], &300i16.to_le_bytes());
+}
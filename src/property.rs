@@ -1,4 +1,5 @@
-use super::*; 
+use super::*;
+pub use crate::proto::xproto::{delete_property, get_atom_name_request};
 
 #[derive(Copy, Clone)]
 pub enum PropertyMode {
@@ -22,34 +23,166 @@ pub fn change_property<S: io::Write, T: AsRef<[u8]>>(
     format: PropertyFormat,
     data: T,
 ) -> io::Result<()> {
-    let mode = mode as u8;
     let data = data.as_ref();
-    let padding = (4 - data.len() as u16 % 4) % 4;
-    let request_len = 6 + (data.len() as u16 + padding) / 4;
-    let format_log = ((format as u32) - 1).count_ones();
-    let divisor = format_log - 3;
-    /*
-       format|exp form| fract| rshift
-       -----------------------------
-       8     | 2^3    | 1/1  |  >> 0
-       16    | 2^4    | 1/2  |  >> 1
-       32    | 2^5    | 1/4  |  >> 2
-
-       divisor = 2^(log_2(format)-3)
-       fract = 1/divisor
-       rshift = divisor
-    */
-    let length_of_data_in_format_units = data.len() as u32 >> divisor;
-    write_primitive(socket, opcodes::CHANGE_PROPERTY)?;
-    write_primitive(socket, mode)?;
-    write_primitive(socket, request_len)?;
-    write_primitive(socket, window_id)?;
-    write_primitive(socket, property)?;
-    write_primitive(socket, ptype)?;
-    write_primitive(socket, format as u8)?;
-    write_primitive(socket, &[0u8; 3][..])?; //unused
-    write_primitive(socket, length_of_data_in_format_units)?;
-    socket.write_all(data)?;
-    write_padding(data.len(), socket)?;
-    Ok(())
+    // in units of the format, 8, 16 or 32 bits
+    let data_len = (data.len() / (format as usize / 8)) as CARD32;
+    proto::xproto::change_property(
+        socket,
+        mode as CARD8,
+        Atom(window_id),
+        property,
+        ptype,
+        format as CARD8,
+        data_len,
+        data,
+    )
+}
+
+/// `ptype` of `None` is AnyPropertyType, `offset` and `length` are in 4 byte units
+pub fn get_property_request<S: io::Write>(
+    socket: &mut S,
+    delete: bool,
+    window: WINDOW,
+    property: Atom,
+    ptype: Option<Atom>,
+    offset: CARD32,
+    length: CARD32,
+) -> io::Result<()> {
+    proto::xproto::get_property_request(
+        socket,
+        delete as BOOL,
+        window,
+        property,
+        ptype.unwrap_or(Atom(0)),
+        offset,
+        length,
+    )
+}
+
+pub fn intern_atom_request<S: io::Write>(
+    socket: &mut S,
+    only_if_exists: bool,
+    name: &str,
+) -> io::Result<()> {
+    proto::xproto::intern_atom_request(socket, only_if_exists as BOOL, &text::latin1_name(name)?)
+}
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+struct GetPropertyReplyHeader {
+    reply: CARD8,
+    format: CARD8,
+    sequence_number: CARD16,
+    reply_length: CARD32,
+    ptype: Atom,
+    bytes_after: CARD32,
+    value_length: CARD32,
+    unused: [u8; 12],
+}
+
+/// The value of a property, `ptype` is `Atom(0)` when the property doesn't exist
+#[derive(Clone, Debug, Default)]
+pub struct PropertyReply {
+    pub ptype: Atom,
+    /// 8, 16 or 32, zero when the property doesn't exist
+    pub format: CARD8,
+    /// bytes left past the end of what was read
+    pub bytes_after: CARD32,
+    /// the raw little endian value
+    pub value: Vec<u8>,
+}
+
+impl PropertyReply {
+    /// decodes a GetProperty reply
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let mut data = reply;
+        let header = read_primitive::<GetPropertyReplyHeader, _>(&mut data)?;
+        if !matches!(header.format, 0 | 8 | 16 | 32) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("property format {}", header.format),
+            ));
+        }
+        let len = (header.value_length as usize).checked_mul(header.format as usize / 8);
+        let value = len.and_then(|len| data.get(..len)).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "property value goes past the end of the reply",
+            )
+        })?;
+        Ok(Self {
+            ptype: header.ptype,
+            format: header.format,
+            bytes_after: header.bytes_after,
+            value: value.to_vec(),
+        })
+    }
+
+    /// the value as a list of 32 bit items, for ATOM, WINDOW, CARDINAL etc.
+    pub fn as_u32s(&self) -> Vec<CARD32> {
+        self.value
+            .chunks_exact(4)
+            .map(|c| CARD32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect()
+    }
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    pub fn intern_atom(&self, name: &str, only_if_exists: bool) -> Result<Atom, XErrorKind> {
+        self.socket_cb(|socket| intern_atom_request(socket, only_if_exists, name))?;
+        let reply = self.read_reply()?;
+        Ok(Atom(read_primitive::<CARD32, _>(&mut &reply[8..12])?))
+    }
+
+    pub fn get_atom_name(&self, atom: Atom) -> Result<String, XErrorKind> {
+        self.socket_cb(|socket| get_atom_name_request(socket, atom))?;
+        let reply = self.read_reply()?;
+        let len = read_primitive::<CARD16, _>(&mut &reply[8..10])? as usize;
+        let name = reply.get(32..32 + len).ok_or(XErrorKind::Unknown)?;
+        Ok(text::decode_latin1(name))
+    }
+
+    /// `offset` and `length` are in 4 byte units like on the wire
+    pub fn get_property(
+        &self,
+        delete: bool,
+        window: WINDOW,
+        property: Atom,
+        ptype: Option<Atom>,
+        offset: CARD32,
+        length: CARD32,
+    ) -> Result<PropertyReply, XErrorKind> {
+        self.socket_cb(|socket| {
+            get_property_request(socket, delete, window, property, ptype, offset, length)
+        })?;
+        let reply = self.read_reply()?;
+        Ok(PropertyReply::parse(&reply)?)
+    }
+
+    /// Reads a whole property in as many round trips as it takes, deleting it after if asked
+    pub fn get_property_all(
+        &self,
+        delete: bool,
+        window: WINDOW,
+        property: Atom,
+        ptype: Option<Atom>,
+    ) -> Result<PropertyReply, XErrorKind> {
+        // keep every reply well under the max request size, some servers cap replies too
+        const CHUNK: CARD32 = 0x10000;
+        let mut result = PropertyReply::default();
+        loop {
+            let offset = (result.value.len() / 4) as CARD32;
+            let part = self.get_property(false, window, property, ptype, offset, CHUNK)?;
+            result.ptype = part.ptype;
+            result.format = part.format;
+            result.value.extend_from_slice(&part.value);
+            // a server that claims more is left but sends none of it would loop forever
+            if part.bytes_after == 0 || part.value.is_empty() {
+                break;
+            }
+        }
+        if delete && result.ptype != Atom(0) {
+            self.socket_cb(|socket| delete_property(socket, window, property))?;
+        }
+        Ok(result)
+    }
 }
@@ -0,0 +1,34 @@
+// Generated by xcbgen from proto/bigreq.xml, edit that and rerun `cargo run -p xcbgen`
+#![allow(clippy::too_many_arguments)]
+
+use super::*;
+
+pub const EXTENSION_NAME: &str = "BIG-REQUESTS";
+pub const MAJOR_VERSION: u32 = 0;
+pub const MINOR_VERSION: u32 = 0;
+
+pub mod opcodes {
+    pub const ENABLE: u8 = 0;
+}
+
+pub const REQUEST_NAMES: [&str; 1] = ["Enable"];
+
+pub fn enable_request<S: io::Write>(socket: &mut S, major_opcode: CARD8) -> io::Result<()> {
+    RequestBuffer::new(major_opcode, opcodes::ENABLE).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnableReply {
+    pub maximum_request_length: CARD32,
+}
+
+impl EnableReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let maximum_request_length: CARD32 = r.read()?;
+        Ok(Self {
+            maximum_request_length,
+        })
+    }
+}
@@ -0,0 +1,63 @@
+// not the crate root's glob, its wrappers share names with the generated requests
+use crate::xio::{list_len, Deserialize, Reader, RequestBuffer, Serialize};
+use crate::xtypes::*;
+use std::io;
+
+/// BIG-REQUESTS
+pub mod bigreq;
+/// RandR 1.5
+pub mod randr;
+/// RENDER 0.11
+pub mod render;
+/// MIT-SHM 1.2
+pub mod shm;
+/// the core protocol
+pub mod xproto;
+
+#[test]
+pub fn generated_requests_and_replies() {
+    use xproto::*;
+    let (server, ctx) = crate::mock::MockServer::connect();
+    server.take_requests();
+
+    let values = [
+        (cw::EVENT_MASK, event_mask::EXPOSURE),
+        (cw::BACK_PIXEL, 0xff),
+    ];
+    let window = Atom(0x0400_0001);
+    ctx.socket_cb(|socket| {
+        create_window(
+            socket,
+            24,
+            window,
+            Atom(0x100),
+            1,
+            2,
+            30,
+            40,
+            0,
+            1,
+            0,
+            &values,
+        )
+    })
+    .unwrap();
+    let request = &server.take_requests()[0];
+    assert_eq!(request.opcode, opcodes::CREATE_WINDOW);
+    assert_eq!(request.card32(28), cw::BACK_PIXEL | cw::EVENT_MASK);
+    // sorted by bit
+    assert_eq!(request.card32(32), 0xff);
+    assert_eq!(request.card32(36), event_mask::EXPOSURE);
+
+    ctx.socket_cb(|socket| intern_atom_request(socket, 0, b"GENERATED"))
+        .unwrap();
+    let reply = InternAtomReply::parse(&ctx.read_reply().unwrap()).unwrap();
+    assert_eq!(Some(reply.atom), server.atom("GENERATED"));
+
+    ctx.socket_cb(|socket| list_extensions_request(socket))
+        .unwrap();
+    let reply = ctx.read_reply().unwrap();
+    assert!(ListExtensionsReply::parse(&reply).is_ok());
+    let truncated = ListExtensionsReply::parse(&reply[..6]);
+    assert_eq!(truncated.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,1002 @@
+// Generated by xcbgen from proto/randr.xml, edit that and rerun `cargo run -p xcbgen`
+#![allow(clippy::too_many_arguments)]
+
+#[allow(unused_imports)]
+use super::xproto::*;
+use super::*;
+use std::io::Write;
+
+pub const EXTENSION_NAME: &str = "RANDR";
+pub const MAJOR_VERSION: u32 = 1;
+pub const MINOR_VERSION: u32 = 5;
+
+pub type MODE = CARD32;
+pub type CRTC = CARD32;
+pub type OUTPUT = CARD32;
+pub type PROVIDER = CARD32;
+pub type NotifyData = [u8; 28];
+
+pub mod opcodes {
+    pub const QUERY_VERSION: u8 = 0;
+    pub const SELECT_INPUT: u8 = 4;
+    pub const GET_SCREEN_SIZE_RANGE: u8 = 6;
+    pub const SET_SCREEN_SIZE: u8 = 7;
+    pub const GET_SCREEN_RESOURCES: u8 = 8;
+    pub const GET_OUTPUT_INFO: u8 = 9;
+    pub const LIST_OUTPUT_PROPERTIES: u8 = 10;
+    pub const QUERY_OUTPUT_PROPERTY: u8 = 11;
+    pub const CONFIGURE_OUTPUT_PROPERTY: u8 = 12;
+    pub const CHANGE_OUTPUT_PROPERTY: u8 = 13;
+    pub const DELETE_OUTPUT_PROPERTY: u8 = 14;
+    pub const GET_OUTPUT_PROPERTY: u8 = 15;
+    pub const GET_CRTC_INFO: u8 = 20;
+    pub const SET_CRTC_CONFIG: u8 = 21;
+    pub const GET_SCREEN_RESOURCES_CURRENT: u8 = 25;
+    pub const SET_OUTPUT_PRIMARY: u8 = 30;
+    pub const GET_OUTPUT_PRIMARY: u8 = 31;
+    pub const GET_MONITORS: u8 = 42;
+    pub const SET_MONITOR: u8 = 43;
+    pub const DELETE_MONITOR: u8 = 44;
+}
+
+pub const REQUEST_NAMES: [&str; 45] = [
+    "QueryVersion",
+    "",
+    "",
+    "",
+    "SelectInput",
+    "",
+    "GetScreenSizeRange",
+    "SetScreenSize",
+    "GetScreenResources",
+    "GetOutputInfo",
+    "ListOutputProperties",
+    "QueryOutputProperty",
+    "ConfigureOutputProperty",
+    "ChangeOutputProperty",
+    "DeleteOutputProperty",
+    "GetOutputProperty",
+    "",
+    "",
+    "",
+    "",
+    "GetCrtcInfo",
+    "SetCrtcConfig",
+    "",
+    "",
+    "",
+    "GetScreenResourcesCurrent",
+    "",
+    "",
+    "",
+    "",
+    "SetOutputPrimary",
+    "GetOutputPrimary",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "GetMonitors",
+    "SetMonitor",
+    "DeleteMonitor",
+];
+
+pub mod event_codes {
+    pub const SCREEN_CHANGE_NOTIFY: u8 = 0;
+    pub const NOTIFY: u8 = 1;
+}
+
+pub mod error_codes {
+    pub const BAD_OUTPUT: u8 = 0;
+    pub const BAD_CRTC: u8 = 1;
+    pub const BAD_MODE: u8 = 2;
+    pub const BAD_PROVIDER: u8 = 3;
+}
+
+pub mod rotation {
+    pub const ROTATE_0: u32 = 1;
+    pub const ROTATE_90: u32 = 2;
+    pub const ROTATE_180: u32 = 4;
+    pub const ROTATE_270: u32 = 8;
+    pub const REFLECT_X: u32 = 16;
+    pub const REFLECT_Y: u32 = 32;
+}
+
+pub mod set_config {
+    pub const SUCCESS: u32 = 0;
+    pub const INVALID_CONFIG_TIME: u32 = 1;
+    pub const INVALID_TIME: u32 = 2;
+    pub const FAILED: u32 = 3;
+}
+
+pub mod notify_mask {
+    pub const SCREEN_CHANGE: u32 = 1;
+    pub const CRTC_CHANGE: u32 = 2;
+    pub const OUTPUT_CHANGE: u32 = 4;
+    pub const OUTPUT_PROPERTY: u32 = 8;
+    pub const PROVIDER_CHANGE: u32 = 16;
+    pub const PROVIDER_PROPERTY: u32 = 32;
+    pub const RESOURCE_CHANGE: u32 = 64;
+}
+
+pub mod mode_flag {
+    pub const HSYNC_POSITIVE: u32 = 1;
+    pub const HSYNC_NEGATIVE: u32 = 2;
+    pub const VSYNC_POSITIVE: u32 = 4;
+    pub const VSYNC_NEGATIVE: u32 = 8;
+    pub const INTERLACE: u32 = 16;
+    pub const DOUBLE_SCAN: u32 = 32;
+    pub const CSYNC: u32 = 64;
+    pub const CSYNC_POSITIVE: u32 = 128;
+    pub const CSYNC_NEGATIVE: u32 = 256;
+    pub const HSKEW_PRESENT: u32 = 512;
+    pub const BCAST: u32 = 1024;
+    pub const PIXEL_MULTIPLEX: u32 = 2048;
+    pub const DOUBLE_CLOCK: u32 = 4096;
+    pub const HALVE_CLOCK: u32 = 8192;
+}
+
+pub mod connection {
+    pub const CONNECTED: u32 = 0;
+    pub const DISCONNECTED: u32 = 1;
+    pub const UNKNOWN: u32 = 2;
+}
+
+pub mod notify {
+    pub const CRTC_CHANGE: u32 = 0;
+    pub const OUTPUT_CHANGE: u32 = 1;
+    pub const OUTPUT_PROPERTY: u32 = 2;
+    pub const PROVIDER_CHANGE: u32 = 3;
+    pub const PROVIDER_PROPERTY: u32 = 4;
+    pub const RESOURCE_CHANGE: u32 = 5;
+}
+
+pub mod property {
+    pub const NEW_VALUE: u32 = 0;
+    pub const DELETE: u32 = 1;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModeInfo {
+    pub id: CARD32,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub dot_clock: CARD32,
+    pub hsync_start: CARD16,
+    pub hsync_end: CARD16,
+    pub htotal: CARD16,
+    pub hskew: CARD16,
+    pub vsync_start: CARD16,
+    pub vsync_end: CARD16,
+    pub vtotal: CARD16,
+    pub name_len: CARD16,
+    pub mode_flags: CARD32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CrtcChange {
+    pub timestamp: TIMESTAMP,
+    pub window: WINDOW,
+    pub crtc: CRTC,
+    pub mode: MODE,
+    pub rotation: CARD16,
+    #[wire(pad = 2)]
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputChange {
+    pub timestamp: TIMESTAMP,
+    pub config_timestamp: TIMESTAMP,
+    pub window: WINDOW,
+    pub output: OUTPUT,
+    pub crtc: CRTC,
+    pub mode: MODE,
+    pub rotation: CARD16,
+    pub connection: CARD8,
+    pub subpixel_order: CARD8,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputProperty {
+    pub window: WINDOW,
+    pub output: OUTPUT,
+    pub atom: Atom,
+    pub timestamp: TIMESTAMP,
+    #[wire(pad_after = 11)]
+    pub status: CARD8,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderChange {
+    pub timestamp: TIMESTAMP,
+    pub window: WINDOW,
+    #[wire(pad_after = 16)]
+    pub provider: PROVIDER,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderProperty {
+    pub window: WINDOW,
+    pub provider: PROVIDER,
+    pub atom: Atom,
+    pub timestamp: TIMESTAMP,
+    #[wire(pad_after = 11)]
+    pub state: CARD8,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceChange {
+    pub timestamp: TIMESTAMP,
+    #[wire(pad_after = 20)]
+    pub window: WINDOW,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonitorInfo {
+    pub name: Atom,
+    pub primary: BOOL,
+    pub automatic: BOOL,
+    #[wire(len_of = "outputs")]
+    pub n_output: CARD16,
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub width_in_millimeters: CARD32,
+    pub height_in_millimeters: CARD32,
+    #[wire(len = "n_output as usize")]
+    pub outputs: Vec<OUTPUT>,
+}
+
+pub fn query_version_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    major_version: CARD32,
+    minor_version: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::QUERY_VERSION);
+    major_version.serialize(&mut request)?;
+    minor_version.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryVersionReply {
+    pub major_version: CARD32,
+    pub minor_version: CARD32,
+}
+
+impl QueryVersionReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let major_version: CARD32 = r.read()?;
+        let minor_version: CARD32 = r.read()?;
+        r.skip(16)?;
+        Ok(Self {
+            major_version,
+            minor_version,
+        })
+    }
+}
+
+pub fn select_input<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+    enable: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::SELECT_INPUT);
+    window.serialize(&mut request)?;
+    enable.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.send(socket)
+}
+
+pub fn get_screen_size_range_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_SCREEN_SIZE_RANGE);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetScreenSizeRangeReply {
+    pub min_width: CARD16,
+    pub min_height: CARD16,
+    pub max_width: CARD16,
+    pub max_height: CARD16,
+}
+
+impl GetScreenSizeRangeReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let min_width: CARD16 = r.read()?;
+        let min_height: CARD16 = r.read()?;
+        let max_width: CARD16 = r.read()?;
+        let max_height: CARD16 = r.read()?;
+        r.skip(16)?;
+        Ok(Self {
+            min_width,
+            min_height,
+            max_width,
+            max_height,
+        })
+    }
+}
+
+pub fn set_screen_size<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+    width: CARD16,
+    height: CARD16,
+    mm_width: CARD32,
+    mm_height: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::SET_SCREEN_SIZE);
+    window.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    mm_width.serialize(&mut request)?;
+    mm_height.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_screen_resources_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_SCREEN_RESOURCES);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetScreenResourcesReply {
+    pub timestamp: TIMESTAMP,
+    pub config_timestamp: TIMESTAMP,
+    pub crtcs: Vec<CRTC>,
+    pub outputs: Vec<OUTPUT>,
+    pub modes: Vec<ModeInfo>,
+    pub names: Vec<u8>,
+}
+
+impl GetScreenResourcesReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let timestamp: TIMESTAMP = r.read()?;
+        let config_timestamp: TIMESTAMP = r.read()?;
+        let num_crtcs: CARD16 = r.read()?;
+        let num_outputs: CARD16 = r.read()?;
+        let num_modes: CARD16 = r.read()?;
+        let names_len: CARD16 = r.read()?;
+        r.skip(8)?;
+        let crtcs = r.list(num_crtcs as usize)?;
+        let outputs = r.list(num_outputs as usize)?;
+        let modes = r.list(num_modes as usize)?;
+        let names = r.bytes(names_len as usize)?.to_vec();
+        Ok(Self {
+            timestamp,
+            config_timestamp,
+            crtcs,
+            outputs,
+            modes,
+            names,
+        })
+    }
+}
+
+pub fn get_output_info_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    output: OUTPUT,
+    config_timestamp: TIMESTAMP,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_OUTPUT_INFO);
+    output.serialize(&mut request)?;
+    config_timestamp.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetOutputInfoReply {
+    pub status: CARD8,
+    pub timestamp: TIMESTAMP,
+    pub crtc: CRTC,
+    pub mm_width: CARD32,
+    pub mm_height: CARD32,
+    pub connection: CARD8,
+    pub subpixel_order: CARD8,
+    pub num_preferred: CARD16,
+    pub crtcs: Vec<CRTC>,
+    pub modes: Vec<MODE>,
+    pub clones: Vec<OUTPUT>,
+    pub name: Vec<u8>,
+}
+
+impl GetOutputInfoReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let status: CARD8 = r.read()?;
+        r.skip(6)?;
+        let timestamp: TIMESTAMP = r.read()?;
+        let crtc: CRTC = r.read()?;
+        let mm_width: CARD32 = r.read()?;
+        let mm_height: CARD32 = r.read()?;
+        let connection: CARD8 = r.read()?;
+        let subpixel_order: CARD8 = r.read()?;
+        let num_crtcs: CARD16 = r.read()?;
+        let num_modes: CARD16 = r.read()?;
+        let num_preferred: CARD16 = r.read()?;
+        let num_clones: CARD16 = r.read()?;
+        let name_len: CARD16 = r.read()?;
+        let crtcs = r.list(num_crtcs as usize)?;
+        let modes = r.list(num_modes as usize)?;
+        let clones = r.list(num_clones as usize)?;
+        let name = r.bytes(name_len as usize)?.to_vec();
+        Ok(Self {
+            status,
+            timestamp,
+            crtc,
+            mm_width,
+            mm_height,
+            connection,
+            subpixel_order,
+            num_preferred,
+            crtcs,
+            modes,
+            clones,
+            name,
+        })
+    }
+}
+
+pub fn list_output_properties_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    output: OUTPUT,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::LIST_OUTPUT_PROPERTIES);
+    output.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListOutputPropertiesReply {
+    pub atoms: Vec<Atom>,
+}
+
+impl ListOutputPropertiesReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let num_atoms: CARD16 = r.read()?;
+        r.skip(22)?;
+        let atoms = r.list(num_atoms as usize)?;
+        Ok(Self { atoms })
+    }
+}
+
+pub fn query_output_property_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    output: OUTPUT,
+    property: Atom,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::QUERY_OUTPUT_PROPERTY);
+    output.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryOutputPropertyReply {
+    pub pending: BOOL,
+    pub range: BOOL,
+    pub immutable: BOOL,
+    pub valid_values: Vec<INT32>,
+}
+
+impl QueryOutputPropertyReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(4)?;
+        let length: CARD32 = r.read()?;
+        let pending: BOOL = r.read()?;
+        let range: BOOL = r.read()?;
+        let immutable: BOOL = r.read()?;
+        r.skip(21)?;
+        let valid_values = r.list(length as usize)?;
+        Ok(Self {
+            pending,
+            range,
+            immutable,
+            valid_values,
+        })
+    }
+}
+
+pub fn configure_output_property<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    output: OUTPUT,
+    property: Atom,
+    pending: BOOL,
+    range: BOOL,
+    values: &[INT32],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::CONFIGURE_OUTPUT_PROPERTY);
+    output.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    pending.serialize(&mut request)?;
+    range.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    for item in values {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn change_output_property<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    output: OUTPUT,
+    property: Atom,
+    type_: Atom,
+    format: CARD8,
+    mode: CARD8,
+    num_units: CARD32,
+    data: &[u8],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::CHANGE_OUTPUT_PROPERTY);
+    output.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    type_.serialize(&mut request)?;
+    format.serialize(&mut request)?;
+    mode.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    num_units.serialize(&mut request)?;
+    request.write_all(data)?;
+    request.send(socket)
+}
+
+pub fn delete_output_property<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    output: OUTPUT,
+    property: Atom,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::DELETE_OUTPUT_PROPERTY);
+    output.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_output_property_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    output: OUTPUT,
+    property: Atom,
+    type_: Atom,
+    long_offset: CARD32,
+    long_length: CARD32,
+    delete: BOOL,
+    pending: BOOL,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_OUTPUT_PROPERTY);
+    output.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    type_.serialize(&mut request)?;
+    long_offset.serialize(&mut request)?;
+    long_length.serialize(&mut request)?;
+    delete.serialize(&mut request)?;
+    pending.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetOutputPropertyReply {
+    pub format: CARD8,
+    pub type_: Atom,
+    pub bytes_after: CARD32,
+    pub num_items: CARD32,
+    pub data: Vec<u8>,
+}
+
+impl GetOutputPropertyReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let format: CARD8 = r.read()?;
+        r.skip(6)?;
+        let type_: Atom = r.read()?;
+        let bytes_after: CARD32 = r.read()?;
+        let num_items: CARD32 = r.read()?;
+        r.skip(12)?;
+        let data = r
+            .bytes((num_items as usize) * ((format as usize) / 8))?
+            .to_vec();
+        Ok(Self {
+            format,
+            type_,
+            bytes_after,
+            num_items,
+            data,
+        })
+    }
+}
+
+pub fn get_crtc_info_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    crtc: CRTC,
+    config_timestamp: TIMESTAMP,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_CRTC_INFO);
+    crtc.serialize(&mut request)?;
+    config_timestamp.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetCrtcInfoReply {
+    pub status: CARD8,
+    pub timestamp: TIMESTAMP,
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub mode: MODE,
+    pub rotation: CARD16,
+    pub rotations: CARD16,
+    pub outputs: Vec<OUTPUT>,
+    pub possible: Vec<OUTPUT>,
+}
+
+impl GetCrtcInfoReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let status: CARD8 = r.read()?;
+        r.skip(6)?;
+        let timestamp: TIMESTAMP = r.read()?;
+        let x: INT16 = r.read()?;
+        let y: INT16 = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        let mode: MODE = r.read()?;
+        let rotation: CARD16 = r.read()?;
+        let rotations: CARD16 = r.read()?;
+        let num_outputs: CARD16 = r.read()?;
+        let num_possible_outputs: CARD16 = r.read()?;
+        let outputs = r.list(num_outputs as usize)?;
+        let possible = r.list(num_possible_outputs as usize)?;
+        Ok(Self {
+            status,
+            timestamp,
+            x,
+            y,
+            width,
+            height,
+            mode,
+            rotation,
+            rotations,
+            outputs,
+            possible,
+        })
+    }
+}
+
+pub fn set_crtc_config_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    crtc: CRTC,
+    timestamp: TIMESTAMP,
+    config_timestamp: TIMESTAMP,
+    x: INT16,
+    y: INT16,
+    mode: MODE,
+    rotation: CARD16,
+    outputs: &[OUTPUT],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::SET_CRTC_CONFIG);
+    crtc.serialize(&mut request)?;
+    timestamp.serialize(&mut request)?;
+    config_timestamp.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    mode.serialize(&mut request)?;
+    rotation.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    for item in outputs {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetCrtcConfigReply {
+    pub status: CARD8,
+    pub timestamp: TIMESTAMP,
+}
+
+impl SetCrtcConfigReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let status: CARD8 = r.read()?;
+        r.skip(6)?;
+        let timestamp: TIMESTAMP = r.read()?;
+        r.skip(20)?;
+        Ok(Self { status, timestamp })
+    }
+}
+
+pub fn get_screen_resources_current_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_SCREEN_RESOURCES_CURRENT);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetScreenResourcesCurrentReply {
+    pub timestamp: TIMESTAMP,
+    pub config_timestamp: TIMESTAMP,
+    pub crtcs: Vec<CRTC>,
+    pub outputs: Vec<OUTPUT>,
+    pub modes: Vec<ModeInfo>,
+    pub names: Vec<u8>,
+}
+
+impl GetScreenResourcesCurrentReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let timestamp: TIMESTAMP = r.read()?;
+        let config_timestamp: TIMESTAMP = r.read()?;
+        let num_crtcs: CARD16 = r.read()?;
+        let num_outputs: CARD16 = r.read()?;
+        let num_modes: CARD16 = r.read()?;
+        let names_len: CARD16 = r.read()?;
+        r.skip(8)?;
+        let crtcs = r.list(num_crtcs as usize)?;
+        let outputs = r.list(num_outputs as usize)?;
+        let modes = r.list(num_modes as usize)?;
+        let names = r.bytes(names_len as usize)?.to_vec();
+        Ok(Self {
+            timestamp,
+            config_timestamp,
+            crtcs,
+            outputs,
+            modes,
+            names,
+        })
+    }
+}
+
+pub fn set_output_primary<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+    output: OUTPUT,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::SET_OUTPUT_PRIMARY);
+    window.serialize(&mut request)?;
+    output.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_output_primary_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_OUTPUT_PRIMARY);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetOutputPrimaryReply {
+    pub output: OUTPUT,
+}
+
+impl GetOutputPrimaryReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let output: OUTPUT = r.read()?;
+        Ok(Self { output })
+    }
+}
+
+pub fn get_monitors_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+    get_active: BOOL,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_MONITORS);
+    window.serialize(&mut request)?;
+    get_active.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetMonitorsReply {
+    pub timestamp: TIMESTAMP,
+    pub n_outputs: CARD32,
+    pub monitors: Vec<MonitorInfo>,
+}
+
+impl GetMonitorsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let timestamp: TIMESTAMP = r.read()?;
+        let n_monitors: CARD32 = r.read()?;
+        let n_outputs: CARD32 = r.read()?;
+        r.skip(12)?;
+        let monitors = r.list(n_monitors as usize)?;
+        Ok(Self {
+            timestamp,
+            n_outputs,
+            monitors,
+        })
+    }
+}
+
+pub fn set_monitor<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+    monitorinfo: MonitorInfo,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::SET_MONITOR);
+    window.serialize(&mut request)?;
+    monitorinfo.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn delete_monitor<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    window: WINDOW,
+    name: Atom,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::DELETE_MONITOR);
+    window.serialize(&mut request)?;
+    name.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScreenChangeNotifyEvent {
+    pub rotation: CARD8,
+    pub timestamp: TIMESTAMP,
+    pub config_timestamp: TIMESTAMP,
+    pub root: WINDOW,
+    pub request_window: WINDOW,
+    pub size_id: CARD16,
+    pub subpixel_order: CARD16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub mwidth: CARD16,
+    pub mheight: CARD16,
+}
+
+impl ScreenChangeNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let rotation: CARD8 = r.read()?;
+        r.skip(2)?;
+        let timestamp: TIMESTAMP = r.read()?;
+        let config_timestamp: TIMESTAMP = r.read()?;
+        let root: WINDOW = r.read()?;
+        let request_window: WINDOW = r.read()?;
+        let size_id: CARD16 = r.read()?;
+        let subpixel_order: CARD16 = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        let mwidth: CARD16 = r.read()?;
+        let mheight: CARD16 = r.read()?;
+        Ok(Self {
+            rotation,
+            timestamp,
+            config_timestamp,
+            root,
+            request_window,
+            size_id,
+            subpixel_order,
+            width,
+            height,
+            mwidth,
+            mheight,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NotifyEvent {
+    pub sub_code: CARD8,
+    pub u: NotifyData,
+}
+
+impl NotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let sub_code: CARD8 = r.read()?;
+        r.skip(2)?;
+        let u: NotifyData = r.read()?;
+        Ok(Self { sub_code, u })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BadOutputError {}
+
+impl BadOutputError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BadCrtcError {}
+
+impl BadCrtcError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BadModeError {}
+
+impl BadModeError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BadProviderError {}
+
+impl BadProviderError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
@@ -0,0 +1,328 @@
+// Generated by xcbgen from proto/render.xml, edit that and rerun `cargo run -p xcbgen`
+#![allow(clippy::too_many_arguments)]
+
+#[allow(unused_imports)]
+use super::xproto::*;
+use super::*;
+
+pub const EXTENSION_NAME: &str = "RENDER";
+pub const MAJOR_VERSION: u32 = 0;
+pub const MINOR_VERSION: u32 = 11;
+
+pub type GLYPHSET = CARD32;
+pub type PICTURE = CARD32;
+pub type PICTFORMAT = CARD32;
+
+pub mod opcodes {
+    pub const QUERY_VERSION: u8 = 0;
+    pub const QUERY_PICT_FORMATS: u8 = 1;
+    pub const CREATE_PICTURE: u8 = 4;
+    pub const FREE_PICTURE: u8 = 7;
+    pub const CREATE_CURSOR: u8 = 27;
+    pub const CREATE_ANIM_CURSOR: u8 = 31;
+}
+
+pub const REQUEST_NAMES: [&str; 32] = [
+    "QueryVersion",
+    "QueryPictFormats",
+    "",
+    "",
+    "CreatePicture",
+    "",
+    "",
+    "FreePicture",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "CreateCursor",
+    "",
+    "",
+    "",
+    "CreateAnimCursor",
+];
+
+pub mod error_codes {
+    pub const PICT_FORMAT: u8 = 0;
+    pub const PICTURE: u8 = 1;
+    pub const PICT_OP: u8 = 2;
+    pub const GLYPH_SET: u8 = 3;
+    pub const GLYPH: u8 = 4;
+}
+
+pub mod pict_type {
+    pub const INDEXED: u32 = 0;
+    pub const DIRECT: u32 = 1;
+}
+
+pub mod cp {
+    pub const REPEAT: u32 = 1;
+    pub const ALPHA_MAP: u32 = 2;
+    pub const ALPHA_X_ORIGIN: u32 = 4;
+    pub const ALPHA_Y_ORIGIN: u32 = 8;
+    pub const CLIP_X_ORIGIN: u32 = 16;
+    pub const CLIP_Y_ORIGIN: u32 = 32;
+    pub const CLIP_MASK: u32 = 64;
+    pub const GRAPHICS_EXPOSURE: u32 = 128;
+    pub const SUBWINDOW_MODE: u32 = 256;
+    pub const POLY_EDGE: u32 = 512;
+    pub const POLY_MODE: u32 = 1024;
+    pub const DITHER: u32 = 2048;
+    pub const COMPONENT_ALPHA: u32 = 4096;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Directformat {
+    pub red_shift: CARD16,
+    pub red_mask: CARD16,
+    pub green_shift: CARD16,
+    pub green_mask: CARD16,
+    pub blue_shift: CARD16,
+    pub blue_mask: CARD16,
+    pub alpha_shift: CARD16,
+    pub alpha_mask: CARD16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pictforminfo {
+    pub id: PICTFORMAT,
+    pub type_: CARD8,
+    pub depth: CARD8,
+    #[wire(pad = 2)]
+    pub direct: Directformat,
+    pub colormap: COLORMAP,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pictvisual {
+    pub visual: VISUALID,
+    pub format: PICTFORMAT,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pictdepth {
+    pub depth: CARD8,
+    #[wire(pad = 1, len_of = "visuals")]
+    pub num_visuals: CARD16,
+    #[wire(pad = 4, len = "num_visuals as usize")]
+    pub visuals: Vec<Pictvisual>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pictscreen {
+    #[wire(len_of = "depths")]
+    pub num_depths: CARD32,
+    pub fallback: PICTFORMAT,
+    #[wire(len = "num_depths as usize")]
+    pub depths: Vec<Pictdepth>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Animcursorelt {
+    pub cursor: CURSOR,
+    pub delay: CARD32,
+}
+
+pub fn query_version_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    client_major_version: CARD32,
+    client_minor_version: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::QUERY_VERSION);
+    client_major_version.serialize(&mut request)?;
+    client_minor_version.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryVersionReply {
+    pub major_version: CARD32,
+    pub minor_version: CARD32,
+}
+
+impl QueryVersionReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let major_version: CARD32 = r.read()?;
+        let minor_version: CARD32 = r.read()?;
+        r.skip(16)?;
+        Ok(Self {
+            major_version,
+            minor_version,
+        })
+    }
+}
+
+pub fn query_pict_formats_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+) -> io::Result<()> {
+    RequestBuffer::new(major_opcode, opcodes::QUERY_PICT_FORMATS).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryPictFormatsReply {
+    pub num_depths: CARD32,
+    pub num_visuals: CARD32,
+    pub formats: Vec<Pictforminfo>,
+    pub screens: Vec<Pictscreen>,
+    pub subpixels: Vec<CARD32>,
+}
+
+impl QueryPictFormatsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let num_formats: CARD32 = r.read()?;
+        let num_screens: CARD32 = r.read()?;
+        let num_depths: CARD32 = r.read()?;
+        let num_visuals: CARD32 = r.read()?;
+        let num_subpixel: CARD32 = r.read()?;
+        r.skip(4)?;
+        let formats = r.list(num_formats as usize)?;
+        let screens = r.list(num_screens as usize)?;
+        let subpixels = r.list(num_subpixel as usize)?;
+        Ok(Self {
+            num_depths,
+            num_visuals,
+            formats,
+            screens,
+            subpixels,
+        })
+    }
+}
+
+pub fn create_picture<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    pid: PICTURE,
+    drawable: DRAWABLE,
+    format: PICTFORMAT,
+    value_list: &[(CARD32, CARD32)],
+) -> io::Result<()> {
+    // the server wants the values in the order of their bits
+    let mut value_list = value_list.to_vec();
+    value_list.sort_by_key(|&(bit, _)| bit);
+    let value_mask = value_list.iter().fold(0, |mask, &(bit, _)| mask | bit);
+    let mut request = RequestBuffer::new(major_opcode, opcodes::CREATE_PICTURE);
+    pid.serialize(&mut request)?;
+    drawable.serialize(&mut request)?;
+    format.serialize(&mut request)?;
+    value_mask.serialize(&mut request)?;
+    for (_, value) in value_list {
+        value.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn free_picture<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    picture: PICTURE,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::FREE_PICTURE);
+    picture.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn create_cursor<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    cid: CURSOR,
+    source: PICTURE,
+    x: CARD16,
+    y: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::CREATE_CURSOR);
+    cid.serialize(&mut request)?;
+    source.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn create_anim_cursor<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    cid: CURSOR,
+    cursors: &[Animcursorelt],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::CREATE_ANIM_CURSOR);
+    cid.serialize(&mut request)?;
+    for item in cursors {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PictFormatError {}
+
+impl PictFormatError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PictureError {}
+
+impl PictureError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PictOpError {}
+
+impl PictOpError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GlyphSetError {}
+
+impl GlyphSetError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GlyphError {}
+
+impl GlyphError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        Ok(Self {})
+    }
+}
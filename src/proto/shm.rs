@@ -0,0 +1,288 @@
+// Generated by xcbgen from proto/shm.xml, edit that and rerun `cargo run -p xcbgen`
+#![allow(clippy::too_many_arguments)]
+
+#[allow(unused_imports)]
+use super::xproto::*;
+use super::*;
+use std::io::Write;
+
+pub const EXTENSION_NAME: &str = "MIT-SHM";
+pub const MAJOR_VERSION: u32 = 1;
+pub const MINOR_VERSION: u32 = 2;
+
+pub type SEG = CARD32;
+
+pub mod opcodes {
+    pub const QUERY_VERSION: u8 = 0;
+    pub const ATTACH: u8 = 1;
+    pub const DETACH: u8 = 2;
+    pub const PUT_IMAGE: u8 = 3;
+    pub const GET_IMAGE: u8 = 4;
+    pub const CREATE_PIXMAP: u8 = 5;
+    pub const ATTACH_FD: u8 = 6;
+    pub const CREATE_SEGMENT: u8 = 7;
+}
+
+pub const REQUEST_NAMES: [&str; 8] = [
+    "QueryVersion",
+    "Attach",
+    "Detach",
+    "PutImage",
+    "GetImage",
+    "CreatePixmap",
+    "AttachFd",
+    "CreateSegment",
+];
+
+pub mod event_codes {
+    pub const COMPLETION: u8 = 0;
+}
+
+pub mod error_codes {
+    pub const BAD_SEG: u8 = 0;
+}
+
+pub fn query_version_request<S: io::Write>(socket: &mut S, major_opcode: CARD8) -> io::Result<()> {
+    RequestBuffer::new(major_opcode, opcodes::QUERY_VERSION).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryVersionReply {
+    pub shared_pixmaps: BOOL,
+    pub major_version: CARD16,
+    pub minor_version: CARD16,
+    pub uid: CARD16,
+    pub gid: CARD16,
+    pub pixmap_format: CARD8,
+}
+
+impl QueryVersionReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let shared_pixmaps: BOOL = r.read()?;
+        r.skip(6)?;
+        let major_version: CARD16 = r.read()?;
+        let minor_version: CARD16 = r.read()?;
+        let uid: CARD16 = r.read()?;
+        let gid: CARD16 = r.read()?;
+        let pixmap_format: CARD8 = r.read()?;
+        r.skip(15)?;
+        Ok(Self {
+            shared_pixmaps,
+            major_version,
+            minor_version,
+            uid,
+            gid,
+            pixmap_format,
+        })
+    }
+}
+
+pub fn attach<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    shmseg: SEG,
+    shmid: CARD32,
+    read_only: BOOL,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::ATTACH);
+    shmseg.serialize(&mut request)?;
+    shmid.serialize(&mut request)?;
+    read_only.serialize(&mut request)?;
+    request.write_all(&[0; 3])?;
+    request.send(socket)
+}
+
+pub fn detach<S: io::Write>(socket: &mut S, major_opcode: CARD8, shmseg: SEG) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::DETACH);
+    shmseg.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn put_image<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    total_width: CARD16,
+    total_height: CARD16,
+    src_x: CARD16,
+    src_y: CARD16,
+    src_width: CARD16,
+    src_height: CARD16,
+    dst_x: INT16,
+    dst_y: INT16,
+    depth: CARD8,
+    format: CARD8,
+    send_event: BOOL,
+    shmseg: SEG,
+    offset: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::PUT_IMAGE);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    total_width.serialize(&mut request)?;
+    total_height.serialize(&mut request)?;
+    src_x.serialize(&mut request)?;
+    src_y.serialize(&mut request)?;
+    src_width.serialize(&mut request)?;
+    src_height.serialize(&mut request)?;
+    dst_x.serialize(&mut request)?;
+    dst_y.serialize(&mut request)?;
+    depth.serialize(&mut request)?;
+    format.serialize(&mut request)?;
+    send_event.serialize(&mut request)?;
+    request.write_all(&[0; 1])?;
+    shmseg.serialize(&mut request)?;
+    offset.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_image_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    drawable: DRAWABLE,
+    x: INT16,
+    y: INT16,
+    width: CARD16,
+    height: CARD16,
+    plane_mask: CARD32,
+    format: CARD8,
+    shmseg: SEG,
+    offset: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::GET_IMAGE);
+    drawable.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    plane_mask.serialize(&mut request)?;
+    format.serialize(&mut request)?;
+    request.write_all(&[0; 3])?;
+    shmseg.serialize(&mut request)?;
+    offset.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetImageReply {
+    pub depth: CARD8,
+    pub visual: VISUALID,
+    pub size: CARD32,
+}
+
+impl GetImageReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let depth: CARD8 = r.read()?;
+        r.skip(6)?;
+        let visual: VISUALID = r.read()?;
+        let size: CARD32 = r.read()?;
+        r.skip(16)?;
+        Ok(Self {
+            depth,
+            visual,
+            size,
+        })
+    }
+}
+
+pub fn create_pixmap<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    pid: PIXMAP,
+    drawable: DRAWABLE,
+    width: CARD16,
+    height: CARD16,
+    depth: CARD8,
+    shmseg: SEG,
+    offset: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::CREATE_PIXMAP);
+    pid.serialize(&mut request)?;
+    drawable.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    depth.serialize(&mut request)?;
+    request.write_all(&[0; 3])?;
+    shmseg.serialize(&mut request)?;
+    offset.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn attach_fd<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    shmseg: SEG,
+    read_only: BOOL,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::ATTACH_FD);
+    shmseg.serialize(&mut request)?;
+    read_only.serialize(&mut request)?;
+    request.write_all(&[0; 3])?;
+    request.send(socket)
+}
+
+pub fn create_segment_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+    shmseg: SEG,
+    size: CARD32,
+    read_only: BOOL,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(major_opcode, opcodes::CREATE_SEGMENT);
+    shmseg.serialize(&mut request)?;
+    size.serialize(&mut request)?;
+    read_only.serialize(&mut request)?;
+    request.write_all(&[0; 3])?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CreateSegmentReply {
+    pub nfd: CARD8,
+}
+
+impl CreateSegmentReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let nfd: CARD8 = r.read()?;
+        r.skip(30)?;
+        Ok(Self { nfd })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompletionEvent {
+    pub drawable: DRAWABLE,
+    pub minor_event: CARD16,
+    pub major_event: CARD8,
+    pub shmseg: SEG,
+    pub offset: CARD32,
+}
+
+impl CompletionEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let drawable: DRAWABLE = r.read()?;
+        let minor_event: CARD16 = r.read()?;
+        let major_event: CARD8 = r.read()?;
+        r.skip(1)?;
+        let shmseg: SEG = r.read()?;
+        let offset: CARD32 = r.read()?;
+        Ok(Self {
+            drawable,
+            minor_event,
+            major_event,
+            shmseg,
+            offset,
+        })
+    }
+}
+
+pub type BadSegError = ValueError;
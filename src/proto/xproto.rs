@@ -0,0 +1,4508 @@
+// Generated by xcbgen from proto/xproto.xml, edit that and rerun `cargo run -p xcbgen`
+#![allow(clippy::too_many_arguments)]
+
+use super::*;
+use std::io::Write;
+
+pub type FONTABLE = CARD32;
+pub type BOOL32 = CARD32;
+pub type VISUALID = CARD32;
+pub type TIMESTAMP = CARD32;
+pub type KEYSYM = CARD32;
+pub type KEYCODE32 = CARD32;
+pub type BUTTON = CARD8;
+pub type ClientMessageData = [u8; 20];
+
+pub mod opcodes {
+    pub const CREATE_WINDOW: u8 = 1;
+    pub const CHANGE_WINDOW_ATTRIBUTES: u8 = 2;
+    pub const GET_WINDOW_ATTRIBUTES: u8 = 3;
+    pub const DESTROY_WINDOW: u8 = 4;
+    pub const DESTROY_SUBWINDOWS: u8 = 5;
+    pub const CHANGE_SAVE_SET: u8 = 6;
+    pub const REPARENT_WINDOW: u8 = 7;
+    pub const MAP_WINDOW: u8 = 8;
+    pub const MAP_SUBWINDOWS: u8 = 9;
+    pub const UNMAP_WINDOW: u8 = 10;
+    pub const UNMAP_SUBWINDOWS: u8 = 11;
+    pub const CONFIGURE_WINDOW: u8 = 12;
+    pub const CIRCULATE_WINDOW: u8 = 13;
+    pub const GET_GEOMETRY: u8 = 14;
+    pub const QUERY_TREE: u8 = 15;
+    pub const INTERN_ATOM: u8 = 16;
+    pub const GET_ATOM_NAME: u8 = 17;
+    pub const CHANGE_PROPERTY: u8 = 18;
+    pub const DELETE_PROPERTY: u8 = 19;
+    pub const GET_PROPERTY: u8 = 20;
+    pub const LIST_PROPERTIES: u8 = 21;
+    pub const SET_SELECTION_OWNER: u8 = 22;
+    pub const GET_SELECTION_OWNER: u8 = 23;
+    pub const CONVERT_SELECTION: u8 = 24;
+    pub const SEND_EVENT: u8 = 25;
+    pub const GRAB_POINTER: u8 = 26;
+    pub const UNGRAB_POINTER: u8 = 27;
+    pub const GRAB_BUTTON: u8 = 28;
+    pub const UNGRAB_BUTTON: u8 = 29;
+    pub const CHANGE_ACTIVE_POINTER_GRAB: u8 = 30;
+    pub const GRAB_KEYBOARD: u8 = 31;
+    pub const UNGRAB_KEYBOARD: u8 = 32;
+    pub const GRAB_KEY: u8 = 33;
+    pub const UNGRAB_KEY: u8 = 34;
+    pub const ALLOW_EVENTS: u8 = 35;
+    pub const GRAB_SERVER: u8 = 36;
+    pub const UNGRAB_SERVER: u8 = 37;
+    pub const QUERY_POINTER: u8 = 38;
+    pub const GET_MOTION_EVENTS: u8 = 39;
+    pub const TRANSLATE_COORDINATES: u8 = 40;
+    pub const WARP_POINTER: u8 = 41;
+    pub const SET_INPUT_FOCUS: u8 = 42;
+    pub const GET_INPUT_FOCUS: u8 = 43;
+    pub const QUERY_KEYMAP: u8 = 44;
+    pub const OPEN_FONT: u8 = 45;
+    pub const CLOSE_FONT: u8 = 46;
+    pub const QUERY_FONT: u8 = 47;
+    pub const QUERY_TEXT_EXTENTS: u8 = 48;
+    pub const LIST_FONTS: u8 = 49;
+    pub const LIST_FONTS_WITH_INFO: u8 = 50;
+    pub const SET_FONT_PATH: u8 = 51;
+    pub const GET_FONT_PATH: u8 = 52;
+    pub const CREATE_PIXMAP: u8 = 53;
+    pub const FREE_PIXMAP: u8 = 54;
+    pub const CREATE_GC: u8 = 55;
+    pub const CHANGE_GC: u8 = 56;
+    pub const COPY_GC: u8 = 57;
+    pub const SET_DASHES: u8 = 58;
+    pub const SET_CLIP_RECTANGLES: u8 = 59;
+    pub const FREE_GC: u8 = 60;
+    pub const CLEAR_AREA: u8 = 61;
+    pub const COPY_AREA: u8 = 62;
+    pub const COPY_PLANE: u8 = 63;
+    pub const POLY_POINT: u8 = 64;
+    pub const POLY_LINE: u8 = 65;
+    pub const POLY_SEGMENT: u8 = 66;
+    pub const POLY_RECTANGLE: u8 = 67;
+    pub const POLY_ARC: u8 = 68;
+    pub const FILL_POLY: u8 = 69;
+    pub const POLY_FILL_RECTANGLE: u8 = 70;
+    pub const POLY_FILL_ARC: u8 = 71;
+    pub const PUT_IMAGE: u8 = 72;
+    pub const GET_IMAGE: u8 = 73;
+    pub const POLY_TEXT8: u8 = 74;
+    pub const POLY_TEXT16: u8 = 75;
+    pub const IMAGE_TEXT8: u8 = 76;
+    pub const IMAGE_TEXT16: u8 = 77;
+    pub const CREATE_COLORMAP: u8 = 78;
+    pub const FREE_COLORMAP: u8 = 79;
+    pub const COPY_COLORMAP_AND_FREE: u8 = 80;
+    pub const INSTALL_COLORMAP: u8 = 81;
+    pub const UNINSTALL_COLORMAP: u8 = 82;
+    pub const LIST_INSTALLED_COLORMAPS: u8 = 83;
+    pub const ALLOC_COLOR: u8 = 84;
+    pub const ALLOC_NAMED_COLOR: u8 = 85;
+    pub const ALLOC_COLOR_CELLS: u8 = 86;
+    pub const ALLOC_COLOR_PLANES: u8 = 87;
+    pub const FREE_COLORS: u8 = 88;
+    pub const STORE_COLORS: u8 = 89;
+    pub const STORE_NAMED_COLOR: u8 = 90;
+    pub const QUERY_COLORS: u8 = 91;
+    pub const LOOKUP_COLOR: u8 = 92;
+    pub const CREATE_CURSOR: u8 = 93;
+    pub const CREATE_GLYPH_CURSOR: u8 = 94;
+    pub const FREE_CURSOR: u8 = 95;
+    pub const RECOLOR_CURSOR: u8 = 96;
+    pub const QUERY_BEST_SIZE: u8 = 97;
+    pub const QUERY_EXTENSION: u8 = 98;
+    pub const LIST_EXTENSIONS: u8 = 99;
+    pub const CHANGE_KEYBOARD_MAPPING: u8 = 100;
+    pub const GET_KEYBOARD_MAPPING: u8 = 101;
+    pub const CHANGE_KEYBOARD_CONTROL: u8 = 102;
+    pub const GET_KEYBOARD_CONTROL: u8 = 103;
+    pub const BELL: u8 = 104;
+    pub const CHANGE_POINTER_CONTROL: u8 = 105;
+    pub const GET_POINTER_CONTROL: u8 = 106;
+    pub const SET_SCREEN_SAVER: u8 = 107;
+    pub const GET_SCREEN_SAVER: u8 = 108;
+    pub const CHANGE_HOSTS: u8 = 109;
+    pub const LIST_HOSTS: u8 = 110;
+    pub const SET_ACCESS_CONTROL: u8 = 111;
+    pub const SET_CLOSE_DOWN_MODE: u8 = 112;
+    pub const KILL_CLIENT: u8 = 113;
+    pub const ROTATE_PROPERTIES: u8 = 114;
+    pub const FORCE_SCREEN_SAVER: u8 = 115;
+    pub const SET_POINTER_MAPPING: u8 = 116;
+    pub const GET_POINTER_MAPPING: u8 = 117;
+    pub const SET_MODIFIER_MAPPING: u8 = 118;
+    pub const GET_MODIFIER_MAPPING: u8 = 119;
+    pub const NO_OPERATION: u8 = 127;
+}
+
+pub const REQUEST_NAMES: [&str; 128] = [
+    "",
+    "CreateWindow",
+    "ChangeWindowAttributes",
+    "GetWindowAttributes",
+    "DestroyWindow",
+    "DestroySubwindows",
+    "ChangeSaveSet",
+    "ReparentWindow",
+    "MapWindow",
+    "MapSubwindows",
+    "UnmapWindow",
+    "UnmapSubwindows",
+    "ConfigureWindow",
+    "CirculateWindow",
+    "GetGeometry",
+    "QueryTree",
+    "InternAtom",
+    "GetAtomName",
+    "ChangeProperty",
+    "DeleteProperty",
+    "GetProperty",
+    "ListProperties",
+    "SetSelectionOwner",
+    "GetSelectionOwner",
+    "ConvertSelection",
+    "SendEvent",
+    "GrabPointer",
+    "UngrabPointer",
+    "GrabButton",
+    "UngrabButton",
+    "ChangeActivePointerGrab",
+    "GrabKeyboard",
+    "UngrabKeyboard",
+    "GrabKey",
+    "UngrabKey",
+    "AllowEvents",
+    "GrabServer",
+    "UngrabServer",
+    "QueryPointer",
+    "GetMotionEvents",
+    "TranslateCoordinates",
+    "WarpPointer",
+    "SetInputFocus",
+    "GetInputFocus",
+    "QueryKeymap",
+    "OpenFont",
+    "CloseFont",
+    "QueryFont",
+    "QueryTextExtents",
+    "ListFonts",
+    "ListFontsWithInfo",
+    "SetFontPath",
+    "GetFontPath",
+    "CreatePixmap",
+    "FreePixmap",
+    "CreateGC",
+    "ChangeGC",
+    "CopyGC",
+    "SetDashes",
+    "SetClipRectangles",
+    "FreeGC",
+    "ClearArea",
+    "CopyArea",
+    "CopyPlane",
+    "PolyPoint",
+    "PolyLine",
+    "PolySegment",
+    "PolyRectangle",
+    "PolyArc",
+    "FillPoly",
+    "PolyFillRectangle",
+    "PolyFillArc",
+    "PutImage",
+    "GetImage",
+    "PolyText8",
+    "PolyText16",
+    "ImageText8",
+    "ImageText16",
+    "CreateColormap",
+    "FreeColormap",
+    "CopyColormapAndFree",
+    "InstallColormap",
+    "UninstallColormap",
+    "ListInstalledColormaps",
+    "AllocColor",
+    "AllocNamedColor",
+    "AllocColorCells",
+    "AllocColorPlanes",
+    "FreeColors",
+    "StoreColors",
+    "StoreNamedColor",
+    "QueryColors",
+    "LookupColor",
+    "CreateCursor",
+    "CreateGlyphCursor",
+    "FreeCursor",
+    "RecolorCursor",
+    "QueryBestSize",
+    "QueryExtension",
+    "ListExtensions",
+    "ChangeKeyboardMapping",
+    "GetKeyboardMapping",
+    "ChangeKeyboardControl",
+    "GetKeyboardControl",
+    "Bell",
+    "ChangePointerControl",
+    "GetPointerControl",
+    "SetScreenSaver",
+    "GetScreenSaver",
+    "ChangeHosts",
+    "ListHosts",
+    "SetAccessControl",
+    "SetCloseDownMode",
+    "KillClient",
+    "RotateProperties",
+    "ForceScreenSaver",
+    "SetPointerMapping",
+    "GetPointerMapping",
+    "SetModifierMapping",
+    "GetModifierMapping",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "NoOperation",
+];
+
+pub mod event_codes {
+    pub const KEY_PRESS: u8 = 2;
+    pub const KEY_RELEASE: u8 = 3;
+    pub const BUTTON_PRESS: u8 = 4;
+    pub const BUTTON_RELEASE: u8 = 5;
+    pub const MOTION_NOTIFY: u8 = 6;
+    pub const ENTER_NOTIFY: u8 = 7;
+    pub const LEAVE_NOTIFY: u8 = 8;
+    pub const FOCUS_IN: u8 = 9;
+    pub const FOCUS_OUT: u8 = 10;
+    pub const KEYMAP_NOTIFY: u8 = 11;
+    pub const EXPOSE: u8 = 12;
+    pub const GRAPHICS_EXPOSURE: u8 = 13;
+    pub const NO_EXPOSURE: u8 = 14;
+    pub const VISIBILITY_NOTIFY: u8 = 15;
+    pub const CREATE_NOTIFY: u8 = 16;
+    pub const DESTROY_NOTIFY: u8 = 17;
+    pub const UNMAP_NOTIFY: u8 = 18;
+    pub const MAP_NOTIFY: u8 = 19;
+    pub const MAP_REQUEST: u8 = 20;
+    pub const REPARENT_NOTIFY: u8 = 21;
+    pub const CONFIGURE_NOTIFY: u8 = 22;
+    pub const CONFIGURE_REQUEST: u8 = 23;
+    pub const GRAVITY_NOTIFY: u8 = 24;
+    pub const RESIZE_REQUEST: u8 = 25;
+    pub const CIRCULATE_NOTIFY: u8 = 26;
+    pub const CIRCULATE_REQUEST: u8 = 27;
+    pub const PROPERTY_NOTIFY: u8 = 28;
+    pub const SELECTION_CLEAR: u8 = 29;
+    pub const SELECTION_REQUEST: u8 = 30;
+    pub const SELECTION_NOTIFY: u8 = 31;
+    pub const COLORMAP_NOTIFY: u8 = 32;
+    pub const CLIENT_MESSAGE: u8 = 33;
+    pub const MAPPING_NOTIFY: u8 = 34;
+}
+
+pub mod error_codes {
+    pub const REQUEST: u8 = 1;
+    pub const VALUE: u8 = 2;
+    pub const WINDOW: u8 = 3;
+    pub const PIXMAP: u8 = 4;
+    pub const ATOM: u8 = 5;
+    pub const CURSOR: u8 = 6;
+    pub const FONT: u8 = 7;
+    pub const MATCH: u8 = 8;
+    pub const DRAWABLE: u8 = 9;
+    pub const ACCESS: u8 = 10;
+    pub const ALLOC: u8 = 11;
+    pub const COLORMAP: u8 = 12;
+    pub const G_CONTEXT: u8 = 13;
+    pub const ID_CHOICE: u8 = 14;
+    pub const NAME: u8 = 15;
+    pub const LENGTH: u8 = 16;
+    pub const IMPLEMENTATION: u8 = 17;
+}
+
+pub mod visual_class {
+    pub const STATIC_GRAY: u32 = 0;
+    pub const GRAY_SCALE: u32 = 1;
+    pub const STATIC_COLOR: u32 = 2;
+    pub const PSEUDO_COLOR: u32 = 3;
+    pub const TRUE_COLOR: u32 = 4;
+    pub const DIRECT_COLOR: u32 = 5;
+}
+
+pub mod event_mask {
+    pub const NO_EVENT: u32 = 0;
+    pub const KEY_PRESS: u32 = 1;
+    pub const KEY_RELEASE: u32 = 2;
+    pub const BUTTON_PRESS: u32 = 4;
+    pub const BUTTON_RELEASE: u32 = 8;
+    pub const ENTER_WINDOW: u32 = 16;
+    pub const LEAVE_WINDOW: u32 = 32;
+    pub const POINTER_MOTION: u32 = 64;
+    pub const POINTER_MOTION_HINT: u32 = 128;
+    pub const BUTTON1_MOTION: u32 = 256;
+    pub const BUTTON2_MOTION: u32 = 512;
+    pub const BUTTON3_MOTION: u32 = 1024;
+    pub const BUTTON4_MOTION: u32 = 2048;
+    pub const BUTTON5_MOTION: u32 = 4096;
+    pub const BUTTON_MOTION: u32 = 8192;
+    pub const KEYMAP_STATE: u32 = 16384;
+    pub const EXPOSURE: u32 = 32768;
+    pub const VISIBILITY_CHANGE: u32 = 65536;
+    pub const STRUCTURE_NOTIFY: u32 = 131072;
+    pub const RESIZE_REDIRECT: u32 = 262144;
+    pub const SUBSTRUCTURE_NOTIFY: u32 = 524288;
+    pub const SUBSTRUCTURE_REDIRECT: u32 = 1048576;
+    pub const FOCUS_CHANGE: u32 = 2097152;
+    pub const PROPERTY_CHANGE: u32 = 4194304;
+    pub const COLOR_MAP_CHANGE: u32 = 8388608;
+    pub const OWNER_GRAB_BUTTON: u32 = 16777216;
+}
+
+pub mod backing_store {
+    pub const NOT_USEFUL: u32 = 0;
+    pub const WHEN_MAPPED: u32 = 1;
+    pub const ALWAYS: u32 = 2;
+}
+
+pub mod image_order {
+    pub const LSB_FIRST: u32 = 0;
+    pub const MSB_FIRST: u32 = 1;
+}
+
+pub mod mod_mask {
+    pub const SHIFT: u32 = 1;
+    pub const LOCK: u32 = 2;
+    pub const CONTROL: u32 = 4;
+    pub const _1: u32 = 8;
+    pub const _2: u32 = 16;
+    pub const _3: u32 = 32;
+    pub const _4: u32 = 64;
+    pub const _5: u32 = 128;
+    pub const ANY: u32 = 32768;
+}
+
+pub mod key_but_mask {
+    pub const SHIFT: u32 = 1;
+    pub const LOCK: u32 = 2;
+    pub const CONTROL: u32 = 4;
+    pub const MOD1: u32 = 8;
+    pub const MOD2: u32 = 16;
+    pub const MOD3: u32 = 32;
+    pub const MOD4: u32 = 64;
+    pub const MOD5: u32 = 128;
+    pub const BUTTON1: u32 = 256;
+    pub const BUTTON2: u32 = 512;
+    pub const BUTTON3: u32 = 1024;
+    pub const BUTTON4: u32 = 2048;
+    pub const BUTTON5: u32 = 4096;
+}
+
+pub mod window {
+    pub const NONE: u32 = 0;
+}
+
+pub mod button_mask {
+    pub const _1: u32 = 256;
+    pub const _2: u32 = 512;
+    pub const _3: u32 = 1024;
+    pub const _4: u32 = 2048;
+    pub const _5: u32 = 4096;
+    pub const ANY: u32 = 32768;
+}
+
+pub mod motion {
+    pub const NORMAL: u32 = 0;
+    pub const HINT: u32 = 1;
+}
+
+pub mod notify_detail {
+    pub const ANCESTOR: u32 = 0;
+    pub const VIRTUAL: u32 = 1;
+    pub const INFERIOR: u32 = 2;
+    pub const NONLINEAR: u32 = 3;
+    pub const NONLINEAR_VIRTUAL: u32 = 4;
+    pub const POINTER: u32 = 5;
+    pub const POINTER_ROOT: u32 = 6;
+    pub const NONE: u32 = 7;
+}
+
+pub mod notify_mode {
+    pub const NORMAL: u32 = 0;
+    pub const GRAB: u32 = 1;
+    pub const UNGRAB: u32 = 2;
+    pub const WHILE_GRABBED: u32 = 3;
+}
+
+pub mod visibility {
+    pub const UNOBSCURED: u32 = 0;
+    pub const PARTIALLY_OBSCURED: u32 = 1;
+    pub const FULLY_OBSCURED: u32 = 2;
+}
+
+pub mod place {
+    pub const ON_TOP: u32 = 0;
+    pub const ON_BOTTOM: u32 = 1;
+}
+
+pub mod property {
+    pub const NEW_VALUE: u32 = 0;
+    pub const DELETE: u32 = 1;
+}
+
+pub mod time {
+    pub const CURRENT_TIME: u32 = 0;
+}
+
+pub mod atom {
+    pub const NONE: u32 = 0;
+    pub const ANY: u32 = 0;
+    pub const PRIMARY: u32 = 1;
+    pub const SECONDARY: u32 = 2;
+    pub const ARC: u32 = 3;
+    pub const ATOM: u32 = 4;
+    pub const BITMAP: u32 = 5;
+    pub const CARDINAL: u32 = 6;
+    pub const COLORMAP: u32 = 7;
+    pub const CURSOR: u32 = 8;
+    pub const CUT_BUFFER0: u32 = 9;
+    pub const CUT_BUFFER1: u32 = 10;
+    pub const CUT_BUFFER2: u32 = 11;
+    pub const CUT_BUFFER3: u32 = 12;
+    pub const CUT_BUFFER4: u32 = 13;
+    pub const CUT_BUFFER5: u32 = 14;
+    pub const CUT_BUFFER6: u32 = 15;
+    pub const CUT_BUFFER7: u32 = 16;
+    pub const DRAWABLE: u32 = 17;
+    pub const FONT: u32 = 18;
+    pub const INTEGER: u32 = 19;
+    pub const PIXMAP: u32 = 20;
+    pub const POINT: u32 = 21;
+    pub const RECTANGLE: u32 = 22;
+    pub const RESOURCE_MANAGER: u32 = 23;
+    pub const RGB_COLOR_MAP: u32 = 24;
+    pub const RGB_BEST_MAP: u32 = 25;
+    pub const RGB_BLUE_MAP: u32 = 26;
+    pub const RGB_DEFAULT_MAP: u32 = 27;
+    pub const RGB_GRAY_MAP: u32 = 28;
+    pub const RGB_GREEN_MAP: u32 = 29;
+    pub const RGB_RED_MAP: u32 = 30;
+    pub const STRING: u32 = 31;
+    pub const VISUALID: u32 = 32;
+    pub const WINDOW: u32 = 33;
+    pub const WM_COMMAND: u32 = 34;
+    pub const WM_HINTS: u32 = 35;
+    pub const WM_CLIENT_MACHINE: u32 = 36;
+    pub const WM_ICON_NAME: u32 = 37;
+    pub const WM_ICON_SIZE: u32 = 38;
+    pub const WM_NAME: u32 = 39;
+    pub const WM_NORMAL_HINTS: u32 = 40;
+    pub const WM_SIZE_HINTS: u32 = 41;
+    pub const WM_ZOOM_HINTS: u32 = 42;
+    pub const MIN_SPACE: u32 = 43;
+    pub const NORM_SPACE: u32 = 44;
+    pub const MAX_SPACE: u32 = 45;
+    pub const END_SPACE: u32 = 46;
+    pub const SUPERSCRIPT_X: u32 = 47;
+    pub const SUPERSCRIPT_Y: u32 = 48;
+    pub const SUBSCRIPT_X: u32 = 49;
+    pub const SUBSCRIPT_Y: u32 = 50;
+    pub const UNDERLINE_POSITION: u32 = 51;
+    pub const UNDERLINE_THICKNESS: u32 = 52;
+    pub const STRIKEOUT_ASCENT: u32 = 53;
+    pub const STRIKEOUT_DESCENT: u32 = 54;
+    pub const ITALIC_ANGLE: u32 = 55;
+    pub const X_HEIGHT: u32 = 56;
+    pub const QUAD_WIDTH: u32 = 57;
+    pub const WEIGHT: u32 = 58;
+    pub const POINT_SIZE: u32 = 59;
+    pub const RESOLUTION: u32 = 60;
+    pub const COPYRIGHT: u32 = 61;
+    pub const NOTICE: u32 = 62;
+    pub const FONT_NAME: u32 = 63;
+    pub const FAMILY_NAME: u32 = 64;
+    pub const FULL_NAME: u32 = 65;
+    pub const CAP_HEIGHT: u32 = 66;
+    pub const WM_CLASS: u32 = 67;
+    pub const WM_TRANSIENT_FOR: u32 = 68;
+}
+
+pub mod colormap_state {
+    pub const UNINSTALLED: u32 = 0;
+    pub const INSTALLED: u32 = 1;
+}
+
+pub mod colormap {
+    pub const NONE: u32 = 0;
+}
+
+pub mod mapping {
+    pub const MODIFIER: u32 = 0;
+    pub const KEYBOARD: u32 = 1;
+    pub const POINTER: u32 = 2;
+}
+
+pub mod window_class {
+    pub const COPY_FROM_PARENT: u32 = 0;
+    pub const INPUT_OUTPUT: u32 = 1;
+    pub const INPUT_ONLY: u32 = 2;
+}
+
+pub mod cw {
+    pub const BACK_PIXMAP: u32 = 1;
+    pub const BACK_PIXEL: u32 = 2;
+    pub const BORDER_PIXMAP: u32 = 4;
+    pub const BORDER_PIXEL: u32 = 8;
+    pub const BIT_GRAVITY: u32 = 16;
+    pub const WIN_GRAVITY: u32 = 32;
+    pub const BACKING_STORE: u32 = 64;
+    pub const BACKING_PLANES: u32 = 128;
+    pub const BACKING_PIXEL: u32 = 256;
+    pub const OVERRIDE_REDIRECT: u32 = 512;
+    pub const SAVE_UNDER: u32 = 1024;
+    pub const EVENT_MASK: u32 = 2048;
+    pub const DONT_PROPAGATE: u32 = 4096;
+    pub const COLORMAP: u32 = 8192;
+    pub const CURSOR: u32 = 16384;
+}
+
+pub mod back_pixmap {
+    pub const NONE: u32 = 0;
+    pub const PARENT_RELATIVE: u32 = 1;
+}
+
+pub mod gravity {
+    pub const BIT_FORGET: u32 = 0;
+    pub const WIN_UNMAP: u32 = 0;
+    pub const NORTH_WEST: u32 = 1;
+    pub const NORTH: u32 = 2;
+    pub const NORTH_EAST: u32 = 3;
+    pub const WEST: u32 = 4;
+    pub const CENTER: u32 = 5;
+    pub const EAST: u32 = 6;
+    pub const SOUTH_WEST: u32 = 7;
+    pub const SOUTH: u32 = 8;
+    pub const SOUTH_EAST: u32 = 9;
+    pub const STATIC: u32 = 10;
+}
+
+pub mod map_state {
+    pub const UNMAPPED: u32 = 0;
+    pub const UNVIEWABLE: u32 = 1;
+    pub const VIEWABLE: u32 = 2;
+}
+
+pub mod set_mode {
+    pub const INSERT: u32 = 0;
+    pub const DELETE: u32 = 1;
+}
+
+pub mod config_window {
+    pub const X: u32 = 1;
+    pub const Y: u32 = 2;
+    pub const WIDTH: u32 = 4;
+    pub const HEIGHT: u32 = 8;
+    pub const BORDER_WIDTH: u32 = 16;
+    pub const SIBLING: u32 = 32;
+    pub const STACK_MODE: u32 = 64;
+}
+
+pub mod stack_mode {
+    pub const ABOVE: u32 = 0;
+    pub const BELOW: u32 = 1;
+    pub const TOP_IF: u32 = 2;
+    pub const BOTTOM_IF: u32 = 3;
+    pub const OPPOSITE: u32 = 4;
+}
+
+pub mod circulate {
+    pub const RAISE_LOWEST: u32 = 0;
+    pub const LOWER_HIGHEST: u32 = 1;
+}
+
+pub mod prop_mode {
+    pub const REPLACE: u32 = 0;
+    pub const PREPEND: u32 = 1;
+    pub const APPEND: u32 = 2;
+}
+
+pub mod get_property_type {
+    pub const ANY: u32 = 0;
+}
+
+pub mod send_event_dest {
+    pub const POINTER_WINDOW: u32 = 0;
+    pub const ITEM_FOCUS: u32 = 1;
+}
+
+pub mod grab_mode {
+    pub const SYNC: u32 = 0;
+    pub const ASYNC: u32 = 1;
+}
+
+pub mod grab_status {
+    pub const SUCCESS: u32 = 0;
+    pub const ALREADY_GRABBED: u32 = 1;
+    pub const INVALID_TIME: u32 = 2;
+    pub const NOT_VIEWABLE: u32 = 3;
+    pub const FROZEN: u32 = 4;
+}
+
+pub mod cursor {
+    pub const NONE: u32 = 0;
+}
+
+pub mod button_index {
+    pub const ANY: u32 = 0;
+    pub const _1: u32 = 1;
+    pub const _2: u32 = 2;
+    pub const _3: u32 = 3;
+    pub const _4: u32 = 4;
+    pub const _5: u32 = 5;
+}
+
+pub mod grab {
+    pub const ANY: u32 = 0;
+}
+
+pub mod allow {
+    pub const ASYNC_POINTER: u32 = 0;
+    pub const SYNC_POINTER: u32 = 1;
+    pub const REPLAY_POINTER: u32 = 2;
+    pub const ASYNC_KEYBOARD: u32 = 3;
+    pub const SYNC_KEYBOARD: u32 = 4;
+    pub const REPLAY_KEYBOARD: u32 = 5;
+    pub const ASYNC_BOTH: u32 = 6;
+    pub const SYNC_BOTH: u32 = 7;
+}
+
+pub mod input_focus {
+    pub const NONE: u32 = 0;
+    pub const POINTER_ROOT: u32 = 1;
+    pub const PARENT: u32 = 2;
+    pub const FOLLOW_KEYBOARD: u32 = 3;
+}
+
+pub mod font_draw {
+    pub const LEFT_TO_RIGHT: u32 = 0;
+    pub const RIGHT_TO_LEFT: u32 = 1;
+}
+
+pub mod gc {
+    pub const FUNCTION: u32 = 1;
+    pub const PLANE_MASK: u32 = 2;
+    pub const FOREGROUND: u32 = 4;
+    pub const BACKGROUND: u32 = 8;
+    pub const LINE_WIDTH: u32 = 16;
+    pub const LINE_STYLE: u32 = 32;
+    pub const CAP_STYLE: u32 = 64;
+    pub const JOIN_STYLE: u32 = 128;
+    pub const FILL_STYLE: u32 = 256;
+    pub const FILL_RULE: u32 = 512;
+    pub const TILE: u32 = 1024;
+    pub const STIPPLE: u32 = 2048;
+    pub const TILE_STIPPLE_ORIGIN_X: u32 = 4096;
+    pub const TILE_STIPPLE_ORIGIN_Y: u32 = 8192;
+    pub const FONT: u32 = 16384;
+    pub const SUBWINDOW_MODE: u32 = 32768;
+    pub const GRAPHICS_EXPOSURES: u32 = 65536;
+    pub const CLIP_ORIGIN_X: u32 = 131072;
+    pub const CLIP_ORIGIN_Y: u32 = 262144;
+    pub const CLIP_MASK: u32 = 524288;
+    pub const DASH_OFFSET: u32 = 1048576;
+    pub const DASH_LIST: u32 = 2097152;
+    pub const ARC_MODE: u32 = 4194304;
+}
+
+pub mod gx {
+    pub const CLEAR: u32 = 0;
+    pub const AND: u32 = 1;
+    pub const AND_REVERSE: u32 = 2;
+    pub const COPY: u32 = 3;
+    pub const AND_INVERTED: u32 = 4;
+    pub const NOOP: u32 = 5;
+    pub const XOR: u32 = 6;
+    pub const OR: u32 = 7;
+    pub const NOR: u32 = 8;
+    pub const EQUIV: u32 = 9;
+    pub const INVERT: u32 = 10;
+    pub const OR_REVERSE: u32 = 11;
+    pub const COPY_INVERTED: u32 = 12;
+    pub const OR_INVERTED: u32 = 13;
+    pub const NAND: u32 = 14;
+    pub const SET: u32 = 15;
+}
+
+pub mod line_style {
+    pub const SOLID: u32 = 0;
+    pub const ON_OFF_DASH: u32 = 1;
+    pub const DOUBLE_DASH: u32 = 2;
+}
+
+pub mod cap_style {
+    pub const NOT_LAST: u32 = 0;
+    pub const BUTT: u32 = 1;
+    pub const ROUND: u32 = 2;
+    pub const PROJECTING: u32 = 3;
+}
+
+pub mod join_style {
+    pub const MITER: u32 = 0;
+    pub const ROUND: u32 = 1;
+    pub const BEVEL: u32 = 2;
+}
+
+pub mod fill_style {
+    pub const SOLID: u32 = 0;
+    pub const TILED: u32 = 1;
+    pub const STIPPLED: u32 = 2;
+    pub const OPAQUE_STIPPLED: u32 = 3;
+}
+
+pub mod fill_rule {
+    pub const EVEN_ODD: u32 = 0;
+    pub const WINDING: u32 = 1;
+}
+
+pub mod subwindow_mode {
+    pub const CLIP_BY_CHILDREN: u32 = 0;
+    pub const INCLUDE_INFERIORS: u32 = 1;
+}
+
+pub mod arc_mode {
+    pub const CHORD: u32 = 0;
+    pub const PIE_SLICE: u32 = 1;
+}
+
+pub mod clip_ordering {
+    pub const UNSORTED: u32 = 0;
+    pub const Y_SORTED: u32 = 1;
+    pub const YX_SORTED: u32 = 2;
+    pub const YX_BANDED: u32 = 3;
+}
+
+pub mod coord_mode {
+    pub const ORIGIN: u32 = 0;
+    pub const PREVIOUS: u32 = 1;
+}
+
+pub mod poly_shape {
+    pub const COMPLEX: u32 = 0;
+    pub const NONCONVEX: u32 = 1;
+    pub const CONVEX: u32 = 2;
+}
+
+pub mod image_format {
+    pub const XY_BITMAP: u32 = 0;
+    pub const XY_PIXMAP: u32 = 1;
+    pub const Z_PIXMAP: u32 = 2;
+}
+
+pub mod colormap_alloc {
+    pub const NONE: u32 = 0;
+    pub const ALL: u32 = 1;
+}
+
+pub mod color_flag {
+    pub const RED: u32 = 1;
+    pub const GREEN: u32 = 2;
+    pub const BLUE: u32 = 4;
+}
+
+pub mod pixmap {
+    pub const NONE: u32 = 0;
+}
+
+pub mod font {
+    pub const NONE: u32 = 0;
+}
+
+pub mod query_shape_of {
+    pub const LARGEST_CURSOR: u32 = 0;
+    pub const FASTEST_TILE: u32 = 1;
+    pub const FASTEST_STIPPLE: u32 = 2;
+}
+
+pub mod kb {
+    pub const KEY_CLICK_PERCENT: u32 = 1;
+    pub const BELL_PERCENT: u32 = 2;
+    pub const BELL_PITCH: u32 = 4;
+    pub const BELL_DURATION: u32 = 8;
+    pub const LED: u32 = 16;
+    pub const LED_MODE: u32 = 32;
+    pub const KEY: u32 = 64;
+    pub const AUTO_REPEAT_MODE: u32 = 128;
+}
+
+pub mod led_mode {
+    pub const OFF: u32 = 0;
+    pub const ON: u32 = 1;
+}
+
+pub mod auto_repeat_mode {
+    pub const OFF: u32 = 0;
+    pub const ON: u32 = 1;
+    pub const DEFAULT: u32 = 2;
+}
+
+pub mod blanking {
+    pub const NOT_PREFERRED: u32 = 0;
+    pub const PREFERRED: u32 = 1;
+    pub const DEFAULT: u32 = 2;
+}
+
+pub mod exposures {
+    pub const NOT_ALLOWED: u32 = 0;
+    pub const ALLOWED: u32 = 1;
+    pub const DEFAULT: u32 = 2;
+}
+
+pub mod host_mode {
+    pub const INSERT: u32 = 0;
+    pub const DELETE: u32 = 1;
+}
+
+pub mod family {
+    pub const INTERNET: u32 = 0;
+    pub const DE_CNET: u32 = 1;
+    pub const CHAOS: u32 = 2;
+    pub const SERVER_INTERPRETED: u32 = 5;
+    pub const INTERNET6: u32 = 6;
+}
+
+pub mod access_control {
+    pub const DISABLE: u32 = 0;
+    pub const ENABLE: u32 = 1;
+}
+
+pub mod close_down {
+    pub const DESTROY_ALL: u32 = 0;
+    pub const RETAIN_PERMANENT: u32 = 1;
+    pub const RETAIN_TEMPORARY: u32 = 2;
+}
+
+pub mod kill {
+    pub const ALL_TEMPORARY: u32 = 0;
+}
+
+pub mod screen_saver {
+    pub const RESET: u32 = 0;
+    pub const ACTIVE: u32 = 1;
+}
+
+pub mod mapping_status {
+    pub const SUCCESS: u32 = 0;
+    pub const BUSY: u32 = 1;
+    pub const FAILURE: u32 = 2;
+}
+
+pub mod map_index {
+    pub const SHIFT: u32 = 0;
+    pub const LOCK: u32 = 1;
+    pub const CONTROL: u32 = 2;
+    pub const _1: u32 = 3;
+    pub const _2: u32 = 4;
+    pub const _3: u32 = 5;
+    pub const _4: u32 = 6;
+    pub const _5: u32 = 7;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Char2b {
+    pub byte1: CARD8,
+    pub byte2: CARD8,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    pub x: INT16,
+    pub y: INT16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rectangle {
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Arc {
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub angle1: INT16,
+    pub angle2: INT16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Format {
+    pub depth: CARD8,
+    pub bits_per_pixel: CARD8,
+    #[wire(pad_after = 5)]
+    pub scanline_pad: CARD8,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Visualtype {
+    pub visual_id: VISUALID,
+    pub class: CARD8,
+    pub bits_per_rgb_value: CARD8,
+    pub colormap_entries: CARD16,
+    pub red_mask: CARD32,
+    pub green_mask: CARD32,
+    #[wire(pad_after = 4)]
+    pub blue_mask: CARD32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Depth {
+    pub depth: CARD8,
+    #[wire(pad = 1, len_of = "visuals")]
+    pub visuals_len: CARD16,
+    #[wire(pad = 4, len = "visuals_len as usize")]
+    pub visuals: Vec<Visualtype>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Screen {
+    pub root: WINDOW,
+    pub default_colormap: COLORMAP,
+    pub white_pixel: CARD32,
+    pub black_pixel: CARD32,
+    pub current_input_masks: CARD32,
+    pub width_in_pixels: CARD16,
+    pub height_in_pixels: CARD16,
+    pub width_in_millimeters: CARD16,
+    pub height_in_millimeters: CARD16,
+    pub min_installed_maps: CARD16,
+    pub max_installed_maps: CARD16,
+    pub root_visual: VISUALID,
+    pub backing_stores: CARD8,
+    pub save_unders: BOOL,
+    pub root_depth: CARD8,
+    #[wire(len_of = "allowed_depths")]
+    pub allowed_depths_len: CARD8,
+    #[wire(len = "allowed_depths_len as usize")]
+    pub allowed_depths: Vec<Depth>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetupRequest {
+    pub byte_order: CARD8,
+    #[wire(pad = 1)]
+    pub protocol_major_version: CARD16,
+    pub protocol_minor_version: CARD16,
+    #[wire(len_of = "authorization_protocol_name")]
+    pub authorization_protocol_name_len: CARD16,
+    #[wire(len_of = "authorization_protocol_data")]
+    pub authorization_protocol_data_len: CARD16,
+    #[wire(pad = 2, len = "authorization_protocol_name_len as usize")]
+    pub authorization_protocol_name: Vec<u8>,
+    #[wire(
+        align = 4,
+        len = "authorization_protocol_data_len as usize",
+        align_after = 4
+    )]
+    pub authorization_protocol_data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetupFailed {
+    pub status: CARD8,
+    #[wire(len_of = "reason")]
+    pub reason_len: CARD8,
+    pub protocol_major_version: CARD16,
+    pub protocol_minor_version: CARD16,
+    pub length: CARD16,
+    #[wire(len = "reason_len as usize")]
+    pub reason: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetupAuthenticate {
+    pub status: CARD8,
+    #[wire(pad = 5)]
+    pub length: CARD16,
+    #[wire(len = "(length as usize) * 4")]
+    pub reason: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Setup {
+    pub status: CARD8,
+    #[wire(pad = 1)]
+    pub protocol_major_version: CARD16,
+    pub protocol_minor_version: CARD16,
+    pub length: CARD16,
+    pub release_number: CARD32,
+    pub resource_id_base: CARD32,
+    pub resource_id_mask: CARD32,
+    pub motion_buffer_size: CARD32,
+    #[wire(len_of = "vendor")]
+    pub vendor_len: CARD16,
+    pub maximum_request_length: CARD16,
+    #[wire(len_of = "roots")]
+    pub roots_len: CARD8,
+    #[wire(len_of = "pixmap_formats")]
+    pub pixmap_formats_len: CARD8,
+    pub image_byte_order: CARD8,
+    pub bitmap_format_bit_order: CARD8,
+    pub bitmap_format_scanline_unit: CARD8,
+    pub bitmap_format_scanline_pad: CARD8,
+    pub min_keycode: KEYCODE,
+    pub max_keycode: KEYCODE,
+    #[wire(pad = 4, len = "vendor_len as usize")]
+    pub vendor: Vec<u8>,
+    #[wire(align = 4, len = "pixmap_formats_len as usize")]
+    pub pixmap_formats: Vec<Format>,
+    #[wire(len = "roots_len as usize")]
+    pub roots: Vec<Screen>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Timecoord {
+    pub time: TIMESTAMP,
+    pub x: INT16,
+    pub y: INT16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fontprop {
+    pub name: Atom,
+    pub value: CARD32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Charinfo {
+    pub left_side_bearing: INT16,
+    pub right_side_bearing: INT16,
+    pub character_width: INT16,
+    pub ascent: INT16,
+    pub descent: INT16,
+    pub attributes: CARD16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Str {
+    #[wire(len_of = "name")]
+    pub name_len: CARD8,
+    #[wire(len = "name_len as usize")]
+    pub name: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub x1: INT16,
+    pub y1: INT16,
+    pub x2: INT16,
+    pub y2: INT16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Coloritem {
+    pub pixel: CARD32,
+    pub red: CARD16,
+    pub green: CARD16,
+    pub blue: CARD16,
+    #[wire(pad_after = 1)]
+    pub flags: CARD8,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rgb {
+    pub red: CARD16,
+    pub green: CARD16,
+    #[wire(pad_after = 2)]
+    pub blue: CARD16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Host {
+    pub family: CARD8,
+    #[wire(pad = 1, len_of = "address")]
+    pub address_len: CARD16,
+    #[wire(len = "address_len as usize", align_after = 4)]
+    pub address: Vec<u8>,
+}
+
+pub fn create_window<S: io::Write>(
+    socket: &mut S,
+    depth: CARD8,
+    wid: WINDOW,
+    parent: WINDOW,
+    x: INT16,
+    y: INT16,
+    width: CARD16,
+    height: CARD16,
+    border_width: CARD16,
+    class: CARD16,
+    visual: VISUALID,
+    value_list: &[(CARD32, CARD32)],
+) -> io::Result<()> {
+    // the server wants the values in the order of their bits
+    let mut value_list = value_list.to_vec();
+    value_list.sort_by_key(|&(bit, _)| bit);
+    let value_mask = value_list.iter().fold(0, |mask, &(bit, _)| mask | bit);
+    let mut request = RequestBuffer::new(opcodes::CREATE_WINDOW, depth);
+    wid.serialize(&mut request)?;
+    parent.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    border_width.serialize(&mut request)?;
+    class.serialize(&mut request)?;
+    visual.serialize(&mut request)?;
+    value_mask.serialize(&mut request)?;
+    for (_, value) in value_list {
+        value.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn change_window_attributes<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    value_list: &[(CARD32, CARD32)],
+) -> io::Result<()> {
+    // the server wants the values in the order of their bits
+    let mut value_list = value_list.to_vec();
+    value_list.sort_by_key(|&(bit, _)| bit);
+    let value_mask = value_list.iter().fold(0, |mask, &(bit, _)| mask | bit);
+    let mut request = RequestBuffer::new(opcodes::CHANGE_WINDOW_ATTRIBUTES, 0);
+    window.serialize(&mut request)?;
+    value_mask.serialize(&mut request)?;
+    for (_, value) in value_list {
+        value.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn get_window_attributes_request<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GET_WINDOW_ATTRIBUTES, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetWindowAttributesReply {
+    pub backing_store: CARD8,
+    pub visual: VISUALID,
+    pub class: CARD16,
+    pub bit_gravity: CARD8,
+    pub win_gravity: CARD8,
+    pub backing_planes: CARD32,
+    pub backing_pixel: CARD32,
+    pub save_under: BOOL,
+    pub map_is_installed: BOOL,
+    pub map_state: CARD8,
+    pub override_redirect: BOOL,
+    pub colormap: COLORMAP,
+    pub all_event_masks: CARD32,
+    pub your_event_mask: CARD32,
+    pub do_not_propagate_mask: CARD16,
+}
+
+impl GetWindowAttributesReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let backing_store: CARD8 = r.read()?;
+        r.skip(6)?;
+        let visual: VISUALID = r.read()?;
+        let class: CARD16 = r.read()?;
+        let bit_gravity: CARD8 = r.read()?;
+        let win_gravity: CARD8 = r.read()?;
+        let backing_planes: CARD32 = r.read()?;
+        let backing_pixel: CARD32 = r.read()?;
+        let save_under: BOOL = r.read()?;
+        let map_is_installed: BOOL = r.read()?;
+        let map_state: CARD8 = r.read()?;
+        let override_redirect: BOOL = r.read()?;
+        let colormap: COLORMAP = r.read()?;
+        let all_event_masks: CARD32 = r.read()?;
+        let your_event_mask: CARD32 = r.read()?;
+        let do_not_propagate_mask: CARD16 = r.read()?;
+        r.skip(2)?;
+        Ok(Self {
+            backing_store,
+            visual,
+            class,
+            bit_gravity,
+            win_gravity,
+            backing_planes,
+            backing_pixel,
+            save_under,
+            map_is_installed,
+            map_state,
+            override_redirect,
+            colormap,
+            all_event_masks,
+            your_event_mask,
+            do_not_propagate_mask,
+        })
+    }
+}
+
+pub fn destroy_window<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::DESTROY_WINDOW, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn destroy_subwindows<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::DESTROY_SUBWINDOWS, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn change_save_set<S: io::Write>(
+    socket: &mut S,
+    mode: CARD8,
+    window: WINDOW,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CHANGE_SAVE_SET, mode);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn reparent_window<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    parent: WINDOW,
+    x: INT16,
+    y: INT16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::REPARENT_WINDOW, 0);
+    window.serialize(&mut request)?;
+    parent.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn map_window<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::MAP_WINDOW, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn map_subwindows<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::MAP_SUBWINDOWS, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn unmap_window<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::UNMAP_WINDOW, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn unmap_subwindows<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::UNMAP_SUBWINDOWS, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn configure_window<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    value_list: &[(CARD32, CARD32)],
+) -> io::Result<()> {
+    // the server wants the values in the order of their bits
+    let mut value_list = value_list.to_vec();
+    value_list.sort_by_key(|&(bit, _)| bit);
+    let value_mask = value_list.iter().fold(0, |mask, &(bit, _)| mask | bit) as CARD16;
+    let mut request = RequestBuffer::new(opcodes::CONFIGURE_WINDOW, 0);
+    window.serialize(&mut request)?;
+    value_mask.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    for (_, value) in value_list {
+        value.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn circulate_window<S: io::Write>(
+    socket: &mut S,
+    direction: CARD8,
+    window: WINDOW,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CIRCULATE_WINDOW, direction);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_geometry_request<S: io::Write>(socket: &mut S, drawable: DRAWABLE) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GET_GEOMETRY, 0);
+    drawable.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetGeometryReply {
+    pub depth: CARD8,
+    pub root: WINDOW,
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub border_width: CARD16,
+}
+
+impl GetGeometryReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let depth: CARD8 = r.read()?;
+        r.skip(6)?;
+        let root: WINDOW = r.read()?;
+        let x: INT16 = r.read()?;
+        let y: INT16 = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        let border_width: CARD16 = r.read()?;
+        r.skip(2)?;
+        Ok(Self {
+            depth,
+            root,
+            x,
+            y,
+            width,
+            height,
+            border_width,
+        })
+    }
+}
+
+pub fn query_tree_request<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::QUERY_TREE, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryTreeReply {
+    pub root: WINDOW,
+    pub parent: WINDOW,
+    pub children: Vec<WINDOW>,
+}
+
+impl QueryTreeReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let root: WINDOW = r.read()?;
+        let parent: WINDOW = r.read()?;
+        let children_len: CARD16 = r.read()?;
+        r.skip(14)?;
+        let children = r.list(children_len as usize)?;
+        Ok(Self {
+            root,
+            parent,
+            children,
+        })
+    }
+}
+
+pub fn intern_atom_request<S: io::Write>(
+    socket: &mut S,
+    only_if_exists: BOOL,
+    name: &[u8],
+) -> io::Result<()> {
+    let name_len = list_len::<CARD16>(name.len())?;
+    let mut request = RequestBuffer::new(opcodes::INTERN_ATOM, only_if_exists);
+    name_len.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.write_all(name)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InternAtomReply {
+    pub atom: Atom,
+}
+
+impl InternAtomReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let atom: Atom = r.read()?;
+        Ok(Self { atom })
+    }
+}
+
+pub fn get_atom_name_request<S: io::Write>(socket: &mut S, atom: Atom) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GET_ATOM_NAME, 0);
+    atom.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetAtomNameReply {
+    pub name: Vec<u8>,
+}
+
+impl GetAtomNameReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let name_len: CARD16 = r.read()?;
+        r.skip(22)?;
+        let name = r.bytes(name_len as usize)?.to_vec();
+        Ok(Self { name })
+    }
+}
+
+pub fn change_property<S: io::Write>(
+    socket: &mut S,
+    mode: CARD8,
+    window: WINDOW,
+    property: Atom,
+    type_: Atom,
+    format: CARD8,
+    data_len: CARD32,
+    data: &[u8],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CHANGE_PROPERTY, mode);
+    window.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    type_.serialize(&mut request)?;
+    format.serialize(&mut request)?;
+    request.write_all(&[0; 3])?;
+    data_len.serialize(&mut request)?;
+    request.write_all(data)?;
+    request.send(socket)
+}
+
+pub fn delete_property<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    property: Atom,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::DELETE_PROPERTY, 0);
+    window.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_property_request<S: io::Write>(
+    socket: &mut S,
+    delete: BOOL,
+    window: WINDOW,
+    property: Atom,
+    type_: Atom,
+    long_offset: CARD32,
+    long_length: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GET_PROPERTY, delete);
+    window.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    type_.serialize(&mut request)?;
+    long_offset.serialize(&mut request)?;
+    long_length.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetPropertyReply {
+    pub format: CARD8,
+    pub type_: Atom,
+    pub bytes_after: CARD32,
+    pub value_len: CARD32,
+    pub value: Vec<u8>,
+}
+
+impl GetPropertyReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let format: CARD8 = r.read()?;
+        r.skip(6)?;
+        let type_: Atom = r.read()?;
+        let bytes_after: CARD32 = r.read()?;
+        let value_len: CARD32 = r.read()?;
+        r.skip(12)?;
+        let value = r
+            .bytes((value_len as usize) * ((format as usize) / 8))?
+            .to_vec();
+        Ok(Self {
+            format,
+            type_,
+            bytes_after,
+            value_len,
+            value,
+        })
+    }
+}
+
+pub fn list_properties_request<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::LIST_PROPERTIES, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListPropertiesReply {
+    pub atoms: Vec<Atom>,
+}
+
+impl ListPropertiesReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let atoms_len: CARD16 = r.read()?;
+        r.skip(22)?;
+        let atoms = r.list(atoms_len as usize)?;
+        Ok(Self { atoms })
+    }
+}
+
+pub fn set_selection_owner<S: io::Write>(
+    socket: &mut S,
+    owner: WINDOW,
+    selection: Atom,
+    time: TIMESTAMP,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::SET_SELECTION_OWNER, 0);
+    owner.serialize(&mut request)?;
+    selection.serialize(&mut request)?;
+    time.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_selection_owner_request<S: io::Write>(
+    socket: &mut S,
+    selection: Atom,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GET_SELECTION_OWNER, 0);
+    selection.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetSelectionOwnerReply {
+    pub owner: WINDOW,
+}
+
+impl GetSelectionOwnerReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let owner: WINDOW = r.read()?;
+        Ok(Self { owner })
+    }
+}
+
+pub fn convert_selection<S: io::Write>(
+    socket: &mut S,
+    requestor: WINDOW,
+    selection: Atom,
+    target: Atom,
+    property: Atom,
+    time: TIMESTAMP,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CONVERT_SELECTION, 0);
+    requestor.serialize(&mut request)?;
+    selection.serialize(&mut request)?;
+    target.serialize(&mut request)?;
+    property.serialize(&mut request)?;
+    time.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn send_event<S: io::Write>(
+    socket: &mut S,
+    propagate: BOOL,
+    destination: WINDOW,
+    event_mask: CARD32,
+    event: &[u8],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::SEND_EVENT, propagate);
+    destination.serialize(&mut request)?;
+    event_mask.serialize(&mut request)?;
+    request.write_all(event)?;
+    request.send(socket)
+}
+
+pub fn grab_pointer_request<S: io::Write>(
+    socket: &mut S,
+    owner_events: BOOL,
+    grab_window: WINDOW,
+    event_mask: CARD16,
+    pointer_mode: CARD8,
+    keyboard_mode: CARD8,
+    confine_to: WINDOW,
+    cursor: CURSOR,
+    time: TIMESTAMP,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GRAB_POINTER, owner_events);
+    grab_window.serialize(&mut request)?;
+    event_mask.serialize(&mut request)?;
+    pointer_mode.serialize(&mut request)?;
+    keyboard_mode.serialize(&mut request)?;
+    confine_to.serialize(&mut request)?;
+    cursor.serialize(&mut request)?;
+    time.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GrabPointerReply {
+    pub status: CARD8,
+}
+
+impl GrabPointerReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let status: CARD8 = r.read()?;
+        r.skip(6)?;
+        Ok(Self { status })
+    }
+}
+
+pub fn ungrab_pointer<S: io::Write>(socket: &mut S, time: TIMESTAMP) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::UNGRAB_POINTER, 0);
+    time.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn grab_button<S: io::Write>(
+    socket: &mut S,
+    owner_events: BOOL,
+    grab_window: WINDOW,
+    event_mask: CARD16,
+    pointer_mode: CARD8,
+    keyboard_mode: CARD8,
+    confine_to: WINDOW,
+    cursor: CURSOR,
+    button: CARD8,
+    modifiers: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GRAB_BUTTON, owner_events);
+    grab_window.serialize(&mut request)?;
+    event_mask.serialize(&mut request)?;
+    pointer_mode.serialize(&mut request)?;
+    keyboard_mode.serialize(&mut request)?;
+    confine_to.serialize(&mut request)?;
+    cursor.serialize(&mut request)?;
+    button.serialize(&mut request)?;
+    request.write_all(&[0; 1])?;
+    modifiers.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn ungrab_button<S: io::Write>(
+    socket: &mut S,
+    button: CARD8,
+    grab_window: WINDOW,
+    modifiers: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::UNGRAB_BUTTON, button);
+    grab_window.serialize(&mut request)?;
+    modifiers.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.send(socket)
+}
+
+pub fn change_active_pointer_grab<S: io::Write>(
+    socket: &mut S,
+    cursor: CURSOR,
+    time: TIMESTAMP,
+    event_mask: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CHANGE_ACTIVE_POINTER_GRAB, 0);
+    cursor.serialize(&mut request)?;
+    time.serialize(&mut request)?;
+    event_mask.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.send(socket)
+}
+
+pub fn grab_keyboard_request<S: io::Write>(
+    socket: &mut S,
+    owner_events: BOOL,
+    grab_window: WINDOW,
+    time: TIMESTAMP,
+    pointer_mode: CARD8,
+    keyboard_mode: CARD8,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GRAB_KEYBOARD, owner_events);
+    grab_window.serialize(&mut request)?;
+    time.serialize(&mut request)?;
+    pointer_mode.serialize(&mut request)?;
+    keyboard_mode.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GrabKeyboardReply {
+    pub status: CARD8,
+}
+
+impl GrabKeyboardReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let status: CARD8 = r.read()?;
+        r.skip(6)?;
+        Ok(Self { status })
+    }
+}
+
+pub fn ungrab_keyboard<S: io::Write>(socket: &mut S, time: TIMESTAMP) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::UNGRAB_KEYBOARD, 0);
+    time.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn grab_key<S: io::Write>(
+    socket: &mut S,
+    owner_events: BOOL,
+    grab_window: WINDOW,
+    modifiers: CARD16,
+    key: KEYCODE,
+    pointer_mode: CARD8,
+    keyboard_mode: CARD8,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GRAB_KEY, owner_events);
+    grab_window.serialize(&mut request)?;
+    modifiers.serialize(&mut request)?;
+    key.serialize(&mut request)?;
+    pointer_mode.serialize(&mut request)?;
+    keyboard_mode.serialize(&mut request)?;
+    request.write_all(&[0; 3])?;
+    request.send(socket)
+}
+
+pub fn ungrab_key<S: io::Write>(
+    socket: &mut S,
+    key: KEYCODE,
+    grab_window: WINDOW,
+    modifiers: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::UNGRAB_KEY, key);
+    grab_window.serialize(&mut request)?;
+    modifiers.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.send(socket)
+}
+
+pub fn allow_events<S: io::Write>(socket: &mut S, mode: CARD8, time: TIMESTAMP) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::ALLOW_EVENTS, mode);
+    time.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn grab_server<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::GRAB_SERVER, 0).send(socket)
+}
+
+pub fn ungrab_server<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::UNGRAB_SERVER, 0).send(socket)
+}
+
+pub fn query_pointer_request<S: io::Write>(socket: &mut S, window: WINDOW) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::QUERY_POINTER, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryPointerReply {
+    pub same_screen: BOOL,
+    pub root: WINDOW,
+    pub child: WINDOW,
+    pub root_x: INT16,
+    pub root_y: INT16,
+    pub win_x: INT16,
+    pub win_y: INT16,
+    pub mask: CARD16,
+}
+
+impl QueryPointerReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let same_screen: BOOL = r.read()?;
+        r.skip(6)?;
+        let root: WINDOW = r.read()?;
+        let child: WINDOW = r.read()?;
+        let root_x: INT16 = r.read()?;
+        let root_y: INT16 = r.read()?;
+        let win_x: INT16 = r.read()?;
+        let win_y: INT16 = r.read()?;
+        let mask: CARD16 = r.read()?;
+        r.skip(2)?;
+        Ok(Self {
+            same_screen,
+            root,
+            child,
+            root_x,
+            root_y,
+            win_x,
+            win_y,
+            mask,
+        })
+    }
+}
+
+pub fn get_motion_events_request<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    start: TIMESTAMP,
+    stop: TIMESTAMP,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GET_MOTION_EVENTS, 0);
+    window.serialize(&mut request)?;
+    start.serialize(&mut request)?;
+    stop.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetMotionEventsReply {
+    pub events: Vec<Timecoord>,
+}
+
+impl GetMotionEventsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let events_len: CARD32 = r.read()?;
+        r.skip(20)?;
+        let events = r.list(events_len as usize)?;
+        Ok(Self { events })
+    }
+}
+
+pub fn translate_coordinates_request<S: io::Write>(
+    socket: &mut S,
+    src_window: WINDOW,
+    dst_window: WINDOW,
+    src_x: INT16,
+    src_y: INT16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::TRANSLATE_COORDINATES, 0);
+    src_window.serialize(&mut request)?;
+    dst_window.serialize(&mut request)?;
+    src_x.serialize(&mut request)?;
+    src_y.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TranslateCoordinatesReply {
+    pub same_screen: BOOL,
+    pub child: WINDOW,
+    pub dst_x: INT16,
+    pub dst_y: INT16,
+}
+
+impl TranslateCoordinatesReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let same_screen: BOOL = r.read()?;
+        r.skip(6)?;
+        let child: WINDOW = r.read()?;
+        let dst_x: INT16 = r.read()?;
+        let dst_y: INT16 = r.read()?;
+        Ok(Self {
+            same_screen,
+            child,
+            dst_x,
+            dst_y,
+        })
+    }
+}
+
+pub fn warp_pointer<S: io::Write>(
+    socket: &mut S,
+    src_window: WINDOW,
+    dst_window: WINDOW,
+    src_x: INT16,
+    src_y: INT16,
+    src_width: CARD16,
+    src_height: CARD16,
+    dst_x: INT16,
+    dst_y: INT16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::WARP_POINTER, 0);
+    src_window.serialize(&mut request)?;
+    dst_window.serialize(&mut request)?;
+    src_x.serialize(&mut request)?;
+    src_y.serialize(&mut request)?;
+    src_width.serialize(&mut request)?;
+    src_height.serialize(&mut request)?;
+    dst_x.serialize(&mut request)?;
+    dst_y.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn set_input_focus<S: io::Write>(
+    socket: &mut S,
+    revert_to: CARD8,
+    focus: WINDOW,
+    time: TIMESTAMP,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::SET_INPUT_FOCUS, revert_to);
+    focus.serialize(&mut request)?;
+    time.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_input_focus_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::GET_INPUT_FOCUS, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetInputFocusReply {
+    pub revert_to: CARD8,
+    pub focus: WINDOW,
+}
+
+impl GetInputFocusReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let revert_to: CARD8 = r.read()?;
+        r.skip(6)?;
+        let focus: WINDOW = r.read()?;
+        Ok(Self { revert_to, focus })
+    }
+}
+
+pub fn query_keymap_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::QUERY_KEYMAP, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryKeymapReply {
+    pub keys: Vec<u8>,
+}
+
+impl QueryKeymapReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let keys = r.bytes(32)?.to_vec();
+        Ok(Self { keys })
+    }
+}
+
+pub fn open_font<S: io::Write>(socket: &mut S, fid: FONT, name: &[u8]) -> io::Result<()> {
+    let name_len = list_len::<CARD16>(name.len())?;
+    let mut request = RequestBuffer::new(opcodes::OPEN_FONT, 0);
+    fid.serialize(&mut request)?;
+    name_len.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.write_all(name)?;
+    request.send(socket)
+}
+
+pub fn close_font<S: io::Write>(socket: &mut S, font: FONT) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CLOSE_FONT, 0);
+    font.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn query_font_request<S: io::Write>(socket: &mut S, font: FONTABLE) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::QUERY_FONT, 0);
+    font.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryFontReply {
+    pub min_bounds: Charinfo,
+    pub max_bounds: Charinfo,
+    pub min_char_or_byte2: CARD16,
+    pub max_char_or_byte2: CARD16,
+    pub default_char: CARD16,
+    pub draw_direction: CARD8,
+    pub min_byte1: CARD8,
+    pub max_byte1: CARD8,
+    pub all_chars_exist: BOOL,
+    pub font_ascent: INT16,
+    pub font_descent: INT16,
+    pub properties: Vec<Fontprop>,
+    pub char_infos: Vec<Charinfo>,
+}
+
+impl QueryFontReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let min_bounds: Charinfo = r.read()?;
+        r.skip(4)?;
+        let max_bounds: Charinfo = r.read()?;
+        r.skip(4)?;
+        let min_char_or_byte2: CARD16 = r.read()?;
+        let max_char_or_byte2: CARD16 = r.read()?;
+        let default_char: CARD16 = r.read()?;
+        let properties_len: CARD16 = r.read()?;
+        let draw_direction: CARD8 = r.read()?;
+        let min_byte1: CARD8 = r.read()?;
+        let max_byte1: CARD8 = r.read()?;
+        let all_chars_exist: BOOL = r.read()?;
+        let font_ascent: INT16 = r.read()?;
+        let font_descent: INT16 = r.read()?;
+        let char_infos_len: CARD32 = r.read()?;
+        let properties = r.list(properties_len as usize)?;
+        let char_infos = r.list(char_infos_len as usize)?;
+        Ok(Self {
+            min_bounds,
+            max_bounds,
+            min_char_or_byte2,
+            max_char_or_byte2,
+            default_char,
+            draw_direction,
+            min_byte1,
+            max_byte1,
+            all_chars_exist,
+            font_ascent,
+            font_descent,
+            properties,
+            char_infos,
+        })
+    }
+}
+
+pub fn query_text_extents_request<S: io::Write>(
+    socket: &mut S,
+    font: FONTABLE,
+    string: &[Char2b],
+) -> io::Result<()> {
+    let odd_length = (string.len() & 1) as BOOL;
+    let mut request = RequestBuffer::new(opcodes::QUERY_TEXT_EXTENTS, odd_length);
+    font.serialize(&mut request)?;
+    for item in string {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryTextExtentsReply {
+    pub draw_direction: CARD8,
+    pub font_ascent: INT16,
+    pub font_descent: INT16,
+    pub overall_ascent: INT16,
+    pub overall_descent: INT16,
+    pub overall_width: INT32,
+    pub overall_left: INT32,
+    pub overall_right: INT32,
+}
+
+impl QueryTextExtentsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let draw_direction: CARD8 = r.read()?;
+        r.skip(6)?;
+        let font_ascent: INT16 = r.read()?;
+        let font_descent: INT16 = r.read()?;
+        let overall_ascent: INT16 = r.read()?;
+        let overall_descent: INT16 = r.read()?;
+        let overall_width: INT32 = r.read()?;
+        let overall_left: INT32 = r.read()?;
+        let overall_right: INT32 = r.read()?;
+        Ok(Self {
+            draw_direction,
+            font_ascent,
+            font_descent,
+            overall_ascent,
+            overall_descent,
+            overall_width,
+            overall_left,
+            overall_right,
+        })
+    }
+}
+
+pub fn list_fonts_request<S: io::Write>(
+    socket: &mut S,
+    max_names: CARD16,
+    pattern: &[u8],
+) -> io::Result<()> {
+    let pattern_len = list_len::<CARD16>(pattern.len())?;
+    let mut request = RequestBuffer::new(opcodes::LIST_FONTS, 0);
+    max_names.serialize(&mut request)?;
+    pattern_len.serialize(&mut request)?;
+    request.write_all(pattern)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListFontsReply {
+    pub names: Vec<Str>,
+}
+
+impl ListFontsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let names_len: CARD16 = r.read()?;
+        r.skip(22)?;
+        let names = r.list(names_len as usize)?;
+        Ok(Self { names })
+    }
+}
+
+pub fn list_fonts_with_info_request<S: io::Write>(
+    socket: &mut S,
+    max_names: CARD16,
+    pattern: &[u8],
+) -> io::Result<()> {
+    let pattern_len = list_len::<CARD16>(pattern.len())?;
+    let mut request = RequestBuffer::new(opcodes::LIST_FONTS_WITH_INFO, 0);
+    max_names.serialize(&mut request)?;
+    pattern_len.serialize(&mut request)?;
+    request.write_all(pattern)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListFontsWithInfoReply {
+    pub min_bounds: Charinfo,
+    pub max_bounds: Charinfo,
+    pub min_char_or_byte2: CARD16,
+    pub max_char_or_byte2: CARD16,
+    pub default_char: CARD16,
+    pub draw_direction: CARD8,
+    pub min_byte1: CARD8,
+    pub max_byte1: CARD8,
+    pub all_chars_exist: BOOL,
+    pub font_ascent: INT16,
+    pub font_descent: INT16,
+    pub replies_hint: CARD32,
+    pub properties: Vec<Fontprop>,
+    pub name: Vec<u8>,
+}
+
+impl ListFontsWithInfoReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let name_len: CARD8 = r.read()?;
+        r.skip(6)?;
+        let min_bounds: Charinfo = r.read()?;
+        r.skip(4)?;
+        let max_bounds: Charinfo = r.read()?;
+        r.skip(4)?;
+        let min_char_or_byte2: CARD16 = r.read()?;
+        let max_char_or_byte2: CARD16 = r.read()?;
+        let default_char: CARD16 = r.read()?;
+        let properties_len: CARD16 = r.read()?;
+        let draw_direction: CARD8 = r.read()?;
+        let min_byte1: CARD8 = r.read()?;
+        let max_byte1: CARD8 = r.read()?;
+        let all_chars_exist: BOOL = r.read()?;
+        let font_ascent: INT16 = r.read()?;
+        let font_descent: INT16 = r.read()?;
+        let replies_hint: CARD32 = r.read()?;
+        let properties = r.list(properties_len as usize)?;
+        let name = r.bytes(name_len as usize)?.to_vec();
+        Ok(Self {
+            min_bounds,
+            max_bounds,
+            min_char_or_byte2,
+            max_char_or_byte2,
+            default_char,
+            draw_direction,
+            min_byte1,
+            max_byte1,
+            all_chars_exist,
+            font_ascent,
+            font_descent,
+            replies_hint,
+            properties,
+            name,
+        })
+    }
+}
+
+pub fn set_font_path<S: io::Write>(socket: &mut S, font: &[Str]) -> io::Result<()> {
+    let font_qty = list_len::<CARD16>(font.len())?;
+    let mut request = RequestBuffer::new(opcodes::SET_FONT_PATH, 0);
+    font_qty.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    for item in font {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn get_font_path_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::GET_FONT_PATH, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetFontPathReply {
+    pub path: Vec<Str>,
+}
+
+impl GetFontPathReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let path_len: CARD16 = r.read()?;
+        r.skip(22)?;
+        let path = r.list(path_len as usize)?;
+        Ok(Self { path })
+    }
+}
+
+pub fn create_pixmap<S: io::Write>(
+    socket: &mut S,
+    depth: CARD8,
+    pid: PIXMAP,
+    drawable: DRAWABLE,
+    width: CARD16,
+    height: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CREATE_PIXMAP, depth);
+    pid.serialize(&mut request)?;
+    drawable.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn free_pixmap<S: io::Write>(socket: &mut S, pixmap: PIXMAP) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::FREE_PIXMAP, 0);
+    pixmap.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn create_gc<S: io::Write>(
+    socket: &mut S,
+    cid: GCONTEXT,
+    drawable: DRAWABLE,
+    value_list: &[(CARD32, CARD32)],
+) -> io::Result<()> {
+    // the server wants the values in the order of their bits
+    let mut value_list = value_list.to_vec();
+    value_list.sort_by_key(|&(bit, _)| bit);
+    let value_mask = value_list.iter().fold(0, |mask, &(bit, _)| mask | bit);
+    let mut request = RequestBuffer::new(opcodes::CREATE_GC, 0);
+    cid.serialize(&mut request)?;
+    drawable.serialize(&mut request)?;
+    value_mask.serialize(&mut request)?;
+    for (_, value) in value_list {
+        value.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn change_gc<S: io::Write>(
+    socket: &mut S,
+    gc: GCONTEXT,
+    value_list: &[(CARD32, CARD32)],
+) -> io::Result<()> {
+    // the server wants the values in the order of their bits
+    let mut value_list = value_list.to_vec();
+    value_list.sort_by_key(|&(bit, _)| bit);
+    let value_mask = value_list.iter().fold(0, |mask, &(bit, _)| mask | bit);
+    let mut request = RequestBuffer::new(opcodes::CHANGE_GC, 0);
+    gc.serialize(&mut request)?;
+    value_mask.serialize(&mut request)?;
+    for (_, value) in value_list {
+        value.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn copy_gc<S: io::Write>(
+    socket: &mut S,
+    src_gc: GCONTEXT,
+    dst_gc: GCONTEXT,
+    value_mask: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::COPY_GC, 0);
+    src_gc.serialize(&mut request)?;
+    dst_gc.serialize(&mut request)?;
+    value_mask.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn set_dashes<S: io::Write>(
+    socket: &mut S,
+    gc: GCONTEXT,
+    dash_offset: CARD16,
+    dashes: &[u8],
+) -> io::Result<()> {
+    let dashes_len = list_len::<CARD16>(dashes.len())?;
+    let mut request = RequestBuffer::new(opcodes::SET_DASHES, 0);
+    gc.serialize(&mut request)?;
+    dash_offset.serialize(&mut request)?;
+    dashes_len.serialize(&mut request)?;
+    request.write_all(dashes)?;
+    request.send(socket)
+}
+
+pub fn set_clip_rectangles<S: io::Write>(
+    socket: &mut S,
+    ordering: CARD8,
+    gc: GCONTEXT,
+    clip_x_origin: INT16,
+    clip_y_origin: INT16,
+    rectangles: &[Rectangle],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::SET_CLIP_RECTANGLES, ordering);
+    gc.serialize(&mut request)?;
+    clip_x_origin.serialize(&mut request)?;
+    clip_y_origin.serialize(&mut request)?;
+    for item in rectangles {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn free_gc<S: io::Write>(socket: &mut S, gc: GCONTEXT) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::FREE_GC, 0);
+    gc.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn clear_area<S: io::Write>(
+    socket: &mut S,
+    exposures: BOOL,
+    window: WINDOW,
+    x: INT16,
+    y: INT16,
+    width: CARD16,
+    height: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CLEAR_AREA, exposures);
+    window.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn copy_area<S: io::Write>(
+    socket: &mut S,
+    src_drawable: DRAWABLE,
+    dst_drawable: DRAWABLE,
+    gc: GCONTEXT,
+    src_x: INT16,
+    src_y: INT16,
+    dst_x: INT16,
+    dst_y: INT16,
+    width: CARD16,
+    height: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::COPY_AREA, 0);
+    src_drawable.serialize(&mut request)?;
+    dst_drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    src_x.serialize(&mut request)?;
+    src_y.serialize(&mut request)?;
+    dst_x.serialize(&mut request)?;
+    dst_y.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn copy_plane<S: io::Write>(
+    socket: &mut S,
+    src_drawable: DRAWABLE,
+    dst_drawable: DRAWABLE,
+    gc: GCONTEXT,
+    src_x: INT16,
+    src_y: INT16,
+    dst_x: INT16,
+    dst_y: INT16,
+    width: CARD16,
+    height: CARD16,
+    bit_plane: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::COPY_PLANE, 0);
+    src_drawable.serialize(&mut request)?;
+    dst_drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    src_x.serialize(&mut request)?;
+    src_y.serialize(&mut request)?;
+    dst_x.serialize(&mut request)?;
+    dst_y.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    bit_plane.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn poly_point<S: io::Write>(
+    socket: &mut S,
+    coordinate_mode: CARD8,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    points: &[Point],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_POINT, coordinate_mode);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    for item in points {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn poly_line<S: io::Write>(
+    socket: &mut S,
+    coordinate_mode: CARD8,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    points: &[Point],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_LINE, coordinate_mode);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    for item in points {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn poly_segment<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    segments: &[Segment],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_SEGMENT, 0);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    for item in segments {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn poly_rectangle<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    rectangles: &[Rectangle],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_RECTANGLE, 0);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    for item in rectangles {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn poly_arc<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    arcs: &[Arc],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_ARC, 0);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    for item in arcs {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn fill_poly<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    shape: CARD8,
+    coordinate_mode: CARD8,
+    points: &[Point],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::FILL_POLY, 0);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    shape.serialize(&mut request)?;
+    coordinate_mode.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    for item in points {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn poly_fill_rectangle<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    rectangles: &[Rectangle],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_FILL_RECTANGLE, 0);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    for item in rectangles {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn poly_fill_arc<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    arcs: &[Arc],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_FILL_ARC, 0);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    for item in arcs {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn put_image<S: io::Write>(
+    socket: &mut S,
+    format: CARD8,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    width: CARD16,
+    height: CARD16,
+    dst_x: INT16,
+    dst_y: INT16,
+    left_pad: CARD8,
+    depth: CARD8,
+    data: &[u8],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::PUT_IMAGE, format);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    dst_x.serialize(&mut request)?;
+    dst_y.serialize(&mut request)?;
+    left_pad.serialize(&mut request)?;
+    depth.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.write_all(data)?;
+    request.send(socket)
+}
+
+pub fn get_image_request<S: io::Write>(
+    socket: &mut S,
+    format: CARD8,
+    drawable: DRAWABLE,
+    x: INT16,
+    y: INT16,
+    width: CARD16,
+    height: CARD16,
+    plane_mask: CARD32,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GET_IMAGE, format);
+    drawable.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    plane_mask.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetImageReply {
+    pub depth: CARD8,
+    pub visual: VISUALID,
+    pub data: Vec<u8>,
+}
+
+impl GetImageReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let depth: CARD8 = r.read()?;
+        r.skip(2)?;
+        let length: CARD32 = r.read()?;
+        let visual: VISUALID = r.read()?;
+        r.skip(20)?;
+        let data = r.bytes((length as usize) * 4)?.to_vec();
+        Ok(Self {
+            depth,
+            visual,
+            data,
+        })
+    }
+}
+
+pub fn poly_text8<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    x: INT16,
+    y: INT16,
+    items: &[u8],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_TEXT8, 0);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    request.write_all(items)?;
+    request.send(socket)
+}
+
+pub fn poly_text16<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    x: INT16,
+    y: INT16,
+    items: &[u8],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::POLY_TEXT16, 0);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    request.write_all(items)?;
+    request.send(socket)
+}
+
+pub fn image_text8<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    x: INT16,
+    y: INT16,
+    string: &[u8],
+) -> io::Result<()> {
+    let string_len = list_len::<CARD8>(string.len())?;
+    let mut request = RequestBuffer::new(opcodes::IMAGE_TEXT8, string_len);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    request.write_all(string)?;
+    request.send(socket)
+}
+
+pub fn image_text16<S: io::Write>(
+    socket: &mut S,
+    drawable: DRAWABLE,
+    gc: GCONTEXT,
+    x: INT16,
+    y: INT16,
+    string: &[Char2b],
+) -> io::Result<()> {
+    let string_len = list_len::<CARD8>(string.len())?;
+    let mut request = RequestBuffer::new(opcodes::IMAGE_TEXT16, string_len);
+    drawable.serialize(&mut request)?;
+    gc.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    for item in string {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn create_colormap<S: io::Write>(
+    socket: &mut S,
+    alloc: CARD8,
+    mid: COLORMAP,
+    window: WINDOW,
+    visual: VISUALID,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CREATE_COLORMAP, alloc);
+    mid.serialize(&mut request)?;
+    window.serialize(&mut request)?;
+    visual.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn free_colormap<S: io::Write>(socket: &mut S, cmap: COLORMAP) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::FREE_COLORMAP, 0);
+    cmap.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn copy_colormap_and_free<S: io::Write>(
+    socket: &mut S,
+    mid: COLORMAP,
+    src_cmap: COLORMAP,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::COPY_COLORMAP_AND_FREE, 0);
+    mid.serialize(&mut request)?;
+    src_cmap.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn install_colormap<S: io::Write>(socket: &mut S, cmap: COLORMAP) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::INSTALL_COLORMAP, 0);
+    cmap.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn uninstall_colormap<S: io::Write>(socket: &mut S, cmap: COLORMAP) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::UNINSTALL_COLORMAP, 0);
+    cmap.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn list_installed_colormaps_request<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::LIST_INSTALLED_COLORMAPS, 0);
+    window.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListInstalledColormapsReply {
+    pub cmaps: Vec<COLORMAP>,
+}
+
+impl ListInstalledColormapsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let cmaps_len: CARD16 = r.read()?;
+        r.skip(22)?;
+        let cmaps = r.list(cmaps_len as usize)?;
+        Ok(Self { cmaps })
+    }
+}
+
+pub fn alloc_color_request<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    red: CARD16,
+    green: CARD16,
+    blue: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::ALLOC_COLOR, 0);
+    cmap.serialize(&mut request)?;
+    red.serialize(&mut request)?;
+    green.serialize(&mut request)?;
+    blue.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AllocColorReply {
+    pub red: CARD16,
+    pub green: CARD16,
+    pub blue: CARD16,
+    pub pixel: CARD32,
+}
+
+impl AllocColorReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let red: CARD16 = r.read()?;
+        let green: CARD16 = r.read()?;
+        let blue: CARD16 = r.read()?;
+        r.skip(2)?;
+        let pixel: CARD32 = r.read()?;
+        Ok(Self {
+            red,
+            green,
+            blue,
+            pixel,
+        })
+    }
+}
+
+pub fn alloc_named_color_request<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    name: &[u8],
+) -> io::Result<()> {
+    let name_len = list_len::<CARD16>(name.len())?;
+    let mut request = RequestBuffer::new(opcodes::ALLOC_NAMED_COLOR, 0);
+    cmap.serialize(&mut request)?;
+    name_len.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.write_all(name)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AllocNamedColorReply {
+    pub pixel: CARD32,
+    pub exact_red: CARD16,
+    pub exact_green: CARD16,
+    pub exact_blue: CARD16,
+    pub visual_red: CARD16,
+    pub visual_green: CARD16,
+    pub visual_blue: CARD16,
+}
+
+impl AllocNamedColorReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let pixel: CARD32 = r.read()?;
+        let exact_red: CARD16 = r.read()?;
+        let exact_green: CARD16 = r.read()?;
+        let exact_blue: CARD16 = r.read()?;
+        let visual_red: CARD16 = r.read()?;
+        let visual_green: CARD16 = r.read()?;
+        let visual_blue: CARD16 = r.read()?;
+        Ok(Self {
+            pixel,
+            exact_red,
+            exact_green,
+            exact_blue,
+            visual_red,
+            visual_green,
+            visual_blue,
+        })
+    }
+}
+
+pub fn alloc_color_cells_request<S: io::Write>(
+    socket: &mut S,
+    contiguous: BOOL,
+    cmap: COLORMAP,
+    colors: CARD16,
+    planes: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::ALLOC_COLOR_CELLS, contiguous);
+    cmap.serialize(&mut request)?;
+    colors.serialize(&mut request)?;
+    planes.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AllocColorCellsReply {
+    pub pixels: Vec<CARD32>,
+    pub masks: Vec<CARD32>,
+}
+
+impl AllocColorCellsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let pixels_len: CARD16 = r.read()?;
+        let masks_len: CARD16 = r.read()?;
+        r.skip(20)?;
+        let pixels = r.list(pixels_len as usize)?;
+        let masks = r.list(masks_len as usize)?;
+        Ok(Self { pixels, masks })
+    }
+}
+
+pub fn alloc_color_planes_request<S: io::Write>(
+    socket: &mut S,
+    contiguous: BOOL,
+    cmap: COLORMAP,
+    colors: CARD16,
+    reds: CARD16,
+    greens: CARD16,
+    blues: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::ALLOC_COLOR_PLANES, contiguous);
+    cmap.serialize(&mut request)?;
+    colors.serialize(&mut request)?;
+    reds.serialize(&mut request)?;
+    greens.serialize(&mut request)?;
+    blues.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AllocColorPlanesReply {
+    pub red_mask: CARD32,
+    pub green_mask: CARD32,
+    pub blue_mask: CARD32,
+    pub pixels: Vec<CARD32>,
+}
+
+impl AllocColorPlanesReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let pixels_len: CARD16 = r.read()?;
+        r.skip(2)?;
+        let red_mask: CARD32 = r.read()?;
+        let green_mask: CARD32 = r.read()?;
+        let blue_mask: CARD32 = r.read()?;
+        r.skip(8)?;
+        let pixels = r.list(pixels_len as usize)?;
+        Ok(Self {
+            red_mask,
+            green_mask,
+            blue_mask,
+            pixels,
+        })
+    }
+}
+
+pub fn free_colors<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    plane_mask: CARD32,
+    pixels: &[CARD32],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::FREE_COLORS, 0);
+    cmap.serialize(&mut request)?;
+    plane_mask.serialize(&mut request)?;
+    for item in pixels {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn store_colors<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    items: &[Coloritem],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::STORE_COLORS, 0);
+    cmap.serialize(&mut request)?;
+    for item in items {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn store_named_color<S: io::Write>(
+    socket: &mut S,
+    flags: CARD8,
+    cmap: COLORMAP,
+    pixel: CARD32,
+    name: &[u8],
+) -> io::Result<()> {
+    let name_len = list_len::<CARD16>(name.len())?;
+    let mut request = RequestBuffer::new(opcodes::STORE_NAMED_COLOR, flags);
+    cmap.serialize(&mut request)?;
+    pixel.serialize(&mut request)?;
+    name_len.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.write_all(name)?;
+    request.send(socket)
+}
+
+pub fn query_colors_request<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    pixels: &[CARD32],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::QUERY_COLORS, 0);
+    cmap.serialize(&mut request)?;
+    for item in pixels {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryColorsReply {
+    pub colors: Vec<Rgb>,
+}
+
+impl QueryColorsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let colors_len: CARD16 = r.read()?;
+        r.skip(22)?;
+        let colors = r.list(colors_len as usize)?;
+        Ok(Self { colors })
+    }
+}
+
+pub fn lookup_color_request<S: io::Write>(
+    socket: &mut S,
+    cmap: COLORMAP,
+    name: &[u8],
+) -> io::Result<()> {
+    let name_len = list_len::<CARD16>(name.len())?;
+    let mut request = RequestBuffer::new(opcodes::LOOKUP_COLOR, 0);
+    cmap.serialize(&mut request)?;
+    name_len.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.write_all(name)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LookupColorReply {
+    pub exact_red: CARD16,
+    pub exact_green: CARD16,
+    pub exact_blue: CARD16,
+    pub visual_red: CARD16,
+    pub visual_green: CARD16,
+    pub visual_blue: CARD16,
+}
+
+impl LookupColorReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let exact_red: CARD16 = r.read()?;
+        let exact_green: CARD16 = r.read()?;
+        let exact_blue: CARD16 = r.read()?;
+        let visual_red: CARD16 = r.read()?;
+        let visual_green: CARD16 = r.read()?;
+        let visual_blue: CARD16 = r.read()?;
+        Ok(Self {
+            exact_red,
+            exact_green,
+            exact_blue,
+            visual_red,
+            visual_green,
+            visual_blue,
+        })
+    }
+}
+
+pub fn create_cursor<S: io::Write>(
+    socket: &mut S,
+    cid: CURSOR,
+    source: PIXMAP,
+    mask: PIXMAP,
+    fore_red: CARD16,
+    fore_green: CARD16,
+    fore_blue: CARD16,
+    back_red: CARD16,
+    back_green: CARD16,
+    back_blue: CARD16,
+    x: CARD16,
+    y: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CREATE_CURSOR, 0);
+    cid.serialize(&mut request)?;
+    source.serialize(&mut request)?;
+    mask.serialize(&mut request)?;
+    fore_red.serialize(&mut request)?;
+    fore_green.serialize(&mut request)?;
+    fore_blue.serialize(&mut request)?;
+    back_red.serialize(&mut request)?;
+    back_green.serialize(&mut request)?;
+    back_blue.serialize(&mut request)?;
+    x.serialize(&mut request)?;
+    y.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn create_glyph_cursor<S: io::Write>(
+    socket: &mut S,
+    cid: CURSOR,
+    source_font: FONT,
+    mask_font: FONT,
+    source_char: CARD16,
+    mask_char: CARD16,
+    fore_red: CARD16,
+    fore_green: CARD16,
+    fore_blue: CARD16,
+    back_red: CARD16,
+    back_green: CARD16,
+    back_blue: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CREATE_GLYPH_CURSOR, 0);
+    cid.serialize(&mut request)?;
+    source_font.serialize(&mut request)?;
+    mask_font.serialize(&mut request)?;
+    source_char.serialize(&mut request)?;
+    mask_char.serialize(&mut request)?;
+    fore_red.serialize(&mut request)?;
+    fore_green.serialize(&mut request)?;
+    fore_blue.serialize(&mut request)?;
+    back_red.serialize(&mut request)?;
+    back_green.serialize(&mut request)?;
+    back_blue.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn free_cursor<S: io::Write>(socket: &mut S, cursor: CURSOR) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::FREE_CURSOR, 0);
+    cursor.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn recolor_cursor<S: io::Write>(
+    socket: &mut S,
+    cursor: CURSOR,
+    fore_red: CARD16,
+    fore_green: CARD16,
+    fore_blue: CARD16,
+    back_red: CARD16,
+    back_green: CARD16,
+    back_blue: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::RECOLOR_CURSOR, 0);
+    cursor.serialize(&mut request)?;
+    fore_red.serialize(&mut request)?;
+    fore_green.serialize(&mut request)?;
+    fore_blue.serialize(&mut request)?;
+    back_red.serialize(&mut request)?;
+    back_green.serialize(&mut request)?;
+    back_blue.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn query_best_size_request<S: io::Write>(
+    socket: &mut S,
+    class: CARD8,
+    drawable: DRAWABLE,
+    width: CARD16,
+    height: CARD16,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::QUERY_BEST_SIZE, class);
+    drawable.serialize(&mut request)?;
+    width.serialize(&mut request)?;
+    height.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryBestSizeReply {
+    pub width: CARD16,
+    pub height: CARD16,
+}
+
+impl QueryBestSizeReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        Ok(Self { width, height })
+    }
+}
+
+pub fn query_extension_request<S: io::Write>(socket: &mut S, name: &[u8]) -> io::Result<()> {
+    let name_len = list_len::<CARD16>(name.len())?;
+    let mut request = RequestBuffer::new(opcodes::QUERY_EXTENSION, 0);
+    name_len.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    request.write_all(name)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryExtensionReply {
+    pub present: BOOL,
+    pub major_opcode: CARD8,
+    pub first_event: CARD8,
+    pub first_error: CARD8,
+}
+
+impl QueryExtensionReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let present: BOOL = r.read()?;
+        let major_opcode: CARD8 = r.read()?;
+        let first_event: CARD8 = r.read()?;
+        let first_error: CARD8 = r.read()?;
+        Ok(Self {
+            present,
+            major_opcode,
+            first_event,
+            first_error,
+        })
+    }
+}
+
+pub fn list_extensions_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::LIST_EXTENSIONS, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListExtensionsReply {
+    pub names: Vec<Str>,
+}
+
+impl ListExtensionsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let names_len: CARD8 = r.read()?;
+        r.skip(30)?;
+        let names = r.list(names_len as usize)?;
+        Ok(Self { names })
+    }
+}
+
+pub fn change_keyboard_mapping<S: io::Write>(
+    socket: &mut S,
+    keycode_count: CARD8,
+    first_keycode: KEYCODE,
+    keysyms_per_keycode: CARD8,
+    keysyms: &[KEYSYM],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CHANGE_KEYBOARD_MAPPING, keycode_count);
+    first_keycode.serialize(&mut request)?;
+    keysyms_per_keycode.serialize(&mut request)?;
+    request.write_all(&[0; 2])?;
+    for item in keysyms {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn get_keyboard_mapping_request<S: io::Write>(
+    socket: &mut S,
+    first_keycode: KEYCODE,
+    count: CARD8,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::GET_KEYBOARD_MAPPING, 0);
+    first_keycode.serialize(&mut request)?;
+    count.serialize(&mut request)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetKeyboardMappingReply {
+    pub keysyms_per_keycode: CARD8,
+    pub keysyms: Vec<KEYSYM>,
+}
+
+impl GetKeyboardMappingReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let keysyms_per_keycode: CARD8 = r.read()?;
+        r.skip(2)?;
+        let length: CARD32 = r.read()?;
+        r.skip(24)?;
+        let keysyms = r.list(length as usize)?;
+        Ok(Self {
+            keysyms_per_keycode,
+            keysyms,
+        })
+    }
+}
+
+pub fn change_keyboard_control<S: io::Write>(
+    socket: &mut S,
+    value_list: &[(CARD32, CARD32)],
+) -> io::Result<()> {
+    // the server wants the values in the order of their bits
+    let mut value_list = value_list.to_vec();
+    value_list.sort_by_key(|&(bit, _)| bit);
+    let value_mask = value_list.iter().fold(0, |mask, &(bit, _)| mask | bit);
+    let mut request = RequestBuffer::new(opcodes::CHANGE_KEYBOARD_CONTROL, 0);
+    value_mask.serialize(&mut request)?;
+    for (_, value) in value_list {
+        value.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn get_keyboard_control_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::GET_KEYBOARD_CONTROL, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetKeyboardControlReply {
+    pub global_auto_repeat: CARD8,
+    pub led_mask: CARD32,
+    pub key_click_percent: CARD8,
+    pub bell_percent: CARD8,
+    pub bell_pitch: CARD16,
+    pub bell_duration: CARD16,
+    pub auto_repeats: Vec<u8>,
+}
+
+impl GetKeyboardControlReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let global_auto_repeat: CARD8 = r.read()?;
+        r.skip(6)?;
+        let led_mask: CARD32 = r.read()?;
+        let key_click_percent: CARD8 = r.read()?;
+        let bell_percent: CARD8 = r.read()?;
+        let bell_pitch: CARD16 = r.read()?;
+        let bell_duration: CARD16 = r.read()?;
+        r.skip(2)?;
+        let auto_repeats = r.bytes(32)?.to_vec();
+        Ok(Self {
+            global_auto_repeat,
+            led_mask,
+            key_click_percent,
+            bell_percent,
+            bell_pitch,
+            bell_duration,
+            auto_repeats,
+        })
+    }
+}
+
+pub fn bell<S: io::Write>(socket: &mut S, percent: INT8) -> io::Result<()> {
+    RequestBuffer::new(opcodes::BELL, percent as CARD8).send(socket)
+}
+
+pub fn change_pointer_control<S: io::Write>(
+    socket: &mut S,
+    acceleration_numerator: INT16,
+    acceleration_denominator: INT16,
+    threshold: INT16,
+    do_acceleration: BOOL,
+    do_threshold: BOOL,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::CHANGE_POINTER_CONTROL, 0);
+    acceleration_numerator.serialize(&mut request)?;
+    acceleration_denominator.serialize(&mut request)?;
+    threshold.serialize(&mut request)?;
+    do_acceleration.serialize(&mut request)?;
+    do_threshold.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_pointer_control_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::GET_POINTER_CONTROL, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetPointerControlReply {
+    pub acceleration_numerator: CARD16,
+    pub acceleration_denominator: CARD16,
+    pub threshold: CARD16,
+}
+
+impl GetPointerControlReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let acceleration_numerator: CARD16 = r.read()?;
+        let acceleration_denominator: CARD16 = r.read()?;
+        let threshold: CARD16 = r.read()?;
+        r.skip(18)?;
+        Ok(Self {
+            acceleration_numerator,
+            acceleration_denominator,
+            threshold,
+        })
+    }
+}
+
+pub fn set_screen_saver<S: io::Write>(
+    socket: &mut S,
+    timeout: INT16,
+    interval: INT16,
+    prefer_blanking: CARD8,
+    allow_exposures: CARD8,
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::SET_SCREEN_SAVER, 0);
+    timeout.serialize(&mut request)?;
+    interval.serialize(&mut request)?;
+    prefer_blanking.serialize(&mut request)?;
+    allow_exposures.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn get_screen_saver_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::GET_SCREEN_SAVER, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetScreenSaverReply {
+    pub timeout: CARD16,
+    pub interval: CARD16,
+    pub prefer_blanking: CARD8,
+    pub allow_exposures: CARD8,
+}
+
+impl GetScreenSaverReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(8)?;
+        let timeout: CARD16 = r.read()?;
+        let interval: CARD16 = r.read()?;
+        let prefer_blanking: CARD8 = r.read()?;
+        let allow_exposures: CARD8 = r.read()?;
+        r.skip(18)?;
+        Ok(Self {
+            timeout,
+            interval,
+            prefer_blanking,
+            allow_exposures,
+        })
+    }
+}
+
+pub fn change_hosts<S: io::Write>(
+    socket: &mut S,
+    mode: CARD8,
+    family: CARD8,
+    address: &[u8],
+) -> io::Result<()> {
+    let address_len = list_len::<CARD16>(address.len())?;
+    let mut request = RequestBuffer::new(opcodes::CHANGE_HOSTS, mode);
+    family.serialize(&mut request)?;
+    request.write_all(&[0; 1])?;
+    address_len.serialize(&mut request)?;
+    request.write_all(address)?;
+    request.send(socket)
+}
+
+pub fn list_hosts_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::LIST_HOSTS, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListHostsReply {
+    pub mode: CARD8,
+    pub hosts: Vec<Host>,
+}
+
+impl ListHostsReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let mode: CARD8 = r.read()?;
+        r.skip(6)?;
+        let hosts_len: CARD16 = r.read()?;
+        r.skip(22)?;
+        let hosts = r.list(hosts_len as usize)?;
+        Ok(Self { mode, hosts })
+    }
+}
+
+pub fn set_access_control<S: io::Write>(socket: &mut S, mode: CARD8) -> io::Result<()> {
+    RequestBuffer::new(opcodes::SET_ACCESS_CONTROL, mode).send(socket)
+}
+
+pub fn set_close_down_mode<S: io::Write>(socket: &mut S, mode: CARD8) -> io::Result<()> {
+    RequestBuffer::new(opcodes::SET_CLOSE_DOWN_MODE, mode).send(socket)
+}
+
+pub fn kill_client<S: io::Write>(socket: &mut S, resource: CARD32) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::KILL_CLIENT, 0);
+    resource.serialize(&mut request)?;
+    request.send(socket)
+}
+
+pub fn rotate_properties<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    delta: INT16,
+    atoms: &[Atom],
+) -> io::Result<()> {
+    let atoms_len = list_len::<CARD16>(atoms.len())?;
+    let mut request = RequestBuffer::new(opcodes::ROTATE_PROPERTIES, 0);
+    window.serialize(&mut request)?;
+    atoms_len.serialize(&mut request)?;
+    delta.serialize(&mut request)?;
+    for item in atoms {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+pub fn force_screen_saver<S: io::Write>(socket: &mut S, mode: CARD8) -> io::Result<()> {
+    RequestBuffer::new(opcodes::FORCE_SCREEN_SAVER, mode).send(socket)
+}
+
+pub fn set_pointer_mapping_request<S: io::Write>(socket: &mut S, map: &[u8]) -> io::Result<()> {
+    let map_len = list_len::<CARD8>(map.len())?;
+    let mut request = RequestBuffer::new(opcodes::SET_POINTER_MAPPING, map_len);
+    request.write_all(map)?;
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetPointerMappingReply {
+    pub status: CARD8,
+}
+
+impl SetPointerMappingReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let status: CARD8 = r.read()?;
+        r.skip(6)?;
+        Ok(Self { status })
+    }
+}
+
+pub fn get_pointer_mapping_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::GET_POINTER_MAPPING, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetPointerMappingReply {
+    pub map: Vec<u8>,
+}
+
+impl GetPointerMappingReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let map_len: CARD8 = r.read()?;
+        r.skip(30)?;
+        let map = r.bytes(map_len as usize)?.to_vec();
+        Ok(Self { map })
+    }
+}
+
+pub fn set_modifier_mapping_request<S: io::Write>(
+    socket: &mut S,
+    keycodes_per_modifier: CARD8,
+    keycodes: &[KEYCODE],
+) -> io::Result<()> {
+    let mut request = RequestBuffer::new(opcodes::SET_MODIFIER_MAPPING, keycodes_per_modifier);
+    for item in keycodes {
+        item.serialize(&mut request)?;
+    }
+    request.send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetModifierMappingReply {
+    pub status: CARD8,
+}
+
+impl SetModifierMappingReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let status: CARD8 = r.read()?;
+        r.skip(6)?;
+        Ok(Self { status })
+    }
+}
+
+pub fn get_modifier_mapping_request<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::GET_MODIFIER_MAPPING, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetModifierMappingReply {
+    pub keycodes_per_modifier: CARD8,
+    pub keycodes: Vec<KEYCODE>,
+}
+
+impl GetModifierMappingReply {
+    pub fn parse(reply: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(reply);
+        r.skip(1)?;
+        let keycodes_per_modifier: CARD8 = r.read()?;
+        r.skip(30)?;
+        let keycodes = r.list((keycodes_per_modifier as usize) * 8)?;
+        Ok(Self {
+            keycodes_per_modifier,
+            keycodes,
+        })
+    }
+}
+
+pub fn no_operation<S: io::Write>(socket: &mut S) -> io::Result<()> {
+    RequestBuffer::new(opcodes::NO_OPERATION, 0).send(socket)
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyPressEvent {
+    pub detail: KEYCODE,
+    pub time: TIMESTAMP,
+    pub root: WINDOW,
+    pub event: WINDOW,
+    pub child: WINDOW,
+    pub root_x: INT16,
+    pub root_y: INT16,
+    pub event_x: INT16,
+    pub event_y: INT16,
+    pub state: CARD16,
+    pub same_screen: BOOL,
+}
+
+impl KeyPressEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let detail: KEYCODE = r.read()?;
+        r.skip(2)?;
+        let time: TIMESTAMP = r.read()?;
+        let root: WINDOW = r.read()?;
+        let event: WINDOW = r.read()?;
+        let child: WINDOW = r.read()?;
+        let root_x: INT16 = r.read()?;
+        let root_y: INT16 = r.read()?;
+        let event_x: INT16 = r.read()?;
+        let event_y: INT16 = r.read()?;
+        let state: CARD16 = r.read()?;
+        let same_screen: BOOL = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            detail,
+            time,
+            root,
+            event,
+            child,
+            root_x,
+            root_y,
+            event_x,
+            event_y,
+            state,
+            same_screen,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ButtonPressEvent {
+    pub detail: BUTTON,
+    pub time: TIMESTAMP,
+    pub root: WINDOW,
+    pub event: WINDOW,
+    pub child: WINDOW,
+    pub root_x: INT16,
+    pub root_y: INT16,
+    pub event_x: INT16,
+    pub event_y: INT16,
+    pub state: CARD16,
+    pub same_screen: BOOL,
+}
+
+impl ButtonPressEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let detail: BUTTON = r.read()?;
+        r.skip(2)?;
+        let time: TIMESTAMP = r.read()?;
+        let root: WINDOW = r.read()?;
+        let event: WINDOW = r.read()?;
+        let child: WINDOW = r.read()?;
+        let root_x: INT16 = r.read()?;
+        let root_y: INT16 = r.read()?;
+        let event_x: INT16 = r.read()?;
+        let event_y: INT16 = r.read()?;
+        let state: CARD16 = r.read()?;
+        let same_screen: BOOL = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            detail,
+            time,
+            root,
+            event,
+            child,
+            root_x,
+            root_y,
+            event_x,
+            event_y,
+            state,
+            same_screen,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MotionNotifyEvent {
+    pub detail: CARD8,
+    pub time: TIMESTAMP,
+    pub root: WINDOW,
+    pub event: WINDOW,
+    pub child: WINDOW,
+    pub root_x: INT16,
+    pub root_y: INT16,
+    pub event_x: INT16,
+    pub event_y: INT16,
+    pub state: CARD16,
+    pub same_screen: BOOL,
+}
+
+impl MotionNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let detail: CARD8 = r.read()?;
+        r.skip(2)?;
+        let time: TIMESTAMP = r.read()?;
+        let root: WINDOW = r.read()?;
+        let event: WINDOW = r.read()?;
+        let child: WINDOW = r.read()?;
+        let root_x: INT16 = r.read()?;
+        let root_y: INT16 = r.read()?;
+        let event_x: INT16 = r.read()?;
+        let event_y: INT16 = r.read()?;
+        let state: CARD16 = r.read()?;
+        let same_screen: BOOL = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            detail,
+            time,
+            root,
+            event,
+            child,
+            root_x,
+            root_y,
+            event_x,
+            event_y,
+            state,
+            same_screen,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnterNotifyEvent {
+    pub detail: CARD8,
+    pub time: TIMESTAMP,
+    pub root: WINDOW,
+    pub event: WINDOW,
+    pub child: WINDOW,
+    pub root_x: INT16,
+    pub root_y: INT16,
+    pub event_x: INT16,
+    pub event_y: INT16,
+    pub state: CARD16,
+    pub mode: CARD8,
+    pub same_screen_focus: CARD8,
+}
+
+impl EnterNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let detail: CARD8 = r.read()?;
+        r.skip(2)?;
+        let time: TIMESTAMP = r.read()?;
+        let root: WINDOW = r.read()?;
+        let event: WINDOW = r.read()?;
+        let child: WINDOW = r.read()?;
+        let root_x: INT16 = r.read()?;
+        let root_y: INT16 = r.read()?;
+        let event_x: INT16 = r.read()?;
+        let event_y: INT16 = r.read()?;
+        let state: CARD16 = r.read()?;
+        let mode: CARD8 = r.read()?;
+        let same_screen_focus: CARD8 = r.read()?;
+        Ok(Self {
+            detail,
+            time,
+            root,
+            event,
+            child,
+            root_x,
+            root_y,
+            event_x,
+            event_y,
+            state,
+            mode,
+            same_screen_focus,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FocusInEvent {
+    pub detail: CARD8,
+    pub event: WINDOW,
+    pub mode: CARD8,
+}
+
+impl FocusInEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let detail: CARD8 = r.read()?;
+        r.skip(2)?;
+        let event: WINDOW = r.read()?;
+        let mode: CARD8 = r.read()?;
+        r.skip(3)?;
+        Ok(Self {
+            detail,
+            event,
+            mode,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeymapNotifyEvent {
+    pub keys: Vec<u8>,
+}
+
+impl KeymapNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let keys = r.bytes(31)?.to_vec();
+        Ok(Self { keys })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExposeEvent {
+    pub window: WINDOW,
+    pub x: CARD16,
+    pub y: CARD16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub count: CARD16,
+}
+
+impl ExposeEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let window: WINDOW = r.read()?;
+        let x: CARD16 = r.read()?;
+        let y: CARD16 = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        let count: CARD16 = r.read()?;
+        r.skip(2)?;
+        Ok(Self {
+            window,
+            x,
+            y,
+            width,
+            height,
+            count,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphicsExposureEvent {
+    pub drawable: DRAWABLE,
+    pub x: CARD16,
+    pub y: CARD16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub minor_opcode: CARD16,
+    pub count: CARD16,
+    pub major_opcode: CARD8,
+}
+
+impl GraphicsExposureEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let drawable: DRAWABLE = r.read()?;
+        let x: CARD16 = r.read()?;
+        let y: CARD16 = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        let minor_opcode: CARD16 = r.read()?;
+        let count: CARD16 = r.read()?;
+        let major_opcode: CARD8 = r.read()?;
+        r.skip(3)?;
+        Ok(Self {
+            drawable,
+            x,
+            y,
+            width,
+            height,
+            minor_opcode,
+            count,
+            major_opcode,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NoExposureEvent {
+    pub drawable: DRAWABLE,
+    pub minor_opcode: CARD16,
+    pub major_opcode: CARD8,
+}
+
+impl NoExposureEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let drawable: DRAWABLE = r.read()?;
+        let minor_opcode: CARD16 = r.read()?;
+        let major_opcode: CARD8 = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            drawable,
+            minor_opcode,
+            major_opcode,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VisibilityNotifyEvent {
+    pub window: WINDOW,
+    pub state: CARD8,
+}
+
+impl VisibilityNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let window: WINDOW = r.read()?;
+        let state: CARD8 = r.read()?;
+        r.skip(3)?;
+        Ok(Self { window, state })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CreateNotifyEvent {
+    pub parent: WINDOW,
+    pub window: WINDOW,
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub border_width: CARD16,
+    pub override_redirect: BOOL,
+}
+
+impl CreateNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let parent: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        let x: INT16 = r.read()?;
+        let y: INT16 = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        let border_width: CARD16 = r.read()?;
+        let override_redirect: BOOL = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            parent,
+            window,
+            x,
+            y,
+            width,
+            height,
+            border_width,
+            override_redirect,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DestroyNotifyEvent {
+    pub event: WINDOW,
+    pub window: WINDOW,
+}
+
+impl DestroyNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let event: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        Ok(Self { event, window })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnmapNotifyEvent {
+    pub event: WINDOW,
+    pub window: WINDOW,
+    pub from_configure: BOOL,
+}
+
+impl UnmapNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let event: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        let from_configure: BOOL = r.read()?;
+        r.skip(3)?;
+        Ok(Self {
+            event,
+            window,
+            from_configure,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MapNotifyEvent {
+    pub event: WINDOW,
+    pub window: WINDOW,
+    pub override_redirect: BOOL,
+}
+
+impl MapNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let event: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        let override_redirect: BOOL = r.read()?;
+        r.skip(3)?;
+        Ok(Self {
+            event,
+            window,
+            override_redirect,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MapRequestEvent {
+    pub parent: WINDOW,
+    pub window: WINDOW,
+}
+
+impl MapRequestEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let parent: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        Ok(Self { parent, window })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReparentNotifyEvent {
+    pub event: WINDOW,
+    pub window: WINDOW,
+    pub parent: WINDOW,
+    pub x: INT16,
+    pub y: INT16,
+    pub override_redirect: BOOL,
+}
+
+impl ReparentNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let event: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        let parent: WINDOW = r.read()?;
+        let x: INT16 = r.read()?;
+        let y: INT16 = r.read()?;
+        let override_redirect: BOOL = r.read()?;
+        r.skip(3)?;
+        Ok(Self {
+            event,
+            window,
+            parent,
+            x,
+            y,
+            override_redirect,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigureNotifyEvent {
+    pub event: WINDOW,
+    pub window: WINDOW,
+    pub above_sibling: WINDOW,
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub border_width: CARD16,
+    pub override_redirect: BOOL,
+}
+
+impl ConfigureNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let event: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        let above_sibling: WINDOW = r.read()?;
+        let x: INT16 = r.read()?;
+        let y: INT16 = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        let border_width: CARD16 = r.read()?;
+        let override_redirect: BOOL = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            event,
+            window,
+            above_sibling,
+            x,
+            y,
+            width,
+            height,
+            border_width,
+            override_redirect,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigureRequestEvent {
+    pub stack_mode: CARD8,
+    pub parent: WINDOW,
+    pub window: WINDOW,
+    pub sibling: WINDOW,
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub border_width: CARD16,
+    pub value_mask: CARD16,
+}
+
+impl ConfigureRequestEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let stack_mode: CARD8 = r.read()?;
+        r.skip(2)?;
+        let parent: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        let sibling: WINDOW = r.read()?;
+        let x: INT16 = r.read()?;
+        let y: INT16 = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        let border_width: CARD16 = r.read()?;
+        let value_mask: CARD16 = r.read()?;
+        Ok(Self {
+            stack_mode,
+            parent,
+            window,
+            sibling,
+            x,
+            y,
+            width,
+            height,
+            border_width,
+            value_mask,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GravityNotifyEvent {
+    pub event: WINDOW,
+    pub window: WINDOW,
+    pub x: INT16,
+    pub y: INT16,
+}
+
+impl GravityNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let event: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        let x: INT16 = r.read()?;
+        let y: INT16 = r.read()?;
+        Ok(Self {
+            event,
+            window,
+            x,
+            y,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResizeRequestEvent {
+    pub window: WINDOW,
+    pub width: CARD16,
+    pub height: CARD16,
+}
+
+impl ResizeRequestEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let window: WINDOW = r.read()?;
+        let width: CARD16 = r.read()?;
+        let height: CARD16 = r.read()?;
+        Ok(Self {
+            window,
+            width,
+            height,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CirculateNotifyEvent {
+    pub event: WINDOW,
+    pub window: WINDOW,
+    pub place: CARD8,
+}
+
+impl CirculateNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let event: WINDOW = r.read()?;
+        let window: WINDOW = r.read()?;
+        r.skip(4)?;
+        let place: CARD8 = r.read()?;
+        r.skip(3)?;
+        Ok(Self {
+            event,
+            window,
+            place,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PropertyNotifyEvent {
+    pub window: WINDOW,
+    pub atom: Atom,
+    pub time: TIMESTAMP,
+    pub state: CARD8,
+}
+
+impl PropertyNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let window: WINDOW = r.read()?;
+        let atom: Atom = r.read()?;
+        let time: TIMESTAMP = r.read()?;
+        let state: CARD8 = r.read()?;
+        r.skip(3)?;
+        Ok(Self {
+            window,
+            atom,
+            time,
+            state,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectionClearEvent {
+    pub time: TIMESTAMP,
+    pub owner: WINDOW,
+    pub selection: Atom,
+}
+
+impl SelectionClearEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let time: TIMESTAMP = r.read()?;
+        let owner: WINDOW = r.read()?;
+        let selection: Atom = r.read()?;
+        Ok(Self {
+            time,
+            owner,
+            selection,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectionRequestEvent {
+    pub time: TIMESTAMP,
+    pub owner: WINDOW,
+    pub requestor: WINDOW,
+    pub selection: Atom,
+    pub target: Atom,
+    pub property: Atom,
+}
+
+impl SelectionRequestEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let time: TIMESTAMP = r.read()?;
+        let owner: WINDOW = r.read()?;
+        let requestor: WINDOW = r.read()?;
+        let selection: Atom = r.read()?;
+        let target: Atom = r.read()?;
+        let property: Atom = r.read()?;
+        Ok(Self {
+            time,
+            owner,
+            requestor,
+            selection,
+            target,
+            property,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectionNotifyEvent {
+    pub time: TIMESTAMP,
+    pub requestor: WINDOW,
+    pub selection: Atom,
+    pub target: Atom,
+    pub property: Atom,
+}
+
+impl SelectionNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let time: TIMESTAMP = r.read()?;
+        let requestor: WINDOW = r.read()?;
+        let selection: Atom = r.read()?;
+        let target: Atom = r.read()?;
+        let property: Atom = r.read()?;
+        Ok(Self {
+            time,
+            requestor,
+            selection,
+            target,
+            property,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColormapNotifyEvent {
+    pub window: WINDOW,
+    pub colormap: COLORMAP,
+    pub new: BOOL,
+    pub state: CARD8,
+}
+
+impl ColormapNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let window: WINDOW = r.read()?;
+        let colormap: COLORMAP = r.read()?;
+        let new: BOOL = r.read()?;
+        let state: CARD8 = r.read()?;
+        r.skip(2)?;
+        Ok(Self {
+            window,
+            colormap,
+            new,
+            state,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientMessageEvent {
+    pub format: CARD8,
+    pub window: WINDOW,
+    pub type_: Atom,
+    pub data: ClientMessageData,
+}
+
+impl ClientMessageEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(1)?;
+        let format: CARD8 = r.read()?;
+        r.skip(2)?;
+        let window: WINDOW = r.read()?;
+        let type_: Atom = r.read()?;
+        let data: ClientMessageData = r.read()?;
+        Ok(Self {
+            format,
+            window,
+            type_,
+            data,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MappingNotifyEvent {
+    pub request: CARD8,
+    pub first_keycode: KEYCODE,
+    pub count: CARD8,
+}
+
+impl MappingNotifyEvent {
+    pub fn parse(event: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(event);
+        r.skip(4)?;
+        let request: CARD8 = r.read()?;
+        let first_keycode: KEYCODE = r.read()?;
+        let count: CARD8 = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            request,
+            first_keycode,
+            count,
+        })
+    }
+}
+
+pub type KeyReleaseEvent = KeyPressEvent;
+pub type ButtonReleaseEvent = ButtonPressEvent;
+pub type LeaveNotifyEvent = EnterNotifyEvent;
+pub type FocusOutEvent = FocusInEvent;
+pub type CirculateRequestEvent = CirculateNotifyEvent;
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RequestError {
+    pub bad_value: CARD32,
+    pub minor_opcode: CARD16,
+    pub major_opcode: CARD8,
+}
+
+impl RequestError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        let bad_value: CARD32 = r.read()?;
+        let minor_opcode: CARD16 = r.read()?;
+        let major_opcode: CARD8 = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            bad_value,
+            minor_opcode,
+            major_opcode,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValueError {
+    pub bad_value: CARD32,
+    pub minor_opcode: CARD16,
+    pub major_opcode: CARD8,
+}
+
+impl ValueError {
+    pub fn parse(packet: &[u8]) -> io::Result<Self> {
+        let r = &mut Reader::new(packet);
+        r.skip(4)?;
+        let bad_value: CARD32 = r.read()?;
+        let minor_opcode: CARD16 = r.read()?;
+        let major_opcode: CARD8 = r.read()?;
+        r.skip(1)?;
+        Ok(Self {
+            bad_value,
+            minor_opcode,
+            major_opcode,
+        })
+    }
+}
+
+pub type WindowError = ValueError;
+pub type PixmapError = ValueError;
+pub type AtomError = ValueError;
+pub type CursorError = ValueError;
+pub type FontError = ValueError;
+pub type MatchError = RequestError;
+pub type DrawableError = ValueError;
+pub type AccessError = RequestError;
+pub type AllocError = RequestError;
+pub type ColormapError = ValueError;
+pub type GContextError = ValueError;
+pub type IDChoiceError = ValueError;
+pub type NameError = RequestError;
+pub type LengthError = RequestError;
+pub type ImplementationError = RequestError;
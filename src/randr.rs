@@ -0,0 +1,540 @@
+use super::*;
+use std::any::Any;
+
+pub use crate::proto::randr::*;
+// the crate root has core ones by the same names
+pub use crate::proto::randr::{error_codes, event_codes, opcodes, select_input};
+use crate::proto::xproto::TIMESTAMP;
+
+/// the version we ask for, GetMonitors needs 1.5
+const CLIENT_MAJOR_VERSION: CARD32 = 1;
+const CLIENT_MINOR_VERSION: CARD32 = 5;
+
+/// used to be its own type, everything is an `XErrorKind` now
+#[deprecated(note = "use XErrorKind")]
+pub type RandrErr = XErrorKind;
+
+/// Everything needed to talk RandR, returned by `XContext::query_randr`
+#[derive(Copy, Clone, Debug)]
+pub struct RandrExtension {
+    pub info: ExtensionInfo,
+    pub major_version: CARD32,
+    pub minor_version: CARD32,
+}
+impl RandrExtension {
+    /// GetMonitors and friends came with 1.5
+    pub fn supports_monitors(&self) -> bool {
+        (self.major_version, self.minor_version) >= (1, 5)
+    }
+
+    fn require(&self, major: CARD32, minor: CARD32) -> Result<(), XErrorKind> {
+        if (self.major_version, self.minor_version) < (major, minor) {
+            return Err(XErrorKind::ExtensionTooOld {
+                extension: EXTENSION_NAME,
+                major_version: self.major_version,
+                minor_version: self.minor_version,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// What `XContext::decode_event` hands out for RandR events, the RRNotify ones come
+/// out of their union already picked apart by subcode
+#[derive(Clone, Debug, PartialEq)]
+pub enum RandrEvent {
+    ScreenChange(ScreenChangeNotifyEvent),
+    CrtcChange(CrtcChange),
+    OutputChange(OutputChange),
+    OutputProperty(OutputProperty),
+    ProviderChange(ProviderChange),
+    ProviderProperty(ProviderProperty),
+    ResourceChange(ResourceChange),
+}
+
+impl RandrEvent {
+    /// returns `None` when `event` isn't a RandR event or is one we don't know
+    pub fn from_event(randr: &RandrExtension, event: &RawEvent) -> Option<Self> {
+        // the top bit is set for events that came from SendEvent
+        let code = (event[0] & 0x7f).checked_sub(randr.info.first_event)?;
+        match code {
+            event_codes::SCREEN_CHANGE_NOTIFY => ScreenChangeNotifyEvent::parse(event)
+                .ok()
+                .map(Self::ScreenChange),
+            event_codes::NOTIFY => {
+                let notify = NotifyEvent::parse(event).ok()?;
+                let data = &mut xio::Reader::new(&notify.u);
+                Some(match notify.sub_code as u32 {
+                    notify::CRTC_CHANGE => Self::CrtcChange(data.read().ok()?),
+                    notify::OUTPUT_CHANGE => Self::OutputChange(data.read().ok()?),
+                    notify::OUTPUT_PROPERTY => Self::OutputProperty(data.read().ok()?),
+                    notify::PROVIDER_CHANGE => Self::ProviderChange(data.read().ok()?),
+                    notify::PROVIDER_PROPERTY => Self::ProviderProperty(data.read().ok()?),
+                    notify::RESOURCE_CHANGE => Self::ResourceChange(data.read().ok()?),
+                    _ => return None,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl ExtensionDecoder for RandrExtension {
+    fn decode_event(&self, _info: &ExtensionInfo, event: &RawEvent) -> Option<Box<dyn Any>> {
+        RandrEvent::from_event(self, event).map(|e| Box::new(e) as Box<dyn Any>)
+    }
+
+    fn error_name(&self, _info: &ExtensionInfo, code: CARD8) -> Option<&'static str> {
+        match code {
+            error_codes::BAD_OUTPUT => Some("BadOutput"),
+            error_codes::BAD_CRTC => Some("BadCrtc"),
+            error_codes::BAD_MODE => Some("BadMode"),
+            error_codes::BAD_PROVIDER => Some("BadProvider"),
+            _ => None,
+        }
+    }
+}
+
+/// One monitor as `XContext::monitors` sees it, in root window coordinates
+#[derive(Clone, Debug, PartialEq)]
+pub struct Monitor {
+    /// usually the output's name, like "DP-1"
+    pub name: String,
+    pub primary: bool,
+    /// made by the server for an output rather than by a client with SetMonitor
+    pub automatic: bool,
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub width_in_millimeters: CARD32,
+    pub height_in_millimeters: CARD32,
+    pub outputs: Vec<OUTPUT>,
+}
+
+impl Monitor {
+    /// whether the root window point `pos` is on this monitor
+    pub fn contains(&self, pos: (INT16, INT16)) -> bool {
+        let (x, y) = (pos.0 as i32, pos.1 as i32);
+        (self.x as i32..self.x as i32 + self.width as i32).contains(&x)
+            && (self.y as i32..self.y as i32 + self.height as i32).contains(&y)
+    }
+
+    /// where a window of `size` goes to be centered on this monitor, for `with_pos`
+    pub fn centered(&self, size: (CARD16, CARD16)) -> (INT16, INT16) {
+        let x = self.x as i32 + (self.width as i32 - size.0 as i32) / 2;
+        let y = self.y as i32 + (self.height as i32 - size.1 as i32) / 2;
+        (x as INT16, y as INT16)
+    }
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    pub fn query_randr(&self) -> Result<RandrExtension, XErrorKind> {
+        let info = self
+            .query_extension(EXTENSION_NAME)?
+            .ok_or(XErrorKind::ExtensionMissing(EXTENSION_NAME))?;
+        self.socket_cb(|socket| {
+            query_version_request(
+                socket,
+                info.major_opcode,
+                CLIENT_MAJOR_VERSION,
+                CLIENT_MINOR_VERSION,
+            )
+        })?;
+        let reply = QueryVersionReply::parse(&self.read_reply()?)?;
+        let randr = RandrExtension {
+            info,
+            major_version: reply.major_version,
+            minor_version: reply.minor_version,
+        };
+        self.register_decoder(EXTENSION_NAME, randr);
+        Ok(randr)
+    }
+
+    /// Asks for the RandR events in `mask` (bits of `notify_mask`, which all fit in 16) on
+    /// `window`.\
+    /// They come out of `decode_event` as `RandrEvent`s
+    pub fn randr_select_input(
+        &self,
+        randr: &RandrExtension,
+        window: WINDOW,
+        mask: CARD16,
+    ) -> Result<(), XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| select_input(socket, major_opcode, window, mask))?;
+        Ok(())
+    }
+
+    /// the CRTCs, outputs and modes of `window`'s screen without polling for new outputs,
+    /// which GetScreenResources does and can take a while
+    pub fn get_screen_resources_current(
+        &self,
+        randr: &RandrExtension,
+        window: WINDOW,
+    ) -> Result<GetScreenResourcesCurrentReply, XErrorKind> {
+        randr.require(1, 3)?;
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| {
+            get_screen_resources_current_request(socket, major_opcode, window)
+        })?;
+        Ok(GetScreenResourcesCurrentReply::parse(&self.read_reply()?)?)
+    }
+
+    /// `config_timestamp` is the one from the screen resources
+    pub fn get_output_info(
+        &self,
+        randr: &RandrExtension,
+        output: OUTPUT,
+        config_timestamp: TIMESTAMP,
+    ) -> Result<GetOutputInfoReply, XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| {
+            get_output_info_request(socket, major_opcode, output, config_timestamp)
+        })?;
+        Ok(GetOutputInfoReply::parse(&self.read_reply()?)?)
+    }
+
+    /// `config_timestamp` is the one from the screen resources
+    pub fn get_crtc_info(
+        &self,
+        randr: &RandrExtension,
+        crtc: CRTC,
+        config_timestamp: TIMESTAMP,
+    ) -> Result<GetCrtcInfoReply, XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| {
+            get_crtc_info_request(socket, major_opcode, crtc, config_timestamp)
+        })?;
+        Ok(GetCrtcInfoReply::parse(&self.read_reply()?)?)
+    }
+
+    /// Shows `mode` on `crtc` at `pos` through `outputs`, a `mode` of 0 turns the CRTC off.\
+    /// Returns the time the configuration changed, a status other than success is
+    /// `XErrorKind::ConfigFailed`
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_crtc_config(
+        &self,
+        randr: &RandrExtension,
+        crtc: CRTC,
+        config_timestamp: TIMESTAMP,
+        pos: (INT16, INT16),
+        mode: MODE,
+        rotation: CARD16,
+        outputs: &[OUTPUT],
+    ) -> Result<TIMESTAMP, XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| {
+            set_crtc_config_request(
+                socket,
+                major_opcode,
+                crtc,
+                Timestamp::current_time().into(),
+                config_timestamp,
+                pos.0,
+                pos.1,
+                mode,
+                rotation,
+                outputs,
+            )
+        })?;
+        let reply = SetCrtcConfigReply::parse(&self.read_reply()?)?;
+        if reply.status as u32 != set_config::SUCCESS {
+            return Err(XErrorKind::ConfigFailed(reply.status));
+        }
+        Ok(reply.timestamp)
+    }
+
+    /// `None` if no output is primary
+    pub fn get_output_primary(
+        &self,
+        randr: &RandrExtension,
+        window: WINDOW,
+    ) -> Result<Option<OUTPUT>, XErrorKind> {
+        randr.require(1, 3)?;
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| get_output_primary_request(socket, major_opcode, window))?;
+        let reply = GetOutputPrimaryReply::parse(&self.read_reply()?)?;
+        Ok((reply.output != 0).then_some(reply.output))
+    }
+
+    pub fn list_output_properties(
+        &self,
+        randr: &RandrExtension,
+        output: OUTPUT,
+    ) -> Result<Vec<Atom>, XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| list_output_properties_request(socket, major_opcode, output))?;
+        Ok(ListOutputPropertiesReply::parse(&self.read_reply()?)?.atoms)
+    }
+
+    /// whether `property` is pending or immutable and the values it can take
+    pub fn query_output_property(
+        &self,
+        randr: &RandrExtension,
+        output: OUTPUT,
+        property: Atom,
+    ) -> Result<QueryOutputPropertyReply, XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| {
+            query_output_property_request(socket, major_opcode, output, property)
+        })?;
+        Ok(QueryOutputPropertyReply::parse(&self.read_reply()?)?)
+    }
+
+    /// Like `get_property` for an output, EDID is the interesting one.\
+    /// `offset` and `length` are in 4 byte units, `pending` asks for the value that
+    /// takes effect on the next SetCrtcConfig
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_output_property(
+        &self,
+        randr: &RandrExtension,
+        output: OUTPUT,
+        property: Atom,
+        ptype: Option<Atom>,
+        offset: CARD32,
+        length: CARD32,
+        pending: bool,
+    ) -> Result<PropertyReply, XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| {
+            get_output_property_request(
+                socket,
+                major_opcode,
+                output,
+                property,
+                ptype.unwrap_or(Atom(0)),
+                offset,
+                length,
+                0,
+                pending as BOOL,
+            )
+        })?;
+        let reply = GetOutputPropertyReply::parse(&self.read_reply()?)?;
+        Ok(PropertyReply {
+            ptype: reply.type_,
+            format: reply.format,
+            bytes_after: reply.bytes_after,
+            value: reply.data,
+        })
+    }
+
+    /// like `change_property` for an output, `data` is little endian in `format` units
+    #[allow(clippy::too_many_arguments)]
+    pub fn change_output_property(
+        &self,
+        randr: &RandrExtension,
+        output: OUTPUT,
+        property: Atom,
+        ptype: Atom,
+        format: PropertyFormat,
+        mode: PropertyMode,
+        data: &[u8],
+    ) -> Result<(), XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        let units = xio::list_len::<CARD32>(data.len() / (format as usize / 8))?;
+        self.socket_cb(|socket| {
+            change_output_property(
+                socket,
+                major_opcode,
+                output,
+                property,
+                ptype,
+                format as CARD8,
+                mode as CARD8,
+                units,
+                data,
+            )
+        })?;
+        Ok(())
+    }
+
+    pub fn delete_output_property(
+        &self,
+        randr: &RandrExtension,
+        output: OUTPUT,
+        property: Atom,
+    ) -> Result<(), XErrorKind> {
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| delete_output_property(socket, major_opcode, output, property))?;
+        Ok(())
+    }
+
+    /// the monitors of `window`'s screen, `active` leaves out the ones with nothing
+    /// connected
+    pub fn get_monitors(
+        &self,
+        randr: &RandrExtension,
+        window: WINDOW,
+        active: bool,
+    ) -> Result<GetMonitorsReply, XErrorKind> {
+        randr.require(1, 5)?;
+        let major_opcode = randr.info.major_opcode;
+        self.socket_cb(|socket| {
+            get_monitors_request(socket, major_opcode, window, active as BOOL)
+        })?;
+        Ok(GetMonitorsReply::parse(&self.read_reply()?)?)
+    }
+
+    /// Every active monitor of the first screen with its name, geometry and whether it's
+    /// the primary one.\
+    /// Needs RandR 1.5, `XErrorKind::ExtensionTooOld` otherwise
+    pub fn monitors(&self, randr: &RandrExtension) -> Result<Vec<Monitor>, XErrorKind> {
+        let root = self.info.list_of_screen[0].root;
+        let reply = self.get_monitors(randr, root, true)?;
+        reply
+            .monitors
+            .into_iter()
+            .map(|info| {
+                Ok(Monitor {
+                    name: self.get_atom_name(info.name)?,
+                    primary: info.primary != 0,
+                    automatic: info.automatic != 0,
+                    x: info.x,
+                    y: info.y,
+                    width: info.width,
+                    height: info.height,
+                    width_in_millimeters: info.width_in_millimeters,
+                    height_in_millimeters: info.height_in_millimeters,
+                    outputs: info.outputs,
+                })
+            })
+            .collect()
+    }
+}
+
+#[test]
+pub fn randr_monitors_and_events() {
+    use mock::{MockResponse, MockServer};
+
+    let (server, ctx) = MockServer::connect();
+    let [dp, hdmi] = ["DP-1", "HDMI-1"].map(|name| ctx.intern_atom(name, false).unwrap());
+    server.on_request(xproto::opcodes::QUERY_EXTENSION, |request| {
+        (request.bytes(8, 5) == b"RANDR").then(|| MockResponse::Reply {
+            data: 0,
+            body: vec![1, 140, 89, 147],
+        })
+    });
+    server.on_request(140, move |request| {
+        let mut body = vec![];
+        match request.data {
+            opcodes::QUERY_VERSION => {
+                body.extend_from_slice(&1u32.to_le_bytes());
+                body.extend_from_slice(&6u32.to_le_bytes());
+            }
+            opcodes::GET_MONITORS => {
+                // the 3840x1080 root from dump.txt, two screens side by side
+                let monitors = [(dp, true, 0, 0x61), (hdmi, false, 1920, 0x62)];
+                body.extend_from_slice(&[0; 4]);
+                body.extend_from_slice(&2u32.to_le_bytes());
+                body.extend_from_slice(&2u32.to_le_bytes());
+                body.extend_from_slice(&[0; 12]);
+                for (name, primary, x, output) in monitors {
+                    let info = MonitorInfo {
+                        name,
+                        primary: primary as BOOL,
+                        automatic: 1,
+                        x,
+                        width: 1920,
+                        height: 1080,
+                        width_in_millimeters: 530,
+                        height_in_millimeters: 300,
+                        outputs: vec![output],
+                        ..Default::default()
+                    };
+                    info.serialize(&mut body).unwrap();
+                }
+            }
+            opcodes::SET_CRTC_CONFIG => {
+                let status = match request.card32(4) {
+                    0x63 => set_config::SUCCESS,
+                    _ => set_config::FAILED,
+                };
+                body.extend_from_slice(&0x1234u32.to_le_bytes());
+                return Some(MockResponse::Reply {
+                    data: status as CARD8,
+                    body,
+                });
+            }
+            _ => return None,
+        }
+        Some(MockResponse::Reply { data: 0, body })
+    });
+
+    let randr = ctx.query_randr().unwrap();
+    assert!(randr.supports_monitors());
+    let monitors = ctx.monitors(&randr).unwrap();
+    assert_eq!(monitors.len(), 2);
+    assert_eq!(
+        (monitors[0].name.as_str(), monitors[0].primary),
+        ("DP-1", true)
+    );
+    assert_eq!(
+        (monitors[1].name.as_str(), monitors[1].primary),
+        ("HDMI-1", false)
+    );
+    assert_eq!(monitors[1].outputs, [0x62]);
+    let on = monitors.iter().find(|m| m.contains((2000, 500))).unwrap();
+    assert_eq!(on.name, "HDMI-1");
+    assert_eq!(on.centered((800, 600)), (2480, 240));
+    ctx.monitors(&randr).unwrap();
+    let versions = server.take_requests().into_iter();
+    let versions = versions.filter(|r| r.opcode == 140 && r.data == opcodes::QUERY_VERSION);
+    assert_eq!(versions.count(), 1);
+
+    let mask = (notify_mask::CRTC_CHANGE | notify_mask::OUTPUT_CHANGE) as CARD16;
+    ctx.randr_select_input(&randr, Atom(0x100), mask).unwrap();
+    let request = server.take_requests().pop().unwrap();
+    assert_eq!(
+        (request.data, request.card32(4), request.card16(8)),
+        (opcodes::SELECT_INPUT, 0x100, 6)
+    );
+    let changed = ctx.set_crtc_config(
+        &randr,
+        0x63,
+        0,
+        (1920, 0),
+        0x70,
+        rotation::ROTATE_0 as CARD16,
+        &[0x62],
+    );
+    assert_eq!(changed.unwrap(), 0x1234);
+    let failed = ctx.set_crtc_config(
+        &randr,
+        0x64,
+        0,
+        (0, 0),
+        0,
+        rotation::ROTATE_0 as CARD16,
+        &[],
+    );
+    assert!(matches!(failed, Err(XErrorKind::ConfigFailed(3))));
+    let request = server.take_requests().into_iter().rev().nth(1).unwrap();
+    assert_eq!(request.data, opcodes::SET_CRTC_CONFIG);
+    assert_eq!(request.card16(16), 1920);
+    assert_eq!(request.card32(28), 0x62);
+
+    // an RRNotify for a CRTC moving, sent through SendEvent
+    let change = CrtcChange {
+        timestamp: 5,
+        window: Atom(0x100),
+        crtc: 0x63,
+        x: 1920,
+        width: 1920,
+        height: 1080,
+        ..Default::default()
+    };
+    let mut event = [0u8; 32];
+    event[0] = 0x80 | (89 + event_codes::NOTIFY);
+    event[1] = notify::CRTC_CHANGE as u8;
+    change.serialize(&mut &mut event[4..]).unwrap();
+    server.send_event(event);
+    let event = ctx.next_event().unwrap();
+    let events::Event::Extension { name, event } = ctx.decode_event(&event) else {
+        panic!("RandR events have a decoder once queried");
+    };
+    assert_eq!(name, EXTENSION_NAME);
+    assert_eq!(
+        event.downcast_ref::<RandrEvent>(),
+        Some(&RandrEvent::CrtcChange(change))
+    );
+}
@@ -0,0 +1,204 @@
+use super::*;
+
+use crate::proto::render as wire;
+pub use crate::proto::render::{opcodes, EXTENSION_NAME, PICTFORMAT, PICTURE};
+
+/// the version we ask for, AnimCursor needs 0.8
+const CLIENT_MAJOR_VERSION: CARD32 = 0;
+const CLIENT_MINOR_VERSION: CARD32 = 11;
+
+/// used to be its own type, everything is an `XErrorKind` now
+#[deprecated(note = "use XErrorKind")]
+pub type RenderErr = XErrorKind;
+
+/// PICTFORMINFO, how a picture format lays out its channels
+#[derive(Copy, Clone, Default, Debug)]
+pub struct PictFormInfo {
+    pub id: PICTFORMAT,
+    /// 0 for indexed, 1 for direct
+    pub format_type: CARD8,
+    pub depth: CARD8,
+    pub red_shift: CARD16,
+    pub red_mask: CARD16,
+    pub green_shift: CARD16,
+    pub green_mask: CARD16,
+    pub blue_shift: CARD16,
+    pub blue_mask: CARD16,
+    pub alpha_shift: CARD16,
+    pub alpha_mask: CARD16,
+    pub colormap: COLORMAP,
+}
+
+impl From<&wire::Pictforminfo> for PictFormInfo {
+    fn from(info: &wire::Pictforminfo) -> Self {
+        let direct = &info.direct;
+        Self {
+            id: info.id,
+            format_type: info.type_,
+            depth: info.depth,
+            red_shift: direct.red_shift,
+            red_mask: direct.red_mask,
+            green_shift: direct.green_shift,
+            green_mask: direct.green_mask,
+            blue_shift: direct.blue_shift,
+            blue_mask: direct.blue_mask,
+            alpha_shift: direct.alpha_shift,
+            alpha_mask: direct.alpha_mask,
+            colormap: info.colormap,
+        }
+    }
+}
+
+impl PictFormInfo {
+    /// the standard 32 bit premultiplied ARGB layout cursors and most images use
+    pub fn is_argb32(&self) -> bool {
+        let info = *self;
+        info.format_type == 1
+            && info.depth == 32
+            && (info.alpha_shift, info.alpha_mask) == (24, 0xff)
+            && (info.red_shift, info.red_mask) == (16, 0xff)
+            && (info.green_shift, info.green_mask) == (8, 0xff)
+            && (info.blue_shift, info.blue_mask) == (0, 0xff)
+    }
+}
+
+/// Everything needed to talk RENDER, returned by `XContext::query_render`
+#[derive(Clone, Debug)]
+pub struct RenderExtension {
+    pub info: ExtensionInfo,
+    pub major_version: CARD32,
+    pub minor_version: CARD32,
+    pub formats: Vec<PictFormInfo>,
+}
+
+impl RenderExtension {
+    /// true if the server is at least `major.minor`
+    pub fn has_version(&self, major: CARD32, minor: CARD32) -> bool {
+        (self.major_version, self.minor_version) >= (major, minor)
+    }
+
+    pub fn argb32_format(&self) -> Option<PICTFORMAT> {
+        self.formats.iter().find(|f| f.is_argb32()).map(|f| f.id)
+    }
+}
+
+pub fn query_version<S: io::Write>(socket: &mut S, major_opcode: CARD8) -> io::Result<()> {
+    wire::query_version_request(
+        socket,
+        major_opcode,
+        CLIENT_MAJOR_VERSION,
+        CLIENT_MINOR_VERSION,
+    )
+}
+
+pub fn query_pict_formats_request<S: io::Write>(
+    socket: &mut S,
+    major_opcode: CARD8,
+) -> io::Result<()> {
+    wire::query_pict_formats_request(socket, major_opcode)
+}
+
+/// creates a picture with every attribute left at its default
+pub fn create_picture<S: io::Write>(
+    socket: &mut S,
+    render: &RenderExtension,
+    pid: PICTURE,
+    drawable: DRAWABLE,
+    format: PICTFORMAT,
+) -> io::Result<()> {
+    wire::create_picture(socket, render.info.major_opcode, pid, drawable, format, &[])
+}
+
+pub fn free_picture<S: io::Write>(
+    socket: &mut S,
+    render: &RenderExtension,
+    picture: PICTURE,
+) -> io::Result<()> {
+    wire::free_picture(socket, render.info.major_opcode, picture)
+}
+
+/// a cursor from an ARGB picture, needs RENDER 0.5
+pub fn create_cursor<S: io::Write>(
+    socket: &mut S,
+    render: &RenderExtension,
+    cid: CURSOR,
+    source: PICTURE,
+    hotspot: (CARD16, CARD16),
+) -> io::Result<()> {
+    wire::create_cursor(
+        socket,
+        render.info.major_opcode,
+        cid,
+        source,
+        hotspot.0,
+        hotspot.1,
+    )
+}
+
+/// cycles through `frames` of (cursor, delay in milliseconds), needs RENDER 0.8
+pub fn create_anim_cursor<S: io::Write>(
+    socket: &mut S,
+    render: &RenderExtension,
+    cid: CURSOR,
+    frames: &[(CURSOR, CARD32)],
+) -> io::Result<()> {
+    let cursors: Vec<_> = frames
+        .iter()
+        .map(|&(cursor, delay)| wire::Animcursorelt { cursor, delay })
+        .collect();
+    wire::create_anim_cursor(socket, render.info.major_opcode, cid, &cursors)
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    /// Checks RENDER is there, negotiates the version and fetches the picture formats
+    pub fn query_render(&self) -> Result<RenderExtension, XErrorKind> {
+        let info = self
+            .query_extension(EXTENSION_NAME)?
+            .ok_or(XErrorKind::ExtensionMissing(EXTENSION_NAME))?;
+        self.socket_cb(|socket| query_version(socket, info.major_opcode))?;
+        let reply = self.read_reply()?;
+        let version = wire::QueryVersionReply::parse(&reply)?;
+
+        self.socket_cb(|socket| query_pict_formats_request(socket, info.major_opcode))?;
+        // screens, depths and subpixel orders come along, nothing needs them yet
+        let reply = wire::QueryPictFormatsReply::parse(&self.read_reply()?)?;
+        let formats = reply.formats.iter().map(PictFormInfo::from).collect();
+        Ok(RenderExtension {
+            info,
+            major_version: version.major_version,
+            minor_version: version.minor_version,
+            formats,
+        })
+    }
+}
+
+#[test]
+pub fn pict_formats_reply() {
+    let mut reply = vec![1, 0, 1, 0];
+    let mut put = |values: &[u32]| {
+        for v in values {
+            reply.extend_from_slice(&v.to_le_bytes());
+        }
+    };
+    // length, formats, screens, depths, visuals, subpixels and the pad
+    put(&[17, 2, 1, 1, 1, 1, 0]);
+    // an 8 bit alpha format, then ARGB32: id, type and depth, the shifts and masks, colormap
+    put(&[0x41, 0x0801, 0, 0, 0, 0xff << 16, 0]);
+    put(&[
+        0x42,
+        0x2001,
+        0xff << 16 | 16,
+        0xff << 16 | 8,
+        0xff << 16,
+        0xff << 16 | 24,
+        0,
+    ]);
+    // a screen with one depth of one visual, then the subpixel order
+    put(&[1, 0x42, 0x0001_0020, 0, 0x21, 0x42, 0]);
+
+    let reply = wire::QueryPictFormatsReply::parse(&reply).unwrap();
+    let formats: Vec<_> = reply.formats.iter().map(PictFormInfo::from).collect();
+    assert!(!formats[0].is_argb32());
+    assert!(formats[1].is_argb32());
+    assert_eq!(reply.screens[0].depths[0].visuals[0].format, 0x42);
+}
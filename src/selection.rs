@@ -0,0 +1,775 @@
+use super::*;
+pub use crate::proto::xproto::get_selection_owner_request;
+use events::{
+    PropertyNotifyEvent, SelectionClearEvent, SelectionNotifyEvent, SelectionRequestEvent,
+};
+use std::{
+    os::unix::io::AsRawFd,
+    time::{Duration, Instant},
+};
+
+/// used to be its own type, everything is an `XErrorKind` now
+#[deprecated(note = "use XErrorKind")]
+pub type SelectionErr = XErrorKind;
+
+/// `owner` of `None` gives the selection up
+pub fn set_selection_owner<S: io::Write>(
+    socket: &mut S,
+    owner: Option<WINDOW>,
+    selection: Atom,
+    timestamp: Timestamp,
+) -> io::Result<()> {
+    proto::xproto::set_selection_owner(
+        socket,
+        owner.unwrap_or(Atom(0)),
+        selection,
+        timestamp.data(),
+    )
+}
+
+/// asks the owner of `selection` to store it as `target` in `property` of `requestor`
+pub fn convert_selection<S: io::Write>(
+    socket: &mut S,
+    requestor: WINDOW,
+    selection: Atom,
+    target: Atom,
+    property: Option<Atom>,
+    timestamp: Timestamp,
+) -> io::Result<()> {
+    proto::xproto::convert_selection(
+        socket,
+        requestor,
+        selection,
+        target,
+        property.unwrap_or(Atom(0)),
+        timestamp.data(),
+    )
+}
+
+pub fn send_event<S: io::Write>(
+    socket: &mut S,
+    propagate: bool,
+    destination: WINDOW,
+    event_mask: CARD32,
+    event: &RawEvent,
+) -> io::Result<()> {
+    proto::xproto::send_event(socket, propagate as BOOL, destination, event_mask, event)
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    /// `None` when nobody owns `selection`
+    pub fn get_selection_owner(&self, selection: Atom) -> Result<Option<WINDOW>, XErrorKind> {
+        self.socket_cb(|socket| get_selection_owner_request(socket, selection))?;
+        let reply = self.read_reply()?;
+        let owner = read_primitive::<CARD32, _>(&mut &reply[8..12])?;
+        Ok((owner != 0).then_some(Atom(owner)))
+    }
+}
+
+/// largest piece of data sent in one go, anything bigger goes out with INCR.
+/// Less when the server's request limit is lower, see `Clipboard::incr_chunk`
+const INCR_CHUNK: usize = 0x40000;
+
+/// the bytes of a ChangeProperty before its data
+const CHANGE_PROPERTY_HEADER: usize = 24;
+
+/// how long `get_atom` waits on the owner unless `set_timeout` says otherwise
+const OWNER_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Copy, Clone)]
+struct SelectionAtoms {
+    targets: Atom,
+    timestamp: Atom,
+    incr: Atom,
+    utf8_string: Atom,
+    compound_text: Atom,
+    text: Atom,
+    text_plain_utf8: Atom,
+    /// the property on our window that conversions land in
+    data: Atom,
+}
+
+/// one target we serve while we own the selection
+struct Offer {
+    target: Atom,
+    ptype: Atom,
+    data: Rc<[u8]>,
+}
+
+/// an INCR transfer to another client that's still going
+struct Transfer {
+    requestor: WINDOW,
+    property: Atom,
+    ptype: Atom,
+    data: Rc<[u8]>,
+    sent: usize,
+}
+
+/// Owns or reads one selection (PRIMARY, CLIPBOARD ...) through a hidden window.\
+/// Serving data needs the events: pass every event to `handle_event` while something is set
+pub struct Clipboard<S: io::Write> {
+    ctx: XContext<S>,
+    selection: Atom,
+    window: WINDOW,
+    atoms: SelectionAtoms,
+    offers: Vec<Offer>,
+    /// when we took the selection, `None` when we don't own it
+    owned_since: Option<Timestamp>,
+    transfers: Vec<Transfer>,
+    /// how long to wait for the owner's answer and for each INCR piece
+    timeout: Duration,
+}
+
+impl<S: io::Read + io::Write> Clipboard<S> {
+    pub fn new(ctx: &mut XContext<S>, selection: Atom) -> Result<Self, XErrorKind> {
+        let window = Atom(ctx.gen_id());
+        let root = ctx.info.list_of_screen[0].root;
+        ctx.socket_cb(|socket| create_hidden_window(socket, window, root))?;
+        let atoms = SelectionAtoms {
+            targets: ctx.intern_atom("TARGETS", false)?,
+            timestamp: ctx.intern_atom("TIMESTAMP", false)?,
+            incr: ctx.intern_atom("INCR", false)?,
+            utf8_string: ctx.intern_atom("UTF8_STRING", false)?,
+            compound_text: ctx.intern_atom("COMPOUND_TEXT", false)?,
+            text: ctx.intern_atom("TEXT", false)?,
+            text_plain_utf8: ctx.intern_atom("text/plain;charset=utf-8", false)?,
+            data: ctx.intern_atom("XPROTO_SELECTION", false)?,
+        };
+        Ok(Self {
+            ctx: ctx.clone(),
+            selection,
+            window,
+            atoms,
+            offers: vec![],
+            owned_since: None,
+            transfers: vec![],
+            timeout: OWNER_TIMEOUT,
+        })
+    }
+
+    /// the CLIPBOARD selection, what ctrl+c/ctrl+v use
+    pub fn new_clipboard(ctx: &mut XContext<S>) -> Result<Self, XErrorKind> {
+        let selection = ctx.intern_atom("CLIPBOARD", false)?;
+        Self::new(ctx, selection)
+    }
+
+    /// the PRIMARY selection, what middle click pastes
+    pub fn new_primary(ctx: &mut XContext<S>) -> Result<Self, XErrorKind> {
+        Self::new(ctx, predefined_atoms::PRIMARY)
+    }
+
+    pub fn selection(&self) -> Atom {
+        self.selection
+    }
+
+    pub fn window(&self) -> WINDOW {
+        self.window
+    }
+
+    /// false once another client takes the selection
+    pub fn owns(&self) -> bool {
+        self.owned_since.is_some()
+    }
+
+    /// how long `get_atom` waits for the owner, 5 seconds unless set
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// the most data one ChangeProperty can carry on this connection, capped at `INCR_CHUNK`
+    fn incr_chunk(&self) -> usize {
+        let max_request = self.ctx.max_request_length() as usize * 4;
+        max_request
+            .saturating_sub(CHANGE_PROPERTY_HEADER)
+            .min(INCR_CHUNK)
+    }
+
+    /// Takes the selection and serves `text` as UTF8_STRING, COMPOUND_TEXT, STRING, TEXT
+    /// and text/plain. STRING gets '?' for whatever Latin-1 doesn't have
+    pub fn set_text(&mut self, text: &str) -> Result<(), XErrorKind> {
+        let utf8: Rc<[u8]> = text.as_bytes().into();
+        let compound: Rc<[u8]> = text::encode_compound_text(text).into();
+        let latin1: Rc<[u8]> = text::encode_latin1_lossy(text).into();
+        let atoms = self.atoms;
+        self.take_selection(vec![
+            Offer {
+                target: atoms.utf8_string,
+                ptype: atoms.utf8_string,
+                data: utf8.clone(),
+            },
+            Offer {
+                target: atoms.compound_text,
+                ptype: atoms.compound_text,
+                data: compound,
+            },
+            Offer {
+                target: predefined_atoms::STRING,
+                ptype: predefined_atoms::STRING,
+                data: latin1,
+            },
+            Offer {
+                target: atoms.text,
+                ptype: atoms.utf8_string,
+                data: utf8.clone(),
+            },
+            Offer {
+                target: atoms.text_plain_utf8,
+                ptype: atoms.text_plain_utf8,
+                data: utf8,
+            },
+        ])
+    }
+
+    /// Takes the selection and serves each `(target, data)`, targets are
+    /// MIME types like `image/png` or atom names like `UTF8_STRING`
+    pub fn set(&mut self, items: &[(&str, &[u8])]) -> Result<(), XErrorKind> {
+        let offers = items
+            .iter()
+            .map(|&(name, data)| {
+                let target = self.ctx.intern_atom(name, false)?;
+                Ok(Offer {
+                    target,
+                    ptype: target,
+                    data: data.into(),
+                })
+            })
+            .collect::<Result<Vec<_>, XErrorKind>>()?;
+        self.take_selection(offers)
+    }
+
+    /// gives the selection up if we have it
+    pub fn clear(&mut self) -> Result<(), XErrorKind> {
+        if self.owned_since.take().is_some() {
+            self.offers.clear();
+            self.ctx.socket_cb(|socket| {
+                set_selection_owner(socket, None, self.selection, Timestamp::current_time())
+            })?;
+        }
+        Ok(())
+    }
+
+    fn take_selection(&mut self, offers: Vec<Offer>) -> Result<(), XErrorKind> {
+        // ICCCM says not to use CurrentTime here, ask the server what time it is instead
+        let time = self.server_time()?;
+        let (window, selection) = (self.window, self.selection);
+        self.ctx
+            .socket_cb(|socket| set_selection_owner(socket, Some(window), selection, time))?;
+        if self.ctx.get_selection_owner(selection)? != Some(window) {
+            self.owned_since = None;
+            self.offers.clear();
+            return Err(XErrorKind::NotSelectionOwner);
+        }
+        self.offers = offers;
+        self.owned_since = Some(time);
+        Ok(())
+    }
+
+    /// gets a timestamp by touching a property on our window and reading the PropertyNotify
+    fn server_time(&self) -> Result<Timestamp, XErrorKind> {
+        let (window, property) = (self.window, self.atoms.data);
+        self.ctx.socket_cb(|socket| {
+            change_property(
+                socket,
+                PropertyMode::Append,
+                property,
+                predefined_atoms::STRING,
+                window.0,
+                PropertyFormat::Bytes,
+                [0u8; 0],
+            )
+        })?;
+        let event = self.ctx.wait_for_event(|e| {
+            PropertyNotifyEvent::from_event(e)
+                .is_some_and(|n| n.window == window && n.atom == property && !n.deleted)
+        })?;
+        self.ctx
+            .socket_cb(|socket| delete_property(socket, window, property))?;
+        Ok(PropertyNotifyEvent::from_event(&event).unwrap().time)
+    }
+
+    /// Reads and deletes `property` on our window.\
+    /// The PropertyNotify events queued by then all come from before the delete, the owner
+    /// writing the INCR property, the last piece or the delete itself, so they're dropped.
+    /// Waiting for the next piece would otherwise see them
+    fn take_property(&self, property: Atom) -> Result<PropertyReply, XErrorKind> {
+        let window = self.window;
+        let reply = self.ctx.get_property_all(true, window, property, None)?;
+        self.ctx.discard_queued(|e| {
+            PropertyNotifyEvent::from_event(e)
+                .is_some_and(|n| n.window == window && n.atom == property)
+        });
+        Ok(reply)
+    }
+
+    fn convert_locally(&self, target: Atom) -> Option<Vec<u8>> {
+        if target == self.atoms.targets {
+            return Some(self.target_list());
+        }
+        self.offers
+            .iter()
+            .find(|offer| offer.target == target)
+            .map(|offer| offer.data.to_vec())
+    }
+
+    /// the reply to TARGETS as a list of 32 bit atoms
+    fn target_list(&self) -> Vec<u8> {
+        [self.atoms.targets, self.atoms.timestamp]
+            .into_iter()
+            .chain(self.offers.iter().map(|offer| offer.target))
+            .flat_map(|atom| atom.0.to_le_bytes())
+            .collect()
+    }
+
+    /// Answers SelectionRequest and SelectionClear for our selection and moves INCR transfers along.\
+    /// Returns true if the event was meant for this clipboard
+    pub fn handle_event(&mut self, event: &RawEvent) -> Result<bool, XErrorKind> {
+        if let Some(request) = SelectionRequestEvent::from_event(event) {
+            if request.selection != self.selection || request.owner != self.window {
+                return Ok(false);
+            }
+            self.answer_request(&request)?;
+            return Ok(true);
+        }
+        if let Some(clear) = SelectionClearEvent::from_event(event) {
+            if clear.selection != self.selection || clear.owner != self.window {
+                return Ok(false);
+            }
+            self.owned_since = None;
+            self.offers.clear();
+            return Ok(true);
+        }
+        if let Some(notify) = PropertyNotifyEvent::from_event(event) {
+            if notify.window == self.window {
+                // left over from our own transfers
+                return Ok(true);
+            }
+            if !notify.deleted {
+                return Ok(false);
+            }
+            let Some(index) = self
+                .transfers
+                .iter()
+                .position(|t| t.requestor == notify.window && t.property == notify.atom)
+            else {
+                return Ok(false);
+            };
+            self.continue_transfer(index)?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn answer_request(&mut self, request: &SelectionRequestEvent) -> Result<(), XErrorKind> {
+        // obsolete clients leave property out and want the reply in target
+        let property = if request.property == Atom(0) {
+            request.target
+        } else {
+            request.property
+        };
+        let owned = self
+            .owned_since
+            .filter(|&since| request.time.data() == 0 || request.time >= since);
+        let requestor = request.requestor;
+
+        let reply: Option<(Atom, PropertyFormat, Rc<[u8]>)> = match owned {
+            None => None,
+            Some(_) if request.target == self.atoms.targets => Some((
+                predefined_atoms::ATOM,
+                PropertyFormat::Word,
+                self.target_list().into(),
+            )),
+            Some(since) if request.target == self.atoms.timestamp => Some((
+                predefined_atoms::INTEGER,
+                PropertyFormat::Word,
+                since.data().to_le_bytes().as_slice().into(),
+            )),
+            Some(_) => self
+                .offers
+                .iter()
+                .find(|offer| offer.target == request.target)
+                .map(|offer| (offer.ptype, PropertyFormat::Bytes, offer.data.clone())),
+        };
+
+        let (incr, chunk) = (self.atoms.incr, self.incr_chunk());
+        let mut transfer = None;
+        self.ctx.socket_cb(|socket| {
+            match &reply {
+                Some((ptype, PropertyFormat::Bytes, data)) if data.len() > chunk => {
+                    // we need to hear about the requestor deleting each piece
+                    select_input(socket, requestor, set_of_events_common::PROPERTY_CHANGE)?;
+                    let size = data.len() as CARD32;
+                    change_property(
+                        socket,
+                        PropertyMode::Replace,
+                        property,
+                        incr,
+                        requestor.0,
+                        PropertyFormat::Word,
+                        size.to_le_bytes(),
+                    )?;
+                    transfer = Some(Transfer {
+                        requestor,
+                        property,
+                        ptype: *ptype,
+                        data: data.clone(),
+                        sent: 0,
+                    });
+                }
+                Some((ptype, format, data)) => change_property(
+                    socket,
+                    PropertyMode::Replace,
+                    property,
+                    *ptype,
+                    requestor.0,
+                    *format,
+                    &data[..],
+                )?,
+                None => (),
+            }
+            let notify = SelectionNotifyEvent {
+                time: request.time,
+                requestor,
+                selection: request.selection,
+                target: request.target,
+                property: if reply.is_some() { property } else { Atom(0) },
+            };
+            send_event(socket, false, requestor, 0, &notify.to_raw())
+        })?;
+        self.transfers.extend(transfer);
+        Ok(())
+    }
+
+    /// writes the next piece of an INCR transfer after the requestor deleted the last one
+    fn continue_transfer(&mut self, index: usize) -> Result<(), XErrorKind> {
+        let size = self.incr_chunk();
+        let transfer = &mut self.transfers[index];
+        let start = transfer.sent;
+        let end = (start + size).min(transfer.data.len());
+        transfer.sent = end;
+        let (requestor, property, ptype) = (transfer.requestor, transfer.property, transfer.ptype);
+        let chunk = transfer.data.clone();
+        self.ctx.socket_cb(|socket| {
+            change_property(
+                socket,
+                PropertyMode::Replace,
+                property,
+                ptype,
+                requestor.0,
+                PropertyFormat::Bytes,
+                &chunk[start..end],
+            )
+        })?;
+        // the zero length piece that ends the transfer just went out
+        if start == end {
+            self.transfers.remove(index);
+            if !self.transfers.iter().any(|t| t.requestor == requestor) {
+                self.ctx
+                    .socket_cb(|socket| select_input(socket, requestor, 0))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: io::Read + io::Write + AsRawFd> Clipboard<S> {
+    /// Fetches the selection as UTF8_STRING, falling back on COMPOUND_TEXT and then Latin-1
+    /// STRING.\
+    /// `None` if nobody owns the selection or it can't be turned into text
+    pub fn get_text(&mut self) -> Result<Option<String>, XErrorKind> {
+        if let Some(data) = self.get_atom(self.atoms.utf8_string)? {
+            return Ok(Some(String::from_utf8_lossy(&data).into_owned()));
+        }
+        if let Some(data) = self.get_atom(self.atoms.compound_text)? {
+            return Ok(Some(text::decode_compound_text(&data)));
+        }
+        let latin1 = self.get_atom(predefined_atoms::STRING)?;
+        Ok(latin1.map(|data| text::decode_latin1(&data)))
+    }
+
+    /// fetches the selection converted to the target called `name`
+    pub fn get(&mut self, name: &str) -> Result<Option<Vec<u8>>, XErrorKind> {
+        let target = self.ctx.intern_atom(name, false)?;
+        self.get_atom(target)
+    }
+
+    /// the names of every target the current owner offers
+    pub fn targets(&mut self) -> Result<Vec<String>, XErrorKind> {
+        let Some(data) = self.get_atom(self.atoms.targets)? else {
+            return Ok(vec![]);
+        };
+        data.chunks_exact(4)
+            .map(|c| {
+                let atom = Atom(CARD32::from_le_bytes([c[0], c[1], c[2], c[3]]));
+                self.ctx.get_atom_name(atom)
+            })
+            .collect()
+    }
+
+    /// Fetches the selection converted to `target`, following INCR transfers to the end.\
+    /// Fails with `SelectionTimeout` if the owner doesn't answer, or stops sending pieces,
+    /// within the timeout
+    pub fn get_atom(&mut self, target: Atom) -> Result<Option<Vec<u8>>, XErrorKind> {
+        if self.owns() {
+            // the request would come back to us and nobody would answer it
+            return Ok(self.convert_locally(target));
+        }
+        let (window, selection, property) = (self.window, self.selection, self.atoms.data);
+        self.ctx.socket_cb(|socket| {
+            convert_selection(
+                socket,
+                window,
+                selection,
+                target,
+                Some(property),
+                Timestamp::current_time(),
+            )
+        })?;
+        let event = self.wait_for_owner(|e| {
+            SelectionNotifyEvent::from_event(e)
+                .is_some_and(|n| n.requestor == window && n.selection == selection)
+        })?;
+        let notify = SelectionNotifyEvent::from_event(&event).unwrap();
+        if notify.property == Atom(0) {
+            return Ok(None);
+        }
+        // deleting the INCR property is what tells the owner to start sending
+        let reply = self.take_property(notify.property)?;
+        if reply.ptype != self.atoms.incr {
+            return Ok((reply.ptype != Atom(0)).then_some(reply.value));
+        }
+        let mut data = vec![];
+        loop {
+            self.wait_for_owner(|e| {
+                PropertyNotifyEvent::from_event(e)
+                    .is_some_and(|n| n.window == window && n.atom == notify.property && !n.deleted)
+            })?;
+            let part = self.take_property(notify.property)?;
+            // a zero length piece marks the end
+            if part.value.is_empty() {
+                return Ok(Some(data));
+            }
+            data.extend_from_slice(&part.value);
+        }
+    }
+
+    /// the first event `pred` accepts, other events stay queued
+    fn wait_for_owner<F>(&self, mut pred: F) -> Result<RawEvent, XErrorKind>
+    where
+        F: FnMut(&RawEvent) -> bool,
+    {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            if let Some(event) = self.ctx.take_queued(&mut pred)? {
+                return Ok(event);
+            }
+            if !self.ctx.wait_until_queued(&mut pred, deadline)? {
+                return Err(XErrorKind::SelectionTimeout);
+            }
+        }
+    }
+}
+
+impl<S: io::Write> Drop for Clipboard<S> {
+    fn drop(&mut self) {
+        // destroying the window also gives up the selection
+        let window = self.window;
+        self.ctx
+            .send_or_defer(|request| destroy_window(request, window));
+    }
+}
+
+/// an unmapped 1x1 InputOnly window that only listens for property changes
+fn create_hidden_window<S: io::Write>(
+    socket: &mut S,
+    window: WINDOW,
+    parent: WINDOW,
+) -> io::Result<()> {
+    const INPUT_ONLY: CARD16 = 2;
+    let values = [(
+        value_masks::EVENT_MASK,
+        set_of_events_common::PROPERTY_CHANGE,
+    )];
+    // visual 0 is CopyFromParent
+    proto::xproto::create_window(
+        socket, 0, window, parent, 0, 0, 1, 1, 0, INPUT_ONLY, 0, &values,
+    )
+}
+
+#[test]
+pub fn selection_notify_round_trip() {
+    let notify = SelectionNotifyEvent {
+        time: 1234.into(),
+        requestor: Atom(0x400001),
+        selection: predefined_atoms::PRIMARY,
+        target: predefined_atoms::STRING,
+        property: Atom(300),
+    };
+    let raw = notify.to_raw();
+    assert_eq!(raw[0], event_codes::SELECTION_NOTIFY);
+    let back = SelectionNotifyEvent::from_event(&raw).unwrap();
+    assert_eq!(back.time, notify.time);
+    assert_eq!(back.requestor, notify.requestor);
+    assert_eq!(back.target, notify.target);
+    assert_eq!(back.property, notify.property);
+    let mut sent = raw;
+    sent[0] |= 0x80;
+    assert!(SelectionNotifyEvent::from_event(&sent).is_some());
+    assert!(SelectionRequestEvent::from_event(&raw).is_none());
+}
+
+#[test]
+pub fn incr_transfer() {
+    let (server, mut ctx) = mock::MockServer::connect();
+    let mut clipboard = Clipboard::new_clipboard(&mut ctx).unwrap();
+    let (window, property, incr) = (clipboard.window, clipboard.atoms.data, clipboard.atoms.incr);
+    let notify = move |deleted| {
+        PropertyNotifyEvent {
+            window,
+            atom: property,
+            time: 0.into(),
+            deleted,
+        }
+        .to_raw()
+    };
+    // the owner writes INCR before answering, its NewValue must not be taken for the first piece
+    server.send_event(notify(false));
+    let answer = SelectionNotifyEvent {
+        time: 0.into(),
+        requestor: window,
+        selection: clipboard.selection,
+        target: predefined_atoms::STRING,
+        property,
+    };
+    server.send_event(answer.to_raw());
+    // each delete has the owner write the next piece, the empty one ends the transfer
+    let mut pieces = vec![b"".to_vec(), b"lo".to_vec(), b"hel".to_vec()];
+    let mut current = (incr, 32, 5u32.to_le_bytes().to_vec());
+    server.on_request(opcodes::GET_PROPERTY, move |_| {
+        let (ptype, format, value) = std::mem::replace(&mut current, (Atom(0), 0, vec![]));
+        let mut body = ptype.0.to_le_bytes().to_vec();
+        body.extend_from_slice(&0u32.to_le_bytes()); //bytes-after
+        body.extend_from_slice(
+            &(value.len() as CARD32 / (format as CARD32 / 8).max(1)).to_le_bytes(),
+        );
+        body.resize(24, 0);
+        body.extend_from_slice(&value);
+        let reply = mock::MockResponse::Reply { data: format, body };
+        // the owner only writes the next piece once it sees the delete, after the reply
+        let Some(piece) = pieces.pop() else {
+            return Some(reply);
+        };
+        current = (predefined_atoms::STRING, 8, piece);
+        Some(reply.with_events(vec![notify(false)]))
+    });
+    assert_eq!(
+        clipboard
+            .get_atom(predefined_atoms::STRING)
+            .unwrap()
+            .as_deref(),
+        Some(&b"hello"[..])
+    );
+    // nothing about the transfer is left for the application
+    assert!(ctx.events.borrow().is_empty());
+}
+
+#[test]
+pub fn plain_transfer_leaves_no_events() {
+    let (server, mut ctx) = mock::MockServer::connect();
+    let mut clipboard = Clipboard::new_clipboard(&mut ctx).unwrap();
+    let (window, property) = (clipboard.window, clipboard.atoms.data);
+    let value = mock::MockProperty {
+        ptype: predefined_atoms::STRING,
+        format: 8,
+        value: b"hi".to_vec(),
+    };
+    server.set_property(window, property, value);
+    let new_value = PropertyNotifyEvent {
+        window,
+        atom: property,
+        time: 0.into(),
+        deleted: false,
+    };
+    server.send_event(new_value.to_raw());
+    let answer = SelectionNotifyEvent {
+        time: 0.into(),
+        requestor: window,
+        selection: clipboard.selection,
+        target: predefined_atoms::STRING,
+        property,
+    };
+    server.send_event(answer.to_raw());
+    assert_eq!(
+        clipboard
+            .get_atom(predefined_atoms::STRING)
+            .unwrap()
+            .as_deref(),
+        Some(&b"hi"[..])
+    );
+    assert!(ctx.events.borrow().is_empty());
+    assert!(server.property(window, property).is_none());
+}
+
+#[test]
+pub fn incr_pieces_fit_a_request() {
+    let (server, mut ctx) = mock::MockServer::connect();
+    let mut clipboard = Clipboard::new_clipboard(&mut ctx).unwrap();
+    let (window, data) = (clipboard.window, clipboard.atoms.data);
+    // what `server_time` waits for, then the owner check after SetSelectionOwner
+    let time = PropertyNotifyEvent {
+        window,
+        atom: data,
+        time: 0.into(),
+        deleted: false,
+    };
+    server.send_event(time.to_raw());
+    server.on_request(opcodes::GET_SELECTION_OWNER, move |_| {
+        let body = window.0.to_le_bytes().to_vec();
+        Some(mock::MockResponse::Reply { data: 0, body })
+    });
+    // short of INCR_CHUNK but longer than a request can be without BIG-REQUESTS
+    clipboard.set_text(&"x".repeat(INCR_CHUNK - 16)).unwrap();
+    server.take_requests();
+
+    let (requestor, property) = (Atom(0x0060_0001), Atom(0x300));
+    let mut request = [0u8; 32];
+    request[0] = event_codes::SELECTION_REQUEST;
+    for (offset, value) in [
+        (8, window.0),
+        (12, requestor.0),
+        (16, clipboard.selection.0),
+        (20, clipboard.atoms.utf8_string.0),
+        (24, property.0),
+    ] {
+        request[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+    assert!(clipboard.handle_event(&request).unwrap());
+    let requests = server.take_requests();
+    let change = requests
+        .iter()
+        .find(|r| r.opcode == opcodes::CHANGE_PROPERTY)
+        .unwrap();
+    assert_eq!(Atom(change.card32(12)), clipboard.atoms.incr);
+
+    let deleted = PropertyNotifyEvent {
+        window: requestor,
+        atom: property,
+        time: 0.into(),
+        deleted: true,
+    };
+    assert!(clipboard.handle_event(&deleted.to_raw()).unwrap());
+    let piece = server.take_requests().remove(0);
+    let limit = ctx.max_request_length() as usize * 4;
+    assert_eq!(piece.card32(20) as usize, limit - CHANGE_PROPERTY_HEADER);
+}
+
+#[test]
+pub fn owner_timeout() {
+    let (_server, mut ctx) = mock::MockServer::connect();
+    let mut clipboard = Clipboard::new_clipboard(&mut ctx).unwrap();
+    // nobody answers the ConvertSelection
+    clipboard.set_timeout(Duration::from_millis(10));
+    assert!(matches!(
+        clipboard.get_atom(predefined_atoms::STRING),
+        Err(XErrorKind::SelectionTimeout)
+    ));
+}
@@ -0,0 +1,382 @@
+//! A connection that can be shared between threads.\
+//! Requests are queued in an output buffer behind a mutex and written out on `flush` or when
+//! someone waits on a reply. Whichever thread is waiting on the socket reads packets for everyone
+//! and files them away, so one thread can block on events while others wait on their replies
+
+use super::*;
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Condvar, Mutex,
+    },
+};
+
+/// the output buffer gets written out once it grows past this many bytes
+const FLUSH_THRESHOLD: usize = 16 * 1024;
+
+/// Sequence number of a request with a reply, redeem it with `SharedXContext::wait_for_reply`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[must_use = "the reply stays stored until it's waited on"]
+pub struct ReplyCookie(CARD16);
+
+struct Output {
+    socket: UnixStream,
+    buffer: Vec<u8>,
+    /// sequence number of the last request put in the buffer
+    sequence: CARD16,
+}
+
+#[derive(Default)]
+struct Input {
+    /// true while some thread is blocked reading the socket
+    reading: bool,
+    /// requests that have a reply coming
+    awaiting: HashSet<CARD16>,
+    replies: HashMap<CARD16, Result<Vec<u8>, XErrorKind>>,
+    /// events along with errors no reply was there to carry
+    events: VecDeque<Result<RawEvent, XErrorKind>>,
+    /// set once reading fails, every waiter gets it from then on
+    broken: Option<(io::ErrorKind, String)>,
+}
+
+struct Inner {
+    output: Mutex<Output>,
+    /// only touched by the thread that set `Input::reading`
+    reader: Mutex<UnixStream>,
+    input: Mutex<Input>,
+    /// signalled whenever a packet is filed away or a reader steps down
+    input_changed: Condvar,
+    info: ConnectionAcceptedInfo,
+    id_count: AtomicU32,
+    max_request_length: AtomicU32,
+    /// locked after `input` when both are needed
+    extensions: Mutex<ExtensionRegistry>,
+}
+
+/// `XContext` for multithreaded programs, `Send + Sync` and cheap to clone
+#[derive(Clone)]
+pub struct SharedXContext {
+    inner: Arc<Inner>,
+}
+
+impl RequestConnection<'_> {
+    /// Like `connect` but returns a connection that can be handed to other threads.\
+    /// `stream` has to be blocking, without a read timeout
+    pub fn connect_shared(self, stream: UnixStream) -> ConnectionResult<SharedXContext> {
+        let mut request = vec![];
+        self.send_request_to_connect(&mut request)?;
+        (&stream).write_all(&request)?;
+        let info = self.read_response(&stream)?;
+
+        let ctx = SharedXContext::from_stream(info, stream)?;
+        if let Err(XErrorKind::SocketIO(e)) = ctx.enable_big_requests() {
+            return Err(e.into());
+        }
+        Ok(ctx)
+    }
+}
+
+impl SharedXContext {
+    fn from_stream(info: ConnectionAcceptedInfo, stream: UnixStream) -> io::Result<Self> {
+        let reader = stream.try_clone()?;
+        Ok(Self {
+            inner: Arc::new(Inner {
+                output: Mutex::new(Output {
+                    socket: stream,
+                    buffer: vec![],
+                    sequence: 0,
+                }),
+                reader: Mutex::new(reader),
+                input: Mutex::default(),
+                input_changed: Condvar::new(),
+                max_request_length: AtomicU32::new(info.max_req_len as CARD32),
+                info,
+                id_count: AtomicU32::new(0),
+                extensions: Mutex::default(),
+            }),
+        })
+    }
+
+    pub fn info(&self) -> &ConnectionAcceptedInfo {
+        &self.inner.info
+    }
+
+    /// the longest request the server accepts, in 4 byte units
+    pub fn max_request_length(&self) -> CARD32 {
+        self.inner.max_request_length.load(Ordering::Relaxed)
+    }
+
+    pub fn gen_id(&self) -> CARD32 {
+        self.inner.id_count.fetch_add(1, Ordering::Relaxed) | self.inner.info.resource_id_base
+    }
+
+    /// Queues a request that has no reply, `write` gets one of the free request functions.\
+    /// Errors it causes come out of `next_event`
+    pub fn send<F>(&self, write: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        self.queue(write, false).map(|_| ())
+    }
+
+    /// Queues a request that has a reply, several can be in flight before waiting on any
+    pub fn send_request<F>(&self, write: F) -> io::Result<ReplyCookie>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        self.queue(write, true).map(ReplyCookie)
+    }
+
+    fn queue<F>(&self, write: F, has_reply: bool) -> io::Result<CARD16>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        let mut output = self.inner.output.lock().unwrap();
+        let start = output.buffer.len();
+        if let Err(e) = write(&mut output.buffer) {
+            // don't leave half a request behind
+            output.buffer.truncate(start);
+            return Err(e);
+        }
+        output.sequence = output.sequence.wrapping_add(1);
+        let sequence = output.sequence;
+        if has_reply {
+            // filed before the request can possibly go out
+            self.inner.input.lock().unwrap().awaiting.insert(sequence);
+        }
+        if output.buffer.len() >= FLUSH_THRESHOLD {
+            Self::write_out(&mut output)?;
+        }
+        Ok(sequence)
+    }
+
+    fn write_out(output: &mut Output) -> io::Result<()> {
+        let Output { socket, buffer, .. } = output;
+        socket.write_all(buffer)?;
+        buffer.clear();
+        Ok(())
+    }
+
+    /// writes out every queued request
+    pub fn flush(&self) -> io::Result<()> {
+        Self::write_out(&mut self.inner.output.lock().unwrap())
+    }
+
+    /// Blocks until the reply for `cookie` or the error it caused arrives
+    pub fn wait_for_reply(&self, cookie: ReplyCookie) -> Result<Vec<u8>, XErrorKind> {
+        self.flush()?;
+        self.wait_until(|input| input.replies.remove(&cookie.0))?
+    }
+
+    /// `send_request` followed by `wait_for_reply`
+    pub fn request<F>(&self, write: F) -> Result<Vec<u8>, XErrorKind>
+    where
+        F: FnOnce(&mut Vec<u8>) -> io::Result<()>,
+    {
+        let cookie = self.send_request(write)?;
+        self.wait_for_reply(cookie)
+    }
+
+    /// Does a round trip to the server.\
+    /// Errors of requests sent before it are in the event queue once it returns
+    pub fn sync(&self) -> Result<(), XErrorKind> {
+        self.request(get_input_focus_request).map(|_| ())
+    }
+
+    /// Blocks until an event arrives, errors of requests without replies come out as `Err`
+    pub fn next_event(&self) -> Result<RawEvent, XErrorKind> {
+        self.flush()?;
+        self.wait_until(|input| input.events.pop_front())?
+    }
+
+    /// an already received event, never touches the socket
+    pub fn poll_queued_event(&self) -> Option<Result<RawEvent, XErrorKind>> {
+        self.inner.input.lock().unwrap().events.pop_front()
+    }
+
+    /// Runs `take` on the input state until it returns something, reading the socket
+    /// when no other thread is
+    fn wait_until<T, F>(&self, mut take: F) -> Result<T, XErrorKind>
+    where
+        F: FnMut(&mut Input) -> Option<T>,
+    {
+        let inner = &self.inner;
+        let mut input = inner.input.lock().unwrap();
+        loop {
+            if let Some(found) = take(&mut input) {
+                return Ok(found);
+            }
+            if let Some((kind, message)) = &input.broken {
+                return Err(io::Error::new(*kind, message.clone()).into());
+            }
+            if input.reading {
+                input = inner.input_changed.wait(input).unwrap();
+                continue;
+            }
+            input.reading = true;
+            drop(input);
+            let packet = read_packet(&mut *inner.reader.lock().unwrap());
+            input = inner.input.lock().unwrap();
+            input.reading = false;
+            match packet {
+                Ok(packet) => {
+                    let extensions = inner.extensions.lock().unwrap();
+                    file_packet(&mut input, packet, &extensions)
+                }
+                Err(e) => input.broken = Some((e.kind(), e.to_string())),
+            }
+            inner.input_changed.notify_all();
+        }
+    }
+
+    /// Same as `XContext::query_extension`, the answers are cached and used to decode errors
+    pub fn query_extension(&self, name: &str) -> Result<Option<ExtensionInfo>, XErrorKind> {
+        if let Some(info) = self.inner.extensions.lock().unwrap().get(name) {
+            return Ok(info);
+        }
+        let reply = self.request(|buf| query_extension_request(buf, name))?;
+        let info = extension::parse_query_extension(&reply)?;
+        self.inner.extensions.lock().unwrap().insert(name, info);
+        Ok(info)
+    }
+
+    /// Same as `XContext::register_decoder`
+    pub fn register_decoder<D>(&self, name: &str, decoder: D)
+    where
+        D: ExtensionDecoder + Send + Sync + 'static,
+    {
+        self.inner
+            .extensions
+            .lock()
+            .unwrap()
+            .insert_decoder(name, decoder);
+    }
+
+    fn enable_big_requests(&self) -> Result<Option<CARD32>, XErrorKind> {
+        let Some(bigreq) = self.query_extension(bigreq::EXTENSION_NAME)? else {
+            return Ok(None);
+        };
+        let reply = self.request(|buf| bigreq::enable_request(buf, bigreq.major_opcode))?;
+        let max = read_primitive::<CARD32, _>(&mut &reply[8..12])?;
+        self.inner.max_request_length.store(max, Ordering::Relaxed);
+        Ok(Some(max))
+    }
+}
+
+/// a 32 byte packet, plus the rest of it for replies
+fn read_packet<R: Read>(socket: &mut R) -> io::Result<Vec<u8>> {
+    let mut packet = vec![0u8; 32];
+    socket.read_exact(&mut packet)?;
+    if packet[0] == 1 {
+        let reply_length = CARD32::from_le_bytes([packet[4], packet[5], packet[6], packet[7]]);
+        xio::read_growing(
+            socket,
+            &mut packet,
+            (reply_length as usize).saturating_mul(4),
+        )?;
+    }
+    Ok(packet)
+}
+
+fn file_packet(input: &mut Input, packet: Vec<u8>, extensions: &ExtensionRegistry) {
+    let sequence = CARD16::from_le_bytes([packet[2], packet[3]]);
+    match packet[0] {
+        0 => {
+            let error = match read_primitive::<XErrorHeader, _>(&mut &packet[..]) {
+                Ok(header) => XErrorKind::from_header_with_extensions(header, extensions),
+                Err(e) => Some(e.into()),
+            }
+            .unwrap_or(XErrorKind::Unknown);
+            if input.awaiting.remove(&sequence) {
+                input.replies.insert(sequence, Err(error));
+            } else {
+                input.events.push_back(Err(error));
+            }
+        }
+        1 => {
+            // a reply nobody asked for is dropped
+            if input.awaiting.remove(&sequence) {
+                input.replies.insert(sequence, Ok(packet));
+            }
+        }
+        _ => {
+            let mut event = [0u8; 32];
+            event.copy_from_slice(&packet[..32]);
+            input.events.push_back(Ok(event));
+        }
+    }
+}
+
+#[test]
+pub fn shared_across_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SharedXContext>();
+
+    let (client, mut server) = UnixStream::pair().unwrap();
+    let ctx = SharedXContext::from_stream(ConnectionAcceptedInfo::default(), client).unwrap();
+
+    // one thread blocks on events while this one waits on a reply
+    let events = {
+        let ctx = ctx.clone();
+        std::thread::spawn(move || ctx.next_event().unwrap())
+    };
+    let server = std::thread::spawn(move || {
+        let mut request = [0u8; 4];
+        server.read_exact(&mut request).unwrap();
+        let mut reply = [0u8; 32];
+        reply[0] = 1;
+        reply[2..4].copy_from_slice(&1u16.to_le_bytes());
+        reply[8] = 0xaa;
+        server.write_all(&reply).unwrap();
+        let mut event = [0u8; 32];
+        event[0] = event_codes::KEYMAP_NOTIFY;
+        server.write_all(&event).unwrap();
+        server
+    });
+    let reply = ctx.request(get_input_focus_request).unwrap();
+    assert_eq!(reply[8], 0xaa);
+    assert_eq!(events.join().unwrap()[0], event_codes::KEYMAP_NOTIFY);
+    assert_ne!(ctx.gen_id(), ctx.clone().gen_id());
+    drop(server.join().unwrap());
+}
+
+#[test]
+pub fn shared_extension_errors() {
+    struct Shm;
+    impl ExtensionDecoder for Shm {
+        fn error_name(&self, _info: &ExtensionInfo, code: CARD8) -> Option<&'static str> {
+            (code == 0).then_some("BadSeg")
+        }
+    }
+
+    let (client, mut server) = UnixStream::pair().unwrap();
+    let ctx = SharedXContext::from_stream(ConnectionAcceptedInfo::default(), client).unwrap();
+    ctx.register_decoder("MIT-SHM", Shm);
+    let server = std::thread::spawn(move || {
+        // QueryExtension with the name padded to 8 bytes
+        let mut request = [0u8; 16];
+        server.read_exact(&mut request).unwrap();
+        let mut reply = [0u8; 32];
+        reply[0] = 1;
+        reply[2..4].copy_from_slice(&1u16.to_le_bytes());
+        reply[8..12].copy_from_slice(&[1, 130, 0, 128]);
+        server.write_all(&reply).unwrap();
+        let mut error = [0u8; 32];
+        error[1] = 128;
+        error[2..4].copy_from_slice(&2u16.to_le_bytes());
+        error[10] = 130;
+        server.write_all(&error).unwrap();
+        server
+    });
+    assert!(ctx.query_extension("MIT-SHM").unwrap().is_some());
+    match ctx.next_event() {
+        Err(XErrorKind::Extension {
+            extension, name, ..
+        }) => assert_eq!((extension.as_str(), name), ("MIT-SHM", Some("BadSeg"))),
+        other => panic!("{:?}", other.map(|event| event[0])),
+    }
+    drop(server.join().unwrap());
+}
@@ -0,0 +1,773 @@
+use super::*;
+use std::{
+    any::Any,
+    mem,
+    os::unix::io::{AsRawFd, RawFd},
+    ptr,
+};
+
+use crate::proto::shm as wire;
+pub use crate::proto::shm::{opcodes, EXTENSION_NAME};
+
+/// used to be its own type, everything is an `XErrorKind` now
+#[deprecated(note = "use XErrorKind")]
+pub type ShmErr = XErrorKind;
+
+/// Everything needed to talk MIT-SHM, returned by `XContext::query_shm`
+#[derive(Copy, Clone, Debug)]
+pub struct ShmExtension {
+    pub info: ExtensionInfo,
+    pub major_version: CARD16,
+    pub minor_version: CARD16,
+    pub uid: CARD16,
+    pub gid: CARD16,
+    pub shared_pixmaps: bool,
+    pub pixmap_format: CARD8,
+}
+impl ShmExtension {
+    pub fn supports_fd_passing(&self) -> bool {
+        (self.major_version, self.minor_version) >= (1, 2)
+    }
+
+    fn too_old(&self) -> XErrorKind {
+        XErrorKind::ExtensionTooOld {
+            extension: EXTENSION_NAME,
+            major_version: self.major_version as CARD32,
+            minor_version: self.minor_version as CARD32,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct GetImageReply {
+    pub depth: CARD8,
+    pub visual: CARD32,
+    pub size: CARD32,
+}
+
+/// Sent when a `put_image` with `send_event` set has finished reading the segment
+#[derive(Copy, Clone, Debug)]
+pub struct CompletionEvent {
+    pub drawable: CARD32,
+    pub minor_event: CARD16,
+    pub major_event: CARD8,
+    pub shmseg: CARD32,
+    pub offset: CARD32,
+}
+
+impl CompletionEvent {
+    /// returns `None` when `event` isn't an ShmCompletion
+    pub fn from_event(shm: &ShmExtension, event: &RawEvent) -> Option<Self> {
+        // the top bit is set for events that came from SendEvent
+        if event[0] & 0x7f != shm.info.first_event {
+            return None;
+        }
+        let msg = wire::CompletionEvent::parse(event).ok()?;
+        Some(Self {
+            drawable: msg.drawable,
+            minor_event: msg.minor_event,
+            major_event: msg.major_event,
+            shmseg: msg.shmseg,
+            offset: msg.offset,
+        })
+    }
+}
+
+impl ExtensionDecoder for ShmExtension {
+    fn decode_event(&self, _info: &ExtensionInfo, event: &RawEvent) -> Option<Box<dyn Any>> {
+        CompletionEvent::from_event(self, event).map(|e| Box::new(e) as Box<dyn Any>)
+    }
+
+    fn error_name(&self, _info: &ExtensionInfo, code: CARD8) -> Option<&'static str> {
+        (code == 0).then_some("BadSeg")
+    }
+}
+
+pub fn query_version<S: io::Write>(socket: &mut S, major_opcode: CARD8) -> io::Result<()> {
+    wire::query_version_request(socket, major_opcode)
+}
+
+pub fn attach<S: io::Write>(
+    socket: &mut S,
+    shm: &ShmExtension,
+    shmseg: CARD32,
+    shmid: CARD32,
+    read_only: bool,
+) -> io::Result<()> {
+    wire::attach(
+        socket,
+        shm.info.major_opcode,
+        shmseg,
+        shmid,
+        read_only as BOOL,
+    )
+}
+
+pub fn detach<S: io::Write>(socket: &mut S, shm: &ShmExtension, shmseg: CARD32) -> io::Result<()> {
+    wire::detach(socket, shm.info.major_opcode, shmseg)
+}
+
+/// Draws `src_rect` (x,y,width,height) of an image of size `total_size` stored at `offset` in
+/// `shmseg` onto `drawable` at `dst_pos`.\
+/// The server reads the segment asynchronously, so don't touch that part of
+/// the buffer until a `CompletionEvent` shows up (ask for one with `send_event`)
+#[allow(clippy::too_many_arguments)]
+pub fn put_image<S: io::Write>(
+    socket: &mut S,
+    shm: &ShmExtension,
+    drawable: CARD32,
+    gc: CARD32,
+    total_size: (CARD16, CARD16),
+    src_rect: (CARD16, CARD16, CARD16, CARD16),
+    dst_pos: (INT16, INT16),
+    depth: CARD8,
+    format: ImageFormat,
+    send_event: bool,
+    shmseg: CARD32,
+    offset: CARD32,
+) -> io::Result<()> {
+    wire::put_image(
+        socket,
+        shm.info.major_opcode,
+        drawable,
+        gc,
+        total_size.0,
+        total_size.1,
+        src_rect.0,
+        src_rect.1,
+        src_rect.2,
+        src_rect.3,
+        dst_pos.0,
+        dst_pos.1,
+        depth,
+        format as CARD8,
+        send_event as BOOL,
+        shmseg,
+        offset,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn get_image_request<S: io::Write>(
+    socket: &mut S,
+    shm: &ShmExtension,
+    drawable: CARD32,
+    pos: (INT16, INT16),
+    size: (CARD16, CARD16),
+    plane_mask: CARD32,
+    format: ImageFormat,
+    shmseg: CARD32,
+    offset: CARD32,
+) -> io::Result<()> {
+    wire::get_image_request(
+        socket,
+        shm.info.major_opcode,
+        drawable,
+        pos.0,
+        pos.1,
+        size.0,
+        size.1,
+        plane_mask,
+        format as CARD8,
+        shmseg,
+        offset,
+    )
+}
+
+/// creates a pixmap whose pixels live in `shmseg` starting at `offset`
+#[allow(clippy::too_many_arguments)]
+pub fn create_pixmap<S: io::Write>(
+    socket: &mut S,
+    shm: &ShmExtension,
+    pid: CARD32,
+    drawable: CARD32,
+    size: (CARD16, CARD16),
+    depth: CARD8,
+    shmseg: CARD32,
+    offset: CARD32,
+) -> io::Result<()> {
+    wire::create_pixmap(
+        socket,
+        shm.info.major_opcode,
+        pid,
+        drawable,
+        size.0,
+        size.1,
+        depth,
+        shmseg,
+        offset,
+    )
+}
+
+/// Attaches the memory behind `fd` as `shmseg`.\
+/// `fd` is sent over the unix socket next to the request, it can be closed afterwards
+pub fn attach_fd<S: io::Write + AsRawFd>(
+    socket: &mut S,
+    shm: &ShmExtension,
+    shmseg: CARD32,
+    fd: RawFd,
+    read_only: bool,
+) -> io::Result<()> {
+    let mut request = Vec::with_capacity(12);
+    wire::attach_fd(
+        &mut request,
+        shm.info.major_opcode,
+        shmseg,
+        read_only as BOOL,
+    )?;
+    let sent = send_with_fd(socket.as_raw_fd(), &request, fd)?;
+    socket.write_all(&request[sent..])
+}
+
+/// asks the server to allocate a segment, the reply carries its file descriptor
+pub fn create_segment_request<S: io::Write>(
+    socket: &mut S,
+    shm: &ShmExtension,
+    shmseg: CARD32,
+    size: CARD32,
+    read_only: bool,
+) -> io::Result<()> {
+    wire::create_segment_request(
+        socket,
+        shm.info.major_opcode,
+        shmseg,
+        size,
+        read_only as BOOL,
+    )
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    pub fn query_shm(&self) -> Result<ShmExtension, XErrorKind> {
+        let info = self
+            .query_extension(EXTENSION_NAME)?
+            .ok_or(XErrorKind::ExtensionMissing(EXTENSION_NAME))?;
+        self.socket_cb(|socket| query_version(socket, info.major_opcode))?;
+        let reply = self.read_reply()?;
+        let reply = wire::QueryVersionReply::parse(&reply)?;
+        let shm = ShmExtension {
+            info,
+            major_version: reply.major_version,
+            minor_version: reply.minor_version,
+            uid: reply.uid,
+            gid: reply.gid,
+            shared_pixmaps: reply.shared_pixmaps != 0,
+            pixmap_format: reply.pixmap_format,
+        };
+        self.register_decoder(EXTENSION_NAME, shm);
+        Ok(shm)
+    }
+
+    /// Copies a region of `drawable` into `segment` at `offset`.\
+    /// The data is in place by the time this returns, the segment is borrowed mutably
+    /// because the server writes to it
+    #[allow(clippy::too_many_arguments)]
+    pub fn shm_get_image(
+        &self,
+        shm: &ShmExtension,
+        drawable: CARD32,
+        pos: (INT16, INT16),
+        size: (CARD16, CARD16),
+        plane_mask: CARD32,
+        format: ImageFormat,
+        segment: &mut ShmSegment<S>,
+        offset: CARD32,
+    ) -> Result<GetImageReply, XErrorKind> {
+        self.socket_cb(|socket| {
+            get_image_request(
+                socket,
+                shm,
+                drawable,
+                pos,
+                size,
+                plane_mask,
+                format,
+                segment.id(),
+                offset,
+            )
+        })?;
+        let reply = self.read_reply()?;
+        let reply = wire::GetImageReply::parse(&reply)?;
+        Ok(GetImageReply {
+            depth: reply.depth,
+            visual: reply.visual,
+            size: reply.size,
+        })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Mapping {
+    SysV,
+    Mmap,
+}
+
+/// A chunk of memory shared with the X server.\
+/// The segment is detached from the server and unmapped when dropped
+pub struct ShmSegment<S: io::Write> {
+    ctx: XContext<S>,
+    shm: ShmExtension,
+    id: CARD32,
+    addr: *mut u8,
+    len: usize,
+    mapping: Mapping,
+    attached: bool,
+}
+
+impl<S: io::Read + io::Write> ShmSegment<S> {
+    /// creates a SysV shared memory segment (shmget/shmat) and attaches it
+    pub fn new(
+        ctx: &mut XContext<S>,
+        shm: &ShmExtension,
+        size: usize,
+        read_only: bool,
+    ) -> Result<Self, XErrorKind> {
+        let shmid = unsafe { libc::shmget(libc::IPC_PRIVATE, size, libc::IPC_CREAT | 0o600) };
+        if shmid < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let addr = unsafe { libc::shmat(shmid, ptr::null(), 0) };
+        if addr as isize == -1 {
+            let err = io::Error::last_os_error();
+            unsafe { libc::shmctl(shmid, libc::IPC_RMID, ptr::null_mut()) };
+            return Err(err.into());
+        }
+        let mut segment = Self {
+            ctx: ctx.clone(),
+            shm: *shm,
+            id: ctx.gen_id(),
+            addr: addr as *mut u8,
+            len: size,
+            mapping: Mapping::SysV,
+            attached: false,
+        };
+        let attached = segment
+            .attach_and_sync(|socket, shm, id| attach(socket, shm, id, shmid as CARD32, read_only));
+        // once the server has attached (or failed to) the id can go away,
+        // the memory itself lives until both sides detach
+        unsafe { libc::shmctl(shmid, libc::IPC_RMID, ptr::null_mut()) };
+        attached?;
+        Ok(segment)
+    }
+
+    /// Sends `request` and does a round trip so errors from it show up here
+    fn attach_and_sync<F>(&mut self, mut request: F) -> Result<(), XErrorKind>
+    where
+        F: FnMut(&mut xio::Counted<'_, S>, &ShmExtension, CARD32) -> io::Result<()>,
+    {
+        let (shm, id) = (self.shm, self.id);
+        self.ctx.socket_cb(|socket| {
+            request(socket, &shm, id)?;
+            query_version(socket, shm.info.major_opcode)
+        })?;
+        self.ctx.read_reply()?;
+        self.attached = true;
+        Ok(())
+    }
+
+    pub fn id(&self) -> CARD32 {
+        self.id
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.addr, self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.addr, self.len) }
+    }
+}
+
+impl<S: io::Read + io::Write + AsRawFd> ShmSegment<S> {
+    /// creates a memfd, maps it and hands it to the server with AttachFd
+    pub fn new_fd(
+        ctx: &mut XContext<S>,
+        shm: &ShmExtension,
+        size: usize,
+        read_only: bool,
+    ) -> Result<Self, XErrorKind> {
+        if !shm.supports_fd_passing() {
+            return Err(shm.too_old());
+        }
+        let fd = unsafe { libc::memfd_create(c"xproto-shm".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        if unsafe { libc::ftruncate(fd, size as libc::off_t) } < 0 {
+            let err = io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(err.into());
+        }
+        let id = ctx.gen_id();
+        let segment = Self::map_fd(ctx, shm, id, fd, size).and_then(|mut segment| {
+            segment.attach_and_sync(|socket, shm, id| attach_fd(socket, shm, id, fd, read_only))?;
+            Ok(segment)
+        });
+        // the server holds its own copy of the fd by now
+        unsafe { libc::close(fd) };
+        segment
+    }
+
+    /// lets the server allocate the memory (CreateSegment) and maps the fd it sends back
+    pub fn create_segment(
+        ctx: &mut XContext<S>,
+        shm: &ShmExtension,
+        size: usize,
+        read_only: bool,
+    ) -> Result<Self, XErrorKind> {
+        if !shm.supports_fd_passing() {
+            return Err(shm.too_old());
+        }
+        let id = ctx.gen_id();
+        // every fd that comes in, the server's goes out with whatever it flushes along with
+        // the reply so it may come with an event ahead of it
+        let mut fds = vec![];
+        let fd = ctx.socket_cb(|socket| -> Result<Option<RawFd>, XErrorKind> {
+            create_segment_request(socket, shm, id, size as CARD32, read_only)?;
+            let sequence = ctx.last_sequence();
+            let reader = &mut FdReader {
+                socket: socket.as_raw_fd(),
+                fds: &mut fds,
+            };
+            loop {
+                // bytes `poll_for_event` buffered come first
+                let packet = ctx.read_packet(reader)?;
+                if ctx.dispatch_reply(reader, packet, sequence)?.is_some() {
+                    return Ok(reader.fds.last().copied());
+                }
+            }
+        });
+        let fd = fd.and_then(|fd| fd.ok_or(XErrorKind::MissingFd));
+        for &other in &fds {
+            if fd.as_ref().ok() != Some(&other) {
+                unsafe { libc::close(other) };
+            }
+        }
+        let fd = fd?;
+        let segment = Self::map_fd(ctx, shm, id, fd, size).map(|mut segment| {
+            segment.attached = true;
+            segment
+        });
+        unsafe { libc::close(fd) };
+        if segment.is_err() {
+            ctx.socket_cb(|socket| detach(socket, shm, id))?;
+        }
+        segment
+    }
+
+    fn map_fd(
+        ctx: &XContext<S>,
+        shm: &ShmExtension,
+        id: CARD32,
+        fd: RawFd,
+        size: usize,
+    ) -> Result<Self, XErrorKind> {
+        let addr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            )
+        };
+        if addr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(Self {
+            ctx: ctx.clone(),
+            shm: *shm,
+            id,
+            addr: addr as *mut u8,
+            len: size,
+            mapping: Mapping::Mmap,
+            attached: false,
+        })
+    }
+}
+
+impl<S: io::Write> Drop for ShmSegment<S> {
+    fn drop(&mut self) {
+        if self.attached {
+            let (shm, id) = (self.shm, self.id);
+            self.ctx.send_or_defer(|request| detach(request, &shm, id));
+        }
+        unsafe {
+            match self.mapping {
+                Mapping::SysV => libc::shmdt(self.addr as *const libc::c_void),
+                Mapping::Mmap => libc::munmap(self.addr as *mut libc::c_void, self.len),
+            };
+        }
+    }
+}
+
+/// Sends `data` with `fd` attached as SCM_RIGHTS.\
+/// returns how many bytes of `data` actually went out
+fn send_with_fd(socket: RawFd, data: &[u8], fd: RawFd) -> io::Result<usize> {
+    // u64s keep the control buffer aligned for cmsghdr
+    let mut control = [0u64; 4];
+    let mut iov = libc::iovec {
+        iov_base: data.as_ptr() as *mut libc::c_void,
+        iov_len: data.len(),
+    };
+    unsafe {
+        let mut msg: libc::msghdr = mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = libc::CMSG_SPACE(mem::size_of::<RawFd>() as u32) as _;
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(mem::size_of::<RawFd>() as u32) as _;
+        ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut RawFd, fd);
+        let sent = libc::sendmsg(socket, &msg, 0);
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(sent as usize)
+    }
+}
+
+/// Reads with recvmsg, keeping the fds the server passes along with the bytes
+struct FdReader<'a> {
+    socket: RawFd,
+    fds: &'a mut Vec<RawFd>,
+}
+
+impl io::Read for FdReader<'_> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut control = [0u64; 4];
+        let mut iov = libc::iovec {
+            iov_base: buffer.as_mut_ptr() as *mut libc::c_void,
+            iov_len: buffer.len(),
+        };
+        unsafe {
+            let mut msg: libc::msghdr = mem::zeroed();
+            msg.msg_iov = &mut iov;
+            msg.msg_iovlen = 1;
+            msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+            msg.msg_controllen = mem::size_of_val(&control) as _;
+            let read = libc::recvmsg(self.socket, &mut msg, libc::MSG_CMSG_CLOEXEC);
+            if read < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                    let data = libc::CMSG_DATA(cmsg) as *const RawFd;
+                    let len = (*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+                    for i in 0..len / mem::size_of::<RawFd>() {
+                        self.fds.push(ptr::read_unaligned(data.add(i)));
+                    }
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+            Ok(read as usize)
+        }
+    }
+}
+
+#[cfg(test)]
+fn test_extension() -> ShmExtension {
+    ShmExtension {
+        info: ExtensionInfo {
+            major_opcode: 130,
+            first_event: 65,
+            first_error: 128,
+        },
+        major_version: 1,
+        minor_version: 2,
+        uid: 0,
+        gid: 0,
+        shared_pixmaps: true,
+        pixmap_format: 2,
+    }
+}
+
+#[test]
+pub fn shm_encoding() {
+    let shm = test_extension();
+    let mut out = vec![];
+    attach(&mut out, &shm, 0x200, 7, true).unwrap();
+    assert_eq!(out.len(), 16);
+    assert_eq!(&out[..4], &[130, opcodes::ATTACH, 4, 0]);
+    assert_eq!(&out[4..8], &0x200u32.to_le_bytes());
+    assert_eq!(&out[8..12], &7u32.to_le_bytes());
+    assert_eq!(out[12], 1);
+
+    out.clear();
+    detach(&mut out, &shm, 0x200).unwrap();
+    assert_eq!(out, [130, opcodes::DETACH, 2, 0, 0, 2, 0, 0]);
+
+    out.clear();
+    put_image(
+        &mut out,
+        &shm,
+        0x300,
+        0x400,
+        (640, 480),
+        (10, 20, 30, 40),
+        (-5, 6),
+        24,
+        ImageFormat::ZPixmap,
+        true,
+        0x200,
+        64,
+    )
+    .unwrap();
+    assert_eq!(out.len(), 40);
+    assert_eq!(&out[..4], &[130, opcodes::PUT_IMAGE, 10, 0]);
+    assert_eq!(&out[12..14], &640u16.to_le_bytes());
+    assert_eq!(&out[24..26], &(-5i16).to_le_bytes());
+    assert_eq!(&out[28..32], &[24, ImageFormat::ZPixmap as u8, 1, 0]);
+    assert_eq!(&out[32..36], &0x200u32.to_le_bytes());
+    assert_eq!(&out[36..40], &64u32.to_le_bytes());
+
+    out.clear();
+    get_image_request(
+        &mut out,
+        &shm,
+        0x300,
+        (1, 2),
+        (3, 4),
+        !0,
+        ImageFormat::ZPixmap,
+        0x200,
+        0,
+    )
+    .unwrap();
+    assert_eq!(out.len(), 32);
+    assert_eq!(&out[..4], &[130, opcodes::GET_IMAGE, 8, 0]);
+
+    out.clear();
+    create_pixmap(&mut out, &shm, 0x500, 0x300, (8, 8), 24, 0x200, 0).unwrap();
+    assert_eq!(out.len(), 28);
+    assert_eq!(&out[..4], &[130, opcodes::CREATE_PIXMAP, 7, 0]);
+    assert_eq!(out[16], 24);
+
+    out.clear();
+    create_segment_request(&mut out, &shm, 0x200, 4096, false).unwrap();
+    assert_eq!(out.len(), 16);
+    assert_eq!(&out[8..12], &4096u32.to_le_bytes());
+}
+
+#[test]
+pub fn completion_event() {
+    let shm = test_extension();
+    let mut event: RawEvent = [0; 32];
+    event[0] = 65;
+    event[4..8].copy_from_slice(&0x300u32.to_le_bytes());
+    event[8..10].copy_from_slice(&(opcodes::PUT_IMAGE as CARD16).to_le_bytes());
+    event[10] = 130;
+    event[12..16].copy_from_slice(&0x200u32.to_le_bytes());
+    event[16..20].copy_from_slice(&64u32.to_le_bytes());
+    let completion = CompletionEvent::from_event(&shm, &event).unwrap();
+    assert_eq!(completion.drawable, 0x300);
+    assert_eq!(completion.minor_event, opcodes::PUT_IMAGE as CARD16);
+    assert_eq!(completion.major_event, 130);
+    assert_eq!(completion.shmseg, 0x200);
+    assert_eq!(completion.offset, 64);
+    // sent with SendEvent
+    event[0] |= 0x80;
+    assert!(CompletionEvent::from_event(&shm, &event).is_some());
+    event[0] = 66;
+    assert!(CompletionEvent::from_event(&shm, &event).is_none());
+}
+
+#[test]
+pub fn segment_detach_is_deferred() {
+    let (server, mut ctx) = mock::MockServer::connect();
+    let shm = test_extension();
+    server.on_request(shm.info.major_opcode, |request| {
+        (request.data == opcodes::QUERY_VERSION).then(|| mock::MockResponse::Reply {
+            data: 1,
+            body: vec![1, 0, 2, 0],
+        })
+    });
+    let segment = ShmSegment::new(&mut ctx, &shm, 4096, false).unwrap();
+    let id = segment.id();
+    server.take_requests();
+    // dropped while the socket is in use, the detach has to wait for it
+    let mut segment = Some(segment);
+    ctx.socket_cb(|_| drop(segment.take()));
+    assert!(server.requests().is_empty());
+    ctx.sync().unwrap();
+    let requests = server.take_requests();
+    assert_eq!(requests[0].opcode, shm.info.major_opcode);
+    assert_eq!(requests[0].data, opcodes::DETACH);
+    assert_eq!(requests[0].body, id.to_le_bytes());
+}
+
+#[test]
+pub fn create_segment_fd_after_event() {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    // sends the bytes with `fd` attached, the way the server passes them
+    let send = |server: &UnixStream, bytes: &[u8], fd: RawFd| {
+        let sent = send_with_fd(server.as_raw_fd(), bytes, fd).unwrap();
+        assert_eq!(sent, bytes.len());
+        unsafe { libc::close(fd) };
+    };
+
+    let shm = test_extension();
+    let (client, mut server) = UnixStream::pair().unwrap();
+    let server = std::thread::spawn(move || {
+        let mut setup = [0u8; 12];
+        server.read_exact(&mut setup).unwrap();
+        server
+            .write_all(&mock::MockSetup::default().encode())
+            .unwrap();
+        let mut sequence = 0u16;
+        loop {
+            let mut header = [0u8; 4];
+            if server.read_exact(&mut header).is_err() {
+                return;
+            }
+            let len = u16::from_le_bytes([header[2], header[3]]) as usize * 4;
+            let mut body = vec![0u8; len - 4];
+            server.read_exact(&mut body).unwrap();
+            sequence += 1;
+            let mut packet = [0u8; 32];
+            packet[2..4].copy_from_slice(&sequence.to_le_bytes());
+            if header[0] == xproto::opcodes::QUERY_EXTENSION {
+                // BIG-REQUESTS isn't there
+                packet[0] = 1;
+                server.write_all(&packet).unwrap();
+            } else if header[0] == shm.info.major_opcode && header[1] == opcodes::CREATE_SEGMENT {
+                // not something a server sends, it's closed and mmap would fail on it
+                let mut pipe = [0; 2];
+                assert_eq!(unsafe { libc::pipe(pipe.as_mut_ptr()) }, 0);
+                unsafe { libc::close(pipe[1]) };
+                packet[0] = event_codes::MAP_NOTIFY;
+                send(&server, &packet, pipe[0]);
+                // the segment's fd comes with the event flushed ahead of the reply
+                let fd = unsafe { libc::memfd_create(c"xproto-test".as_ptr(), libc::MFD_CLOEXEC) };
+                assert_eq!(unsafe { libc::ftruncate(fd, 4096) }, 0);
+                let mut both = packet.to_vec();
+                packet[0] = 1;
+                packet[1] = 1; //nfd
+                both.extend_from_slice(&packet);
+                send(&server, &both, fd);
+            }
+        }
+    });
+    let mut ctx = RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None)
+        .connect(client)
+        .unwrap();
+    let mut segment = ShmSegment::create_segment(&mut ctx, &shm, 4096, false).unwrap();
+    segment.as_mut_slice()[4095] = 1;
+    assert_eq!(ctx.events.borrow().len(), 2);
+    drop(segment);
+    drop(ctx);
+    server.join().unwrap();
+}
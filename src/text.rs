@@ -0,0 +1,310 @@
+use super::*;
+use std::borrow::Cow;
+
+/// Latin-1 bytes to a `String`, every byte is the code point of the same value.\
+/// STRING properties, atom names, font names and everything else the core protocol
+/// calls STRING8 are Latin-1
+pub fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// `None` if `text` has anything past U+00FF, borrowed if it's plain ASCII
+pub fn encode_latin1(text: &str) -> Option<Cow<'_, [u8]>> {
+    if text.is_ascii() {
+        return Some(Cow::Borrowed(text.as_bytes()));
+    }
+    text.chars()
+        .map(|c| u8::try_from(c as u32).ok())
+        .collect::<Option<Vec<u8>>>()
+        .map(Cow::Owned)
+}
+
+/// like `encode_latin1` but anything that doesn't fit becomes '?'
+pub fn encode_latin1_lossy(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| u8::try_from(c as u32).unwrap_or(b'?'))
+        .collect()
+}
+
+/// names of atoms, fonts, colors and extensions, these can't be made lossy
+/// without asking the server for the wrong thing
+pub(crate) fn latin1_name(name: &str) -> io::Result<Cow<'_, [u8]>> {
+    encode_latin1(name).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:?} isn't Latin-1", name),
+        )
+    })
+}
+
+const ESC: u8 = 0x1b;
+const CSI: u8 = 0x9b;
+
+#[derive(Copy, Clone)]
+enum Charset {
+    Ascii,
+    /// the right half of ISO 8859-1
+    Latin1,
+    /// a set we don't have tables for, how many bytes one of its characters takes
+    Other(usize),
+}
+
+/// Decodes COMPOUND_TEXT (ISO 2022 as the X consortium profiled it).\
+/// ASCII, Latin-1 and UTF-8 segments come out as they are, characters from the
+/// other sets (JIS, GB, KSC...) become U+FFFD. Never fails, bad input is skipped
+pub fn decode_compound_text(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    // what a compound text starts with
+    let (mut gl, mut gr) = (Charset::Ascii, Charset::Latin1);
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        match byte {
+            ESC => {
+                let rest = &bytes[i + 1..];
+                i += 1 + match rest {
+                    [b'(', f, ..] => {
+                        gl = if *f == b'B' {
+                            Charset::Ascii
+                        } else {
+                            Charset::Other(1)
+                        };
+                        2
+                    }
+                    [b')', _, ..] => {
+                        gr = Charset::Other(1);
+                        2
+                    }
+                    [b'-', f, ..] => {
+                        gr = if *f == b'A' {
+                            Charset::Latin1
+                        } else {
+                            Charset::Other(1)
+                        };
+                        2
+                    }
+                    [b'$', b'(', _, ..] => {
+                        gl = Charset::Other(2);
+                        3
+                    }
+                    [b'$', b')', _, ..] => {
+                        gr = Charset::Other(2);
+                        3
+                    }
+                    [b'%', b'G', utf8 @ ..] => {
+                        let end = utf8
+                            .windows(3)
+                            .position(|w| w == [ESC, b'%', b'@'])
+                            .unwrap_or(utf8.len());
+                        out.push_str(&String::from_utf8_lossy(&utf8[..end]));
+                        2 + (end + 3).min(utf8.len())
+                    }
+                    // an extended segment, two bytes of length then the encoding's name and text
+                    [b'%', b'/', _, m, l, ..] => {
+                        let len = ((*m as usize) & 0x7f) << 7 | (*l as usize) & 0x7f;
+                        out.push(char::REPLACEMENT_CHARACTER);
+                        (5 + len).min(rest.len())
+                    }
+                    _ => {
+                        // some other escape, intermediate bytes then a final one
+                        let intermediates = rest.iter().take_while(|b| (0x20..0x30).contains(*b));
+                        (intermediates.count() + 1).min(rest.len())
+                    }
+                };
+            }
+            // direction changes, we only care about the characters
+            CSI => {
+                let rest = &bytes[i + 1..];
+                let params = rest.iter().take_while(|b| (0x20..0x40).contains(*b));
+                i += 1 + (params.count() + 1).min(rest.len());
+            }
+            b'\n' | b'\t' | b' ' => {
+                out.push(char::from(byte));
+                i += 1;
+            }
+            0x21..=0x7e | 0xa0..=0xff => {
+                let set = if byte < 0x80 { gl } else { gr };
+                match set {
+                    Charset::Ascii => out.push(char::from(byte & 0x7f)),
+                    Charset::Latin1 => out.push(char::from(byte | 0x80)),
+                    Charset::Other(_) => out.push(char::REPLACEMENT_CHARACTER),
+                }
+                i += match set {
+                    Charset::Other(width) => width.min(bytes.len() - i),
+                    _ => 1,
+                };
+            }
+            // the other C0 and C1 controls aren't allowed in compound text
+            _ => i += 1,
+        }
+    }
+    out
+}
+
+/// Encodes COMPOUND_TEXT, Latin-1 goes out as it is and everything else as UTF-8 segments.\
+/// Controls other than newline and tab aren't allowed and get dropped
+pub fn encode_compound_text(text: &str) -> Vec<u8> {
+    let mut out = vec![];
+    let mut utf8 = false;
+    for c in text.chars() {
+        let latin1 = u8::try_from(c as u32).ok();
+        match (latin1, utf8) {
+            (Some(_), true) => {
+                out.extend_from_slice(&[ESC, b'%', b'@']);
+                utf8 = false;
+            }
+            (None, false) => {
+                out.extend_from_slice(&[ESC, b'%', b'G']);
+                utf8 = true;
+            }
+            _ => {}
+        }
+        match latin1 {
+            Some(byte @ (b'\n' | b'\t')) => out.push(byte),
+            Some(0..=0x1f | 0x7f..=0x9f) => {}
+            Some(byte) => out.push(byte),
+            None => out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    if utf8 {
+        out.extend_from_slice(&[ESC, b'%', b'@']);
+    }
+    out
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    fn set_string_property(
+        &self,
+        window: WINDOW,
+        property: Atom,
+        ptype: Atom,
+        value: &[u8],
+    ) -> Result<(), XErrorKind> {
+        self.socket_cb(|socket| {
+            change_property(
+                socket,
+                PropertyMode::Replace,
+                property,
+                ptype,
+                window.0,
+                PropertyFormat::Bytes,
+                value,
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Sets `property` to `text` the way ICCCM says to for TEXT properties like WM_NAME,
+    /// as STRING when it's all Latin-1 and as COMPOUND_TEXT when it isn't
+    pub fn set_text_property(
+        &self,
+        window: WINDOW,
+        property: Atom,
+        text: &str,
+    ) -> Result<(), XErrorKind> {
+        match encode_latin1(text) {
+            Some(latin1) => {
+                self.set_string_property(window, property, predefined_atoms::STRING, &latin1)
+            }
+            None => {
+                let compound_text = self.intern_atom("COMPOUND_TEXT", false)?;
+                self.set_string_property(
+                    window,
+                    property,
+                    compound_text,
+                    &encode_compound_text(text),
+                )
+            }
+        }
+    }
+
+    /// sets `property` to `text` as UTF8_STRING, for the _NET_* properties
+    pub fn set_utf8_property(
+        &self,
+        window: WINDOW,
+        property: Atom,
+        text: &str,
+    ) -> Result<(), XErrorKind> {
+        let utf8_string = self.intern_atom("UTF8_STRING", false)?;
+        self.set_string_property(window, property, utf8_string, text.as_bytes())
+    }
+
+    /// Reads a text property decoded by its type, STRING, UTF8_STRING or COMPOUND_TEXT.\
+    /// `None` if it isn't set or isn't 8 bit, other types are taken to be UTF-8
+    pub fn get_text_property(
+        &self,
+        window: WINDOW,
+        property: Atom,
+    ) -> Result<Option<String>, XErrorKind> {
+        let reply = self.get_property_all(false, window, property, None)?;
+        if reply.format != 8 {
+            return Ok(None);
+        }
+        if reply.ptype == predefined_atoms::STRING {
+            return Ok(Some(decode_latin1(&reply.value)));
+        }
+        Ok(Some(match &*self.get_atom_name(reply.ptype)? {
+            "COMPOUND_TEXT" => decode_compound_text(&reply.value),
+            _ => String::from_utf8_lossy(&reply.value).into_owned(),
+        }))
+    }
+}
+
+#[test]
+pub fn text_encodings() {
+    assert_eq!(decode_latin1(b"caf\xe9"), "café");
+    assert_eq!(encode_latin1("café").unwrap(), &b"caf\xe9"[..]);
+    assert!(encode_latin1("καφές").is_none());
+    assert_eq!(encode_latin1_lossy("a€b"), b"a?b");
+
+    let compound = encode_compound_text("é ∑\tx");
+    assert_eq!(compound, b"\xe9 \x1b%G\xe2\x88\x91\x1b%@\tx");
+    assert_eq!(decode_compound_text(&compound), "é ∑\tx");
+    // Latin-1 selected explicitly, then a JIS X 0208 segment and back to ASCII
+    let jis = b"\x1b-A\xe0\x1b$(B\x30\x21\x30\x22\x1b(Bok\x9b1]\x07";
+    assert_eq!(decode_compound_text(jis), "à\u{fffd}\u{fffd}ok");
+    // cut short anywhere, nothing panics
+    for len in 0..jis.len() {
+        decode_compound_text(&jis[..len]);
+        decode_compound_text(&compound[..len.min(compound.len())]);
+    }
+
+    let (server, ctx) = mock::MockServer::connect();
+    let window = ctx.info().list_of_screen[0].root;
+    let name = predefined_atoms::WM_NAME;
+    ctx.set_text_property(window, name, "café").unwrap();
+    let property = server.property(window, name).unwrap();
+    assert_eq!(
+        (property.ptype, &property.value[..]),
+        (predefined_atoms::STRING, &b"caf\xe9"[..])
+    );
+    assert_eq!(
+        ctx.get_text_property(window, name).unwrap().unwrap(),
+        "café"
+    );
+
+    ctx.set_text_property(window, name, "καφές").unwrap();
+    assert_eq!(
+        server.property(window, name).unwrap().ptype,
+        server.atom("COMPOUND_TEXT").unwrap()
+    );
+    assert_eq!(
+        ctx.get_text_property(window, name).unwrap().unwrap(),
+        "καφές"
+    );
+
+    ctx.set_utf8_property(window, name, "☕").unwrap();
+    assert_eq!(
+        server.property(window, name).unwrap().value,
+        "☕".as_bytes()
+    );
+    assert_eq!(ctx.get_text_property(window, name).unwrap().unwrap(), "☕");
+    assert_eq!(
+        ctx.get_text_property(window, predefined_atoms::WM_CLASS)
+            .unwrap(),
+        None
+    );
+
+    let err = ctx.intern_atom("ΑΤΟΜ", false).unwrap_err();
+    assert!(matches!(err, XErrorKind::SocketIO(e) if e.kind() == io::ErrorKind::InvalidInput));
+}
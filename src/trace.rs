@@ -0,0 +1,461 @@
+//! xtrace style protocol logging.\
+//! `Traced` wraps the socket handed to `RequestConnection::connect` and logs every request going
+//! out and every reply, event and error coming back through the `log` crate, under the
+//! `xproto::trace` target. Requests and what comes back are logged at debug level, errors at warn.\
+//! Only what passes through `Read` and `Write` is seen, the file descriptors MIT-SHM passes
+//! alongside its requests and replies aren't
+
+use super::*;
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    os::unix::io::{AsRawFd, RawFd},
+};
+
+const TARGET: &str = "xproto::trace";
+
+const EVENT_NAMES: [&str; 36] = [
+    "",
+    "",
+    "KeyPress",
+    "KeyRelease",
+    "ButtonPress",
+    "ButtonRelease",
+    "MotionNotify",
+    "EnterNotify",
+    "LeaveNotify",
+    "FocusIn",
+    "FocusOut",
+    "KeymapNotify",
+    "Expose",
+    "GraphicsExposure",
+    "NoExposure",
+    "VisibilityNotify",
+    "CreateNotify",
+    "DestroyNotify",
+    "UnmapNotify",
+    "MapNotify",
+    "MapRequest",
+    "ReparentNotify",
+    "ConfigureNotify",
+    "ConfigureRequest",
+    "GravityNotify",
+    "ResizeRequest",
+    "CirculateNotify",
+    "CirculateRequest",
+    "PropertyNotify",
+    "SelectionClear",
+    "SelectionRequest",
+    "SelectionNotify",
+    "ColormapNotify",
+    "ClientMessage",
+    "MappingNotify",
+    "GenericEvent",
+];
+
+/// A socket that logs the protocol going through it, see the module docs
+pub struct Traced<S> {
+    socket: S,
+    hex_dump: bool,
+    /// written bytes that don't make up a whole request yet
+    output: Vec<u8>,
+    /// read bytes that don't make up a whole packet yet
+    input: Vec<u8>,
+    setup_sent: bool,
+    setup_received: bool,
+    /// sequence number of the last request written
+    sequence: CARD16,
+    /// requests with a reply coming, the name is kept for QueryExtension
+    awaiting: HashMap<CARD16, (CARD8, Option<String>)>,
+    /// extension names by major opcode, learned from QueryExtension replies
+    extensions: HashMap<CARD8, String>,
+}
+
+impl<S> Traced<S> {
+    pub fn new(socket: S) -> Self {
+        Self {
+            socket,
+            hex_dump: false,
+            output: vec![],
+            input: vec![],
+            setup_sent: false,
+            setup_received: false,
+            sequence: 0,
+            awaiting: HashMap::new(),
+            extensions: HashMap::new(),
+        }
+    }
+
+    /// also log every packet as hex, at trace level
+    pub fn with_hex_dump(mut self, hex_dump: bool) -> Self {
+        self.hex_dump = hex_dump;
+        self
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.socket
+    }
+
+    pub fn into_inner(self) -> S {
+        self.socket
+    }
+
+    fn log_output(&mut self) {
+        if !self.setup_sent {
+            let Some(len) = xio::setup_request_len(&self.output) else {
+                return;
+            };
+            let setup: Vec<u8> = self.output.drain(..len).collect();
+            log::debug!(target: TARGET, "--> connection setup, {} bytes", len);
+            self.dump(&setup);
+            self.setup_sent = true;
+        }
+        while let Some(len) = xio::request_len(&self.output) {
+            let request: Vec<u8> = self.output.drain(..len).collect();
+            self.sequence = self.sequence.wrapping_add(1);
+            let name = self.request_name(request[0], request[1]);
+            log::debug!(
+                target: TARGET,
+                "--> {} seq={} len={}{}",
+                name,
+                self.sequence,
+                len,
+                describe_request(&request)
+            );
+            self.dump(&request);
+            if has_reply(request[0]) {
+                let extension = (request[0] == opcodes::QUERY_EXTENSION)
+                    .then(|| {
+                        let len = card16_at(&request, 4) as usize;
+                        request.get(8..8 + len)
+                    })
+                    .flatten()
+                    .map(text::decode_latin1);
+                self.awaiting.insert(self.sequence, (request[0], extension));
+            }
+        }
+    }
+
+    fn log_input(&mut self) {
+        if !self.setup_received {
+            let Some(header) = self.input.get(..8) else {
+                return;
+            };
+            let len = 8 + card16_at(header, 6) as usize * 4;
+            if self.input.len() < len {
+                return;
+            }
+            let status = match self.input[0] {
+                0 => "failed",
+                1 => "accepted",
+                _ => "needs authentication",
+            };
+            let setup: Vec<u8> = self.input.drain(..len).collect();
+            log::debug!(target: TARGET, "<-- connection setup {}, {} bytes", status, len);
+            self.dump(&setup);
+            self.setup_received = true;
+        }
+        while let Some(len) = xio::packet_len(&self.input) {
+            let packet: Vec<u8> = self.input.drain(..len).collect();
+            let sequence = card16_at(&packet, 2);
+            match packet[0] {
+                0 => {
+                    let request = self.request_name(packet[10], card16_at(&packet, 8) as CARD8);
+                    let error = read_primitive::<XErrorHeader, _>(&mut &packet[..])
+                        .ok()
+                        .and_then(XErrorKind::from_header)
+                        .unwrap_or(XErrorKind::Unknown);
+                    log::warn!(
+                        target: TARGET,
+                        "<-- error seq={} from {}: {:?}",
+                        sequence,
+                        request,
+                        error
+                    );
+                    self.settle(sequence);
+                }
+                1 => {
+                    let (opcode, extension) = self.settle(sequence).unwrap_or_default();
+                    if let (Some(name), 1) = (extension, packet[8]) {
+                        self.extensions.insert(packet[9], name);
+                    }
+                    log::debug!(
+                        target: TARGET,
+                        "<-- reply seq={} to {} len={}{}",
+                        sequence,
+                        request_name(opcode).unwrap_or("?"),
+                        len,
+                        describe_reply(opcode, &packet)
+                    );
+                }
+                _ => {
+                    let code = packet[0] & 0x7f;
+                    log::debug!(
+                        target: TARGET,
+                        "<-- {}{} seq={}{}",
+                        EVENT_NAMES
+                            .get(code as usize)
+                            .map_or_else(|| format!("event {}", code), |name| name.to_string()),
+                        if packet[0] & 0x80 != 0 { " (sent)" } else { "" },
+                        sequence,
+                        describe_event(&packet)
+                    );
+                }
+            }
+            self.dump(&packet);
+        }
+    }
+
+    /// Takes the request `sequence` answers. Replies and errors come in request order,
+    /// so anything still awaited from before it never got one, extension requests
+    /// without a reply mostly
+    fn settle(&mut self, sequence: CARD16) -> Option<(CARD8, Option<String>)> {
+        // sequence numbers wrap, the awaited ones are at most half the range behind
+        self.awaiting
+            .retain(|&awaited, _| matches!(sequence.wrapping_sub(awaited), 0 | 0x8000..));
+        self.awaiting.remove(&sequence)
+    }
+
+    fn request_name(&self, major: CARD8, minor: CARD8) -> String {
+        match request_name(major) {
+            Some(name) => name.to_string(),
+            _ => match self.extensions.get(&major) {
+                Some(extension) => format!("{}:{}", extension, minor),
+                None => format!("request {}:{}", major, minor),
+            },
+        }
+    }
+
+    fn dump(&self, bytes: &[u8]) {
+        if self.hex_dump && log::log_enabled!(target: TARGET, log::Level::Trace) {
+            log::trace!(target: TARGET, "{}", hex_dump(bytes));
+        }
+    }
+}
+
+impl<S: io::Write> io::Write for Traced<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.socket.write(buf)?;
+        self.output.extend_from_slice(&buf[..written]);
+        self.log_output();
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.socket.flush()
+    }
+}
+
+impl<S: io::Read> io::Read for Traced<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.socket.read(buf)?;
+        self.input.extend_from_slice(&buf[..read]);
+        self.log_input();
+        Ok(read)
+    }
+}
+
+impl<S: AsRawFd> AsRawFd for Traced<S> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.as_raw_fd()
+    }
+}
+
+/// core requests that get a reply, extensions are assumed to and `settle` forgets the ones that don't
+fn has_reply(opcode: CARD8) -> bool {
+    matches!(
+        opcode,
+        3 | 14..=17 | 20 | 21 | 23 | 26 | 31 | 38..=40 | 43 | 44 | 47..=50 | 52 | 73 | 83..=87
+            | 91 | 92 | 97..=99 | 101 | 103 | 106 | 108 | 110 | 116..=119
+    ) || opcode >= 128
+}
+
+/// zero past the end, so a short or odd packet just logs less
+fn word_at(bytes: &[u8], offset: usize) -> CARD32 {
+    let word = bytes.get(offset..offset + 4);
+    word.map_or(0, |word| card32_at(word, 0))
+}
+
+fn string_at(bytes: &[u8], offset: usize, len: usize) -> String {
+    let end = (offset + len).min(bytes.len());
+    text::decode_latin1(bytes.get(offset..end).unwrap_or_default())
+}
+
+/// the interesting fields of a request, with a leading space
+fn describe_request(request: &[u8]) -> String {
+    let word = |offset| word_at(request, offset);
+    match request[0] {
+        opcodes::CREATE_WINDOW => format!(
+            " window={:#x} parent={:#x} {}x{}+{}+{} depth={}",
+            word(4),
+            word(8),
+            card16_at(request, 16),
+            card16_at(request, 18),
+            card16_at(request, 12) as INT16,
+            card16_at(request, 14) as INT16,
+            request[1]
+        ),
+        opcodes::INTERN_ATOM => format!(
+            " name={:?} only_if_exists={}",
+            string_at(request, 8, card16_at(request, 4) as usize),
+            request[1] != 0
+        ),
+        opcodes::QUERY_EXTENSION => format!(
+            " name={:?}",
+            string_at(request, 8, card16_at(request, 4) as usize)
+        ),
+        opcodes::CHANGE_PROPERTY => format!(
+            " window={:#x} property={} type={} format={} units={}",
+            word(4),
+            word(8),
+            word(12),
+            request[16],
+            word(20)
+        ),
+        opcodes::GET_PROPERTY => format!(
+            " window={:#x} property={} type={} offset={} length={}",
+            word(4),
+            word(8),
+            word(12),
+            word(16),
+            word(20)
+        ),
+        opcodes::DELETE_PROPERTY => format!(" window={:#x} property={}", word(4), word(8)),
+        opcodes::GET_ATOM_NAME => format!(" atom={}", word(4)),
+        opcodes::SEND_EVENT => format!(
+            " destination={:#x} event={}",
+            word(4),
+            EVENT_NAMES
+                .get((request.get(12).copied().unwrap_or_default() & 0x7f) as usize)
+                .unwrap_or(&"?")
+        ),
+        opcodes::OPEN_FONT => format!(
+            " font={:#x} name={:?}",
+            word(4),
+            string_at(request, 12, card16_at(request, 8) as usize)
+        ),
+        // most of the rest lead with the window or resource they act on
+        1..=127 if request.len() >= 8 => format!(" {:#x}", word(4)),
+        _ => String::new(),
+    }
+}
+
+fn describe_reply(opcode: CARD8, reply: &[u8]) -> String {
+    let word = |offset| word_at(reply, offset);
+    match opcode {
+        opcodes::INTERN_ATOM => format!(" atom={}", word(8)),
+        opcodes::GET_ATOM_NAME => {
+            format!(
+                " name={:?}",
+                string_at(reply, 32, card16_at(reply, 8) as usize)
+            )
+        }
+        opcodes::GET_PROPERTY => format!(
+            " format={} type={} bytes_after={} units={}",
+            reply[1],
+            word(8),
+            word(12),
+            word(16)
+        ),
+        opcodes::QUERY_EXTENSION => format!(" present={} major_opcode={}", reply[8] != 0, reply[9]),
+        opcodes::GET_INPUT_FOCUS => format!(" focus={:#x}", word(8)),
+        _ => String::new(),
+    }
+}
+
+fn describe_event(event: &[u8]) -> String {
+    let word = |offset| word_at(event, offset);
+    match event[0] & 0x7f {
+        event_codes::KEY_PRESS..=event_codes::LEAVE_NOTIFY => format!(
+            " detail={} window={:#x} pos=({}, {})",
+            event[1],
+            word(12),
+            card16_at(event, 24) as INT16,
+            card16_at(event, 26) as INT16
+        ),
+        event_codes::EXPOSE => format!(
+            " window={:#x} {}x{}+{}+{} count={}",
+            word(4),
+            card16_at(event, 12),
+            card16_at(event, 14),
+            card16_at(event, 8),
+            card16_at(event, 10),
+            card16_at(event, 16)
+        ),
+        event_codes::DESTROY_NOTIFY..=event_codes::MAP_NOTIFY => {
+            format!(" event={:#x} window={:#x}", word(4), word(8))
+        }
+        event_codes::CONFIGURE_NOTIFY => format!(
+            " window={:#x} {}x{}+{}+{}",
+            word(8),
+            card16_at(event, 20),
+            card16_at(event, 22),
+            card16_at(event, 16) as INT16,
+            card16_at(event, 18) as INT16
+        ),
+        event_codes::PROPERTY_NOTIFY => format!(
+            " window={:#x} atom={} state={}",
+            word(4),
+            word(8),
+            if event[16] == 0 {
+                "NewValue"
+            } else {
+                "Deleted"
+            }
+        ),
+        event_codes::CLIENT_MESSAGE => format!(
+            " window={:#x} type={} format={}",
+            word(4),
+            word(8),
+            event[1]
+        ),
+        _ => String::new(),
+    }
+}
+
+/// 16 bytes a line, offset first
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        let _ = write!(out, "\n  {:04x}:", line * 16);
+        for byte in chunk {
+            let _ = write!(out, " {:02x}", byte);
+        }
+    }
+    out
+}
+
+#[test]
+pub fn trace_descriptions() {
+    let mut request = vec![opcodes::INTERN_ATOM, 1, 4, 0, 4, 0, 0, 0];
+    request.extend_from_slice(b"TEST");
+    assert_eq!(
+        describe_request(&request),
+        " name=\"TEST\" only_if_exists=true"
+    );
+
+    // QueryExtension replies teach it the extension's name
+    let (server, stream) = mock::MockServer::new(mock::MockSetup::default());
+    server.on_request(opcodes::QUERY_EXTENSION, |request| {
+        (request.bytes(8, 5) == b"RANDR").then(|| mock::MockResponse::Reply {
+            data: 0,
+            body: vec![1, 140],
+        })
+    });
+    let ctx = RequestConnection::new(ByteOrder::LittleEndian, 11, 0, AuthProtocol::None)
+        .connect(Traced::new(stream))
+        .unwrap();
+    ctx.socket_cb(|socket| query_extension_request(socket, "RANDR"))
+        .unwrap();
+    assert_eq!(ctx.read_reply().unwrap()[9], 140);
+    assert_eq!(ctx.socket.borrow().request_name(140, 5), "RANDR:5");
+    assert_eq!(ctx.socket.borrow().awaiting.len(), 0);
+    // an extension request without a reply is dropped once a later one is answered
+    ctx.socket_cb(|socket| io::Write::write_all(socket, &[140, 1, 1, 0]))
+        .unwrap();
+    ctx.socket_cb(|socket| query_extension_request(socket, "RANDR"))
+        .unwrap();
+    ctx.read_reply().unwrap();
+    assert_eq!(ctx.socket.borrow().awaiting.len(), 0);
+    assert_eq!(hex_dump(&[0xde, 0xad]), "\n  0000: de ad");
+}
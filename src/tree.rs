@@ -0,0 +1,223 @@
+use super::*;
+pub use crate::proto::xproto::{
+    get_geometry_request, get_window_attributes_request, list_properties_request,
+    query_tree_request,
+};
+
+#[derive(Clone, Debug)]
+pub struct QueryTreeReply {
+    pub root: WINDOW,
+    /// `None` for a root window
+    pub parent: Option<WINDOW>,
+    /// in stacking order, bottom-most first
+    pub children: Vec<WINDOW>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Geometry {
+    pub root: WINDOW,
+    pub depth: CARD8,
+    /// relative to the parent's origin, to the outer corner of the border
+    pub x: INT16,
+    pub y: INT16,
+    pub width: CARD16,
+    pub height: CARD16,
+    pub border_width: CARD16,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WindowClass {
+    InputOutput,
+    InputOnly,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MapState {
+    Unmapped,
+    /// mapped but an ancestor isn't
+    Unviewable,
+    Viewable,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct WindowAttributes {
+    /// 0 NotUseful, 1 WhenMapped, 2 Always
+    pub backing_store: CARD8,
+    pub visual: Atom,
+    pub class: WindowClass,
+    pub bit_gravity: CARD8,
+    pub win_gravity: CARD8,
+    pub backing_planes: CARD32,
+    pub backing_pixel: CARD32,
+    pub save_under: bool,
+    pub map_is_installed: bool,
+    pub map_state: MapState,
+    pub override_redirect: bool,
+    /// `None` if the window has no colormap
+    pub colormap: Option<COLORMAP>,
+    /// what every client selected on the window OR'd together
+    pub all_event_masks: CARD32,
+    pub your_event_mask: CARD32,
+    pub do_not_propagate_mask: CARD16,
+}
+
+/// One window found by `XContext::walk_tree`
+#[derive(Clone, Debug)]
+pub struct WindowInfo {
+    pub window: WINDOW,
+    pub parent: Option<WINDOW>,
+    /// how far below the window the walk started at, that one is 0
+    pub depth: usize,
+    pub geometry: Geometry,
+    pub attributes: WindowAttributes,
+    pub name: Option<String>,
+    /// (instance, class)
+    pub class: Option<(String, String)>,
+}
+
+/// splits WM_CLASS into its two null terminated strings
+fn parse_wm_class(value: &[u8]) -> Option<(String, String)> {
+    let mut parts = value.split(|&b| b == 0);
+    let instance = text::decode_latin1(parts.next()?);
+    let class = text::decode_latin1(parts.next().unwrap_or_default());
+    Some((instance, class))
+}
+
+/// a window vanishing mid walk isn't worth failing the whole walk over
+fn is_gone(e: &XErrorKind) -> bool {
+    matches!(e, XErrorKind::Window { .. } | XErrorKind::Drawable { .. })
+}
+
+impl<S: io::Read + io::Write> XContext<S> {
+    pub fn query_tree(&self, window: WINDOW) -> Result<QueryTreeReply, XErrorKind> {
+        self.socket_cb(|socket| query_tree_request(socket, window))?;
+        let reply = self.read_reply()?;
+        let count = card16_at(&reply, 16) as usize;
+        let mut data = reply.get(32..).ok_or(XErrorKind::Unknown)?;
+        Ok(QueryTreeReply {
+            root: Atom(card32_at(&reply, 8)),
+            parent: (card32_at(&reply, 12) != 0).then(|| Atom(card32_at(&reply, 12))),
+            children: xio::read_primitive_list(&mut data, count)?,
+        })
+    }
+
+    pub fn get_geometry(&self, drawable: DRAWABLE) -> Result<Geometry, XErrorKind> {
+        self.socket_cb(|socket| get_geometry_request(socket, drawable))?;
+        let reply = self.read_reply()?;
+        Ok(Geometry {
+            root: Atom(card32_at(&reply, 8)),
+            depth: reply[1],
+            x: card16_at(&reply, 12) as INT16,
+            y: card16_at(&reply, 14) as INT16,
+            width: card16_at(&reply, 16),
+            height: card16_at(&reply, 18),
+            border_width: card16_at(&reply, 20),
+        })
+    }
+
+    pub fn get_window_attributes(&self, window: WINDOW) -> Result<WindowAttributes, XErrorKind> {
+        self.socket_cb(|socket| get_window_attributes_request(socket, window))?;
+        let reply = self.read_reply()?;
+        let reply = reply.get(..44).ok_or(XErrorKind::Unknown)?;
+        let colormap = card32_at(reply, 28);
+        Ok(WindowAttributes {
+            backing_store: reply[1],
+            visual: Atom(card32_at(reply, 8)),
+            class: match card16_at(reply, 12) {
+                2 => WindowClass::InputOnly,
+                _ => WindowClass::InputOutput,
+            },
+            bit_gravity: reply[14],
+            win_gravity: reply[15],
+            backing_planes: card32_at(reply, 16),
+            backing_pixel: card32_at(reply, 20),
+            save_under: reply[24] != 0,
+            map_is_installed: reply[25] != 0,
+            map_state: match reply[26] {
+                0 => MapState::Unmapped,
+                1 => MapState::Unviewable,
+                _ => MapState::Viewable,
+            },
+            override_redirect: reply[27] != 0,
+            colormap: (colormap != 0).then_some(colormap),
+            all_event_masks: card32_at(reply, 32),
+            your_event_mask: card32_at(reply, 36),
+            do_not_propagate_mask: card16_at(reply, 40),
+        })
+    }
+
+    /// the atoms of every property set on `window`
+    pub fn list_properties(&self, window: WINDOW) -> Result<Vec<Atom>, XErrorKind> {
+        self.socket_cb(|socket| list_properties_request(socket, window))?;
+        let reply = self.read_reply()?;
+        let count = card16_at(&reply, 8) as usize;
+        let mut data = reply.get(32..).ok_or(XErrorKind::Unknown)?;
+        Ok(xio::read_primitive_list(&mut data, count)?)
+    }
+
+    /// WM_NAME decoded by its type, `None` if it isn't set
+    pub fn wm_name(&self, window: WINDOW) -> Result<Option<String>, XErrorKind> {
+        self.get_text_property(window, predefined_atoms::WM_NAME)
+    }
+
+    /// WM_CLASS as (instance, class), `None` if it isn't set
+    pub fn wm_class(&self, window: WINDOW) -> Result<Option<(String, String)>, XErrorKind> {
+        let reply = self.get_property_all(false, window, predefined_atoms::WM_CLASS, None)?;
+        Ok(if reply.format == 8 {
+            parse_wm_class(&reply.value)
+        } else {
+            None
+        })
+    }
+
+    /// Everything below `window` and `window` itself, depth first with parents before their
+    /// children.\
+    /// It's one round trip per request per window, so walking from the root of a busy
+    /// display takes a moment. Windows destroyed during the walk are left out
+    pub fn walk_tree(&self, window: WINDOW) -> Result<Vec<WindowInfo>, XErrorKind> {
+        let mut windows = vec![];
+        let mut stack = vec![(window, 0)];
+        while let Some((window, depth)) = stack.pop() {
+            match self.window_info(window, depth) {
+                Ok((info, children)) => {
+                    // reversed so the bottom-most child comes out first
+                    stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+                    windows.push(info);
+                }
+                Err(e) if is_gone(&e) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(windows)
+    }
+
+    fn window_info(
+        &self,
+        window: WINDOW,
+        depth: usize,
+    ) -> Result<(WindowInfo, Vec<WINDOW>), XErrorKind> {
+        let tree = self.query_tree(window)?;
+        let info = WindowInfo {
+            window,
+            parent: tree.parent,
+            depth,
+            geometry: self.get_geometry(window.0)?,
+            attributes: self.get_window_attributes(window)?,
+            name: self.wm_name(window)?,
+            class: self.wm_class(window)?,
+        };
+        Ok((info, tree.children))
+    }
+}
+
+#[test]
+pub fn wm_class_parsing() {
+    assert_eq!(
+        parse_wm_class(b"xterm\0XTerm\0"),
+        Some(("xterm".to_string(), "XTerm".to_string()))
+    );
+    assert_eq!(
+        parse_wm_class(b"solo"),
+        Some(("solo".to_string(), String::new()))
+    );
+}
@@ -1,5 +1,8 @@
 use super::*;
 
+/// The value list this used to stand for can't be laid out as a struct,
+/// which values are there depends on the mask
+#[deprecated(note = "use WindowBuilder::with_value with a value_masks bit or the typed setters")]
 #[repr(C, packed(1))]
 #[derive(Copy, Clone, Default)]
 pub struct WindowValue {
@@ -20,6 +23,20 @@ pub struct WindowValue {
     cursor: CARD32,
 }
 
+/// Errors `WindowBuilder::build` checks for are only reported, through `log` with `trace` on.\
+/// A socket error is returned
+fn report(result: Result<(), XErrorKind>) -> io::Result<()> {
+    match result {
+        Err(XErrorKind::SocketIO(e)) => Err(e),
+        #[cfg(feature = "trace")]
+        Err(e) => {
+            log::warn!(target: "xproto::trace", "creating a window failed: {}", e);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
 #[derive(Clone)]
 pub struct XWindow<S> {
     ctx: XContext<S>,
@@ -29,6 +46,8 @@ pub struct XWindow<S> {
     y: i16,
     w: u16,
     h: u16,
+    /// the colormap `with_visual_type` made for the window, freed along with it
+    colormap: Option<COLORMAP>,
 }
 impl<S> XWindow<S> {
     pub fn parent(&self) -> Atom {
@@ -37,13 +56,49 @@ impl<S> XWindow<S> {
     pub fn id(&self) -> Atom {
         self.id
     }
+    pub fn context(&self) -> &XContext<S> {
+        &self.ctx
+    }
+    pub fn pos(&self) -> (i16, i16) {
+        (self.x, self.y)
+    }
+    pub fn size(&self) -> (u16, u16) {
+        (self.w, self.h)
+    }
+}
+impl<S: io::Read + io::Write> XWindow<S> {
+    /// Sets WM_NAME, as STRING or COMPOUND_TEXT depending on what's in it,
+    /// and _NET_WM_NAME as UTF8_STRING for window managers that prefer that
+    pub fn set_title(&self, title: &str) -> Result<(), XErrorKind> {
+        self.ctx
+            .set_text_property(self.id, predefined_atoms::WM_NAME, title)?;
+        let net_wm_name = self.ctx.intern_atom("_NET_WM_NAME", false)?;
+        self.ctx.set_utf8_property(self.id, net_wm_name, title)
+    }
+
+    /// Destroys the window and frees the colormap `WindowBuilder` made for it, if any.
+    /// Other clones of this window are left with a stale id
+    pub fn destroy(self) -> Result<(), XErrorKind> {
+        self.ctx.socket_cb(|socket| {
+            destroy_window(socket, self.id)?;
+            match self.colormap {
+                Some(cmap) => colormap::free_colormap(socket, cmap),
+                None => Ok(()),
+            }
+        })?;
+        self.ctx.sync()
+    }
+
+    /// shows `cursor` while the pointer is in the window, `None` goes back to the parent's
+    pub fn define_cursor(&self, cursor: Option<CURSOR>) -> Result<(), XErrorKind> {
+        self.ctx
+            .socket_cb(|socket| cursor::define_cursor(socket, self.id, cursor))?;
+        Ok(())
+    }
 }
 pub struct WindowBuilder<'a, T> {
     ctx: &'a mut XContext<T>,
-    opcode: CARD8,
     depth: CARD8,
-    request_length: CARD16,
-    wid: Atom,
     parent: Atom,
     x: INT16,
     y: INT16,
@@ -52,8 +107,10 @@ pub struct WindowBuilder<'a, T> {
     border_width: CARD16,
     class: CARD16,
     visual: Atom,
-    value_mask: CARD32,
-    value_list: Vec<WindowValue>,
+    /// (value-mask bit, value) pairs that go out in the request's value list
+    values: Vec<(CARD32, CARD32)>,
+    /// a colormap `with_visual_type` wants made for (id, visual) before the window
+    new_colormap: Option<(COLORMAP, Atom)>,
     window_id: Atom,
     title: Option<&'a str>,
 }
@@ -62,25 +119,22 @@ where
     T: io::Write + io::Read,
 {
     pub fn new(state: &'a mut XContext<T>) -> Self {
-        let mut res_unitit = MaybeUninit::<Self>::zeroed();
         let window_id = Atom(state.gen_id());
-        unsafe {
-            let res = res_unitit.assume_init_mut();
-            res.title = None;
-            res.window_id = window_id;
-            res.opcode = opcodes::CREATE_WINDOW;
-            res.request_length = 8;
-            res.wid = window_id;
-            res.parent = state.info.list_of_screen[0].root;
-            res.depth = 0;
-            res.border_width = 0;
-            res.class = 0;
-            res.visual = Atom(0); //copy from parent
-            res.value_mask = 0;
-            res.value_list = vec![];
-            res.ctx = state;
-
-            res_unitit.assume_init_read()
+        Self {
+            depth: 0,
+            parent: state.info.list_of_screen[0].root,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            border_width: 0,
+            class: 0,
+            visual: Atom(0), //copy from parent
+            values: vec![],
+            new_colormap: None,
+            window_id,
+            title: None,
+            ctx: state,
         }
     }
 
@@ -110,128 +164,141 @@ where
         self
     }
 
-    pub fn with_value(mut self, value: WindowValue) -> Self {
-        self.request_length += 1;
-        self.value_list.push(value);
+    /// Uses `visual` for the window along with everything a non default visual needs:
+    /// its depth, a colormap made for it and a border pixel, the parent's border
+    /// pixmap having the wrong depth would make CreateWindow fail with BadMatch.\
+    /// `build` skips the colormap if the parent already has `visual`, otherwise
+    /// `XWindow::destroy` frees it
+    pub fn with_visual_type(mut self, visual: &XVisualType) -> Self {
+        let visual_id = visual.visual_id;
+        let depth = self
+            .ctx
+            .info
+            .list_of_screen
+            .iter()
+            .find_map(|screen| screen.depth_of_visual(visual_id));
+        let Some(depth) = depth else {
+            // not a visual of this display, let the server complain about it
+            self.visual = visual_id;
+            return self;
+        };
+        self.visual = visual_id;
+        self.depth = depth;
+        let mid = self.ctx.gen_id();
+        self.new_colormap = Some((mid, visual_id));
+        self.set_value(value_masks::COLORMAP, mid);
+        self.set_value(value_masks::BORDER_PIXEL, 0);
+        self
+    }
+
+    pub fn with_colormap(mut self, colormap: COLORMAP) -> Self {
+        self.set_value(value_masks::COLORMAP, colormap);
         self
     }
 
+    /// Sets the attribute for `bit`, one of `value_masks`, replacing an earlier value for it
+    pub fn with_value(mut self, bit: CARD32, value: CARD32) -> Self {
+        self.set_value(bit, value);
+        self
+    }
+
+    /// the `set_of_events_common` this client gets for the window
+    pub fn with_event_mask(self, event_mask: CARD32) -> Self {
+        self.with_value(value_masks::EVENT_MASK, event_mask)
+    }
+
+    /// keeps the window manager from reparenting or placing the window, for popups and menus
+    pub fn with_override_redirect(self, override_redirect: bool) -> Self {
+        self.with_value(value_masks::OVERRIDE_REDIRECT, override_redirect as CARD32)
+    }
+
+    pub fn with_background_pixel(self, pixel: CARD32) -> Self {
+        self.with_value(value_masks::BACKGROUND_PIXEL, pixel)
+    }
+
+    pub fn with_cursor(self, cursor: CURSOR) -> Self {
+        self.with_value(value_masks::CURSOR, cursor)
+    }
+
+    fn set_value(&mut self, bit: CARD32, value: CARD32) {
+        self.values.retain(|&(b, _)| b != bit);
+        self.values.push((bit, value));
+    }
+
     pub fn with_title(mut self, title: &'a str) -> Self {
         self.title = Some(title);
         self
     }
 
     pub fn build(mut self) -> Result<XWindow<T>, io::Error> {
-        let ctx = self.ctx;
-        let &mut opcode = &mut self.opcode;
-        let &mut depth = &mut self.depth;
-        let &mut request_length = &mut self.request_length;
-        let &mut wid = &mut self.wid;
-        let &mut parent = &mut self.parent;
-        let &mut x = &mut self.x;
-        let &mut y = &mut self.y;
-        let &mut width = &mut self.width;
-        let &mut height = &mut self.height;
-        let &mut border_width = &mut self.border_width;
-        let &mut class = &mut self.class;
-        let &mut visual = &mut self.visual;
-        let &mut value_mask = &mut self.value_mask;
-        let socket = &mut *ctx.socket.as_ref().borrow_mut();
-
-        xio::write_primitive(socket, opcode)?;
-        xio::write_primitive(socket, depth)?;
-        xio::write_primitive(socket, request_length)?;
-        xio::write_primitive(socket, wid)?;
-        xio::write_primitive(socket, parent)?;
-        xio::write_primitive(socket, x)?;
-        xio::write_primitive(socket, y)?;
-        xio::write_primitive(socket, width)?;
-        xio::write_primitive(socket, height)?;
-        xio::write_primitive(socket, border_width)?;
-        xio::write_primitive(socket, class)?;
-        xio::write_primitive(socket, visual)?;
-        xio::write_primitive(socket, value_mask)?;
-
-        if let Err(e) = check_for_error(socket) {
-            println!("err = {:?}", e);
-        } else {
-            println!("no errors");
+        if let Some((mid, visual)) = self.new_colormap {
+            // the parent's colormap does for a window of the same visual
+            if parent_visual(self.ctx, self.parent)? == Some(visual) {
+                self.values
+                    .retain(|&value| value != (value_masks::COLORMAP, mid));
+                self.new_colormap = None;
+            }
         }
-        map_window(socket, self.window_id.0)?;
+        let ctx = self.ctx;
+        let mut guard = ctx.lock_socket()?;
+        let socket = &mut xio::Counted::new(&mut *guard, &ctx.requests);
 
-        allow_events(socket, EventMode::AsyncBoth, Timestamp::current_time())?;
-        if let Err(e) = check_for_error(socket) {
-            println!("err = {:?}", e);
-        } else {
-            println!("no errors");
+        let mut new_colormap = None;
+        if let Some((mid, visual)) = self.new_colormap {
+            // skipped if `with_colormap` replaced it afterwards
+            if self.values.contains(&(value_masks::COLORMAP, mid)) {
+                colormap::create_colormap(socket, ColormapAlloc::None, mid, self.parent, visual)?;
+                new_colormap = Some(mid);
+            }
         }
 
-        // let grab_err = grab_keyboard(
-        //     socket,
-        //     true,
-        //     Atom(0),
-        //     Timestamp::current_time(),
-        //     SynchKind::Asynchronous,
-        //     SynchKind::Asynchronous,
-        // );
-        // println!("grab err ={:?} ",grab_err);
-        // if let Err(e) = check_for_error(socket) {
-        //     println!("err = {:?}", e);
-        // } else {
-        //     println!("no errors");
-        // }
+        proto::xproto::create_window(
+            socket,
+            self.depth,
+            self.window_id,
+            self.parent,
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            self.border_width,
+            self.class,
+            self.visual.0,
+            &self.values,
+        )?;
 
-        if let Some(title) = self.title {
-            property::change_property(
-                socket,
-                PropertyMode::Replace,
-                xconsts::predefined_atoms::WM_NAME,
-                xconsts::predefined_atoms::STRING,
-                self.window_id.0,
-                PropertyFormat::Bytes,
-                title,
-            )?;
-            if let Err(e) = check_for_error(socket) {
-                println!("err = {:?}", e);
-            } else {
-                println!("no errors");
-            }
-        }
+        map_window(socket, self.window_id.0)?;
+
+        allow_events(socket, EventMode::AsyncBoth, Timestamp::current_time())?;
 
         grab_button(
             socket,
             false,
             self.window_id,
-            0,
+            PointerEventMask::NONE,
             SynchKind::Asynchronous,
             SynchKind::Asynchronous,
-            Atom(0),
-            Atom(0),
-            0,
             None,
+            None,
+            None,
+            ModMask::ANY,
         )?;
-        if let Err(e) = check_for_error(socket) {
-            println!("err = {:?}", e);
-        } else {
-            println!("no errors");
-        }
 
         grab_key(
             socket,
             false,
             self.window_id,
+            ModMask::ANY,
             None,
-            0,
             SynchKind::Asynchronous,
             SynchKind::Asynchronous,
         )?;
-        if let Err(e) = check_for_error(socket) {
-            println!("err = {:?}", e);
-        } else {
-            println!("no errors");
-        }
+        // the round trip and setting the title need the socket back
+        drop(guard);
+        // the first error of the requests above, if any, the rest stay queued for `next_event`
+        report(ctx.sync())?;
 
-        Ok(XWindow {
+        let window = XWindow {
             ctx: ctx.clone(),
             id: self.window_id,
             parent_id: self.parent,
@@ -239,6 +306,119 @@ where
             y: self.y,
             w: self.width,
             h: self.height,
-        })
+            colormap: new_colormap,
+        };
+        if let Some(title) = self.title {
+            report(window.set_title(title))?;
+        }
+        Ok(window)
+    }
+}
+
+/// The visual of `parent`, from the setup for a root window and a round trip otherwise,
+/// `None` if the server wouldn't say
+fn parent_visual<T: io::Read + io::Write>(
+    ctx: &XContext<T>,
+    parent: Atom,
+) -> io::Result<Option<Atom>> {
+    let screen = ctx
+        .info
+        .list_of_screen
+        .iter()
+        .find(|screen| screen.root == parent);
+    if let Some(screen) = screen {
+        return Ok(Some(screen.root_visual));
     }
+    match ctx.get_window_attributes(parent) {
+        Ok(attributes) => Ok(Some(attributes.visual)),
+        Err(XErrorKind::SocketIO(e)) => Err(e),
+        Err(_) => Ok(None),
+    }
+}
+
+#[test]
+pub fn create_window_encoding() {
+    let (server, mut ctx) = mock::MockServer::connect();
+    let window = WindowBuilder::new(&mut ctx)
+        .with_pos((10, -20))
+        .with_width(300)
+        .with_height(200)
+        .with_colormap(0x55)
+        .build()
+        .unwrap();
+    let create = server.take_requests().remove(0);
+    assert_eq!(create.opcode, opcodes::CREATE_WINDOW);
+    assert_eq!(create.body.len(), 9 * 4 - 4);
+    assert_eq!(Atom(create.card32(4)), window.id());
+    assert_eq!(create.card16(12), 10);
+    assert_eq!(create.card16(14), (-20i16) as CARD16);
+    assert_eq!(create.card16(16), 300);
+    assert_eq!(create.card32(28), value_masks::COLORMAP);
+    assert_eq!(create.card32(32), 0x55);
+}
+
+#[test]
+pub fn window_values() {
+    let (server, mut ctx) = mock::MockServer::connect();
+    let window = WindowBuilder::new(&mut ctx)
+        .with_cursor(0x77)
+        .with_event_mask(set_of_events_common::KEY_PRESS)
+        .with_override_redirect(true)
+        .with_value(value_masks::EVENT_MASK, set_of_events_common::EXPOSURE)
+        .build()
+        .unwrap();
+    let create = server.take_requests().remove(0);
+    assert_eq!(
+        create.card32(28),
+        value_masks::OVERRIDE_REDIRECT | value_masks::EVENT_MASK | value_masks::CURSOR
+    );
+    assert_eq!(create.card32(32), 1);
+    assert_eq!(create.card32(36), set_of_events_common::EXPOSURE);
+    assert_eq!(create.card32(40), 0x77);
+
+    window.define_cursor(None).unwrap();
+    let change = server.take_requests().remove(0);
+    assert_eq!(change.opcode, opcodes::CHANGE_WINDOW_ATTRIBUTES);
+    assert_eq!(Atom(change.card32(4)), window.id());
+    assert_eq!(change.card32(8), value_masks::CURSOR);
+    assert_eq!(change.card32(12), 0);
+}
+
+#[test]
+pub fn visual_type_colormap() {
+    let (server, mut ctx) = mock::MockServer::connect();
+    let visuals: Vec<XVisualType> = ctx.info.list_of_screen[0]
+        .depth_list
+        .iter()
+        .flat_map(|depth| depth.visuals.clone())
+        .collect();
+    let root_visual = visuals.iter().find(|v| v.visual_id == Atom(0x21)).unwrap();
+    let argb_visual = visuals.iter().find(|v| v.visual_id == Atom(0x22)).unwrap();
+
+    // the root's visual needs no colormap of its own
+    let window = WindowBuilder::new(&mut ctx)
+        .with_visual_type(root_visual)
+        .build()
+        .unwrap();
+    let requests = server.take_requests();
+    assert!(requests
+        .iter()
+        .all(|r| r.opcode != opcodes::CREATE_COLORMAP));
+    assert_eq!(requests[0].card32(28) & value_masks::COLORMAP, 0);
+    window.destroy().unwrap();
+    let requests = server.take_requests();
+    assert!(requests.iter().all(|r| r.opcode != opcodes::FREE_COLORMAP));
+
+    let window = WindowBuilder::new(&mut ctx)
+        .with_visual_type(argb_visual)
+        .build()
+        .unwrap();
+    let requests = server.take_requests();
+    assert_eq!(requests[0].opcode, opcodes::CREATE_COLORMAP);
+    let cmap = requests[0].card32(4);
+    window.destroy().unwrap();
+    let requests = server.take_requests();
+    assert_eq!(requests[0].opcode, opcodes::DESTROY_WINDOW);
+    assert_eq!(requests[1].opcode, opcodes::FREE_COLORMAP);
+    assert_eq!(requests[1].card32(4), cmap);
 }
@@ -1,49 +1,77 @@
 use super::*;
 
-mod create_window;
-pub use create_window::value_masks;
+/// the CreateWindow/ChangeWindowAttributes value mask bits, generated from proto/xproto.xml
+pub mod value_masks {
+    pub use crate::proto::xproto::cw::*;
+    pub const BACKGROUND_PIXMAP: u32 = BACK_PIXMAP;
+    pub const BACKGROUND_PIXEL: u32 = BACK_PIXEL;
+    pub const DO_NOT_PROPAGATE_MASK: u32 = DONT_PROPAGATE;
+}
 
+/// SETofEVENT, generated from proto/xproto.xml
 pub mod set_of_events_common {
-    pub const KEY_PRESS: u16 = 0x00000001;
-    pub const KEY_RELEASE: u16 = 0x00000002;
-    pub const BUTTON_PRESS: u16 = 0x00000004;
-    pub const BUTTON_RELEASE: u16 = 0x00000008;
-    pub const ENTER_WINDOW: u16 = 0x00000010;
-    pub const LEAVE_WINDOW: u16 = 0x00000020;
-    pub const POINTER_MOTION: u16 = 0x00000040;
-    pub const POINTER_MOTION_HINT: u16 = 0x00000080;
-    pub const BUTTON1_MOTION: u16 = 0x00000100;
-    pub const BUTTON2_MOTION: u16 = 0x00000200;
-    pub const BUTTON3_MOTION: u16 = 0x00000400;
-    pub const BUTTON4_MOTION: u16 = 0x00000800;
-    pub const BUTTON5_MOTION: u16 = 0x00001000;
-    pub const BUTTON_MOTION: u16 = 0x00002000;
-    pub const KEYMAP_STATE: u16 = 0x00004000;
-    pub const EXPOSURE: u16 = 0x00008000;
-    pub const VISIBILITY_CHANGE: u32 = 0x00010000;
-    pub const STRUCTURE_NOTIFY: u32 = 0x00020000;
-    pub const RESIZE_REDIRECT: u32 = 0x00040000;
-    pub const SUBSTRUCTURE_NOTIFY: u32 = 0x00080000;
-    pub const SUBSTRUCTURE_REDIRECT: u32 = 0x00100000;
-    pub const FOCUS_CHANGE: u32 = 0x00200000;
-    pub const PROPERTY_CHANGE: u32 = 0x00400000;
-    pub const COLORMAP_CHANGE: u32 = 0x00800000;
-    pub const OWNER_GRAB_BUTTON: u32 = 0x01000000;
-    pub const UNUSED_EVENT: u32 = 0xFE000000;
-    pub const UNUSED_POINTER: u32 = 0xfff8003;
+    pub use crate::proto::xproto::event_mask::*;
+    pub const COLORMAP_CHANGE: u32 = COLOR_MAP_CHANGE;
+}
+
+/// the state field of input events, also used for grab modifiers
+pub mod key_masks {
+    pub const SHIFT: u16 = 0x0001;
+    pub const LOCK: u16 = 0x0002;
+    pub const CONTROL: u16 = 0x0004;
+    pub const MOD1: u16 = 0x0008;
+    pub const MOD2: u16 = 0x0010;
+    pub const MOD3: u16 = 0x0020;
+    pub const MOD4: u16 = 0x0040;
+    pub const MOD5: u16 = 0x0080;
+    pub const BUTTON1: u16 = 0x0100;
+    pub const BUTTON2: u16 = 0x0200;
+    pub const BUTTON3: u16 = 0x0400;
+    pub const BUTTON4: u16 = 0x0800;
+    pub const BUTTON5: u16 = 0x1000;
+    pub const ANY_MODIFIER: u16 = 0x8000;
 }
 
-pub mod opcodes {
-    pub const CREATE_WINDOW: u8 = 1;
-    pub const MAP_WINDOW: u8 = 8;
-    pub const CHANGE_PROPERTY: u8 = 18;
-    pub const GRAB_BUTTON: u8 = 28;
-    pub const GRAB_KEYBOARD: u8 = 31;
-    pub const UNGRAB_KEYBOARD: u8 = 32;
-    pub const ALLOW_EVENTS: u8 = 35;
-    pub const GRAB_KEY: u8 = 33;
+pub mod event_codes {
+    pub const KEY_PRESS: u8 = 2;
+    pub const KEY_RELEASE: u8 = 3;
+    pub const BUTTON_PRESS: u8 = 4;
+    pub const BUTTON_RELEASE: u8 = 5;
+    pub const MOTION_NOTIFY: u8 = 6;
+    pub const ENTER_NOTIFY: u8 = 7;
+    pub const LEAVE_NOTIFY: u8 = 8;
+    pub const FOCUS_IN: u8 = 9;
+    pub const FOCUS_OUT: u8 = 10;
+    pub const KEYMAP_NOTIFY: u8 = 11;
+    pub const EXPOSE: u8 = 12;
+    pub const GRAPHICS_EXPOSURE: u8 = 13;
+    pub const NO_EXPOSURE: u8 = 14;
+    pub const VISIBILITY_NOTIFY: u8 = 15;
+    pub const CREATE_NOTIFY: u8 = 16;
+    pub const DESTROY_NOTIFY: u8 = 17;
+    pub const UNMAP_NOTIFY: u8 = 18;
+    pub const MAP_NOTIFY: u8 = 19;
+    pub const MAP_REQUEST: u8 = 20;
+    pub const REPARENT_NOTIFY: u8 = 21;
+    pub const CONFIGURE_NOTIFY: u8 = 22;
+    pub const CONFIGURE_REQUEST: u8 = 23;
+    pub const GRAVITY_NOTIFY: u8 = 24;
+    pub const RESIZE_REQUEST: u8 = 25;
+    pub const CIRCULATE_NOTIFY: u8 = 26;
+    pub const CIRCULATE_REQUEST: u8 = 27;
+    pub const PROPERTY_NOTIFY: u8 = 28;
+    pub const SELECTION_CLEAR: u8 = 29;
+    pub const SELECTION_REQUEST: u8 = 30;
+    pub const SELECTION_NOTIFY: u8 = 31;
+    pub const COLORMAP_NOTIFY: u8 = 32;
+    pub const CLIENT_MESSAGE: u8 = 33;
+    pub const MAPPING_NOTIFY: u8 = 34;
+    pub const GENERIC_EVENT: u8 = 35;
 }
 
+/// generated from proto/xproto.xml
+pub use crate::proto::xproto::opcodes;
+
 pub mod predefined_atoms {
     use super::*;
     pub const SECONDARY: Atom = Atom(2);
@@ -3,8 +3,14 @@ use super::*;
 use std::io::{self, Read, Write};
 
 mod hasbytes;
+mod request;
+mod sequence;
+mod wire;
 
 pub use hasbytes::*;
+pub use request::*;
+pub use sequence::*;
+pub use wire::*;
 
 pub fn write_primitive<T, Writable: Write>(file: &mut Writable, prim: T) -> io::Result<()>
 where
@@ -22,17 +28,44 @@ where
     file.write_all(prim.as_bytes_le(&mut buffer))
 }
 
+/// reads a `T` that always takes up the same number of bytes straight off a stream
 pub fn read_primitive<T, Readable>(file: &mut Readable) -> Result<T, std::io::Error>
 where
-    T: Copy + Default + Sized,
+    T: Deserialize,
     Readable: Read,
 {
-    let mut data = T::default();
-    let bytes_to_read: usize = std::mem::size_of::<T>();
-    let data_as_slice =
-        unsafe { std::slice::from_raw_parts_mut((&mut data) as *mut T as *mut u8, bytes_to_read) };
-    file.read_exact(data_as_slice)?;
-    Ok(data)
+    let Some(size) = T::SIZE else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "read_primitive needs something with a fixed size",
+        ));
+    };
+    let mut small = [0u8; 64];
+    let mut large = vec![];
+    let buffer = match size <= small.len() {
+        true => &mut small[..size],
+        false => {
+            large.resize(size, 0);
+            &mut large[..]
+        }
+    };
+    file.read_exact(buffer)?;
+    Reader::new(buffer).read()
+}
+
+/// the 16 bit field at `offset` of a reply or event, panics past the end
+pub fn card16_at(packet: &[u8], offset: usize) -> CARD16 {
+    CARD16::from_le_bytes([packet[offset], packet[offset + 1]])
+}
+
+/// the 32 bit field at `offset` of a reply or event, panics past the end
+pub fn card32_at(packet: &[u8], offset: usize) -> CARD32 {
+    CARD32::from_le_bytes([
+        packet[offset],
+        packet[offset + 1],
+        packet[offset + 2],
+        packet[offset + 3],
+    ])
 }
 
 pub fn read_primitive_list<Prim, Data: Read>(
@@ -40,24 +73,49 @@ pub fn read_primitive_list<Prim, Data: Read>(
     len: usize,
 ) -> Result<Vec<Prim>, std::io::Error>
 where
-    Prim: Copy + Default + Sized,
+    Prim: Deserialize,
 {
     (0..len).map(|_| read_primitive(socket)).collect()
 }
 
+/// reads a STRING8, which the protocol says is Latin-1
+pub fn read_latin1_string<Data: Read>(
+    socket: Data,
+    string_len: usize,
+) -> Result<String, std::io::Error> {
+    let mut bytes = vec![];
+    read_growing(socket, &mut bytes, string_len)?;
+    Ok(text::decode_latin1(&bytes))
+}
+
+/// always was Latin-1, not ASCII
+#[deprecated(note = "use read_latin1_string")]
 pub fn read_ascii_string<Data: Read>(
-    mut socket: Data,
+    socket: Data,
     string_len: usize,
 ) -> Result<String, std::io::Error> {
-    (0..string_len)
-        .map(|_| read_primitive::<CARD8, _>(&mut socket).map(|a| a as char))
-        .collect()
+    read_latin1_string(socket, string_len)
+}
+
+/// Appends `len` bytes to `out`.\
+/// `len` comes from the server so the buffer grows with what actually arrives,
+/// a made up length runs out of input instead of memory
+pub fn read_growing<Data: Read>(socket: Data, out: &mut Vec<u8>, len: usize) -> io::Result<()> {
+    let want = out.len().saturating_add(len);
+    socket.take(len as u64).read_to_end(out)?;
+    match out.len() < want {
+        true => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the server sent less than it said it would",
+        )),
+        false => Ok(()),
+    }
 }
 
 pub fn write_padding<T: Write>(n: usize, out: &mut T) -> Result<usize, std::io::Error> {
     let padding = (4 - n % 4) % 4;
     for _ in 0..padding {
-        out.write(&[0])?;
+        out.write_all(&[0])?;
     }
     Ok(padding)
 }
@@ -67,20 +125,45 @@ pub fn read_padding<T: Read>(mut out: T, n: usize) -> Result<usize, std::io::Err
     let padding = (4 - n % 4) % 4;
     let mut byte = [0u8];
     for _ in 0..padding {
-        out.read(&mut byte)?;
+        out.read_exact(&mut byte)?;
     }
     Ok(padding)
 }
 
-pub fn flush_read<T: Read>(mut file: T) -> Result<(), std::io::Error> {
-    let mut buffer = [0u8; 31];
-    loop {
-        let bytes_read = file.read(&mut buffer)?;
-        if bytes_read < buffer.len() || bytes_read == 0 {
-            break;
-        }
+/// how many bytes the packet at the start of `input` takes up, `None` if it isn't all there
+pub(crate) fn packet_len(input: &[u8]) -> Option<usize> {
+    let header = input.get(..32)?;
+    let len = if header[0] == 1 {
+        let reply_length = CARD32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        32 + reply_length as usize * 4
+    } else {
+        32
+    };
+    (input.len() >= len).then_some(len)
+}
+
+/// how long the connection setup request at the start of `input` is, if it's all there
+#[cfg(any(test, feature = "mock", feature = "trace"))]
+pub(crate) fn setup_request_len(input: &[u8]) -> Option<usize> {
+    let header = input.get(..12)?;
+    let pad = |len: usize| len.div_ceil(4) * 4;
+    let name_len = CARD16::from_le_bytes([header[6], header[7]]) as usize;
+    let data_len = CARD16::from_le_bytes([header[8], header[9]]) as usize;
+    let len = 12 + pad(name_len) + pad(data_len);
+    (input.len() >= len).then_some(len)
+}
+
+/// how long the request at the start of `input` is, if it's all there
+#[cfg(any(test, feature = "mock", feature = "trace"))]
+pub(crate) fn request_len(input: &[u8]) -> Option<usize> {
+    let header = input.get(..4)?;
+    let mut len = CARD16::from_le_bytes([header[2], header[3]]) as usize * 4;
+    if len == 0 {
+        // BIG-REQUESTS puts the real length after the header
+        let big = input.get(4..8)?;
+        len = CARD32::from_le_bytes([big[0], big[1], big[2], big[3]]) as usize * 4;
     }
-    Ok(())
+    (input.len() >= len.max(4)).then_some(len.max(4))
 }
 
 #[test]
@@ -0,0 +1,71 @@
+use super::*;
+
+/// Assembles a single request in memory so its length field can be filled in once
+/// everything has been written.\
+/// Requests longer than 65535 units are sent in the BIG-REQUESTS form:
+/// the 16 bit length is 0 and a 32 bit length follows the header
+pub struct RequestBuffer {
+    data: Vec<u8>,
+}
+
+impl RequestBuffer {
+    pub fn new(opcode: CARD8, data: CARD8) -> Self {
+        let mut data = vec![opcode, data, 0, 0];
+        data.reserve(28);
+        Self { data }
+    }
+
+    /// length of the request in 4 byte units, as it'll go out on the wire
+    pub fn units(&self) -> usize {
+        let units = self.data.len().div_ceil(4);
+        if units > u16::MAX as usize {
+            units + 1
+        } else {
+            units
+        }
+    }
+
+    pub fn send<S: Write>(mut self, socket: &mut S) -> io::Result<()> {
+        write_padding(self.data.len(), &mut self.data)?;
+        let units = self.data.len() / 4;
+        if let Ok(units) = CARD16::try_from(units) {
+            self.data[2..4].copy_from_slice(&units.to_le_bytes());
+            return socket.write_all(&self.data);
+        }
+        // the extended length counts the extra 4 bytes it takes up
+        let big_units = CARD32::try_from(units + 1)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "request too large"))?;
+        socket.write_all(&self.data[0..4])?;
+        write_primitive(socket, big_units)?;
+        socket.write_all(&self.data[4..])
+    }
+}
+
+impl Write for RequestBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+pub fn big_request_length() {
+    let mut small = RequestBuffer::new(18, 0);
+    write_primitive(&mut small, 0xAABBCCDDu32).unwrap();
+    write_primitive(&mut small, 7u8).unwrap();
+    let mut out = vec![];
+    small.send(&mut out).unwrap();
+    assert_eq!(out, [18, 0, 3, 0, 0xDD, 0xCC, 0xBB, 0xAA, 7, 0, 0, 0]);
+
+    let mut big = RequestBuffer::new(72, 2);
+    big.write_all(&vec![1u8; 4 * 70_000]).unwrap();
+    assert_eq!(big.units(), 70_002);
+    let mut out = vec![];
+    big.send(&mut out).unwrap();
+    assert_eq!(out[0..4], [72, 2, 0, 0]);
+    assert_eq!(out[4..8], 70_002u32.to_le_bytes());
+    assert_eq!(out.len(), 70_002 * 4);
+}
@@ -0,0 +1,129 @@
+use super::*;
+use std::cell::RefCell;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// Counts requests as their bytes go out, so replies and errors can be matched to the
+/// request they belong to.\
+/// The server only sends the low 16 bits of a sequence number, the full count is kept here
+#[derive(Default)]
+pub struct RequestCounter {
+    /// sequence number of the last request that started going out
+    sequence: u64,
+    /// the start of a request whose length isn't known yet
+    header: [u8; 8],
+    header_len: usize,
+    /// bytes of the current request still to come
+    remaining: usize,
+}
+
+impl RequestCounter {
+    /// sequence number of the last request written
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// The full sequence number of a request already sent, from the 16 bits the server echoes.\
+    /// Anything the server sends is about the last 65536 requests at most
+    pub fn widen(&self, sequence: CARD16) -> u64 {
+        let behind = (self.sequence as CARD16).wrapping_sub(sequence);
+        self.sequence.saturating_sub(behind as u64)
+    }
+
+    /// moves past `bytes`, counting every request that starts in them
+    pub fn count(&mut self, mut bytes: &[u8]) {
+        while !bytes.is_empty() {
+            if self.remaining > 0 {
+                let skip = self.remaining.min(bytes.len());
+                self.remaining -= skip;
+                bytes = &bytes[skip..];
+                continue;
+            }
+            // a BIG-REQUESTS length of 0 means the real one is in the 4 bytes after it
+            let big = self.header_len >= 4 && self.header[2..4] == [0, 0];
+            let want = if big { 8 } else { 4 };
+            let take = (want - self.header_len).min(bytes.len());
+            self.header[self.header_len..self.header_len + take].copy_from_slice(&bytes[..take]);
+            self.header_len += take;
+            bytes = &bytes[take..];
+            let len = match self.header_len {
+                4 if self.header[2..4] != [0, 0] => {
+                    CARD16::from_le_bytes([self.header[2], self.header[3]]) as usize * 4
+                }
+                8 => {
+                    let big = &self.header[4..8];
+                    CARD32::from_le_bytes([big[0], big[1], big[2], big[3]]) as usize * 4
+                }
+                _ => continue,
+            };
+            self.sequence += 1;
+            self.remaining = len.saturating_sub(self.header_len);
+            self.header_len = 0;
+        }
+    }
+}
+
+/// A socket whose writes go through a `RequestCounter`, what `XContext::socket_cb` hands out
+pub struct Counted<'a, S> {
+    socket: &'a mut S,
+    requests: &'a RefCell<RequestCounter>,
+}
+
+impl<'a, S> Counted<'a, S> {
+    pub fn new(socket: &'a mut S, requests: &'a RefCell<RequestCounter>) -> Self {
+        Self { socket, requests }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        self.socket
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        self.socket
+    }
+}
+
+impl<S: Write> Write for Counted<'_, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.socket.write(buf)?;
+        self.requests.borrow_mut().count(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.socket.flush()
+    }
+}
+
+impl<S: Read> Read for Counted<'_, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.socket.read(buf)
+    }
+}
+
+impl<S: AsRawFd> AsRawFd for Counted<'_, S> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.as_raw_fd()
+    }
+}
+
+#[test]
+pub fn requests_are_counted() {
+    let mut counter = RequestCounter::default();
+    // a 2 unit request split mid header, then a 1 unit one
+    counter.count(&[8, 0]);
+    assert_eq!(counter.sequence(), 0);
+    counter.count(&[2, 0, 1, 2]);
+    assert_eq!(counter.sequence(), 1);
+    counter.count(&[3, 4, 43, 0, 1, 0]);
+    assert_eq!(counter.sequence(), 2);
+    // BIG-REQUESTS, 3 units counting the extended length
+    counter.count(&[72, 0, 0, 0, 3, 0]);
+    assert_eq!(counter.sequence(), 2);
+    counter.count(&[0, 0, 9, 9, 9, 9, 43, 0, 1, 0]);
+    assert_eq!(counter.sequence(), 4);
+
+    counter.sequence = 0x1_0002;
+    assert_eq!(counter.widen(2), 0x1_0002);
+    assert_eq!(counter.widen(0xffff), 0xffff);
+    assert_eq!(counter.widen(3), 0x3);
+}
@@ -0,0 +1,238 @@
+use std::io;
+
+use crate::Atom;
+
+pub use xproto_derive::{Deserialize, Serialize};
+
+/// Something that goes out to the server, little endian like everything else we send
+pub trait Serialize {
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()>;
+}
+
+/// Something that comes from the server.\
+/// Reading never panics, running out of bytes is an `UnexpectedEof` error
+/// and a value that can't be is `InvalidData`
+pub trait Deserialize: Sized {
+    /// how many bytes it always takes up, `None` if that depends on what's in it
+    const SIZE: Option<usize> = None;
+
+    fn deserialize(r: &mut Reader<'_>) -> io::Result<Self>;
+}
+
+macro_rules! wire_primitive {
+    ($($ty:ty),*) => {$(
+        impl Serialize for $ty {
+            fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+                w.write_all(&self.to_le_bytes())
+            }
+        }
+        impl Deserialize for $ty {
+            const SIZE: Option<usize> = Some(std::mem::size_of::<$ty>());
+
+            fn deserialize(r: &mut Reader<'_>) -> io::Result<Self> {
+                let bytes = r.bytes(std::mem::size_of::<$ty>())?;
+                Ok(<$ty>::from_le_bytes(bytes.try_into().expect("sized above")))
+            }
+        }
+    )*};
+}
+wire_primitive!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+impl Serialize for bool {
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        (*self as u8).serialize(w)
+    }
+}
+impl Deserialize for bool {
+    const SIZE: Option<usize> = Some(1);
+
+    fn deserialize(r: &mut Reader<'_>) -> io::Result<Self> {
+        r.read::<u8>().map(|b| b != 0)
+    }
+}
+
+impl Serialize for Atom {
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        { self.0 }.serialize(w)
+    }
+}
+impl Deserialize for Atom {
+    const SIZE: Option<usize> = Some(4);
+
+    fn deserialize(r: &mut Reader<'_>) -> io::Result<Self> {
+        r.read().map(Atom)
+    }
+}
+
+impl<const N: usize> Serialize for [u8; N] {
+    fn serialize<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(self)
+    }
+}
+impl<const N: usize> Deserialize for [u8; N] {
+    const SIZE: Option<usize> = Some(N);
+
+    fn deserialize(r: &mut Reader<'_>) -> io::Result<Self> {
+        Ok(r.bytes(N)?.try_into().expect("sized above"))
+    }
+}
+
+/// a cursor over a reply, event or anything else coming from the server
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn read<T: Deserialize>(&mut self) -> io::Result<T> {
+        T::deserialize(self)
+    }
+
+    /// how far in we are
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len());
+        let Some(end) = end else {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "packet too short",
+            ));
+        };
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn skip(&mut self, len: usize) -> io::Result<()> {
+        self.bytes(len).map(drop)
+    }
+
+    /// skips to the next multiple of `align` from the start
+    pub fn align(&mut self, align: usize) -> io::Result<()> {
+        self.skip(self.pos.next_multiple_of(align) - self.pos)
+    }
+
+    pub fn list<T: Deserialize>(&mut self, len: usize) -> io::Result<Vec<T>> {
+        // no with_capacity, `len` comes from the server
+        (0..len).map(|_| self.read()).collect()
+    }
+
+    /// whatever is left
+    pub fn rest_bytes(&mut self) -> &'a [u8] {
+        let rest = &self.data[self.pos..];
+        self.pos = self.data.len();
+        rest
+    }
+
+    /// as many `T` as what's left holds
+    pub fn rest<T: Deserialize>(&mut self) -> io::Result<Vec<T>> {
+        let mut items = vec![];
+        while self.pos < self.data.len() {
+            let start = self.pos;
+            items.push(self.read()?);
+            if self.pos == start {
+                break;
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// a list's length as the type of its length field
+pub fn list_len<T: TryFrom<usize>>(len: usize) -> io::Result<T> {
+    T::try_from(len).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "list too long for its length field",
+        )
+    })
+}
+
+/// for `Deserialize::SIZE` in the derive
+#[doc(hidden)]
+pub const fn add_sizes(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        _ => None,
+    }
+}
+
+#[doc(hidden)]
+pub const fn align_size(size: Option<usize>, align: usize) -> Option<usize> {
+    match size {
+        Some(size) => Some(size.next_multiple_of(align)),
+        None => None,
+    }
+}
+
+#[test]
+pub fn derived_wire_structs() {
+    use crate::xtypes::{CARD16, CARD8};
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    #[wire(repr = "CARD8")]
+    enum Shade {
+        #[default]
+        Light = 1,
+        Dark = 7,
+    }
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Host {
+        shade: Shade,
+        #[wire(pad = 1, len_of = "address")]
+        address_len: CARD16,
+        #[wire(len = "address_len", align_after = 4)]
+        address: Vec<CARD8>,
+        window: Atom,
+    }
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Header {
+        code: CARD8,
+        #[wire(pad_after = 1)]
+        detail: bool,
+        sequence_number: CARD16,
+    }
+    assert_eq!(Header::SIZE, Some(5));
+    assert_eq!(Host::SIZE, None);
+
+    let host = Host {
+        shade: Shade::Dark,
+        address_len: 0,
+        address: vec![127, 0, 0, 1, 9],
+        window: Atom(0x0400_0001),
+    };
+    let mut bytes = vec![];
+    host.serialize(&mut bytes).unwrap();
+    assert_eq!(
+        bytes,
+        [7, 0, 5, 0, 127, 0, 0, 1, 9, 0, 0, 0, 1, 0, 0, 4],
+        "the length comes from the list and the list is padded to 4"
+    );
+    let read = Reader::new(&bytes).read::<Host>().unwrap();
+    assert_eq!(read.address, host.address);
+    assert_eq!(read.address_len, 5);
+    assert_eq!(read.window, host.window);
+
+    let truncated = Reader::new(&bytes[..10]).read::<Host>();
+    assert_eq!(truncated.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+    bytes[0] = 3;
+    let bad_shade = Reader::new(&bytes).read::<Host>();
+    assert_eq!(bad_shade.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    let header = crate::xio::read_primitive::<Header, _>(&mut &[2u8, 1, 0, 0x34, 0x12][..]);
+    let header = header.unwrap();
+    assert!(header.detail);
+    assert_eq!(header.sequence_number, 0x1234);
+}
@@ -8,6 +8,13 @@ pub type CARD16 = u16;
 pub type CARD32 = u32;
 pub type BOOL = CARD8;
 pub type WINDOW = Atom;
+pub type KEYCODE = CARD8;
+pub type CURSOR = CARD32;
+pub type FONT = CARD32;
+pub type GCONTEXT = CARD32;
+pub type DRAWABLE = CARD32;
+pub type COLORMAP = CARD32;
+pub type PIXMAP = CARD32;
 
 #[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub struct Timestamp(CARD32);
@@ -20,13 +27,146 @@ impl Timestamp {
         self.0
     }
 }
-impl Into<CARD32> for Timestamp {
-    fn into(self) -> CARD32 {
-        self.data()
+impl From<CARD32> for Timestamp {
+    fn from(time: CARD32) -> Self {
+        Timestamp(time)
     }
 }
+impl From<Timestamp> for CARD32 {
+    fn from(time: Timestamp) -> CARD32 {
+        time.data()
+    }
+}
+
+/// A set of modifier keys, as used by grabs and the state field of input events
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ModMask(pub CARD16);
+
+impl ModMask {
+    pub const NONE: Self = Self(0);
+    pub const SHIFT: Self = Self(key_masks::SHIFT);
+    pub const LOCK: Self = Self(key_masks::LOCK);
+    pub const CONTROL: Self = Self(key_masks::CONTROL);
+    /// usually Alt
+    pub const MOD1: Self = Self(key_masks::MOD1);
+    /// usually NumLock
+    pub const MOD2: Self = Self(key_masks::MOD2);
+    pub const MOD3: Self = Self(key_masks::MOD3);
+    /// usually Super
+    pub const MOD4: Self = Self(key_masks::MOD4);
+    pub const MOD5: Self = Self(key_masks::MOD5);
+    /// only valid for grabs, matches every combination of modifiers
+    pub const ANY: Self = Self(key_masks::ANY_MODIFIER);
+
+    pub fn bits(&self) -> CARD16 {
+        self.0
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// drops the pointer button bits of an event state, leaving only the modifiers
+    pub fn from_state(state: CARD16) -> Self {
+        Self(state & 0xff)
+    }
+}
+
+impl std::ops::BitOr for ModMask {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for ModMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl std::ops::BitAnd for ModMask {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl std::ops::Not for ModMask {
+    type Output = Self;
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl Debug for ModMask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: [&str; 8] = [
+            "Shift", "Lock", "Control", "Mod1", "Mod2", "Mod3", "Mod4", "Mod5",
+        ];
+        if *self == Self::ANY {
+            return write!(f, "ModMask(Any)");
+        }
+        let names = (0..8)
+            .filter(|bit| self.0 & (1 << bit) != 0)
+            .map(|bit| NAMES[bit])
+            .collect::<Vec<_>>();
+        write!(f, "ModMask({})", names.join(" | "))
+    }
+}
+
+/// The pointer events a pointer or button grab reports, a subset of the window event mask
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash, Debug)]
+pub struct PointerEventMask(pub CARD16);
+
+impl PointerEventMask {
+    pub const NONE: Self = Self(0);
+    pub const BUTTON_PRESS: Self = Self(set_of_events_common::BUTTON_PRESS as CARD16);
+    pub const BUTTON_RELEASE: Self = Self(set_of_events_common::BUTTON_RELEASE as CARD16);
+    pub const ENTER_WINDOW: Self = Self(set_of_events_common::ENTER_WINDOW as CARD16);
+    pub const LEAVE_WINDOW: Self = Self(set_of_events_common::LEAVE_WINDOW as CARD16);
+    pub const POINTER_MOTION: Self = Self(set_of_events_common::POINTER_MOTION as CARD16);
+    /// one MotionNotify until the pointer is queried again, instead of a stream of them
+    pub const POINTER_MOTION_HINT: Self = Self(set_of_events_common::POINTER_MOTION_HINT as CARD16);
+    pub const BUTTON1_MOTION: Self = Self(set_of_events_common::BUTTON1_MOTION as CARD16);
+    pub const BUTTON2_MOTION: Self = Self(set_of_events_common::BUTTON2_MOTION as CARD16);
+    pub const BUTTON3_MOTION: Self = Self(set_of_events_common::BUTTON3_MOTION as CARD16);
+    pub const BUTTON4_MOTION: Self = Self(set_of_events_common::BUTTON4_MOTION as CARD16);
+    pub const BUTTON5_MOTION: Self = Self(set_of_events_common::BUTTON5_MOTION as CARD16);
+    /// motion while any button is held
+    pub const BUTTON_MOTION: Self = Self(set_of_events_common::BUTTON_MOTION as CARD16);
+    pub const KEYMAP_STATE: Self = Self(set_of_events_common::KEYMAP_STATE as CARD16);
+
+    pub fn bits(&self) -> CARD16 {
+        self.0
+    }
 
-#[derive(Copy, Clone, Debug)]
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for PointerEventMask {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for PointerEventMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    XYBitmap = 0,
+    XYPixmap = 1,
+    ZPixmap = 2,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum XClass {
     StaticGray = 0,
     GrayScale = 1,
@@ -34,14 +174,9 @@ pub enum XClass {
     PseudoColor = 3,
     TrueColor = 4,
     DirectColor = 5,
+    #[default]
     Unknown = 6,
 }
-
-impl Default for XClass {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
 impl XClass {
     pub fn from_class_code(code: CARD8) -> Self {
         match code {
@@ -51,7 +186,7 @@ impl XClass {
             3 => Self::PseudoColor,
             4 => Self::TrueColor,
             5 => Self::DirectColor,
-            _ => panic!("invalid class code, io may contain a bug"),
+            _ => Self::Unknown,
         }
     }
 }
@@ -107,8 +242,7 @@ impl Debug for XVisualType {
     }
 }
 
-#[repr(C, packed(1))]
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
 pub struct XVisualTypeIntermediate {
     visual_id: Atom,
     class: CARD8,
@@ -140,7 +274,7 @@ impl XDepth {
             res.number_of_visual_types as usize,
         )?
         .into_iter()
-        .map(|a| XVisualType::from_intermediate(a))
+        .map(XVisualType::from_intermediate)
         .collect();
         Ok(res)
     }
@@ -167,7 +301,7 @@ impl Debug for XDepth {
 #[derive(Clone, Default)]
 pub struct XScreen {
     pub root: Atom,
-    pub default_colormap: CARD32,
+    pub default_colormap: COLORMAP,
     pub white_pixel: CARD32,
     pub black_pixel: CARD32,
     pub current_input_masks: CARD32,
@@ -220,10 +354,7 @@ impl Debug for XScreen {
 }
 
 impl XScreen {
-    pub fn from_socket<T: std::io::Read>(
-        mut socket: T,
-        number_of_formats: usize,
-    ) -> std::io::Result<Self> {
+    pub fn from_socket<T: std::io::Read>(mut socket: T) -> std::io::Result<Self> {
         let mut res = Self::default();
         res.root = xio::read_primitive(&mut socket)?;
         res.default_colormap = xio::read_primitive(&mut socket)?;
@@ -241,15 +372,63 @@ impl XScreen {
         res.save_unders = xio::read_primitive(&mut socket)?;
         res.root_depth = xio::read_primitive(&mut socket)?;
         res.number_of_depths_in_allowed_depths = xio::read_primitive(&mut socket)?;
-        res.depth_list = (0..number_of_formats)
+        res.depth_list = (0..res.number_of_depths_in_allowed_depths)
             .map(|_| XDepth::from_socket(&mut socket))
             .collect::<Result<Vec<_>, _>>()?;
         Ok(res)
     }
 }
 
-#[repr(C, packed(1))]
-#[derive(Copy, Clone, Default, Debug)]
+impl XScreen {
+    /// every visual the screen supports along with its depth
+    pub fn visuals(&self) -> impl Iterator<Item = (CARD8, &XVisualType)> {
+        self.depth_list.iter().flat_map(|depth| {
+            depth
+                .visuals
+                .iter()
+                .map(move |visual| (depth.depth, visual))
+        })
+    }
+
+    pub fn find_visual(&self, class: XClass, depth: CARD8) -> Option<&XVisualType> {
+        self.visuals()
+            .find(|&(d, visual)| d == depth && visual.class == class)
+            .map(|(_, visual)| visual)
+    }
+
+    /// a 32 bit TrueColor visual with 8 bits of alpha above the color, for windows
+    /// that blend with what's behind them under a compositor
+    pub fn argb_visual(&self) -> Option<&XVisualType> {
+        self.visuals()
+            .find(|&(depth, visual)| {
+                depth == 32
+                    && visual.class == XClass::TrueColor
+                    && visual.red_mask == 0xff0000
+                    && visual.green_mask == 0xff00
+                    && visual.blue_mask == 0xff
+            })
+            .map(|(_, visual)| visual)
+    }
+
+    pub fn root_visual_type(&self) -> Option<&XVisualType> {
+        self.visual_by_id(self.root_visual)
+    }
+
+    pub fn visual_by_id(&self, visual_id: Atom) -> Option<&XVisualType> {
+        self.visuals()
+            .find(|(_, visual)| visual.visual_id == visual_id)
+            .map(|(_, visual)| visual)
+    }
+
+    /// the depth windows using `visual_id` have to be created with
+    pub fn depth_of_visual(&self, visual_id: Atom) -> Option<CARD8> {
+        self.visuals()
+            .find(|(_, visual)| visual.visual_id == visual_id)
+            .map(|(depth, _)| depth)
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, Serialize, Deserialize)]
 pub struct XFormat {
     pub depth: CARD8,
     pub bits_per_pixel: CARD8,
@@ -258,7 +437,7 @@ pub struct XFormat {
 }
 
 #[repr(C, packed(1))]
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
 pub struct Atom(pub CARD32);
 
 const ATOM_MASK: u32 = (1 << 29) - 1;
@@ -268,3 +447,41 @@ impl<T: Into<u32>> From<T> for Atom {
         Self(val.into() & ATOM_MASK)
     }
 }
+
+#[test]
+pub fn visual_lookup() {
+    let visual = |id: u32, class: XClass, masks: (u32, u32, u32)| XVisualType {
+        visual_id: Atom(id),
+        class,
+        red_mask: masks.0,
+        green_mask: masks.1,
+        blue_mask: masks.2,
+        ..Default::default()
+    };
+    let rgb = (0xff0000, 0xff00, 0xff);
+    let screen = XScreen {
+        root_visual: Atom(0x21),
+        depth_list: vec![
+            XDepth {
+                depth: 24,
+                visuals: vec![
+                    visual(0x21, XClass::TrueColor, rgb),
+                    visual(0x22, XClass::DirectColor, rgb),
+                ],
+                ..Default::default()
+            },
+            XDepth {
+                depth: 32,
+                visuals: vec![visual(0x40, XClass::TrueColor, rgb)],
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    assert_eq!(screen.root_visual_type().unwrap().visual_id, Atom(0x21));
+    assert_eq!(screen.argb_visual().unwrap().visual_id, Atom(0x40));
+    let direct = screen.find_visual(XClass::DirectColor, 24).unwrap();
+    assert_eq!(direct.visual_id, Atom(0x22));
+    assert!(screen.find_visual(XClass::DirectColor, 32).is_none());
+    assert_eq!(screen.depth_of_visual(Atom(0x40)), Some(32));
+}
@@ -0,0 +1,810 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::model::*;
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// types the crate already has under the same name
+const CRATE_TYPES: &[&str] = &[
+    "INT8", "INT16", "INT32", "CARD8", "CARD16", "CARD32", "BOOL", "WINDOW", "KEYCODE", "CURSOR",
+    "FONT", "GCONTEXT", "DRAWABLE", "COLORMAP", "PIXMAP",
+];
+
+/// `CreateGC` -> `create_gc`, `Button1Motion` -> `button1_motion`
+pub fn snake(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_lower)
+            {
+                out.push('_');
+            }
+        }
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+fn screaming(name: &str) -> String {
+    let name = snake(name).to_uppercase();
+    match name.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("_{}", name),
+        false => name,
+    }
+}
+
+/// `VISUALTYPE` -> `Visualtype`, names that already have lower case letters stay as they are
+fn camel(name: &str) -> String {
+    if name.chars().any(|c| c.is_ascii_lowercase()) {
+        return name.to_string();
+    }
+    name.split('_')
+        .map(|part| {
+            let mut part = part.to_ascii_lowercase();
+            part[..1].make_ascii_uppercase();
+            part
+        })
+        .collect()
+}
+
+/// field names, some extensions use camelCase ones like `nOutput`
+fn ident(name: &str) -> String {
+    let name = snake(name);
+    match KEYWORDS.contains(&name.as_str()) {
+        true => format!("{}_", name),
+        false => name,
+    }
+}
+
+fn unqualified(ty: &str) -> &str {
+    ty.rsplit(':').next().unwrap_or(ty)
+}
+
+/// lists of these go out and come in as plain bytes
+fn is_bytes(ty: &str) -> bool {
+    matches!(unqualified(ty), "CARD8" | "BYTE" | "char" | "void")
+}
+
+fn primitive(ty: &str) -> Option<(&'static str, usize)> {
+    Some(match ty {
+        "CARD8" | "BYTE" => ("CARD8", 1),
+        "char" | "void" => ("u8", 1),
+        "BOOL" => ("BOOL", 1),
+        "INT8" => ("INT8", 1),
+        "CARD16" => ("CARD16", 2),
+        "INT16" => ("INT16", 2),
+        "CARD32" => ("CARD32", 4),
+        "INT32" => ("INT32", 4),
+        "CARD64" => ("u64", 8),
+        "INT64" => ("i64", 8),
+        "float" => ("f32", 4),
+        "double" => ("f64", 8),
+        _ => return None,
+    })
+}
+
+fn primitive_size(ty: &str) -> Option<usize> {
+    primitive(unqualified(ty)).map(|(_, size)| size)
+}
+
+/// what the generator knows about every type name, across modules
+#[derive(Default)]
+pub struct Types {
+    /// xml name -> (rust name, fixed size)
+    known: HashMap<String, (String, Option<usize>)>,
+}
+
+impl Types {
+    pub fn add_module(&mut self, module: &Module) {
+        for xid in &module.xids {
+            let rust = match xid.as_str() {
+                "ATOM" => "Atom".to_string(),
+                _ => xid.clone(),
+            };
+            self.known.insert(xid.clone(), (rust, Some(4)));
+        }
+        for (new, old) in &module.typedefs {
+            let size = self.size(old);
+            self.known.insert(new.clone(), (new.clone(), size));
+        }
+        for s in &module.structs {
+            let size = s.items.iter().try_fold(0, |total, item| {
+                Some(
+                    total
+                        + match item {
+                            Item::Field { ty, .. } => self.size(ty)?,
+                            Item::Pad(bytes) => *bytes,
+                            Item::List {
+                                ty,
+                                len: Some(Expr::Value(n)),
+                                ..
+                            } => self.size(ty)? * *n as usize,
+                            _ => return None,
+                        },
+                )
+            });
+            self.known.insert(s.name.clone(), (camel(&s.name), size));
+        }
+        // after the structs, RandR's NotifyData is a union of them
+        for union in &module.unions {
+            let size = union
+                .members
+                .iter()
+                .try_fold(0, |size: usize, (ty, count)| {
+                    Some(size.max(self.size(ty)? * count))
+                });
+            self.known
+                .insert(union.name.clone(), (union.name.clone(), size));
+        }
+    }
+
+    fn size(&self, ty: &str) -> Option<usize> {
+        let ty = unqualified(ty);
+        primitive_size(ty).or_else(|| self.known.get(ty).and_then(|(_, size)| *size))
+    }
+
+    fn rust(&self, ty: &str) -> String {
+        let ty = unqualified(ty);
+        if let Some((rust, _)) = primitive(ty) {
+            return rust.to_string();
+        }
+        match self.known.get(ty) {
+            Some((rust, _)) => rust.clone(),
+            None => ty.to_string(),
+        }
+    }
+
+    /// element type of a list
+    fn element(&self, ty: &str) -> String {
+        match is_bytes(ty) {
+            true => "u8".to_string(),
+            false => self.rust(ty),
+        }
+    }
+}
+
+/// names of fields that only hold the length of a list that follows them
+fn derived_lengths(items: &[Item]) -> HashSet<String> {
+    let mut derived = HashSet::new();
+    for item in items {
+        match item {
+            Item::List {
+                len: Some(Expr::FieldRef(field)),
+                ..
+            } => {
+                derived.insert(field.clone());
+            }
+            Item::Switch { mask, .. } => {
+                derived.insert(mask.clone());
+            }
+            _ => {}
+        }
+    }
+    // only fields of this struct, `length` of a reply isn't one
+    derived.retain(|name| {
+        items
+            .iter()
+            .any(|item| matches!(item, Item::Field { name: field, .. } if field == name))
+    });
+    derived
+}
+
+fn refs(expr: &Expr, out: &mut HashSet<String>) {
+    match expr {
+        Expr::Value(_) => {}
+        Expr::FieldRef(name) => {
+            out.insert(name.clone());
+        }
+        Expr::Op(_, a, b) => {
+            refs(a, out);
+            refs(b, out);
+        }
+        Expr::Popcount(a) => refs(a, out),
+    }
+}
+
+/// `expr` as a usize, `field` turns a field name into something that can be cast
+fn rust_expr(expr: &Expr, field: &dyn Fn(&str) -> String) -> String {
+    match expr {
+        Expr::Value(value) => value.to_string(),
+        Expr::FieldRef(name) => field(name),
+        Expr::Op(op, a, b) => {
+            let (a, b) = (rust_expr(a, field), rust_expr(b, field));
+            match op.as_str() {
+                "-" => format!("{}.saturating_sub({})", a, b),
+                "/" if !b.chars().all(|c| c.is_ascii_digit()) => {
+                    format!("{}.checked_div({}).unwrap_or(0)", a, b)
+                }
+                _ => format!("({} {} {})", a, op, b),
+            }
+        }
+        Expr::Popcount(a) => format!("({}.count_ones() as usize)", rust_expr(a, field)),
+    }
+}
+
+/// a function argument, without the parentheses around the whole expression
+fn argument(expr: &Expr, field: &dyn Fn(&str) -> String) -> String {
+    let expr = rust_expr(expr, field);
+    let Some(inner) = expr.strip_prefix('(').and_then(|e| e.strip_suffix(')')) else {
+        return expr;
+    };
+    // `(a) * (b)` starts and ends with parentheses that don't match
+    let mut depth = 0;
+    for c in inner.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return expr,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    inner.to_string()
+}
+
+const WRITE_IMPORT: &str = "use std::io::Write; // if needed";
+
+pub struct Generator<'a> {
+    types: &'a Types,
+    module: &'a Module,
+    out: String,
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(types: &'a Types, module: &'a Module) -> Self {
+        Self {
+            types,
+            module,
+            out: String::new(),
+        }
+    }
+
+    fn line(&mut self, text: impl AsRef<str>) {
+        let text = text.as_ref();
+        // back to back skips become one
+        let skip = |line: &str| {
+            line.strip_prefix("r.skip(")?
+                .strip_suffix(")?;")?
+                .parse()
+                .ok()
+        };
+        let last = self
+            .out
+            .trim_end_matches('\n')
+            .rsplit('\n')
+            .next()
+            .unwrap_or("");
+        if let (Some(a), Some(b)) = (skip(last), skip(text)) {
+            let a: usize = a;
+            let len = self.out.trim_end_matches('\n').len() - last.len();
+            self.out.truncate(len);
+            self.out.push_str(&format!("r.skip({})?;\n", a + b));
+            return;
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn size(&self, item: &Item) -> Option<usize> {
+        match item {
+            Item::Field { ty, .. } | Item::ExprField { ty, .. } => self.types.size(ty),
+            Item::Pad(bytes) => Some(*bytes),
+            _ => None,
+        }
+    }
+
+    pub fn generate(mut self, source: &str) -> Result<String, String> {
+        let module = self.module;
+        self.line(format!(
+            "// Generated by xcbgen from {}, edit that and rerun `cargo run -p xcbgen`",
+            source
+        ));
+        self.line("#![allow(clippy::too_many_arguments)]");
+        self.line("");
+        self.line("use super::*;");
+        for import in &module.imports {
+            self.line(format!(
+                "#[allow(unused_imports)]\nuse super::{}::*;",
+                import
+            ));
+        }
+        // filled in at the end, once we know if anything needs it
+        self.line(WRITE_IMPORT);
+        self.line("");
+
+        if let Some(ext) = &module.extension {
+            self.line(format!(
+                "pub const EXTENSION_NAME: &str = \"{}\";",
+                ext.xname
+            ));
+            self.line(format!(
+                "pub const MAJOR_VERSION: u32 = {};",
+                ext.major_version
+            ));
+            self.line(format!(
+                "pub const MINOR_VERSION: u32 = {};",
+                ext.minor_version
+            ));
+            self.line("");
+        }
+        for xid in &module.xids {
+            if !CRATE_TYPES.contains(&xid.as_str()) && xid != "ATOM" {
+                self.line(format!("pub type {} = CARD32;", xid));
+            }
+        }
+        for (new, old) in &module.typedefs {
+            if !CRATE_TYPES.contains(&new.as_str()) {
+                self.line(format!("pub type {} = {};", new, self.types.rust(old)));
+            }
+        }
+        for union in &module.unions {
+            let size = self
+                .types
+                .size(&union.name)
+                .ok_or_else(|| format!("{}: a union member without a fixed size", union.name))?;
+            self.line(format!("pub type {} = [u8; {}];", union.name, size));
+        }
+        self.line("");
+
+        self.codes(
+            "opcodes",
+            "u8",
+            module.requests.iter().map(|r| (&r.name, r.opcode)),
+        );
+        self.request_names(&module.requests);
+        let events = module.events.iter().map(|e| (&e.name, e.number));
+        let copies = module.event_copies.iter().map(|e| (&e.name, e.number));
+        let mut codes: Vec<_> = events.chain(copies).collect();
+        codes.sort_by_key(|&(_, number)| number);
+        self.codes("event_codes", "u8", codes.into_iter());
+        let errors = module.errors.iter().map(|e| (&e.name, e.number));
+        let copies = module.error_copies.iter().map(|e| (&e.name, e.number));
+        let mut codes: Vec<_> = errors.chain(copies).collect();
+        codes.sort_by_key(|&(_, number)| number);
+        self.codes("error_codes", "u8", codes.into_iter());
+
+        for e in &module.enums {
+            self.codes(
+                &snake(&e.name),
+                "u32",
+                e.items.iter().map(|(name, value)| (name, *value)),
+            );
+        }
+        for s in &module.structs {
+            self.structure(s)?;
+        }
+        for request in &module.requests {
+            self.request(request)?;
+        }
+        for event in &module.events {
+            self.packet(&event.name, "Event", &event.items, event.no_sequence_number)?;
+        }
+        for copy in &module.event_copies {
+            self.line(format!("pub type {}Event = {}Event;", copy.name, copy.of));
+        }
+        for error in &module.errors {
+            self.packet(&error.name, "Error", &error.items, false)?;
+        }
+        for copy in &module.error_copies {
+            self.line(format!("pub type {}Error = {}Error;", copy.name, copy.of));
+        }
+        let needs_write = self.out.contains("request.write_all");
+        let import = if needs_write {
+            "use std::io::Write;\n"
+        } else {
+            ""
+        };
+        Ok(self.out.replacen(&format!("{}\n", WRITE_IMPORT), import, 1))
+    }
+
+    fn codes<'n, T: std::fmt::Display>(
+        &mut self,
+        module: &str,
+        ty: &str,
+        codes: impl Iterator<Item = (&'n String, T)>,
+    ) {
+        let codes: Vec<_> = codes.collect();
+        if codes.is_empty() {
+            return;
+        }
+        self.line(format!("pub mod {} {{", module));
+        for (name, value) in codes {
+            self.line(format!(
+                "pub const {}: {} = {};",
+                screaming(name),
+                ty,
+                value
+            ));
+        }
+        self.line("}\n");
+    }
+
+    /// `REQUEST_NAMES`, the request names indexed by opcode with "" for unused ones
+    fn request_names(&mut self, requests: &[Request]) {
+        let Some(len) = requests.iter().map(|r| r.opcode as usize + 1).max() else {
+            return;
+        };
+        let mut names = vec![""; len];
+        for request in requests {
+            names[request.opcode as usize] = &request.name;
+        }
+        self.line(format!("pub const REQUEST_NAMES: [&str; {}] = [", len));
+        for name in names {
+            self.line(format!("\"{}\",", name));
+        }
+        self.line("];\n");
+    }
+
+    /// struct fields, leaving out padding and list lengths
+    fn fields(&self, items: &[Item]) -> Result<Vec<(String, String)>, String> {
+        let derived = derived_lengths(items);
+        let mut fields = vec![];
+        for item in items {
+            match item {
+                Item::Field { name, ty } | Item::ExprField { name, ty, .. } => {
+                    if !derived.contains(name) {
+                        fields.push((ident(name), self.types.rust(ty)));
+                    }
+                }
+                Item::List { name, ty, .. } => {
+                    fields.push((ident(name), format!("Vec<{}>", self.types.element(ty))))
+                }
+                Item::Switch { name, .. } => return Err(format!("{}: switch in a struct", name)),
+                Item::Pad(_) | Item::Align(_) => {}
+            }
+        }
+        Ok(fields)
+    }
+
+    fn declare(&mut self, name: &str, fields: &[(String, String)]) {
+        self.line("#[derive(Clone, Debug, Default, PartialEq)]");
+        self.line(format!("pub struct {} {{", name));
+        for (field, ty) in fields {
+            self.line(format!("pub {}: {},", field, ty));
+        }
+        self.line("}\n");
+    }
+
+    /// reads `items` into locals of the same names
+    fn read_items(&mut self, items: &[Item]) -> Result<(), String> {
+        let local = |name: &str| format!("({} as usize)", ident(name));
+        for item in items {
+            let line = match item {
+                Item::Field { name, ty } | Item::ExprField { name, ty, .. } => {
+                    format!("let {}: {} = r.read()?;", ident(name), self.types.rust(ty))
+                }
+                Item::Pad(bytes) => format!("r.skip({})?;", bytes),
+                Item::Align(align) => format!("r.align({})?;", align),
+                Item::List {
+                    name,
+                    ty,
+                    len: Some(len),
+                } => match is_bytes(ty) {
+                    true => format!(
+                        "let {} = r.bytes({})?.to_vec();",
+                        ident(name),
+                        argument(len, &local)
+                    ),
+                    false => format!("let {} = r.list({})?;", ident(name), argument(len, &local)),
+                },
+                Item::List {
+                    name,
+                    ty,
+                    len: None,
+                } => match is_bytes(ty) {
+                    true => format!("let {} = r.rest_bytes().to_vec();", ident(name)),
+                    false => format!("let {} = r.rest()?;", ident(name)),
+                },
+                Item::Switch { name, .. } => return Err(format!("{}: switch in a reply", name)),
+            };
+            self.line(line);
+        }
+        Ok(())
+    }
+
+    fn construct(&mut self, fields: &[(String, String)]) {
+        let names: Vec<_> = fields.iter().map(|(name, _)| name.as_str()).collect();
+        self.line(format!("Ok(Self {{ {} }})", names.join(", ")));
+    }
+
+    /// structs get `#[derive(Serialize, Deserialize)]`, the XML's padding and list lengths
+    /// turn into `#[wire(...)]` on the fields around them
+    fn structure(&mut self, s: &Struct) -> Result<(), String> {
+        let name = camel(&s.name);
+        let derived = derived_lengths(&s.items);
+        let local = |name: &str| format!("({} as usize)", ident(name));
+        let mut fields: Vec<(Vec<String>, String, String)> = vec![];
+        let mut before = vec![];
+        for item in &s.items {
+            let (field, ty) = match item {
+                Item::Pad(bytes) => {
+                    before.push(format!("pad = {}", bytes));
+                    continue;
+                }
+                Item::Align(align) => {
+                    before.push(format!("align = {}", align));
+                    continue;
+                }
+                Item::Field { name, ty } if derived.contains(name) => {
+                    let list = s.items.iter().find_map(|item| match item {
+                        Item::List {
+                            name: list,
+                            len: Some(Expr::FieldRef(f)),
+                            ..
+                        } if f == name => Some(list),
+                        _ => None,
+                    });
+                    let list = ident(list.expect("derived from a list"));
+                    before.push(format!("len_of = \"{}\"", list));
+                    (name, self.types.rust(ty))
+                }
+                Item::Field { name, ty } => (name, self.types.rust(ty)),
+                Item::List { name, ty, len } => {
+                    if let Some(len) = len {
+                        before.push(format!("len = \"{}\"", argument(len, &local)));
+                    }
+                    (name, format!("Vec<{}>", self.types.element(ty)))
+                }
+                Item::ExprField { name, .. } | Item::Switch { name, .. } => {
+                    return Err(format!("{}: {} in a struct", s.name, name))
+                }
+            };
+            fields.push((std::mem::take(&mut before), ident(field), ty));
+        }
+        if !before.is_empty() {
+            let last = fields
+                .last_mut()
+                .ok_or_else(|| format!("{}: only padding", s.name))?;
+            for attr in before {
+                last.0.push(attr.replacen(" =", "_after =", 1));
+            }
+        }
+        self.line("#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]");
+        self.line(format!("pub struct {} {{", name));
+        for (attrs, field, ty) in fields {
+            if !attrs.is_empty() {
+                self.line(format!("#[wire({})]", attrs.join(", ")));
+            }
+            self.line(format!("pub {}: {},", field, ty));
+        }
+        self.line("}\n");
+        Ok(())
+    }
+
+    fn request(&mut self, request: &Request) -> Result<(), String> {
+        let extension = self.module.extension.is_some();
+        let items = &request.items;
+        let derived = derived_lengths(items);
+        let fn_name = match request.reply {
+            Some(_) => format!("{}_request", snake(&request.name)),
+            None => snake(&request.name),
+        };
+
+        let mut params = vec!["socket: &mut S".to_string()];
+        if extension {
+            params.push("major_opcode: CARD8".to_string());
+        }
+        for item in items {
+            match item {
+                Item::Field { name, ty } if !derived.contains(name) => {
+                    params.push(format!("{}: {}", ident(name), self.types.rust(ty)))
+                }
+                Item::List { name, ty, .. } => {
+                    params.push(format!("{}: &[{}]", ident(name), self.types.element(ty)))
+                }
+                Item::Switch { name, .. } => {
+                    params.push(format!("{}: &[(CARD32, CARD32)]", ident(name)))
+                }
+                _ => {}
+            }
+        }
+        self.line(format!(
+            "pub fn {}<S: io::Write>({}) -> io::Result<()> {{",
+            fn_name,
+            params.join(", ")
+        ));
+
+        // lengths and masks that come from the arguments
+        for item in items {
+            match item {
+                Item::List {
+                    name,
+                    len: Some(Expr::FieldRef(field)),
+                    ..
+                } if derived.contains(field) => {
+                    let ty = items.iter().find_map(|item| match item {
+                        Item::Field { name, ty } if name == field => Some(self.types.rust(ty)),
+                        _ => None,
+                    });
+                    self.line(format!(
+                        "let {} = list_len::<{}>({}.len())?;",
+                        ident(field),
+                        ty.expect("derived lengths are fields"),
+                        ident(name)
+                    ));
+                }
+                Item::ExprField { name, ty, expr } => {
+                    let lists: HashSet<_> = items.iter().filter_map(Item::name).collect();
+                    let field = |field: &str| match field.strip_suffix("_len") {
+                        Some(list) if lists.contains(list) && !lists.contains(field) => {
+                            format!("{}.len()", ident(list))
+                        }
+                        _ => format!("({} as usize)", ident(field)),
+                    };
+                    let ty = self.types.rust(ty);
+                    self.line(format!(
+                        "let {} = {} as {};",
+                        ident(name),
+                        rust_expr(expr, &field),
+                        ty
+                    ));
+                }
+                Item::Switch { name, mask, cases } => {
+                    if let Some((field, _)) =
+                        cases.iter().find(|(_, ty)| self.types.size(ty) != Some(4))
+                    {
+                        return Err(format!("{}: {} isn't a 4 byte value", request.name, field));
+                    }
+                    let ty = items.iter().find_map(|item| match item {
+                        Item::Field { name, ty } if name == mask => Some(self.types.rust(ty)),
+                        _ => None,
+                    });
+                    let ty =
+                        ty.ok_or_else(|| format!("{}: no mask field {}", request.name, mask))?;
+                    let name = ident(name);
+                    self.line("// the server wants the values in the order of their bits");
+                    self.line(format!("let mut {} = {}.to_vec();", name, name));
+                    self.line(format!("{}.sort_by_key(|&(bit, _)| bit);", name));
+                    let cast = match ty.as_str() {
+                        "CARD32" => String::new(),
+                        _ => format!(" as {}", ty),
+                    };
+                    self.line(format!(
+                        "let {} = {}.iter().fold(0, |mask, &(bit, _)| mask | bit){};",
+                        ident(mask),
+                        name,
+                        cast
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        let opcode = format!("opcodes::{}", screaming(&request.name));
+        let mut rest = &items[..];
+        let (major, minor) = match (extension, items.first()) {
+            (true, _) => ("major_opcode".to_string(), opcode),
+            (false, Some(Item::Pad(1))) => {
+                rest = &items[1..];
+                (opcode, "0".to_string())
+            }
+            (false, Some(item @ (Item::Field { name, ty } | Item::ExprField { name, ty, .. })))
+                if self.size(item) == Some(1) =>
+            {
+                rest = &items[1..];
+                let data = match self.types.rust(ty).as_str() {
+                    "INT8" => format!("{} as CARD8", ident(name)),
+                    _ => ident(name),
+                };
+                (opcode, data)
+            }
+            (false, _) => (opcode, "0".to_string()),
+        };
+        if rest.is_empty() {
+            self.line(format!(
+                "RequestBuffer::new({}, {}).send(socket)",
+                major, minor
+            ));
+            self.line("}\n");
+            return self.reply(request);
+        }
+        self.line(format!(
+            "let mut request = RequestBuffer::new({}, {});",
+            major, minor
+        ));
+        for item in rest {
+            let line = match item {
+                Item::Field { name, .. } | Item::ExprField { name, .. } => {
+                    format!("{}.serialize(&mut request)?;", ident(name))
+                }
+                Item::Pad(bytes) => format!("request.write_all(&[0; {}])?;", bytes),
+                Item::Align(_) => return Err(format!("{}: aligned padding", request.name)),
+                Item::List { name, ty, .. } if is_bytes(ty) => {
+                    format!("request.write_all({})?;", ident(name))
+                }
+                Item::List { name, .. } => {
+                    format!(
+                        "for item in {} {{ item.serialize(&mut request)?; }}",
+                        ident(name)
+                    )
+                }
+                Item::Switch { name, .. } => format!(
+                    "for (_, value) in {} {{ value.serialize(&mut request)?; }}",
+                    ident(name)
+                ),
+            };
+            self.line(line);
+        }
+        self.line("request.send(socket)");
+        self.line("}\n");
+        self.reply(request)
+    }
+
+    fn reply(&mut self, request: &Request) -> Result<(), String> {
+        match &request.reply {
+            Some(reply) => self.packet(&request.name, "Reply", reply, false),
+            None => Ok(()),
+        }
+    }
+
+    /// replies, events and errors: 32 byte packets with a header around their fields
+    fn packet(
+        &mut self,
+        name: &str,
+        kind: &str,
+        items: &[Item],
+        no_sequence_number: bool,
+    ) -> Result<(), String> {
+        let name = format!("{}{}", name, kind);
+        let fields = self.fields(items)?;
+        self.declare(&name, &fields);
+        self.line(format!("impl {} {{", name));
+        let arg = match kind {
+            "Reply" => "reply",
+            "Event" => "event",
+            _ => "packet",
+        };
+        self.line(format!(
+            "pub fn parse({}: &[u8]) -> io::Result<Self> {{",
+            arg
+        ));
+        self.line(format!("let r = &mut Reader::new({});", arg));
+
+        let mut rest = items;
+        if kind == "Error" {
+            // code and error code
+            self.line("r.skip(2)?;");
+        } else {
+            self.line("r.skip(1)?;");
+            if !no_sequence_number {
+                match items.first() {
+                    Some(item) if self.size(item) == Some(1) => {
+                        self.read_items(&items[..1])?;
+                        rest = &items[1..];
+                    }
+                    _ => self.line("r.skip(1)?;"),
+                }
+            }
+        }
+        let mut used = HashSet::new();
+        for item in items {
+            if let Item::List { len: Some(len), .. } = item {
+                refs(len, &mut used);
+            }
+        }
+        match (kind, no_sequence_number) {
+            ("Reply", _) if used.contains("length") => {
+                self.line("r.skip(2)?;");
+                self.line("let length: CARD32 = r.read()?;");
+            }
+            // sequence number and length
+            ("Reply", _) => self.line("r.skip(6)?;"),
+            (_, false) => self.line("r.skip(2)?;"),
+            (_, true) => {}
+        }
+        self.read_items(rest)?;
+        self.construct(&fields);
+        self.line("}\n}\n");
+        Ok(())
+    }
+}
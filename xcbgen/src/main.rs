@@ -0,0 +1,97 @@
+//! Generates the protocol bindings in `src/proto` from the xcb-proto XML in `proto/`.\
+//! Run it from anywhere in the workspace with `cargo run -p xcbgen`, or with
+//! `cargo run -p xcbgen -- --check` to only fail if `src/proto` is out of date
+
+mod emit;
+mod model;
+
+use std::{
+    env, fs,
+    io::Write,
+    path::Path,
+    process::{self, Command, Stdio},
+};
+
+use emit::{snake, Generator, Types};
+use model::Module;
+
+/// in dependency order, extensions come after what they import
+const SOURCES: &[&str] = &["xproto", "bigreq", "randr", "render", "shm"];
+
+fn main() {
+    let check = match env::args().nth(1).as_deref() {
+        None => false,
+        Some("--check") => true,
+        Some(arg) => {
+            eprintln!("unknown argument {}, the only one is --check", arg);
+            process::exit(2);
+        }
+    };
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let root = root.canonicalize().unwrap_or(root);
+    let mut types = Types::default();
+    let mut modules = vec![];
+    for name in SOURCES {
+        let path = root.join("proto").join(format!("{}.xml", name));
+        let text = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("can't read {}: {}", path.display(), e));
+        let module = Module::parse(&text).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        for skipped in &module.skipped {
+            eprintln!("{}.xml: skipped {}", name, skipped);
+        }
+        types.add_module(&module);
+        modules.push((name, module));
+    }
+
+    let mut stale = false;
+    for (name, module) in &modules {
+        let source = format!("proto/{}.xml", name);
+        let code = Generator::new(&types, module)
+            .generate(&source)
+            .unwrap_or_else(|e| panic!("{}: {}", source, e));
+        let out = root
+            .join("src/proto")
+            .join(format!("{}.rs", snake(&module.header)));
+        let formatted = rustfmt(&code);
+        if check {
+            let Some(code) = formatted else {
+                eprintln!("--check needs rustfmt");
+                process::exit(2);
+            };
+            if fs::read_to_string(&out).ok().as_deref() != Some(code.as_str()) {
+                eprintln!("{} is out of date", out.display());
+                stale = true;
+            }
+            continue;
+        }
+        // not fatal, the code is fine unformatted
+        if formatted.is_none() {
+            eprintln!("couldn't rustfmt {}", out.display());
+        }
+        let code = formatted.unwrap_or(code);
+        fs::write(&out, code).unwrap_or_else(|e| panic!("can't write {}: {}", out.display(), e));
+        println!("wrote {}", out.display());
+    }
+    if stale {
+        eprintln!("rerun `cargo run -p xcbgen` to update it");
+        process::exit(1);
+    }
+}
+
+/// `code` the way rustfmt formats it, `None` if rustfmt isn't there or fails
+fn rustfmt(code: &str) -> Option<String> {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // dropped at the end of the statement, closing stdin so rustfmt gets going
+    child.stdin.take()?.write_all(code.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
@@ -0,0 +1,353 @@
+use roxmltree::Node;
+
+/// length and value expressions, only the parts xproto and the extensions we use need
+#[derive(Clone, Debug)]
+pub enum Expr {
+    Value(u64),
+    FieldRef(String),
+    Op(String, Box<Expr>, Box<Expr>),
+    Popcount(Box<Expr>),
+}
+
+#[derive(Clone, Debug)]
+pub enum Item {
+    Field {
+        name: String,
+        ty: String,
+    },
+    Pad(usize),
+    Align(usize),
+    List {
+        name: String,
+        ty: String,
+        len: Option<Expr>,
+    },
+    ExprField {
+        name: String,
+        ty: String,
+        expr: Expr,
+    },
+    /// a value list, every bitcase holds a single 4 byte value
+    Switch {
+        name: String,
+        mask: String,
+        cases: Vec<(String, String)>,
+    },
+}
+
+impl Item {
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Item::Field { name, .. }
+            | Item::List { name, .. }
+            | Item::ExprField { name, .. }
+            | Item::Switch { name, .. } => Some(name),
+            Item::Pad(_) | Item::Align(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Struct {
+    pub name: String,
+    pub items: Vec<Item>,
+}
+
+#[derive(Debug)]
+pub struct Request {
+    pub name: String,
+    pub opcode: u8,
+    pub items: Vec<Item>,
+    pub reply: Option<Vec<Item>>,
+}
+
+#[derive(Debug)]
+pub struct Event {
+    pub name: String,
+    pub number: u8,
+    pub items: Vec<Item>,
+    pub no_sequence_number: bool,
+}
+
+#[derive(Debug)]
+pub struct Copy {
+    pub name: String,
+    pub number: u8,
+    pub of: String,
+}
+
+#[derive(Debug)]
+pub struct Enum {
+    pub name: String,
+    pub items: Vec<(String, u32)>,
+}
+
+/// sized once every type it's made of is known, see `Types::add_module`
+#[derive(Debug)]
+pub struct Union {
+    pub name: String,
+    /// (type, how many) of every member
+    pub members: Vec<(String, usize)>,
+}
+
+#[derive(Debug)]
+pub struct Extension {
+    pub xname: String,
+    pub major_version: u32,
+    pub minor_version: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct Module {
+    pub header: String,
+    pub extension: Option<Extension>,
+    pub imports: Vec<String>,
+    pub xids: Vec<String>,
+    pub typedefs: Vec<(String, String)>,
+    /// unions become byte arrays of the largest member's size
+    pub unions: Vec<Union>,
+    pub structs: Vec<Struct>,
+    pub enums: Vec<Enum>,
+    pub requests: Vec<Request>,
+    pub events: Vec<Event>,
+    pub event_copies: Vec<Copy>,
+    pub errors: Vec<Event>,
+    pub error_copies: Vec<Copy>,
+    /// things in the XML we don't know how to generate
+    pub skipped: Vec<String>,
+}
+
+fn attr<'a>(node: Node<'a, '_>, name: &str) -> Result<&'a str, String> {
+    node.attribute(name)
+        .ok_or_else(|| format!("<{}> without {}", node.tag_name().name(), name))
+}
+
+fn number<T: std::str::FromStr>(node: Node, name: &str) -> Result<T, String> {
+    let text = attr(node, name)?;
+    text.parse()
+        .map_err(|_| format!("{} isn't a number in <{}>", text, node.tag_name().name()))
+}
+
+fn elements<'a, 'i>(node: Node<'a, 'i>) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children().filter(|n| n.is_element())
+}
+
+fn expr(node: Node) -> Result<Expr, String> {
+    let text = || node.text().unwrap_or("").trim().to_string();
+    match node.tag_name().name() {
+        "value" => {
+            let text = text();
+            let value = match text.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16),
+                None => text.parse(),
+            };
+            value
+                .map(Expr::Value)
+                .map_err(|_| format!("bad value {}", text))
+        }
+        "fieldref" => Ok(Expr::FieldRef(text())),
+        "op" => {
+            let mut args = elements(node).map(expr);
+            let (Some(a), Some(b)) = (args.next(), args.next()) else {
+                return Err("<op> needs two operands".into());
+            };
+            Ok(Expr::Op(
+                attr(node, "op")?.to_string(),
+                Box::new(a?),
+                Box::new(b?),
+            ))
+        }
+        "popcount" => {
+            let arg = elements(node).next().ok_or("empty <popcount>")?;
+            Ok(Expr::Popcount(Box::new(expr(arg)?)))
+        }
+        other => Err(format!("unsupported expression <{}>", other)),
+    }
+}
+
+fn items(node: Node) -> Result<Vec<Item>, String> {
+    let mut items = vec![];
+    for child in elements(node) {
+        let item = match child.tag_name().name() {
+            "field" => Item::Field {
+                name: attr(child, "name")?.to_string(),
+                ty: attr(child, "type")?.to_string(),
+            },
+            "pad" => match child.attribute("align") {
+                Some(_) => Item::Align(number(child, "align")?),
+                None => Item::Pad(number(child, "bytes")?),
+            },
+            "list" => Item::List {
+                name: attr(child, "name")?.to_string(),
+                ty: attr(child, "type")?.to_string(),
+                len: elements(child).next().map(expr).transpose()?,
+            },
+            "exprfield" => Item::ExprField {
+                name: attr(child, "name")?.to_string(),
+                ty: attr(child, "type")?.to_string(),
+                expr: expr(elements(child).next().ok_or("empty <exprfield>")?)?,
+            },
+            "switch" => switch(child)?,
+            // file descriptors go next to the bytes as SCM_RIGHTS, not in them
+            "reply" | "doc" | "required_start_align" | "fd" => continue,
+            other => return Err(format!("unsupported <{}>", other)),
+        };
+        items.push(item);
+    }
+    Ok(items)
+}
+
+fn switch(node: Node) -> Result<Item, String> {
+    let name = attr(node, "name")?.to_string();
+    let mut mask = None;
+    let mut cases = vec![];
+    for child in elements(node) {
+        match child.tag_name().name() {
+            "fieldref" => mask = Some(child.text().unwrap_or("").trim().to_string()),
+            "bitcase" => {
+                let fields: Vec<_> = elements(child)
+                    .filter(|n| n.tag_name().name() == "field")
+                    .collect();
+                let [field] = fields[..] else {
+                    return Err(format!("{}: bitcases with more than one field", name));
+                };
+                cases.push((
+                    attr(field, "name")?.to_string(),
+                    attr(field, "type")?.to_string(),
+                ));
+            }
+            other => return Err(format!("{}: unsupported <{}> in a switch", name, other)),
+        }
+    }
+    let mask = mask.ok_or_else(|| format!("{}: switch without a mask", name))?;
+    Ok(Item::Switch { name, mask, cases })
+}
+
+fn enumeration(node: Node) -> Result<Enum, String> {
+    let mut items = vec![];
+    for item in elements(node).filter(|n| n.tag_name().name() == "item") {
+        let value = elements(item).next().ok_or("empty enum <item>")?;
+        let text = value.text().unwrap_or("").trim();
+        let parsed: u32 = text
+            .parse()
+            .map_err(|_| format!("bad enum value {}", text))?;
+        let value = match value.tag_name().name() {
+            "bit" => 1 << parsed,
+            _ => parsed,
+        };
+        items.push((attr(item, "name")?.to_string(), value));
+    }
+    Ok(Enum {
+        name: attr(node, "name")?.to_string(),
+        items,
+    })
+}
+
+/// members of a union, fields and lists of a fixed length
+fn union(node: Node) -> Result<Union, String> {
+    let name = attr(node, "name")?.to_string();
+    let mut members = vec![];
+    for member in elements(node) {
+        let count = match member.tag_name().name() {
+            "field" => 1,
+            "list" => match elements(member).next().map(expr).transpose()? {
+                Some(Expr::Value(count)) => count as usize,
+                _ => return Err(format!("{}: unsized union member", name)),
+            },
+            other => return Err(format!("{}: unsupported <{}> in a union", name, other)),
+        };
+        members.push((attr(member, "type")?.to_string(), count));
+    }
+    Ok(Union { name, members })
+}
+
+impl Module {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let doc = roxmltree::Document::parse(text).map_err(|e| e.to_string())?;
+        let root = doc.root_element();
+        let mut module = Module {
+            header: attr(root, "header")?.to_string(),
+            ..Default::default()
+        };
+        if let Some(xname) = root.attribute("extension-xname") {
+            module.extension = Some(Extension {
+                xname: xname.to_string(),
+                major_version: number(root, "major-version")?,
+                minor_version: number(root, "minor-version")?,
+            });
+        }
+        for node in elements(root) {
+            let name = node.attribute("name").unwrap_or_default().to_string();
+            match node.tag_name().name() {
+                "import" => module
+                    .imports
+                    .push(node.text().unwrap_or("").trim().to_string()),
+                "xidtype" | "xidunion" => module.xids.push(name),
+                "typedef" => module.typedefs.push((
+                    attr(node, "newname")?.to_string(),
+                    attr(node, "oldname")?.to_string(),
+                )),
+                "enum" => module.enums.push(enumeration(node)?),
+                "union" => match union(node) {
+                    Ok(union) => module.unions.push(union),
+                    Err(e) => module.skipped.push(e),
+                },
+                "struct" => match items(node) {
+                    Ok(items) => module.structs.push(Struct { name, items }),
+                    Err(e) => module.skipped.push(format!("{}: {}", name, e)),
+                },
+                "request" => {
+                    let reply = elements(node).find(|n| n.tag_name().name() == "reply");
+                    match (items(node), reply.map(items).transpose()) {
+                        (Ok(items), Ok(reply)) => module.requests.push(Request {
+                            name,
+                            opcode: number(node, "opcode")?,
+                            items,
+                            reply,
+                        }),
+                        (Err(e), _) | (_, Err(e)) => {
+                            module.skipped.push(format!("{}: {}", name, e))
+                        }
+                    }
+                }
+                "event" | "error" => {
+                    if node.attribute("xge") == Some("true") {
+                        module.skipped.push(format!("{}: generic events", name));
+                        continue;
+                    }
+                    let event = match items(node) {
+                        Ok(items) => Event {
+                            name,
+                            number: number(node, "number")?,
+                            items,
+                            no_sequence_number: node.attribute("no-sequence-number")
+                                == Some("true"),
+                        },
+                        Err(e) => {
+                            module.skipped.push(format!("{}: {}", name, e));
+                            continue;
+                        }
+                    };
+                    match node.tag_name().name() {
+                        "event" => module.events.push(event),
+                        _ => module.errors.push(event),
+                    }
+                }
+                "eventcopy" | "errorcopy" => {
+                    let copy = Copy {
+                        name,
+                        number: number(node, "number")?,
+                        of: attr(node, "ref")?.to_string(),
+                    };
+                    match node.tag_name().name() {
+                        "eventcopy" => module.event_copies.push(copy),
+                        _ => module.error_copies.push(copy),
+                    }
+                }
+                "doc" => {}
+                other => module.skipped.push(format!("<{}> {}", other, name)),
+            }
+        }
+        Ok(module)
+    }
+}
@@ -0,0 +1,359 @@
+//! `#[derive(Serialize, Deserialize)]` for structs that go over the X wire.
+//!
+//! Fields are read and written in order, little endian, with `#[wire(...)]` for the rest:\
+//! `pad = N` / `align = N` skip bytes before a field, `pad_after` / `align_after` after it,
+//! alignment counts from the start of the struct\
+//! `len = "expr"` is how many items a `Vec` field holds, `expr` can use the fields before it,
+//! without it the list takes up whatever is left\
+//! `len_of = "list"` on a length field writes `list`'s length instead of the field\
+//! `repr = "CARD8"` on a fieldless enum is the type its values go out as
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as Tokens};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Expr, Fields, GenericArgument, Ident, LitInt,
+    LitStr, Member, PathArguments, Type,
+};
+
+#[proc_macro_derive(Serialize, attributes(wire))]
+pub fn derive_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, serialize).into()
+}
+
+#[proc_macro_derive(Deserialize, attributes(wire))]
+pub fn derive_deserialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input, deserialize).into()
+}
+
+fn expand(input: &DeriveInput, body: fn(&DeriveInput, &Shape) -> Tokens) -> Tokens {
+    match Shape::parse(input) {
+        Ok(shape) => body(input, &shape),
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+enum Step {
+    Pad(usize),
+    Align(usize),
+}
+
+struct Field {
+    member: Member,
+    /// what the field is called while it's being read
+    local: Ident,
+    ty: Type,
+    before: Vec<Step>,
+    after: Vec<Step>,
+    len: Option<Expr>,
+    len_of: Option<Ident>,
+}
+
+enum Shape {
+    Struct {
+        fields: Vec<Field>,
+        tuple: bool,
+    },
+    Enum {
+        repr: Box<Type>,
+        variants: Vec<Ident>,
+    },
+}
+
+fn wire_attrs(
+    attrs: &[Attribute],
+    mut each: impl FnMut(syn::meta::ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|a| a.path().is_ident("wire")) {
+        attr.parse_nested_meta(&mut each)?;
+    }
+    Ok(())
+}
+
+fn number(meta: &syn::meta::ParseNestedMeta) -> syn::Result<usize> {
+    meta.value()?.parse::<LitInt>()?.base10_parse()
+}
+
+fn string<T: syn::parse::Parse>(meta: &syn::meta::ParseNestedMeta) -> syn::Result<T> {
+    meta.value()?.parse::<LitStr>()?.parse()
+}
+
+/// `T` for a `Vec<T>`
+fn element(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else { return None };
+    let last = path.path.segments.last()?;
+    if last.ident != "Vec" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+impl Shape {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        match &input.data {
+            Data::Struct(data) => {
+                let tuple = matches!(data.fields, Fields::Unnamed(_));
+                let mut fields = vec![];
+                for (i, field) in data.fields.iter().enumerate() {
+                    let (member, local) = match &field.ident {
+                        Some(name) => (Member::Named(name.clone()), name.clone()),
+                        None => (Member::Unnamed(i.into()), format_ident!("__field{}", i)),
+                    };
+                    let mut parsed = Field {
+                        member,
+                        local,
+                        ty: field.ty.clone(),
+                        before: vec![],
+                        after: vec![],
+                        len: None,
+                        len_of: None,
+                    };
+                    wire_attrs(&field.attrs, |meta| {
+                        let path = &meta.path;
+                        if path.is_ident("pad") {
+                            parsed.before.push(Step::Pad(number(&meta)?));
+                        } else if path.is_ident("align") {
+                            parsed.before.push(Step::Align(number(&meta)?));
+                        } else if path.is_ident("pad_after") {
+                            parsed.after.push(Step::Pad(number(&meta)?));
+                        } else if path.is_ident("align_after") {
+                            parsed.after.push(Step::Align(number(&meta)?));
+                        } else if path.is_ident("len") {
+                            parsed.len = Some(string(&meta)?);
+                        } else if path.is_ident("len_of") {
+                            parsed.len_of = Some(string(&meta)?);
+                        } else {
+                            return Err(meta.error("unknown wire attribute"));
+                        }
+                        Ok(())
+                    })?;
+                    if parsed.len.is_some() && element(&parsed.ty).is_none() {
+                        return Err(syn::Error::new_spanned(&field.ty, "`len` needs a Vec"));
+                    }
+                    fields.push(parsed);
+                }
+                Ok(Shape::Struct { fields, tuple })
+            }
+            Data::Enum(data) => {
+                let mut repr = None;
+                wire_attrs(&input.attrs, |meta| {
+                    if meta.path.is_ident("repr") {
+                        repr = Some(string(&meta)?);
+                        Ok(())
+                    } else {
+                        Err(meta.error("unknown wire attribute"))
+                    }
+                })?;
+                let repr: Box<Type> = repr.ok_or_else(|| {
+                    syn::Error::new_spanned(&input.ident, "enums need #[wire(repr = \"...\")]")
+                })?;
+                let mut variants = vec![];
+                for variant in &data.variants {
+                    if !matches!(variant.fields, Fields::Unit) {
+                        return Err(syn::Error::new_spanned(variant, "only fieldless enums"));
+                    }
+                    variants.push(variant.ident.clone());
+                }
+                Ok(Shape::Enum { repr, variants })
+            }
+            Data::Union(_) => Err(syn::Error::new(
+                Span::call_site(),
+                "unions aren't supported",
+            )),
+        }
+    }
+}
+
+fn serialize(input: &DeriveInput, shape: &Shape) -> Tokens {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = match shape {
+        Shape::Struct { fields, .. } => {
+            let steps = |steps: &[Step]| -> Tokens {
+                steps
+                    .iter()
+                    .map(|step| match step {
+                        Step::Pad(n) => quote! { ::std::io::Write::write_all(__w, &[0; #n])?; },
+                        Step::Align(n) => quote! { __w.resize(__w.len().next_multiple_of(#n), 0); },
+                    })
+                    .collect()
+            };
+            let writes = fields.iter().map(|field| {
+                let member = &field.member;
+                let before = steps(&field.before);
+                let after = steps(&field.after);
+                let write = match (&field.len_of, element(&field.ty)) {
+                    (Some(list), _) => {
+                        let ty = &field.ty;
+                        quote! {
+                            let __len = ::xproto::xio::list_len::<#ty>(self.#list.len())?;
+                            ::xproto::xio::Serialize::serialize(&__len, __w)?;
+                        }
+                    }
+                    (None, Some(_)) => quote! {
+                        for __item in &self.#member {
+                            ::xproto::xio::Serialize::serialize(__item, __w)?;
+                        }
+                    },
+                    (None, None) => quote! {
+                        ::xproto::xio::Serialize::serialize(&self.#member, __w)?;
+                    },
+                };
+                quote! { #before #write #after }
+            });
+            let aligned = fields
+                .iter()
+                .flat_map(|f| f.before.iter().chain(&f.after))
+                .any(|step| matches!(step, Step::Align(_)));
+            if aligned {
+                // padding to a multiple of something needs to know how much came before
+                quote! {
+                    let __out = __w;
+                    let __w = &mut ::std::vec::Vec::<u8>::new();
+                    #(#writes)*
+                    ::std::io::Write::write_all(__out, __w)
+                }
+            } else {
+                quote! {
+                    #(#writes)*
+                    Ok(())
+                }
+            }
+        }
+        Shape::Enum { repr, variants } => quote! {
+            let __value: #repr = match self {
+                #(Self::#variants => Self::#variants as #repr,)*
+            };
+            ::xproto::xio::Serialize::serialize(&__value, __w)
+        },
+    };
+    quote! {
+        impl #impl_generics ::xproto::xio::Serialize for #name #ty_generics #where_clause {
+            fn serialize<__W: ::std::io::Write>(&self, __w: &mut __W) -> ::std::io::Result<()> {
+                #body
+            }
+        }
+    }
+}
+
+fn deserialize(input: &DeriveInput, shape: &Shape) -> Tokens {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (size, body) = match shape {
+        Shape::Struct { fields, tuple } => {
+            let steps = |steps: &[Step]| -> Tokens {
+                steps
+                    .iter()
+                    .map(|step| match step {
+                        Step::Pad(n) => quote! { __r.skip(#n)?; },
+                        Step::Align(n) => quote! {
+                            let __at = __r.position() - __start;
+                            __r.skip(__at.next_multiple_of(#n) - __at)?;
+                        },
+                    })
+                    .collect()
+            };
+            let size_steps = |steps: &[Step]| -> Tokens {
+                steps
+                    .iter()
+                    .map(|step| match step {
+                        Step::Pad(n) => quote! {
+                            let __size = ::xproto::xio::add_sizes(__size, Some(#n));
+                        },
+                        Step::Align(n) => quote! {
+                            let __size = ::xproto::xio::align_size(__size, #n);
+                        },
+                    })
+                    .collect()
+            };
+            let sizes = fields.iter().map(|field| {
+                let ty = &field.ty;
+                let field_size = match element(ty) {
+                    Some(_) => quote! { None },
+                    None => quote! { <#ty as ::xproto::xio::Deserialize>::SIZE },
+                };
+                let before = size_steps(&field.before);
+                let after = size_steps(&field.after);
+                quote! {
+                    #before
+                    let __size = ::xproto::xio::add_sizes(__size, #field_size);
+                    #after
+                }
+            });
+            let reads = fields.iter().map(|field| {
+                let local = &field.local;
+                let ty = &field.ty;
+                let before = steps(&field.before);
+                let after = steps(&field.after);
+                let read = match (element(ty), &field.len) {
+                    (Some(_), Some(len)) => quote! {
+                        let #local: #ty = __r.list((#len) as usize)?;
+                    },
+                    (Some(_), None) => quote! { let #local: #ty = __r.rest()?; },
+                    (None, _) => quote! {
+                        let #local: #ty = ::xproto::xio::Deserialize::deserialize(__r)?;
+                    },
+                };
+                quote! { #before #read #after }
+            });
+            let locals = fields.iter().map(|field| &field.local);
+            let construct = match tuple {
+                true => quote! { Self(#(#locals),*) },
+                false => {
+                    let members = fields.iter().map(|field| &field.member);
+                    quote! { Self { #(#members: #locals),* } }
+                }
+            };
+            (
+                quote! {
+                    let __size = Some(0);
+                    #(#sizes)*
+                    __size
+                },
+                quote! {
+                    let __start = __r.position();
+                    #(#reads)*
+                    let _ = __start;
+                    Ok(#construct)
+                },
+            )
+        }
+        Shape::Enum { repr, variants } => {
+            let what = name.to_string();
+            (
+                quote! { <#repr as ::xproto::xio::Deserialize>::SIZE },
+                quote! {
+                    let __value: #repr = ::xproto::xio::Deserialize::deserialize(__r)?;
+                    #(
+                        if __value == Self::#variants as #repr {
+                            return Ok(Self::#variants);
+                        }
+                    )*
+                    Err(::std::io::Error::new(
+                        ::std::io::ErrorKind::InvalidData,
+                        format!("{} isn't a {}", __value, #what),
+                    ))
+                },
+            )
+        }
+    };
+    quote! {
+        impl #impl_generics ::xproto::xio::Deserialize for #name #ty_generics #where_clause {
+            const SIZE: Option<usize> = { #size };
+
+            fn deserialize(
+                __r: &mut ::xproto::xio::Reader<'_>,
+            ) -> ::std::io::Result<Self> {
+                #body
+            }
+        }
+    }
+}